@@ -20,9 +20,8 @@
 )]
 
 use crate::bcos2sdk::bcosrpcwraper::BcosRPC;
-use crate::bcos2sdk::bcostransaction;
 use crate::bcos2sdk::bcostransaction::{BcosTransaction, BcosTransactionWithSig};
-use crate::bcossdkutil::accountutil::{account_from_pem, BcosAccount};
+use crate::bcossdkutil::accountutil::{account_from_config, BcosAccount};
 use crate::bcossdkutil::bcosclientconfig::BcosClientProtocol;
 use crate::bcossdkutil::bcosclientconfig::{BcosCryptoKind, ClientConfig};
 use crate::bcossdkutil::commonhash::{CommonHash, HashType};
@@ -30,11 +29,12 @@ use crate::bcossdkutil::commonsigner::{
     CommonSignerWeDPR_SM2, CommonSignerWeDPR_Secp256, ICommonSigner,
 };
 use crate::bcossdkutil::contractabi::ContractABI;
+use crate::bcossdkutil::externalsigner::ExternalSigner;
 use crate::bcossdkutil::fileutils;
 use crate::bcossdkutil::kisserror::{KissErrKind, KissError};
-use crate::{kisserr, printlnex};
+use crate::bcossdkutil::txstatus::check_bcos2_receipt;
+use crate::{kisserr, kisserrcode, printlnex};
 use ethabi::Token;
-use ethereum_types::U256;
 use serde_json::{json, Value as JsonValue};
 use time::Tm;
 
@@ -45,6 +45,8 @@ pub struct Bcos2Client {
     pub netclient: BcosRPC,
     pub ecdsasigner: Option<CommonSignerWeDPR_Secp256>,
     pub gmsigner: Option<CommonSignerWeDPR_SM2>,
+    ///外部或自定义的签名器，设置后优先于ecdsasigner/gmsigner，私钥可以不在本进程里
+    pub signer: Option<Box<dyn ICommonSigner + Send + Sync>>,
     //重要：当前sdk实例采用的hash算法，如keccak,国密等，当前客户端的编解码，签名都必须基于相同的hash算法
     //主要牵涉： account生成和加载，transaction签名，abi编解码
     pub hashtype: HashType,
@@ -83,16 +85,43 @@ impl Bcos2Client {
     pub fn new_from_config(configfile: &str) -> Result<Bcos2Client, KissError> {
         let config = ClientConfig::load(configfile)?;
         printlnex!("config is {:?}", config);
+        //配置了外部签名服务时，账户的公钥和地址从签名服务获取
+        match ExternalSigner::from_config(&config.common)? {
+            Some(signer) => {
+                let account = signer.account.clone();
+                Bcos2Client::build(config, account, Some(Box::new(signer)))
+            }
+            None => {
+                let account = account_from_config(&config.common)?;
+                Bcos2Client::build(config, account, None)
+            }
+        }
+    }
 
+    ///使用任意的签名器创建client，account为签名器对应的账户，私钥可以为空
+    pub fn new_with_signer(
+        configfile: &str,
+        signer: Box<dyn ICommonSigner + Send + Sync>,
+        account: BcosAccount,
+    ) -> Result<Bcos2Client, KissError> {
+        let config = ClientConfig::load(configfile)?;
+        Bcos2Client::build(config, account, Some(signer))
+    }
+
+    fn build(
+        config: ClientConfig,
+        account: BcosAccount,
+        signer: Option<Box<dyn ICommonSigner + Send + Sync>>,
+    ) -> Result<Bcos2Client, KissError> {
         //国密和非国密关键步骤，设置hash,账户类型和签名的密码学方法
         //一定要先设置hash算法，这是基础中的基础
         let hashtype = CommonHash::crypto_to_hashtype(&config.common.crypto);
 
         let mut ecdsasigner = Option::None;
         let mut gmsigner = Option::None;
-        let account = account_from_pem(config.common.accountpem.as_str(), &config.common.crypto)?;
         //printlnex!("done account");
         match &config.common.crypto {
+            _ if signer.is_some() => {}
             BcosCryptoKind::ECDSA => {
                 let mut signer = CommonSignerWeDPR_Secp256::default();
                 signer.account = account.clone();
@@ -112,6 +141,7 @@ impl Bcos2Client {
             netclient,
             gmsigner: gmsigner.clone(),
             ecdsasigner: ecdsasigner.clone(),
+            signer,
             hashtype: hashtype.clone(),
             //默认初始化为500秒前，以便第一次一定会去取一下blocknum
             updateblocknum_tick: time::now() - chrono::Duration::seconds(500),
@@ -238,33 +268,51 @@ impl Bcos2Client {
         Ok(value)
     }
 
-    ///引用客户端配置，构建一个未签名的交易
+    ///输入已经解析好的param，调用合约的只读方法
+    pub fn call_with_tokenparam(
+        &mut self,
+        contract: &ContractABI,
+        address: &str,
+        method: &str,
+        params: &[Token],
+    ) -> Result<JsonValue, KissError> {
+        let groupid = self.config.bcos2.groupid;
+        let from = hex::encode(&self.account.address);
+        let function = contract.find_function_unwrap(method)?;
+        let txinput =
+            ContractABI::encode_function_input_to_abi_by_tokens(&function, params, &self.hashtype)?;
+        let paramobj = json!([groupid,
+        {"from":from,
+        "to":address,
+        "data":hex::encode(txinput),
+        "value":0
+        }]);
+        let value = self.netclient.rpc_request_sync("call", &paramobj)?;
+        Ok(value)
+    }
+
+    ///引用客户端配置，构建一个未签名的交易。离线构建用BcosTransaction::new_unsigned
     pub fn make_transaction(
         &self,
         to_address: &str,
         txinput: &str,
         block_limit_i32: u32,
     ) -> Option<BcosTransaction> {
-        let randid: u64 = rand::random();
-        let chainid = self.config.bcos2.chainid;
-        let groupid = self.config.bcos2.groupid;
-        Option::from(BcosTransaction {
-            to_address: bcostransaction::encode_address(to_address),
-            random_id: U256::from(randid),
-            gas_price: U256::from(30000000),
-            gas_limit: U256::from(30000000),
-            block_limit: U256::from(block_limit_i32),
-            value: U256::from(0),
-            data: hex::decode(txinput).unwrap(),
-            fisco_chain_id: U256::from(chainid),
-            group_id: U256::from(groupid),
-            extra_data: b"".to_vec(),
-            hashtype: self.hashtype.clone(), //sdk在这里把hash算法配置传给了transaction
-        })
+        Option::from(BcosTransaction::new_unsigned(
+            to_address,
+            hex::decode(txinput).unwrap(),
+            block_limit_i32 as u64,
+            self.config.bcos2.chainid,
+            self.config.bcos2.groupid,
+            &self.hashtype, //sdk在这里把hash算法配置传给了transaction
+        ))
     }
 
     ///根据配置选择签名算法实现
     pub fn pick_signer(&self) -> &dyn ICommonSigner {
+        if let Some(signer) = &self.signer {
+            return signer.as_ref();
+        }
         match self.config.common.crypto {
             BcosCryptoKind::ECDSA => {
                 let signer = self.ecdsasigner.as_ref().unwrap();
@@ -281,29 +329,36 @@ impl Bcos2Client {
 
     ///引用客户端已经配置好的account，对交易进行签名
     pub fn encode_sign_raw_tx(&self, tx: &BcosTransaction) -> Result<Vec<u8>, KissError> {
-        let signer = self.pick_signer();
-
-        let txsig = BcosTransactionWithSig::sign(signer, tx)?;
-        let rawdata = txsig.encode();
-        Ok(rawdata)
+        BcosTransactionWithSig::sign_and_encode(self.pick_signer(), tx)
     }
-    ///输入已经解析好的param，直接根据组包，调用合约
-    pub fn send_raw_transaction_withtokenparam(
+    ///按已经解析好的param组包并签名，返回签名后的交易数据
+    pub fn encode_sign_tx_withtokenparam(
         &mut self,
         contract: &ContractABI,
         to_address: &str,
         methodname: &str,
         params: &[Token],
-    ) -> Result<JsonValue, KissError> {
+    ) -> Result<Vec<u8>, KissError> {
         let block_limit = self.getBlockLimit()?;
         let function = contract.find_function_unwrap(methodname)?;
         //println!("function : {:?}",function);
         let txinput =
             ContractABI::encode_function_input_to_abi_by_tokens(&function, params, &self.hashtype)?;
         let tx = self.make_transaction(to_address, &hex::encode(txinput), block_limit);
+        self.encode_sign_raw_tx(&tx.unwrap())
+    }
+    ///输入已经解析好的param，直接根据组包，调用合约
+    pub fn send_raw_transaction_withtokenparam(
+        &mut self,
+        contract: &ContractABI,
+        to_address: &str,
+        methodname: &str,
+        params: &[Token],
+    ) -> Result<JsonValue, KissError> {
+        let rawdata =
+            self.encode_sign_tx_withtokenparam(contract, to_address, methodname, params)?;
         let groupid = self.config.bcos2.groupid;
         let cmd = "sendRawTransaction";
-        let rawdata = self.encode_sign_raw_tx(&tx.unwrap())?;
         let hexdata = hex::encode(rawdata);
         let paramobj = json!([groupid, hexdata]);
         let value = self.netclient.rpc_request_sync(cmd, &paramobj)?;
@@ -324,6 +379,87 @@ impl Bcos2Client {
         Ok(value)
     }
 
+    ///发送签名好的交易并等待回执，返回的格式同getTransactionReceipt。
+    ///交易上链但执行失败(status非0)时返回ETxFailed，msg里带解析后的状态和revert原因
+    pub fn send_signed_tx_get_receipt(
+        &mut self,
+        rawdata: &[u8],
+        timeoutsec: i64,
+    ) -> Result<JsonValue, KissError> {
+        let receipt = self.send_signed_tx_wait_receipt(rawdata, timeoutsec)?;
+        check_bcos2_receipt(&receipt)?;
+        Ok(receipt)
+    }
+
+    ///CHANNEL协议下等节点推送的TX_COMMITTED，没等到(如推送丢失、连接断开)时再查一次回执；
+    ///RPC协议没有推送，轮询getTransactionReceipt
+    fn send_signed_tx_wait_receipt(
+        &mut self,
+        rawdata: &[u8],
+        timeoutsec: i64,
+    ) -> Result<JsonValue, KissError> {
+        let txhash = self.send_signed_tx(rawdata)?;
+        if self.config.bcos2.protocol != BcosClientProtocol::CHANNEL {
+            return self.try_getTransactionReceipt(txhash.as_str(), timeoutsec, false);
+        }
+        let seq = self.netclient.channel_client.last_request_seq;
+        let pushed = self
+            .netclient
+            .channel_client
+            .wait_tx_committed(&seq, timeoutsec);
+        match pushed {
+            Ok(text) => match serde_json::from_str::<JsonValue>(text.as_str()) {
+                Ok(receipt) => Ok(json!({"id": 1, "jsonrpc": "2.0", "result": receipt})),
+                Err(e) => kisserr!(
+                    KissErrKind::EFormat,
+                    "tx committed data is not json {},{:?}",
+                    text,
+                    e
+                ),
+            },
+            Err(e) => {
+                log::warn!("no TX_COMMITTED for {}: {:?}, query receipt", txhash, e);
+                let v = self.getTransactionReceipt(txhash.as_str())?;
+                if v["result"] == JsonValue::Null {
+                    return kisserr!(
+                        KissErrKind::ETimeout,
+                        "transaction {} not committed in {} seconds",
+                        txhash,
+                        timeoutsec
+                    );
+                }
+                Ok(v)
+            }
+        }
+    }
+
+    ///发送签名好的交易，不等回执，返回节点给的交易hash。
+    ///CHANNEL协议下交易上链后节点仍会推送TX_COMMITTED，可以用channel_client.take_tx_committed收取
+    pub fn send_signed_tx(&mut self, rawdata: &[u8]) -> Result<String, KissError> {
+        let groupid = self.config.bcos2.groupid;
+        let paramobj = json!([groupid, hex::encode(rawdata)]);
+        let response = self
+            .netclient
+            .rpc_request_sync("sendRawTransaction", &paramobj)?;
+        //节点拒绝交易时(如blocklimit过期、nonce重复)带上节点的错误码
+        if let Some(err) = response.get("error") {
+            return kisserrcode!(
+                KissErrKind::Error,
+                err["code"].as_i64().unwrap_or(-1),
+                "sendRawTransaction error: {}",
+                err["message"].as_str().unwrap_or_default()
+            );
+        }
+        match response["result"].as_str() {
+            Some(h) => Ok(h.to_string()),
+            None => kisserr!(
+                KissErrKind::EFormat,
+                "sendRawTransaction response has no txhash: {}",
+                response
+            ),
+        }
+    }
+
     ///传入的是类型已经按ABI好的token
     pub fn sendRawTransactionGetReceiptWithTokenParam(
         &mut self,
@@ -332,13 +468,13 @@ impl Bcos2Client {
         methodname: &str,
         params: &[Token],
     ) -> Result<JsonValue, KissError> {
-        let response =
-            self.send_raw_transaction_withtokenparam(&contract, &to_address, methodname, params)?;
-        let txhash = response["result"].as_str().unwrap();
-        self.try_getTransactionReceipt(txhash, 3, false)
+        let rawdata =
+            self.encode_sign_tx_withtokenparam(contract, to_address, methodname, params)?;
+        let timeout = self.config.bcos2.receipt_timeout_sec as i64;
+        self.send_signed_tx_get_receipt(&rawdata, timeout)
     }
 
-    ///简单封装下同步的发送交易且获得回执的方法。默认等待1s,这是个非常常用的方法，尤其是用于demo时
+    ///简单封装下同步的发送交易且获得回执的方法。等待时间为配置的receipt_timeout_sec,这是个非常常用的方法，尤其是用于demo时
     pub fn sendRawTransactionGetReceipt(
         &mut self,
         contract: &ContractABI,
@@ -346,10 +482,8 @@ impl Bcos2Client {
         methodname: &str,
         params: &[String],
     ) -> Result<JsonValue, KissError> {
-        let response = self.send_raw_transaction(&contract, &to_address, methodname, &params)?;
-        println!("response {:?}", response);
-        let txhash = response["result"].as_str().unwrap();
-        self.try_getTransactionReceipt(txhash, 3, false)
+        let txinput = contract.convert_function_input_str_to_token(methodname, params, true)?;
+        self.sendRawTransactionGetReceiptWithTokenParam(contract, to_address, methodname, &txinput)
     }
     ///https://fisco-bcos-documentation.readthedocs.io/zh_CN/latest/docs/api.html#sendrawtransactionandgetproof
     pub fn sendRawTransactionAndGetProof(
@@ -12,11 +12,17 @@
 
 use crate::bcos2sdk::bcos_ssl_native::BcosNativeTlsClient;
 use crate::bcos2sdk::bcos_ssl_normal::BcosSSLClient;
-use crate::bcos2sdk::channelpack::{make_channel_pack, ChannelPack, CHANNEL_PACK_TYPE};
+use crate::bcos2sdk::channelpack::{
+    make_channel_pack, make_channel_pack_by_rawdata, make_heartbeat_data, ChannelPack,
+    CHANNEL_PACK_TYPE, CHANNEL_PROTOCOL_MAX, CHANNEL_PROTOCOL_MIN, CHANNEL_PROTOCOL_V1,
+    CHANNEL_PROTOCOL_V3,
+};
 use crate::bcossdkutil::bcosclientconfig::{BcosCryptoKind, ChannelConfig};
 use crate::bcossdkutil::bufferqueue::BufferQueue;
 use crate::bcossdkutil::kisserror::{KissErrKind, KissError};
 use crate::{kisserr, printlnex};
+use ethereum_types::H256;
+use serde_json::{json, Value as JsonValue};
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
@@ -37,6 +43,20 @@ pub struct BcosChannelClient {
     pub config: ChannelConfig,
     pub bufferqueue: BufferQueue,
     pub channelpackpool: Vec<ChannelPack>, //一个池子，存没有被处理的channelpack，在推送等流程用到
+    ///已订阅的AMOP topic和已注册的事件过滤请求(CLIENT_REGISTER_EVENT_LOG包的数据)，重连后重新发送
+    pub topics: Vec<String>,
+    pub eventfilters: Vec<Vec<u8>>,
+    ///最后一次收到数据和发出心跳的时间
+    pub last_active: time::Tm,
+    pub last_heartbeat: time::Tm,
+    ///发出了心跳，之后还没有收到任何数据
+    pub heartbeat_pending: bool,
+    ///握手协商得到的channel协议版本(CHANNEL_PROTOCOL_xxx)，未握手时为0
+    pub protocol_version: i64,
+    ///握手时节点返回的版本号，如"2.9.1"
+    pub node_version: String,
+    ///最近一次request_sync请求包的seq，交易上链后节点用这个seq推送TX_COMMITTED
+    pub last_request_seq: H256,
 }
 
 //unsafe impl Send for BcosChannelClient {}
@@ -57,6 +77,12 @@ impl IBcosChannel for BcosChannelClient {
         //println!("recv");
         let res = self.channelimpl.lock().unwrap().recv();
         //println!("recv done");
+        if let Ok(data) = &res {
+            if !data.is_empty() {
+                self.last_active = time::now();
+                self.heartbeat_pending = false;
+            }
+        }
         res
     }
 
@@ -75,11 +101,20 @@ impl BcosChannelClient {
             bufferqueue: Default::default(),
             channelimpl: channelimpl,
             channelpackpool: vec![],
+            topics: vec![],
+            eventfilters: vec![],
+            last_active: time::now(),
+            last_heartbeat: time::now(),
+            heartbeat_pending: false,
+            protocol_version: 0,
+            node_version: "".to_string(),
+            last_request_seq: H256::default(),
         }
     }
-    pub fn new(config: &ChannelConfig) -> Result<BcosChannelClient, KissError> {
+
+    ///按配置的tls类型建立连接
+    fn connect_channelimpl(config: &ChannelConfig) -> Result<IBcosChannelImpl, KissError> {
         let channelimpl: IBcosChannelImpl;
-        println!("config.tlskind {:?}", &config);
         match config.tlskind {
             BcosCryptoKind::ECDSA => {
                 let mut ssl_client = BcosSSLClient::default(&config);
@@ -94,16 +129,187 @@ impl BcosChannelClient {
                 //channelimpl = Arc::new(tls_client)
             }
         }
+        Ok(channelimpl)
+    }
 
-        let wraper = BcosChannelClient {
-            config: config.clone(),
-            bufferqueue: Default::default(),
-            channelimpl: channelimpl,
-            channelpackpool: vec![],
-        };
+    pub fn new(config: &ChannelConfig) -> Result<BcosChannelClient, KissError> {
+        printlnex!("config.tlskind {:?}", &config);
+        let mut wraper = BcosChannelClient::default(config);
+        wraper.channelimpl = BcosChannelClient::connect_channelimpl(config)?;
+        wraper.handshake()?;
         Ok(wraper)
     }
 
+    ///连接后和节点协商协议版本，记录节点版本。
+    ///不认识握手包的老节点不会应答，超时后按V1处理
+    pub fn handshake(&mut self) -> Result<i64, KissError> {
+        let req = json!({
+            "minimumSupport": CHANNEL_PROTOCOL_MIN,
+            "maximumSupport": CHANNEL_PROTOCOL_MAX,
+            "clientType": "rust-gears-sdk",
+        });
+        let outpack =
+            make_channel_pack(CHANNEL_PACK_TYPE::HAND_SHAKE, req.to_string().as_str()).unwrap();
+        self.try_send(&outpack.pack())?;
+        let response = match self.read_to_match(&outpack) {
+            Ok(pack) => pack,
+            Err(e) if e.kind == KissErrKind::ETimeout => {
+                log::warn!("node does not answer handshake, use protocol V1");
+                self.protocol_version = CHANNEL_PROTOCOL_V1;
+                return Ok(self.protocol_version);
+            }
+            Err(e) => return Err(e),
+        };
+        let value: JsonValue = match serde_json::from_slice(&response.data) {
+            Ok(v) => v,
+            Err(e) => {
+                return kisserr!(
+                    KissErrKind::EFormat,
+                    "handshake response is not json {:?}",
+                    e
+                )
+            }
+        };
+        let protocol = value["protocol"].as_i64().unwrap_or(-1);
+        if !(CHANNEL_PROTOCOL_MIN..=CHANNEL_PROTOCOL_MAX).contains(&protocol) {
+            return kisserr!(
+                KissErrKind::EArgument,
+                "no common channel protocol version with node, response {}",
+                value
+            );
+        }
+        self.protocol_version = protocol;
+        self.node_version = value["nodeVersion"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+        log::info!(
+            "channel handshake protocol {}, node version {}",
+            self.protocol_version,
+            self.node_version
+        );
+        Ok(self.protocol_version)
+    }
+
+    ///节点是否支持事件推送
+    pub fn supports_event_push(&self) -> bool {
+        self.protocol_version >= CHANNEL_PROTOCOL_V3
+    }
+
+    ///断开并重新连接，清掉缓冲区里不完整的数据，然后重新订阅topic和事件
+    pub fn reconnect(&mut self) -> Result<(), KissError> {
+        log::warn!(
+            "reconnect channel {}:{}",
+            self.config.ip.as_str(),
+            self.config.port
+        );
+        self.finish();
+        //重连的可能是升级过的节点，重新握手
+        let fresh = BcosChannelClient::new(&self.config)?;
+        self.adopt(fresh)
+    }
+
+    ///换上在别处(如锁外)新建好的连接，保留已订阅的topic、事件和未处理的包，并重新订阅
+    pub fn adopt(&mut self, fresh: BcosChannelClient) -> Result<(), KissError> {
+        self.finish();
+        self.channelimpl = fresh.channelimpl;
+        self.bufferqueue = fresh.bufferqueue;
+        self.last_active = time::now();
+        self.last_heartbeat = time::now();
+        self.heartbeat_pending = false;
+        self.protocol_version = fresh.protocol_version;
+        self.node_version = fresh.node_version;
+        self.resubscribe()
+    }
+
+    ///把已记录的AMOP topic和事件过滤请求重新发给节点
+    pub fn resubscribe(&mut self) -> Result<(), KissError> {
+        if !self.topics.is_empty() {
+            let topics = self.topics.clone();
+            self.report_topics(&topics)?;
+        }
+        if !self.supports_event_push() {
+            return Ok(());
+        }
+        for data in self.eventfilters.clone().iter() {
+            let pack =
+                make_channel_pack_by_rawdata(CHANNEL_PACK_TYPE::CLIENT_REGISTER_EVENT_LOG, data)
+                    .unwrap();
+            self.try_send(&pack.pack())?;
+        }
+        Ok(())
+    }
+
+    ///向节点报告本连接订阅的全部AMOP topic(TOPIC_REPORT)，并记录下来用于重连
+    pub fn report_topics(&mut self, topics: &[String]) -> Result<(), KissError> {
+        self.topics = topics.to_vec();
+        let text = serde_json::to_string(&self.topics).unwrap();
+        let pack = make_channel_pack(CHANNEL_PACK_TYPE::TOPIC_REPORT, text.as_str()).unwrap();
+        self.try_send(&pack.pack())?;
+        Ok(())
+    }
+
+    ///发送事件过滤请求，data为pack_amop打包后的过滤json，记录下来用于重连
+    pub fn register_event_filter(&mut self, data: &Vec<u8>) -> Result<ChannelPack, KissError> {
+        if !self.supports_event_push() {
+            return kisserr!(
+                KissErrKind::EArgument,
+                "node {} (channel protocol {}) does not support event push",
+                self.node_version,
+                self.protocol_version
+            );
+        }
+        let pack = make_channel_pack_by_rawdata(CHANNEL_PACK_TYPE::CLIENT_REGISTER_EVENT_LOG, data)
+            .unwrap();
+        self.try_send(&pack.pack())?;
+        self.eventfilters.push(data.clone());
+        Ok(pack)
+    }
+
+    ///空闲超过一个心跳间隔时发心跳，心跳发出后2个间隔内没有收到任何数据时重连，返回是否发生了重连。
+    ///只是长时间没有调用(同步使用、没有心跳线程)不算断线，下次调用时先发心跳确认。
+    ///请求和读包时会调用，异步读取时应由后台线程定时调用
+    pub fn keep_alive(&mut self) -> Result<bool, KissError> {
+        if self.check_heartbeat() {
+            self.reconnect()?;
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    ///keep_alive的前半部分：到时间时发心跳，返回是否需要重连，本身不重连。
+    ///心跳线程在锁内调用，需要重连时在锁外用BcosChannelClient::new建连接，再用adopt换上
+    pub fn check_heartbeat(&mut self) -> bool {
+        let interval = self.config.heartbeat_sec as i64;
+        if interval == 0 {
+            return false;
+        }
+        let now = time::now();
+        if self.heartbeat_pending {
+            if now - self.last_heartbeat > chrono::Duration::seconds(interval * 2) {
+                log::warn!(
+                    "no heartbeat reply for {} seconds",
+                    (now - self.last_heartbeat).num_seconds()
+                );
+                return true;
+            }
+            return false;
+        }
+        if now - self.last_heartbeat >= chrono::Duration::seconds(interval)
+            && now - self.last_active >= chrono::Duration::seconds(interval)
+        {
+            self.last_heartbeat = now;
+            self.heartbeat_pending = true;
+            let data = make_heartbeat_data(self.protocol_version, "0");
+            let pack = make_channel_pack(CHANNEL_PACK_TYPE::HEART_BEAT, data.as_str()).unwrap();
+            if let Err(e) = self.try_send(&pack.pack()) {
+                log::warn!("send heartbeat error {:?}", e);
+                return true;
+            }
+        }
+        false
+    }
+
     ///尝试最多5次异步发送
     pub fn try_send(&mut self, outbuffer: &Vec<u8>) -> Result<i32, KissError> {
         let mut i: u32 = 0;
@@ -130,12 +336,13 @@ impl BcosChannelClient {
             i += 1;
             std::thread::sleep(Duration::from_millis(100));
         }
-        kisserr!(KissErrKind::ENetwork, "recv time out")
+        kisserr!(KissErrKind::ETimeout, "recv time out")
     }
 
     ///传入json 字符串，打入channelpack发送出去，同步等待read，然后返回从channelpack解析好的value
     pub fn request_sync(&mut self, reqtext: &str) -> Result<String, KissError> {
         let outpack = make_channel_pack(CHANNEL_PACK_TYPE::RPC, reqtext).unwrap();
+        self.last_request_seq = outpack.seq;
         let returnpack = self.request_channelpack_sync(&outpack)?;
         let res = String::from_utf8(returnpack.data);
         match res {
@@ -171,7 +378,11 @@ impl BcosChannelClient {
                     self.bufferqueue.cut(pack.length);
                     //获得了一个回包，要判断下是否预期的回包，否则仅加入等待区
 
-                    if pack.packtype == outpack.packtype && pack.seq == outpack.seq {
+                    if pack.packtype == CHANNEL_PACK_TYPE::HEART_BEAT as u16
+                        && outpack.packtype != pack.packtype
+                    {
+                        //心跳的回包只用来确认连接存活，收到数据时已经更新了last_active
+                    } else if pack.packtype == outpack.packtype && pack.seq == outpack.seq {
                         //println!(">>>match pack type: 0x{:02X},seq: {}",pack.packtype,pack.seq);
                         //是想要的回包
                         thepack = Option::from(pack);
@@ -234,7 +445,15 @@ impl BcosChannelClient {
         //println!("try recv");
         while i < 1 {
             //println!("try recv-->");
-            let mut res = self.recv()?;
+            //读失败说明连接已断，重连后这次先返回空
+            let mut res = match self.recv() {
+                Ok(r) => r,
+                Err(e) => {
+                    log::warn!("channel recv error {:?}, reconnect", e);
+                    self.reconnect()?;
+                    return Ok(vec![]);
+                }
+            };
             //println!("try recv res {:?}", res.len());
             if res.len() == 0 {
                 break;
@@ -244,7 +463,127 @@ impl BcosChannelClient {
             vecres = BcosChannelClient::pop_queue_to_packet(&mut self.bufferqueue)?;
             i += 1
         }
-        Ok(vecres)
+        vecres.retain(|p| p.packtype != CHANNEL_PACK_TYPE::HEART_BEAT as u16);
+        //同步请求期间收到的推送包暂存在pool里，一并返回
+        let mut pooled: Vec<ChannelPack> = self.channelpackpool.drain(..).collect();
+        pooled.append(&mut vecres);
+        Ok(pooled)
+    }
+
+    ///等待节点推送seq对应的TX_COMMITTED，返回包体(回执json)。
+    ///推送可能在同步请求的应答之前或同时到达，先在pool里找
+    pub fn wait_tx_committed(&mut self, seq: &H256, timeout_sec: i64) -> Result<String, KissError> {
+        let expect = ChannelPack {
+            packtype: CHANNEL_PACK_TYPE::TX_COMMITTED as u16,
+            seq: *seq,
+            ..Default::default()
+        };
+        let start = time::now();
+        let mut found = self
+            .channelpackpool
+            .iter()
+            .position(|p| p.packtype == expect.packtype && p.seq == expect.seq)
+            .map(|pos| self.channelpackpool.remove(pos));
+        while found.is_none() {
+            if let Ok(pack) = self.try_match_channelpack(&expect) {
+                found = Some(pack);
+                break;
+            }
+            if time::now() - start >= chrono::Duration::seconds(timeout_sec) {
+                return kisserr!(KissErrKind::ETimeout, "wait tx committed timeout");
+            }
+            let mut data = self.recv()?;
+            if data.is_empty() {
+                std::thread::sleep(Duration::from_millis(20));
+            } else {
+                self.bufferqueue.append(&mut data);
+            }
+        }
+        match String::from_utf8(found.unwrap().data) {
+            Ok(s) => Ok(s),
+            Err(e) => kisserr!(
+                KissErrKind::EFormat,
+                "tx committed data is not string {:?}",
+                e
+            ),
+        }
+    }
+
+    ///取出已经收到的TX_COMMITTED推送的包体(回执json)，不关心seq。
+    ///pool里和缓冲区里都没有时最多等待wait_ms，用于连续发送多笔交易后统一收回执
+    pub fn take_tx_committed(&mut self, wait_ms: u64) -> Result<Vec<String>, KissError> {
+        let start = time::now();
+        loop {
+            let mut data = self.recv()?;
+            let received = !data.is_empty();
+            if received {
+                self.bufferqueue.append(&mut data);
+            }
+            let mut packs = BcosChannelClient::pop_queue_to_packet(&mut self.bufferqueue)?;
+            packs.retain(|p| p.packtype != CHANNEL_PACK_TYPE::HEART_BEAT as u16);
+            self.channelpackpool.append(&mut packs);
+            let mut committed = vec![];
+            self.channelpackpool.retain(|p| {
+                if p.packtype != CHANNEL_PACK_TYPE::TX_COMMITTED as u16 {
+                    return true;
+                }
+                match String::from_utf8(p.data.clone()) {
+                    Ok(s) => committed.push(s),
+                    Err(e) => log::warn!("tx committed data is not string {:?}", e),
+                }
+                false
+            });
+            if !committed.is_empty()
+                || time::now() - start >= chrono::Duration::milliseconds(wait_ms as i64)
+            {
+                return Ok(committed);
+            }
+            if !received {
+                std::thread::sleep(Duration::from_millis(20));
+            }
+        }
+    }
+
+    ///等待节点推送的新块通知(TX_BLOCKNUM)，返回收到的最大块高，超时返回None。
+    ///需要先用report_topics订阅"_block_notify_{groupid}"，其他推送包留在pool里
+    pub fn wait_block_notify(&mut self, timeout_ms: u64) -> Result<Option<u64>, KissError> {
+        let start = time::now();
+        loop {
+            let mut data = self.recv()?;
+            let received = !data.is_empty();
+            if received {
+                self.bufferqueue.append(&mut data);
+            }
+            let mut packs = BcosChannelClient::pop_queue_to_packet(&mut self.bufferqueue)?;
+            packs.retain(|p| p.packtype != CHANNEL_PACK_TYPE::HEART_BEAT as u16);
+            self.channelpackpool.append(&mut packs);
+            let mut number: Option<u64> = None;
+            self.channelpackpool.retain(|p| {
+                if p.packtype != CHANNEL_PACK_TYPE::TX_BLOCKNUM as u16 {
+                    return true;
+                }
+                //{"groupID":"1","blockNumber":"10"}
+                if let Ok(v) = serde_json::from_slice::<JsonValue>(&p.data) {
+                    let n = match &v["blockNumber"] {
+                        JsonValue::String(s) => s.parse::<u64>().ok(),
+                        other => other.as_u64(),
+                    };
+                    if let Some(n) = n {
+                        number = Some(number.map_or(n, |m| m.max(n)));
+                    }
+                }
+                false
+            });
+            if number.is_some() {
+                return Ok(number);
+            }
+            if time::now() - start >= chrono::Duration::milliseconds(timeout_ms as i64) {
+                return Ok(None);
+            }
+            if !received {
+                std::thread::sleep(Duration::from_millis(20));
+            }
+        }
     }
 
     pub fn read_to_match(&mut self, outpack: &ChannelPack) -> Result<ChannelPack, KissError> {
@@ -275,7 +614,21 @@ impl BcosChannelClient {
     ) -> Result<ChannelPack, KissError> {
         let outbuffer = outpack.pack();
         printlnex!("chanel buffer length {} ", outbuffer.len());
-        self.try_send(&outbuffer)?;
+        self.keep_alive()?;
+        //先读一次，已到达的数据放进缓冲区，同时发现已经被对端关闭的连接
+        match self.recv() {
+            Ok(mut data) => self.bufferqueue.append(&mut data),
+            Err(e) => {
+                log::warn!("channel recv error {:?}, reconnect", e);
+                self.reconnect()?;
+            }
+        }
+        //发送失败说明连接已断，重连后重发一次；已发出的请求超时不重发，避免交易重复
+        if let Err(e) = self.try_send(&outbuffer) {
+            log::warn!("channel send error {:?}, reconnect", e);
+            self.reconnect()?;
+            self.try_send(&outbuffer)?;
+        }
         self.read_to_match(&outpack)
     }
 }
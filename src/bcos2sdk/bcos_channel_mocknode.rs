@@ -0,0 +1,1248 @@
+/*
+  FISCO BCOS/rust-SDK is a rust client for FISCO BCOS2.0 (https://github.com/FISCO-BCOS/)
+  FISCO BCOS/rust-SDK is free software: you can redistribute it and/or modify it under the
+  terms of the MIT License as published by the Free Software Foundation. This project is
+  distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even
+  the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+  @author: kentzhang
+  @date: 2021-07
+*/
+#![allow(
+    clippy::unreadable_literal,
+    clippy::upper_case_acronyms,
+    dead_code,
+    non_camel_case_types,
+    non_snake_case,
+    non_upper_case_globals,
+    overflowing_literals,
+    unused_variables,
+    unused_assignments
+)]
+
+/*
+进程内的FISCO BCOS 2.x 模拟节点，用于没有真实节点时的测试和CI：
+在本地端口上监听TLS(ECDSA)，按ChannelPack协议收发，支持的包类型：
+RPC(0x12)：从内存里的MockChainState应答bcossdkquery.rs里用到的json rpc，以及call/sendRawTransaction
+HEART_BEAT(0x13)：按连接协商的协议版本检查包体(V1为"0"，V2起为{"heartBeat":"0"})，正确时回"1"，格式不对不回
+HAND_SHAKE(0x14)：按客户端的maximumSupport和节点支持的版本协商，回{"protocol":x,"nodeVersion":"..."}
+CLIENT_REGISTER_EVENT_LOG(0x15)：记录filter，出块时把匹配的log用EVENT_LOG_PUSH(0x1002)推送
+TOPIC_REPORT(0x32)：记录连接订阅的topic，AMOP_REQ(0x30)转发给订阅了topic的连接，AMOP_RESP(0x31)回给发送方，
+TOPIC_MULTICAST(0x35)转发给其他所有订阅了topic的连接
+订阅了_block_notify_{groupid}的连接在出块时收到TX_BLOCKNUM(0x1001)，
+sendRawTransaction的连接在交易上链后收到TX_COMMITTED(0x1000)，seq和请求相同，包体为回执json，
+sendRawTransactionAndGetProof推送的回执里带txProof和receiptProof。区块的交易根和回执根按2.x的merkle树计算，
+区块hash按2.x的区块头编码计算，sealer_keys里的共识节点都对区块hash签名
+证书在启动时生成(自签名CA，节点证书和sdk证书，曲线secp256k1)，写到workdir下，
+write_client_config生成可以直接给Bcos2Client::new_from_config用的配置文件。
+*/
+use std::collections::HashMap;
+use std::fs;
+use std::io::{ErrorKind, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender, TryRecvError};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration;
+
+use ethereum_types::H256;
+use openssl::asn1::Asn1Time;
+use openssl::bn::{BigNum, MsbOption};
+use openssl::ec::{EcGroup, EcKey};
+use openssl::hash::MessageDigest;
+use openssl::nid::Nid;
+use openssl::pkey::{PKey, Private};
+use openssl::ssl::{SslAcceptor, SslFiletype, SslMethod, SslStream, SslVerifyMode, SslVersion};
+use openssl::x509::extension::BasicConstraints;
+use openssl::x509::{X509Name, X509};
+use serde_json::{json, Value as JsonValue};
+
+use crate::bcos2sdk::bcos_channel_client::BcosChannelClient;
+use crate::bcos2sdk::bcostransaction;
+use crate::bcos2sdk::channelpack::{
+    make_channel_pack_by_rawdata, make_heartbeat_data, pack_amop, unpack_amop, ChannelPack,
+    CHANNEL_PACK_TYPE, CHANNEL_PROTOCOL_V1,
+};
+use crate::bcossdkutil::bcosclientconfig::{BcosCryptoKind, ChannelConfig};
+use crate::bcossdkutil::blocksignature;
+use crate::bcossdkutil::bufferqueue::BufferQueue;
+use crate::bcossdkutil::commonhash::{CommonHash, HashType};
+use crate::bcossdkutil::kisserror::{KissErrKind, KissError};
+use crate::bcossdkutil::merkleproof;
+use crate::kisserr;
+
+fn tohex(v: u64) -> String {
+    format!("0x{:x}", v)
+}
+
+fn hexstr(data: &[u8]) -> String {
+    format!("0x{}", hex::encode(data))
+}
+
+///交易执行结果，sendRawTransaction时按to地址(部署合约时为"")从MockChainState::tx_results里查找
+#[derive(Debug, Clone)]
+pub struct MockTxResult {
+    pub status: u64,
+    pub output: String,
+    ///log的json，格式同回执里的logs，address为空时填入交易的to
+    pub logs: Vec<JsonValue>,
+}
+
+impl Default for MockTxResult {
+    fn default() -> Self {
+        MockTxResult {
+            status: 0,
+            output: "0x".to_string(),
+            logs: vec![],
+        }
+    }
+}
+
+///模拟节点的链上状态，测试代码可以直接修改各字段来编排场景
+#[derive(Debug, Clone)]
+pub struct MockChainState {
+    pub group_id: u64,
+    pub chain_id: u64,
+    pub node_version: String,
+    ///节点支持的channel协议版本范围，握手时和客户端的范围协商
+    pub min_protocol: i64,
+    pub max_protocol: i64,
+    pub hashtype: HashType,
+    ///下标即块高，块里的transactions只存hash
+    pub blocks: Vec<JsonValue>,
+    pub transactions: HashMap<String, JsonValue>,
+    pub receipts: HashMap<String, JsonValue>,
+    pub codes: HashMap<String, String>,
+    pub system_config: HashMap<String, String>,
+    pub sealers: Vec<String>,
+    ///共识节点的私钥，和sealers一一对应，出块时用来签名区块hash。修改hashtype后要重新生成sealers
+    pub sealer_keys: Vec<Vec<u8>>,
+    pub observers: Vec<String>,
+    pub pbft_view: u64,
+    ///call的返回，key为"to地址:4字节selector的hex"或"to地址"，地址为小写带0x
+    pub call_outputs: HashMap<String, String>,
+    pub tx_results: HashMap<String, MockTxResult>,
+    ///直接指定某个rpc方法的result，优先于内置的处理
+    pub responses: HashMap<String, JsonValue>,
+    ///各rpc方法被调用的次数
+    pub rpc_calls: HashMap<String, u64>,
+    ///出块时按merkle树生成的交易和回执证明，key为交易hash
+    pub tx_proofs: HashMap<String, JsonValue>,
+    pub receipt_proofs: HashMap<String, JsonValue>,
+}
+
+impl Default for MockChainState {
+    fn default() -> Self {
+        MockChainState::new()
+    }
+}
+
+impl MockChainState {
+    ///只有创世块的链
+    pub fn new() -> Self {
+        let mut state = MockChainState {
+            group_id: 1,
+            chain_id: 1,
+            node_version: "2.9.1".to_string(),
+            min_protocol: 1,
+            max_protocol: 3,
+            hashtype: HashType::WEDPR_KECCAK,
+            blocks: vec![],
+            transactions: HashMap::new(),
+            receipts: HashMap::new(),
+            codes: HashMap::new(),
+            system_config: HashMap::new(),
+            sealers: vec![],
+            sealer_keys: (1..=4u8).map(|i| vec![i; 32]).collect(),
+            observers: vec![],
+            pbft_view: 0,
+            call_outputs: HashMap::new(),
+            tx_results: HashMap::new(),
+            responses: HashMap::new(),
+            rpc_calls: HashMap::new(),
+            tx_proofs: HashMap::new(),
+            receipt_proofs: HashMap::new(),
+        };
+        state
+            .system_config
+            .insert("tx_count_limit".to_string(), "1000".to_string());
+        state
+            .system_config
+            .insert("tx_gas_limit".to_string(), "300000000".to_string());
+        state.sealers = state
+            .sealer_keys
+            .iter()
+            .map(|k| hex::encode(blocksignature::nodeid_from_privkey(k, &state.hashtype).unwrap()))
+            .collect();
+        state.seal_block(vec![]);
+        state
+    }
+
+    pub fn block_number(&self) -> u64 {
+        (self.blocks.len() as u64).saturating_sub(1)
+    }
+
+    fn hash_of(&self, data: &[u8]) -> String {
+        hexstr(&CommonHash::hash(&data.to_vec(), &self.hashtype))
+    }
+
+    ///把交易打包成一个新块，交易和回执要先放进transactions和receipts，返回新块高
+    pub fn seal_block(&mut self, txhashes: Vec<String>) -> u64 {
+        let number = self.blocks.len() as u64;
+        let parent_hash = match self.blocks.last() {
+            Some(b) => b["hash"].as_str().unwrap_or_default().to_string(),
+            None => hexstr(&[0u8; 32]),
+        };
+        let mut txleaves = vec![];
+        let mut receiptleaves = vec![];
+        for (i, txhash) in txhashes.iter().enumerate() {
+            let items = vec![
+                self.transactions.get_mut(txhash),
+                self.receipts.get_mut(txhash),
+            ];
+            for v in items.into_iter().flatten() {
+                v["blockNumber"] = json!(tohex(number));
+                v["transactionIndex"] = json!(tohex(i as u64));
+            }
+            txleaves.push(merkleproof::transaction_leaf_v2(i as u64, txhash).unwrap_or_default());
+            let leaf = self
+                .receipts
+                .get(txhash)
+                .and_then(|r| merkleproof::receipt_leaf_v2(i as u64, r, &self.hashtype).ok());
+            receiptleaves.push(leaf.unwrap_or_default());
+        }
+        //和节点一样用16叉merkle树计算交易根和回执根
+        let (txs_root, txproofs) = merkleproof::merkle_proofs_v2(&txleaves, &self.hashtype);
+        let (receipts_root, receiptproofs) =
+            merkleproof::merkle_proofs_v2(&receiptleaves, &self.hashtype);
+        for (i, txhash) in txhashes.iter().enumerate() {
+            self.tx_proofs.insert(txhash.clone(), json!(txproofs[i]));
+            self.receipt_proofs
+                .insert(txhash.clone(), json!(receiptproofs[i]));
+        }
+        let mut block = json!({
+            "number": tohex(number),
+            "parentHash": parent_hash,
+            "timestamp": tohex(1600000000000 + number * 1000),
+            "sealer": tohex(number % self.sealers.len().max(1) as u64),
+            "sealerList": self.sealers.clone(),
+            "transactions": txhashes,
+            "transactionsRoot": hexstr(&txs_root),
+            "receiptsRoot": hexstr(&receipts_root),
+            "stateRoot": self.hash_of(format!("state{}", number).as_bytes()),
+            "dbHash": self.hash_of(format!("db{}", number).as_bytes()),
+            "gasLimit": "0x0",
+            "gasUsed": "0x0",
+            "logsBloom": hexstr(&[0u8; 256]),
+            "extraData": [],
+        });
+        //和节点一样按区块头计算hash，所有共识节点都签名
+        let hash = blocksignature::header_hash_v2(&block, &self.hashtype).unwrap_or_default();
+        let signatures: Vec<JsonValue> = self
+            .sealer_keys
+            .iter()
+            .enumerate()
+            .filter_map(|(i, key)| {
+                blocksignature::sign_block_hash(key, &hash, &self.hashtype)
+                    .ok()
+                    .map(|sig| json!({"index": tohex(i as u64), "signature": hexstr(&sig)}))
+            })
+            .collect();
+        let hash = hexstr(&hash);
+        block["hash"] = json!(hash);
+        block["signatureList"] = json!(signatures);
+        for txhash in txhashes.iter() {
+            let items = vec![
+                self.transactions.get_mut(txhash),
+                self.receipts.get_mut(txhash),
+            ];
+            for v in items.into_iter().flatten() {
+                v["blockHash"] = json!(hash.clone());
+            }
+        }
+        self.blocks.push(block);
+        number
+    }
+
+    ///解码签名交易，生成交易和回执，并打包到新块，返回交易hash
+    pub fn apply_raw_transaction(&mut self, rawtx: &[u8]) -> Result<String, KissError> {
+        //和节点一样，签名不对的交易直接拒绝
+        let decoded = bcostransaction::decode_signed_transaction(rawtx, &self.hashtype, None)?;
+        let tx = decoded.transaction.transaction;
+        //和节点一样，只接受blocklimit在(当前块高,当前块高+1000]范围内的交易
+        let number = self.block_number();
+        if tx.block_limit <= number.into() || tx.block_limit > (number + 1000).into() {
+            return kisserr!(
+                KissErrKind::EArgument,
+                "block limit check fail, blockLimit {} , current block {}",
+                tx.block_limit,
+                number
+            );
+        }
+        let txhash = self.hash_of(rawtx);
+        if self.transactions.contains_key(&txhash) {
+            return kisserr!(
+                KissErrKind::EArgument,
+                "transaction {} already known",
+                txhash
+            );
+        }
+        let to = if tx.to_address.is_empty() {
+            "".to_string()
+        } else {
+            hexstr(&tx.to_address)
+        };
+        let mut result = self.tx_results.get(&to).cloned().unwrap_or_default();
+        let contract_address = if to.is_empty() {
+            let h = CommonHash::hash(&hex::decode(&txhash[2..]).unwrap(), &self.hashtype);
+            let addr = hexstr(&h[12..]);
+            self.codes.insert(addr.clone(), hexstr(&tx.data));
+            addr
+        } else {
+            "0x0000000000000000000000000000000000000000".to_string()
+        };
+        let logaddress = if to.is_empty() {
+            contract_address.clone()
+        } else {
+            to.clone()
+        };
+        for log in result.logs.iter_mut() {
+            if log["address"].as_str().unwrap_or_default().is_empty() {
+                log["address"] = json!(logaddress.clone());
+            }
+        }
+        let from = decoded.sender;
+        let txjson = json!({
+            "hash": txhash,
+            "from": from,
+            "to": if to.is_empty() { JsonValue::Null } else { json!(to) },
+            "input": hexstr(&tx.data),
+            "nonce": format!("0x{:x}", tx.random_id),
+            "blockLimit": format!("0x{:x}", tx.block_limit),
+            "gas": format!("0x{:x}", tx.gas_limit),
+            "gasPrice": format!("0x{:x}", tx.gas_price),
+            "value": format!("0x{:x}", tx.value),
+            "chainId": format!("0x{:x}", tx.fisco_chain_id),
+            "groupId": format!("0x{:x}", tx.group_id),
+            "extraData": hexstr(&tx.extra_data),
+        });
+        let receipt = json!({
+            "transactionHash": txhash,
+            "from": from,
+            "to": if to.is_empty() { JsonValue::Null } else { json!(to) },
+            "contractAddress": contract_address,
+            "input": hexstr(&tx.data),
+            "output": result.output,
+            "status": tohex(result.status),
+            "gasUsed": "0x5208",
+            "logs": result.logs,
+            "logsBloom": hexstr(&[0u8; 256]),
+            "root": self.hash_of(format!("root{}", txhash).as_bytes()),
+        });
+        self.transactions.insert(txhash.clone(), txjson);
+        self.receipts.insert(txhash.clone(), receipt);
+        self.seal_block(vec![txhash.clone()]);
+        Ok(txhash)
+    }
+
+    fn param_u64(params: &JsonValue, index: usize) -> Option<u64> {
+        match &params[index] {
+            JsonValue::Number(n) => n.as_u64(),
+            JsonValue::String(s) => {
+                let s = s.trim();
+                match s.strip_prefix("0x") {
+                    Some(h) => u64::from_str_radix(h, 16).ok(),
+                    None => s.parse().ok(),
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn block_json(&self, block: &JsonValue, include_txs: bool, header: bool) -> JsonValue {
+        let mut v = block.clone();
+        if header {
+            if let Some(obj) = v.as_object_mut() {
+                obj.remove("transactions");
+            }
+        } else if include_txs {
+            let txs: Vec<JsonValue> = block["transactions"]
+                .as_array()
+                .cloned()
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|h| {
+                    self.transactions
+                        .get(h.as_str().unwrap_or_default())
+                        .cloned()
+                })
+                .collect();
+            v["transactions"] = json!(txs);
+        }
+        v
+    }
+
+    fn find_block_by_hash(&self, hash: &str) -> Option<&JsonValue> {
+        self.blocks
+            .iter()
+            .find(|b| b["hash"].as_str() == Some(hash))
+    }
+
+    ///处理一个json rpc请求，返回result，或者(错误码，错误信息)
+    pub fn handle_rpc(
+        &mut self,
+        method: &str,
+        params: &JsonValue,
+    ) -> Result<JsonValue, (i64, String)> {
+        *self.rpc_calls.entry(method.to_string()).or_insert(0) += 1;
+        if let Some(v) = self.responses.get(method) {
+            return Ok(v.clone());
+        }
+        let notfound = |what: &str| (-32602i64, format!("{} not found", what));
+        let result = match method {
+            "getBlockNumber" => json!(tohex(self.block_number())),
+            "getClientVersion" => json!({
+                "Build Time": "20220101 00:00:00",
+                "Build Type": "Linux/clang/Release",
+                "Chain Id": self.chain_id.to_string(),
+                "FISCO-BCOS Version": self.node_version,
+                "Git Branch": "mock",
+                "Git Commit Hash": "mock",
+                "Supported Version": self.node_version,
+            }),
+            "getNodeInfo" => json!({
+                "Agency": "agency",
+                "IPAndPort": "127.0.0.1:30300",
+                "Node": "node0",
+                "NodeID": self.sealers.first().cloned().unwrap_or_default(),
+                "Topic": [],
+            }),
+            "getPbftView" => json!(tohex(self.pbft_view)),
+            "getSealerList" | "getNodeIDList" | "getGroupPeers" => json!(self.sealers),
+            "getObserverList" => json!(self.observers),
+            "getConsensusStatus" => {
+                json!([{"accountType": 1, "blockNumber": self.block_number(), "nodeNum": self.sealers.len(), "view": self.pbft_view}])
+            }
+            "getSyncStatus" => json!({
+                "blockNumber": self.block_number(),
+                "isSyncing": false,
+                "latestHash": self.blocks.last().map(|b| b["hash"].clone()).unwrap_or_default(),
+                "nodeId": self.sealers.first().cloned().unwrap_or_default(),
+                "peers": [],
+                "protocolId": 65544,
+            }),
+            "getPeers" => json!([]),
+            "getGroupList" => json!([self.group_id]),
+            "getBlockByNumber" | "getBlockHeaderByNumber" | "getBlockHashByNumber" => {
+                let num = MockChainState::param_u64(params, 1).unwrap_or(u64::MAX);
+                let block = match self.blocks.get(num as usize) {
+                    Some(b) => b,
+                    None => return Err(notfound("block")),
+                };
+                match method {
+                    "getBlockHashByNumber" => block["hash"].clone(),
+                    "getBlockHeaderByNumber" => {
+                        //第三个参数为includeSigList
+                        let mut header = self.block_json(block, false, true);
+                        if let (false, Some(obj)) =
+                            (params[2].as_bool().unwrap_or(false), header.as_object_mut())
+                        {
+                            obj.remove("signatureList");
+                        }
+                        header
+                    }
+                    _ => self.block_json(block, params[2].as_bool().unwrap_or(false), false),
+                }
+            }
+            "getBlockByHash" | "getBlockHeaderByHash" => {
+                let block = match self.find_block_by_hash(params[1].as_str().unwrap_or_default()) {
+                    Some(b) => b,
+                    None => return Err(notfound("block")),
+                };
+                match method {
+                    "getBlockHeaderByHash" => self.block_json(block, false, true),
+                    _ => self.block_json(block, params[2].as_bool().unwrap_or(false), false),
+                }
+            }
+            //和真实节点一样，交易或回执不存在时result为null
+            "getTransactionByHash" => self
+                .transactions
+                .get(params[1].as_str().unwrap_or_default())
+                .cloned()
+                .unwrap_or(JsonValue::Null),
+            "getTransactionReceipt" => self
+                .receipts
+                .get(params[1].as_str().unwrap_or_default())
+                .cloned()
+                .unwrap_or(JsonValue::Null),
+            "getTransactionByHashWithProof" => {
+                let txhash = params[1].as_str().unwrap_or_default();
+                match self.transactions.get(txhash) {
+                    Some(tx) => json!({"transaction": tx, "txProof": self.tx_proofs[txhash]}),
+                    None => JsonValue::Null,
+                }
+            }
+            "getTransactionReceiptByHashWithProof" => {
+                let txhash = params[1].as_str().unwrap_or_default();
+                match self.receipts.get(txhash) {
+                    Some(r) => json!({
+                        "transactionReceipt": r,
+                        "receiptProof": self.receipt_proofs[txhash],
+                    }),
+                    None => JsonValue::Null,
+                }
+            }
+            "getTransactionByBlockHashAndIndex" | "getTransactionByBlockNumberAndIndex" => {
+                let block = if method == "getTransactionByBlockHashAndIndex" {
+                    self.find_block_by_hash(params[1].as_str().unwrap_or_default())
+                } else {
+                    MockChainState::param_u64(params, 1).and_then(|n| self.blocks.get(n as usize))
+                };
+                let index = MockChainState::param_u64(params, 2).unwrap_or(0) as usize;
+                match block.and_then(|b| b["transactions"].get(index)) {
+                    Some(h) => self
+                        .transactions
+                        .get(h.as_str().unwrap_or_default())
+                        .cloned()
+                        .unwrap_or(JsonValue::Null),
+                    None => return Err(notfound("transaction")),
+                }
+            }
+            "getPendingTransactions" => json!([]),
+            "getPendingTxSize" => json!("0x0"),
+            "getTotalTransactionCount" => json!({
+                "blockNumber": tohex(self.block_number()),
+                "failedTxSum": "0x0",
+                "txSum": tohex(self.transactions.len() as u64),
+            }),
+            "getCode" => json!(self
+                .codes
+                .get(&params[1].as_str().unwrap_or_default().to_lowercase())
+                .cloned()
+                .unwrap_or_else(|| "0x".to_string())),
+            "getSystemConfigByKey" => match self
+                .system_config
+                .get(params[1].as_str().unwrap_or_default())
+            {
+                Some(v) => json!(v),
+                None => return Err(notfound("system config")),
+            },
+            "call" => {
+                let to = params[1]["to"].as_str().unwrap_or_default().to_lowercase();
+                let to = if to.starts_with("0x") {
+                    to
+                } else {
+                    format!("0x{}", to)
+                };
+                let data = params[1]["data"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .trim_start_matches("0x");
+                let selector = if data.len() >= 8 { &data[..8] } else { "" };
+                let output = self
+                    .call_outputs
+                    .get(&format!("{}:{}", to, selector))
+                    .or_else(|| self.call_outputs.get(&to))
+                    .cloned();
+                match output {
+                    Some(o) => {
+                        json!({"currentBlockNumber": tohex(self.block_number()), "output": o, "status": "0x0"})
+                    }
+                    None => {
+                        json!({"currentBlockNumber": tohex(self.block_number()), "output": "0x", "status": "0x16"})
+                    }
+                }
+            }
+            "sendRawTransaction" | "sendRawTransactionAndGetProof" => {
+                let data = match hex::decode(
+                    params[1]
+                        .as_str()
+                        .unwrap_or_default()
+                        .trim_start_matches("0x"),
+                ) {
+                    Ok(d) => d,
+                    Err(e) => return Err((-32602, format!("invalid transaction hex {:?}", e))),
+                };
+                match self.apply_raw_transaction(&data) {
+                    Ok(h) => json!(h),
+                    Err(e) => return Err((-32602, format!("{:?}", e))),
+                }
+            }
+            _ => {
+                return Err((
+                    -32601,
+                    format!("method {} not supported by mock node", method),
+                ))
+            }
+        };
+        Ok(result)
+    }
+}
+
+//一个已连接的客户端，数据通过sender交给连接线程写出
+struct MockConnection {
+    id: usize,
+    sender: Sender<Vec<u8>>,
+    topics: Vec<String>,
+    filters: Vec<JsonValue>,
+    //HAND_SHAKE协商的协议版本，没有握手时按V1
+    protocol: i64,
+}
+
+struct MockShared {
+    state: Mutex<MockChainState>,
+    conns: Mutex<Vec<MockConnection>>,
+    //AMOP请求的seq -> 发送方连接id，用于把AMOP_RESP送回
+    pending_amop: Mutex<HashMap<H256, usize>>,
+    stop: AtomicBool,
+    //模拟节点宕机：不接受新连接
+    offline: AtomicBool,
+    //模拟节点无响应(半开连接)：收到的包都丢弃
+    silent: AtomicBool,
+    //交易上链后是否推送TX_COMMITTED
+    tx_push: AtomicBool,
+    next_id: AtomicUsize,
+}
+
+impl MockShared {
+    fn send_to(&self, id: usize, pack: &ChannelPack) {
+        if let Some(c) = self.conns.lock().unwrap().iter().find(|c| c.id == id) {
+            let _ = c.sender.send(pack.pack());
+        }
+    }
+
+    ///出块后推送块高和event log
+    fn notify_block(&self, number: u64, logs: &[JsonValue]) {
+        let group_id = self.state.lock().unwrap().group_id;
+        let topic = format!("_block_notify_{}", group_id);
+        let blockdata = json!({"groupID": group_id.to_string(), "blockNumber": number.to_string()});
+        for c in self.conns.lock().unwrap().iter() {
+            if c.topics.contains(&topic) {
+                let pack = make_channel_pack_by_rawdata(
+                    CHANNEL_PACK_TYPE::TX_BLOCKNUM,
+                    &blockdata.to_string().into_bytes(),
+                )
+                .unwrap();
+                let _ = c.sender.send(pack.pack());
+            }
+            for filter in c.filters.iter() {
+                let matched: Vec<JsonValue> = logs
+                    .iter()
+                    .filter(|l| log_match(filter, l))
+                    .cloned()
+                    .collect();
+                if matched.is_empty() {
+                    continue;
+                }
+                let data = json!({"filterID": filter["filterID"], "result": 0, "logs": matched});
+                let pack = make_channel_pack_by_rawdata(
+                    CHANNEL_PACK_TYPE::EVENT_LOG_PUSH,
+                    &data.to_string().into_bytes(),
+                )
+                .unwrap();
+                let _ = c.sender.send(pack.pack());
+            }
+        }
+    }
+}
+
+///filter里的addresses为空时不限地址，topics按位置匹配
+fn log_match(filter: &JsonValue, log: &JsonValue) -> bool {
+    let address = log["address"].as_str().unwrap_or_default().to_lowercase();
+    if let Some(addrs) = filter["addresses"].as_array() {
+        if !addrs.is_empty()
+            && !addrs
+                .iter()
+                .any(|a| a.as_str().unwrap_or_default().to_lowercase() == address)
+        {
+            return false;
+        }
+    }
+    let empty = vec![];
+    let logtopics = log["topics"].as_array().unwrap_or(&empty);
+    if let Some(topics) = filter["topics"].as_array() {
+        for (i, t) in topics.iter().enumerate() {
+            let t = t.as_str().unwrap_or_default();
+            if t.is_empty() {
+                continue;
+            }
+            match logtopics.get(i).and_then(|x| x.as_str()) {
+                Some(lt) if lt.eq_ignore_ascii_case(t) => {}
+                _ => return false,
+            }
+        }
+    }
+    true
+}
+
+fn response_pack(request: &ChannelPack, data: &[u8]) -> ChannelPack {
+    ChannelPack {
+        length: 42 + data.len(),
+        packtype: request.packtype,
+        seq: request.seq,
+        result: 0,
+        data: data.to_vec(),
+    }
+}
+
+//处理一个客户端发来的包
+fn handle_pack(shared: &Arc<MockShared>, connid: usize, pack: ChannelPack) {
+    if shared.silent.load(Ordering::SeqCst) {
+        return;
+    }
+    let packtype = pack.packtype;
+    if packtype == CHANNEL_PACK_TYPE::RPC as u16 {
+        let req: JsonValue = serde_json::from_slice(&pack.data).unwrap_or_default();
+        let method = req["method"].as_str().unwrap_or_default().to_string();
+        let (res, sealed, committed) = {
+            let mut state = shared.state.lock().unwrap();
+            let before = state.block_number();
+            let res = state.handle_rpc(method.as_str(), &req["params"]);
+            let after = state.block_number();
+            let mut sealed = vec![];
+            for n in before + 1..=after {
+                let mut logs = vec![];
+                if let Some(hashes) = state.blocks[n as usize]["transactions"].as_array() {
+                    for h in hashes {
+                        if let Some(r) = state.receipts.get(h.as_str().unwrap_or_default()) {
+                            logs.extend(r["logs"].as_array().cloned().unwrap_or_default());
+                        }
+                    }
+                }
+                sealed.push((n, logs));
+            }
+            //交易上链后用请求的seq推送回执
+            let committed = match (&res, method.as_str()) {
+                (Ok(txhash), "sendRawTransaction") => {
+                    txhash.as_str().and_then(|h| state.receipts.get(h)).cloned()
+                }
+                //回执里带上交易和回执的证明
+                (Ok(txhash), "sendRawTransactionAndGetProof") => {
+                    let h = txhash.as_str().unwrap_or_default();
+                    state.receipts.get(h).map(|r| {
+                        let mut r = r.clone();
+                        r["txProof"] = state.tx_proofs[h].clone();
+                        r["receiptProof"] = state.receipt_proofs[h].clone();
+                        r
+                    })
+                }
+                _ => None,
+            };
+            (res, sealed, committed)
+        };
+        let response = match res {
+            Ok(result) => json!({"id": req["id"], "jsonrpc": "2.0", "result": result}),
+            Err((code, message)) => {
+                json!({"id": req["id"], "jsonrpc": "2.0", "error": {"code": code, "message": message}})
+            }
+        };
+        shared.send_to(
+            connid,
+            &response_pack(&pack, response.to_string().as_bytes()),
+        );
+        if let Some(receipt) = committed {
+            if shared.tx_push.load(Ordering::SeqCst) {
+                let mut push = response_pack(&pack, receipt.to_string().as_bytes());
+                push.packtype = CHANNEL_PACK_TYPE::TX_COMMITTED as u16;
+                shared.send_to(connid, &push);
+            }
+        }
+        for (n, logs) in sealed {
+            shared.notify_block(n, &logs);
+        }
+    } else if packtype == CHANNEL_PACK_TYPE::HEART_BEAT as u16 {
+        let protocol = shared
+            .conns
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|c| c.id == connid)
+            .map(|c| c.protocol)
+            .unwrap_or(CHANNEL_PROTOCOL_V1);
+        if pack.data == make_heartbeat_data(protocol, "0").into_bytes() {
+            let data = make_heartbeat_data(protocol, "1");
+            shared.send_to(connid, &response_pack(&pack, data.as_bytes()));
+        } else {
+            log::warn!(
+                "bad heartbeat for protocol {}: {}",
+                protocol,
+                String::from_utf8_lossy(&pack.data)
+            );
+        }
+    } else if packtype == CHANNEL_PACK_TYPE::HAND_SHAKE as u16 {
+        let req: JsonValue = serde_json::from_slice(&pack.data).unwrap_or_default();
+        let state = shared.state.lock().unwrap();
+        let client_max = req["maximumSupport"].as_i64().unwrap_or(1);
+        let client_min = req["minimumSupport"].as_i64().unwrap_or(1);
+        let protocol = client_max.min(state.max_protocol);
+        //没有共同支持的版本时回-1
+        let protocol = if protocol < client_min || protocol < state.min_protocol {
+            -1
+        } else {
+            protocol
+        };
+        let data = json!({"protocol": protocol, "nodeVersion": state.node_version});
+        drop(state);
+        if protocol > 0 {
+            if let Some(c) = shared
+                .conns
+                .lock()
+                .unwrap()
+                .iter_mut()
+                .find(|c| c.id == connid)
+            {
+                c.protocol = protocol;
+            }
+        }
+        shared.send_to(connid, &response_pack(&pack, data.to_string().as_bytes()));
+    } else if packtype == CHANNEL_PACK_TYPE::CLIENT_REGISTER_EVENT_LOG as u16 {
+        let (_, data) = unpack_amop(&pack.data);
+        let filter: JsonValue = serde_json::from_slice(&data).unwrap_or_default();
+        let filterid = filter["filterID"].clone();
+        if let Some(c) = shared
+            .conns
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .find(|c| c.id == connid)
+        {
+            c.filters.push(filter);
+        }
+        let resp = json!({"filterID": filterid, "result": 0}).to_string();
+        let data = pack_amop(&vec![], &resp.into_bytes());
+        shared.send_to(connid, &response_pack(&pack, &data));
+    } else if packtype == CHANNEL_PACK_TYPE::TOPIC_REPORT as u16 {
+        let topics: Vec<String> = serde_json::from_slice(&pack.data).unwrap_or_default();
+        if let Some(c) = shared
+            .conns
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .find(|c| c.id == connid)
+        {
+            c.topics = topics;
+        }
+    } else if packtype == CHANNEL_PACK_TYPE::AMOP_REQ as u16 {
+        let (topic, _) = unpack_amop(&pack.data);
+        let topic = String::from_utf8_lossy(&topic).to_string();
+        let target = {
+            let conns = shared.conns.lock().unwrap();
+            //优先发给其他连接，和节点把消息路由给订阅方的行为一致
+            let mut subs: Vec<usize> = conns
+                .iter()
+                .filter(|c| c.topics.contains(&topic))
+                .map(|c| c.id)
+                .collect();
+            subs.sort_by_key(|id| *id == connid);
+            subs.first().cloned()
+        };
+        match target {
+            Some(id) => {
+                shared.pending_amop.lock().unwrap().insert(pack.seq, connid);
+                shared.send_to(id, &pack);
+            }
+            None => {
+                let mut resp = response_pack(&pack, &pack.data);
+                resp.packtype = CHANNEL_PACK_TYPE::AMOP_RESP as u16;
+                resp.result = 100;
+                shared.send_to(connid, &resp);
+            }
+        }
+    } else if packtype == CHANNEL_PACK_TYPE::TOPIC_MULTICAST as u16 {
+        let (topic, _) = unpack_amop(&pack.data);
+        let topic = String::from_utf8_lossy(&topic).to_string();
+        let subs: Vec<usize> = shared
+            .conns
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|c| c.id != connid && c.topics.contains(&topic))
+            .map(|c| c.id)
+            .collect();
+        for id in subs {
+            shared.send_to(id, &pack);
+        }
+    } else if packtype == CHANNEL_PACK_TYPE::AMOP_RESP as u16 {
+        let origin = shared.pending_amop.lock().unwrap().remove(&pack.seq);
+        if let Some(id) = origin {
+            shared.send_to(id, &pack);
+        }
+    }
+}
+
+fn serve_connection(
+    shared: Arc<MockShared>,
+    connid: usize,
+    mut stream: SslStream<TcpStream>,
+    receiver: Receiver<Vec<u8>>,
+) {
+    let mut queue = BufferQueue::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    while !shared.stop.load(Ordering::SeqCst) {
+        loop {
+            match receiver.try_recv() {
+                Ok(data) => {
+                    if stream.write_all(&data).is_err() {
+                        shared.conns.lock().unwrap().retain(|c| c.id != connid);
+                        return;
+                    }
+                }
+                Err(TryRecvError::Empty) => break,
+                //连接已经从conns里移除(drop_connections)
+                Err(TryRecvError::Disconnected) => {
+                    let _ = stream.shutdown();
+                    return;
+                }
+            }
+        }
+        match stream.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(n) => {
+                queue.append(&mut buffer[..n].to_vec());
+                if let Ok(packs) = BcosChannelClient::pop_queue_to_packet(&mut queue) {
+                    for pack in packs {
+                        handle_pack(&shared, connid, pack);
+                    }
+                }
+            }
+            Err(e) if e.kind() == ErrorKind::WouldBlock || e.kind() == ErrorKind::TimedOut => {}
+            Err(_) => break,
+        }
+    }
+    shared.conns.lock().unwrap().retain(|c| c.id != connid);
+    let _ = stream.shutdown();
+}
+
+fn new_ec_key() -> Result<PKey<Private>, openssl::error::ErrorStack> {
+    let group = EcGroup::from_curve_name(Nid::SECP256K1)?;
+    PKey::from_ec_key(EcKey::generate(&group)?)
+}
+
+fn new_cert(
+    cn: &str,
+    key: &PKey<Private>,
+    issuer: Option<(&X509, &PKey<Private>)>,
+) -> Result<X509, openssl::error::ErrorStack> {
+    let mut name = X509Name::builder()?;
+    name.append_entry_by_text("CN", cn)?;
+    let name = name.build();
+    let mut builder = X509::builder()?;
+    builder.set_version(2)?;
+    let mut serial = BigNum::new()?;
+    serial.rand(64, MsbOption::MAYBE_ZERO, false)?;
+    let serial = serial.to_asn1_integer()?;
+    builder.set_serial_number(&serial)?;
+    builder.set_subject_name(&name)?;
+    builder.set_pubkey(key)?;
+    let not_before = Asn1Time::days_from_now(0)?;
+    let not_after = Asn1Time::days_from_now(3650)?;
+    builder.set_not_before(&not_before)?;
+    builder.set_not_after(&not_after)?;
+    match issuer {
+        Some((cacert, cakey)) => {
+            builder.set_issuer_name(cacert.subject_name())?;
+            builder.sign(cakey, MessageDigest::sha256())?;
+        }
+        None => {
+            builder.set_issuer_name(&name)?;
+            builder.append_extension(BasicConstraints::new().critical().ca().build()?)?;
+            builder.sign(key, MessageDigest::sha256())?;
+        }
+    }
+    Ok(builder.build())
+}
+
+///在dir下生成ca.crt, node.crt/node.key, sdk.crt/sdk.key
+pub fn generate_mock_certs(dir: &Path) -> Result<(), KissError> {
+    let write = |name: &str, data: Vec<u8>| -> Result<(), KissError> {
+        match fs::write(dir.join(name), data) {
+            Ok(_) => Ok(()),
+            Err(e) => kisserr!(KissErrKind::EFileWrite, "write {} error {:?}", name, e),
+        }
+    };
+    let build = || -> Result<Vec<(&'static str, Vec<u8>)>, openssl::error::ErrorStack> {
+        let cakey = new_ec_key()?;
+        let cacert = new_cert("mock-ca", &cakey, None)?;
+        let mut files = vec![("ca.crt", cacert.to_pem()?)];
+        for name in ["node", "sdk"].iter() {
+            let key = new_ec_key()?;
+            let cert = new_cert(name, &key, Some((&cacert, &cakey)))?;
+            files.push((
+                if *name == "node" {
+                    "node.crt"
+                } else {
+                    "sdk.crt"
+                },
+                cert.to_pem()?,
+            ));
+            files.push((
+                if *name == "node" {
+                    "node.key"
+                } else {
+                    "sdk.key"
+                },
+                key.private_key_to_pem_pkcs8()?,
+            ));
+        }
+        Ok(files)
+    };
+    if let Err(e) = fs::create_dir_all(dir) {
+        return kisserr!(
+            KissErrKind::EFileWrite,
+            "create dir {:?} error {:?}",
+            dir,
+            e
+        );
+    }
+    let files = match build() {
+        Ok(f) => f,
+        Err(e) => return kisserr!(KissErrKind::Error, "generate mock certs error {:?}", e),
+    };
+    for (name, data) in files {
+        write(name, data)?;
+    }
+    Ok(())
+}
+
+pub struct MockChannelNode {
+    pub port: u16,
+    pub workdir: PathBuf,
+    shared: Arc<MockShared>,
+}
+
+impl MockChannelNode {
+    ///在127.0.0.1的随机端口上启动，证书和配置文件写在workdir下。
+    ///workdir里已有证书时直接使用，所以多个节点用同一个workdir就共用一套证书
+    pub fn start(state: MockChainState, workdir: &str) -> Result<MockChannelNode, KissError> {
+        let workdir = PathBuf::from(workdir);
+        if !workdir.join("ca.crt").exists() {
+            generate_mock_certs(&workdir)?;
+        }
+        let acceptor = match MockChannelNode::make_acceptor(&workdir) {
+            Ok(a) => Arc::new(a),
+            Err(e) => {
+                return kisserr!(
+                    KissErrKind::ENetwork,
+                    "mock node ssl acceptor error {:?}",
+                    e
+                )
+            }
+        };
+        let listener = match TcpListener::bind("127.0.0.1:0") {
+            Ok(l) => l,
+            Err(e) => return kisserr!(KissErrKind::ENetwork, "mock node bind error {:?}", e),
+        };
+        let port = listener.local_addr().unwrap().port();
+        let _ = listener.set_nonblocking(true);
+        let shared = Arc::new(MockShared {
+            state: Mutex::new(state),
+            conns: Mutex::new(vec![]),
+            pending_amop: Mutex::new(HashMap::new()),
+            stop: AtomicBool::new(false),
+            offline: AtomicBool::new(false),
+            silent: AtomicBool::new(false),
+            tx_push: AtomicBool::new(true),
+            next_id: AtomicUsize::new(1),
+        });
+        let accept_shared = shared.clone();
+        thread::spawn(move || {
+            while !accept_shared.stop.load(Ordering::SeqCst) {
+                let tcp = match listener.accept() {
+                    Ok((s, _)) => s,
+                    Err(_) => {
+                        thread::sleep(Duration::from_millis(20));
+                        continue;
+                    }
+                };
+                if accept_shared.offline.load(Ordering::SeqCst) {
+                    continue;
+                }
+                let _ = tcp.set_nonblocking(false);
+                let acceptor = acceptor.clone();
+                let shared = accept_shared.clone();
+                thread::spawn(move || {
+                    let stream = match acceptor.accept(tcp) {
+                        Ok(s) => s,
+                        Err(e) => {
+                            log::warn!("mock node tls accept error {:?}", e);
+                            return;
+                        }
+                    };
+                    let _ = stream
+                        .get_ref()
+                        .set_read_timeout(Some(Duration::from_millis(20)));
+                    let (sender, receiver) = channel();
+                    let connid = shared.next_id.fetch_add(1, Ordering::SeqCst);
+                    shared.conns.lock().unwrap().push(MockConnection {
+                        id: connid,
+                        sender,
+                        topics: vec![],
+                        filters: vec![],
+                        protocol: CHANNEL_PROTOCOL_V1,
+                    });
+                    serve_connection(shared, connid, stream, receiver);
+                });
+            }
+        });
+        Ok(MockChannelNode {
+            port,
+            workdir,
+            shared,
+        })
+    }
+
+    fn make_acceptor(dir: &Path) -> Result<SslAcceptor, openssl::error::ErrorStack> {
+        let mut builder = SslAcceptor::mozilla_intermediate(SslMethod::tls())?;
+        //和fisco bcos 2.x节点一样使用tls1.2和secp256k1
+        builder.set_max_proto_version(Some(SslVersion::TLS1_2))?;
+        let ecdh = EcKey::from_curve_name(Nid::SECP256K1)?;
+        builder.set_tmp_ecdh(&ecdh)?;
+        builder.set_ca_file(dir.join("ca.crt"))?;
+        builder.set_certificate_chain_file(dir.join("node.crt"))?;
+        builder.set_private_key_file(dir.join("node.key"), SslFiletype::PEM)?;
+        builder.check_private_key()?;
+        builder.set_verify(SslVerifyMode::PEER | SslVerifyMode::FAIL_IF_NO_PEER_CERT);
+        Ok(builder.build())
+    }
+
+    ///修改链上状态，如编排call的返回、预置交易等
+    pub fn state(&self) -> MutexGuard<'_, MockChainState> {
+        self.shared.state.lock().unwrap()
+    }
+
+    ///当前连接数
+    pub fn connection_count(&self) -> usize {
+        self.shared.conns.lock().unwrap().len()
+    }
+
+    ///断开所有连接(节点重启之类的场景)，监听继续
+    pub fn drop_connections(&self) {
+        self.shared.conns.lock().unwrap().clear();
+    }
+
+    ///模拟节点宕机和恢复，宕机时断开所有连接并拒绝新连接
+    pub fn set_offline(&self, offline: bool) {
+        self.shared.offline.store(offline, Ordering::SeqCst);
+        if offline {
+            self.drop_connections();
+        }
+    }
+
+    ///连接保持，但不再应答任何包，用于测试心跳超时
+    pub fn set_silent(&self, silent: bool) {
+        self.shared.silent.store(silent, Ordering::SeqCst);
+    }
+
+    ///关闭后交易上链不再推送TX_COMMITTED，模拟推送丢失
+    pub fn set_tx_push(&self, enable: bool) {
+        self.shared.tx_push.store(enable, Ordering::SeqCst);
+    }
+
+    ///手工出一个空块并推送块高
+    pub fn seal_empty_block(&self) -> u64 {
+        let number = self.state().seal_block(vec![]);
+        self.shared.notify_block(number, &[]);
+        number
+    }
+
+    ///向所有连接推送一个包，如TX_COMMITTED
+    pub fn push_to_all(&self, packtype: CHANNEL_PACK_TYPE, data: &[u8]) {
+        let pack = make_channel_pack_by_rawdata(packtype, &data.to_vec()).unwrap();
+        for c in self.shared.conns.lock().unwrap().iter() {
+            let _ = c.sender.send(pack.pack());
+        }
+    }
+
+    pub fn channel_config(&self) -> ChannelConfig {
+        let mut config = ChannelConfig::default();
+        config.ip = "127.0.0.1".to_string();
+        config.port = self.port as u32;
+        config.timeout = 5;
+        config.tlskind = BcosCryptoKind::ECDSA;
+        config.cacert = self.workdir.join("ca.crt").to_str().unwrap().to_string();
+        config.sdkcert = self.workdir.join("sdk.crt").to_str().unwrap().to_string();
+        config.sdkkey = self.workdir.join("sdk.key").to_str().unwrap().to_string();
+        config
+    }
+
+    ///生成连接本节点的客户端配置文件workdir/config.toml，返回文件名
+    pub fn write_client_config(
+        &self,
+        accountpem: &str,
+        contractpath: &str,
+    ) -> Result<String, KissError> {
+        let channel = self.channel_config();
+        let (chain_id, group_id) = {
+            let state = self.state();
+            (state.chain_id, state.group_id)
+        };
+        let text = format!(
+            r#"[common]
+crypto = "ECDSA"
+accountpem = "{accountpem}"
+contractpath = "{contractpath}"
+solc = ""
+solcgm = ""
+
+[bcos3]
+sdk_config_file = ""
+group = "group0"
+
+[bcos2]
+chainid = {chain_id}
+groupid = {group_id}
+protocol = "CHANNEL"
+
+[rpc]
+url = ""
+timeout = 3
+
+[channel]
+ip = "{ip}"
+port = {port}
+tlskind = "ECDSA"
+timeout = {timeout}
+nativelib_echo_mode = 0
+cacert = "{cacert}"
+sdkcert = "{sdkcert}"
+sdkkey = "{sdkkey}"
+gmcacert = ""
+gmsdkcert = ""
+gmsdkkey = ""
+gmensdkcert = ""
+gmensdkkey = ""
+"#,
+            accountpem = accountpem,
+            contractpath = contractpath,
+            chain_id = chain_id,
+            group_id = group_id,
+            ip = channel.ip,
+            port = channel.port,
+            timeout = channel.timeout,
+            cacert = channel.cacert,
+            sdkcert = channel.sdkcert,
+            sdkkey = channel.sdkkey
+        );
+        let configfile = self.workdir.join("config.toml");
+        if let Err(e) = fs::write(&configfile, text) {
+            return kisserr!(
+                KissErrKind::EFileWrite,
+                "write {:?} error {:?}",
+                configfile,
+                e
+            );
+        }
+        Ok(configfile.to_str().unwrap().to_string())
+    }
+
+    pub fn stop(&self) {
+        self.shared.stop.store(true, Ordering::SeqCst);
+    }
+}
+
+impl Drop for MockChannelNode {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
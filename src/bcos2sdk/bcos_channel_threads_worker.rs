@@ -125,31 +125,41 @@ pub fn getNodeVersionPack() -> Option<ChannelPack> {
     make_channel_pack(CHANNEL_PACK_TYPE::RPC, req.encode().unwrap().as_str())
 }
 
-///每3秒钟发一次heartbeat
+///定时检查连接：按配置的间隔发心跳，连接断开时重连并重新订阅，并探测down了的节点是否已恢复
 async fn heart_beat_thread(worker_arc: BcosChannelWorkerArc) {
-    let mut last_heartbeat_time = time::now() - chrono::Duration::seconds(10);
-
     loop {
         tokio::time::sleep(Duration::from_millis(100)).await;
         if !is_working(&worker_arc) {
             break;
         }
-
-        if time::now() - last_heartbeat_time < chrono::Duration::seconds(5) {
+        //锁内只发心跳、决定要不要重连和探测，建连接在锁外做，不挡住其他请求和AMOP
+        let task = worker_arc
+            .lock()
+            .unwrap()
+            .bcossdk
+            .netclient
+            .keep_alive_task();
+        if task.is_empty() {
             continue;
         }
-        //let heartbeatpack = make_channel_pack(CHANNEL_PACK_TYPE::HEART_BEAT, "");
-        let heartbeatpack = getNodeVersionPack();
-        last_heartbeat_time = time::now();
-        println!(
-            "heartbeat send {:?}",
-            heartbeatpack.as_ref().unwrap().detail()
-        );
-        let res = send_packet(&worker_arc, &heartbeatpack.unwrap());
-
-        println!("heartbeat send result {:?}", res);
-        //let recvres = worker.bcossdkutil.netclient.channel_client.try_recv();
-        //println!("heartbeat recv result {:?}",recvres);
+        let result = match tokio::task::spawn_blocking(move || task.run()).await {
+            Ok(r) => r,
+            Err(e) => {
+                log::warn!("keep alive task error {:?}", e);
+                continue;
+            }
+        };
+        let res = worker_arc
+            .lock()
+            .unwrap()
+            .bcossdk
+            .netclient
+            .apply_keep_alive(result);
+        match res {
+            Ok(true) => log::info!("channel reconnected"),
+            Ok(false) => {}
+            Err(e) => log::warn!("keep alive error {:?}", e),
+        }
     }
     println!("heartbeat thread done!!")
 }
@@ -163,9 +173,11 @@ pub async fn start(configfile: &str) {
 
 pub fn handle_packet(worker_arc: &BcosChannelWorkerArc, pack: &ChannelPack) {
     println!(">>>handle packet {}", pack.detail());
-    let worker = worker_arc.lock().unwrap();
-    println!("worker ->>>> {}", worker.handlemanager.count_handler());
-    let handleOpt = worker.handlemanager.get_handle(&pack.packtype);
+    //取出handler后先释放worker，回调里可能还要用worker发包(如AMOP的回包)
+    let handleOpt = {
+        let worker = worker_arc.lock().unwrap();
+        worker.handlemanager.get_handle(&pack.packtype).cloned()
+    };
     match handleOpt {
         Some(handle) => {
             handle.lock().unwrap().handle(&pack);
@@ -24,7 +24,7 @@ use std::path::Path;
 
 use openssl::ssl::{
     ShutdownResult, SslConnector, SslConnectorBuilder, SslFiletype, SslMethod, SslStream,
-    SslVerifyMode,
+    SslVerifyMode, SslVersion,
 };
 
 use crate::bcos2sdk::bcos_channel_client::IBcosChannel;
@@ -66,7 +66,7 @@ impl ISslStreamWrap for SslStreamWrap {
         match res {
             Ok(t) => return Ok(t),
             Err(e) => {
-                let raw_code = e.raw_os_error().unwrap();
+                let raw_code = e.raw_os_error().unwrap_or(-1);
                 //println!("SslStream send return {:?},",raw_code);
                 match raw_code {
                     10035 => {
@@ -88,9 +88,14 @@ impl ISslStreamWrap for SslStreamWrap {
         let res = self.ssl_stream.read(buf);
         //println!("SslStreamWrap recv {:?}",res);
         match res {
+            //would block时返回的是错误，读到0字节表示对端已经关闭了连接
+            Ok(0) if !buf.is_empty() => Err(io::Error::new(
+                io::ErrorKind::ConnectionAborted,
+                "connection closed by peer",
+            )),
             Ok(t) => return Ok(t),
             Err(e) => {
-                let raw_code = e.raw_os_error().unwrap();
+                let raw_code = e.raw_os_error().unwrap_or(-1);
                 //println!("SslStream read return {:?},", raw_code);
                 match raw_code {
                     10035 => {
@@ -153,8 +158,18 @@ impl IBcosChannel for BcosSSLClient {
             }
         };
         let tcp_stream =
-            TcpStream::connect(format!("{}:{}", self.config.ip.as_str(), self.config.port))
-                .unwrap();
+            match TcpStream::connect(format!("{}:{}", self.config.ip.as_str(), self.config.port)) {
+                Ok(s) => s,
+                Err(e) => {
+                    return kisserr!(
+                        KissErrKind::ENetwork,
+                        "connect {}:{} error {:?}",
+                        self.config.ip,
+                        self.config.port,
+                        e
+                    );
+                }
+            };
         //let res = tcp_stream.set_nonblocking(true);
         let mut ssl_stream = match SslStream::new(ssl, tcp_stream) {
             Ok(s) => s,
@@ -285,6 +300,10 @@ impl BcosSSLClient {
                 );
             }
         };
+        //2.x节点只支持tls1.2，secp256k1在tls1.3里不可用，不限制版本的话新版openssl会握手失败
+        if let Err(e) = ctx.set_max_proto_version(Some(SslVersion::TLS1_2)) {
+            return kisserr!(KissErrKind::ENetwork, "set max proto version error {:?}", e);
+        }
         let res = match ctx.set_tmp_ecdh(&curve) {
             Ok(()) => (),
             Err(e) => {
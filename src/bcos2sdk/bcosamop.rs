@@ -0,0 +1,312 @@
+/*
+  FISCO BCOS/rust-SDK is a rust client for FISCO BCOS2.0 (https://github.com/FISCO-BCOS/)
+  FISCO BCOS/rust-SDK is free software: you can redistribute it and/or modify it under the
+  terms of the MIT License as published by the Free Software Foundation. This project is
+  distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even
+  the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+  @author: kentzhang
+  @date: 2021-07
+*/
+#![allow(
+    clippy::unreadable_literal,
+    clippy::upper_case_acronyms,
+    dead_code,
+    non_camel_case_types,
+    non_snake_case,
+    non_upper_case_globals,
+    overflowing_literals,
+    unused_variables,
+    unused_assignments
+)]
+
+/*
+FISCO BCOS 2.x的AMOP(链上信使协议)，基于channel连接：
+订阅：TOPIC_REPORT(0x32)向节点报告本连接订阅的全部topic，收到的AMOP_REQ(0x30)/TOPIC_MULTICAST(0x35)按topic回调
+单播请求：发AMOP_REQ，节点选一个订阅方转发，订阅方用同seq的AMOP_RESP(0x31)回包；没有订阅方时节点回result=100的AMOP_RESP
+广播：发TOPIC_MULTICAST，所有订阅方都会收到，没有回包
+包体都是pack_amop(topic,data)的格式。
+收包可以用start_bcos_channel_worker的后台线程，也可以自己定时调用poll
+回调在worker线程里调用，用户闭包里的panic用catch_unwind截住并记日志，不让它带走worker线程；
+锁被毒化时表里的数据仍然完整，照常使用。
+*/
+use crate::bcos2sdk::bcos2client::Bcos2Client;
+use crate::bcos2sdk::bcos_channel_handler_manager::{
+    ChannelPushHandlerManager, IChannelPushHandlerFacade, HANDLE_FACADE_OBJ,
+};
+use crate::bcos2sdk::bcos_channel_threads_worker;
+use crate::bcos2sdk::bcos_channel_threads_worker::BcosChannelWorker;
+use crate::bcos2sdk::channelpack::{
+    make_channel_pack_by_rawdata, pack_amop, unpack_amop, ChannelPack, CHANNEL_PACK_TYPE,
+};
+use crate::bcossdkutil::bcosclientconfig::BcosClientProtocol;
+use crate::bcossdkutil::kisserror::{KissErrKind, KissError};
+use crate::{kisserr, kisserrcode};
+use ethereum_types::H256;
+use std::collections::HashMap;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, Weak};
+use std::time::Duration;
+
+///收到的AMOP消息
+#[derive(Debug, Clone)]
+pub struct AmopMessage {
+    pub topic: String,
+    pub data: Vec<u8>,
+    pub seq: H256,
+    ///广播(TOPIC_MULTICAST)消息，不需要回包
+    pub is_broadcast: bool,
+}
+
+///topic的回调，返回Some时作为回包发给请求方，广播消息的返回值被忽略
+pub type AmopCallback = Arc<dyn Fn(&AmopMessage) -> Option<Vec<u8>> + Send + Sync>;
+
+///回调和等待中的请求，BcosAmop和注册到worker的handler共用
+#[derive(Default)]
+struct AmopShared {
+    callbacks: Mutex<HashMap<String, AmopCallback>>,
+    //请求的seq -> 收到的AMOP_RESP
+    pending: Mutex<HashMap<H256, Option<ChannelPack>>>,
+}
+
+impl AmopShared {
+    fn callbacks(&self) -> MutexGuard<'_, HashMap<String, AmopCallback>> {
+        self.callbacks
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn pending(&self) -> MutexGuard<'_, HashMap<H256, Option<ChannelPack>>> {
+        self.pending.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+fn lock_worker(worker: &Mutex<BcosChannelWorker>) -> MutexGuard<'_, BcosChannelWorker> {
+    worker.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+///注册到ChannelPushHandlerManager，处理AMOP_REQ/AMOP_RESP/TOPIC_MULTICAST
+pub struct AmopPushHandler {
+    shared: Arc<AmopShared>,
+    //handler被worker持有，这里用Weak避免循环引用
+    worker: Weak<Mutex<BcosChannelWorker>>,
+}
+
+impl AmopPushHandler {
+    fn reply(&self, msg: &AmopMessage, data: &[u8]) {
+        let worker = match self.worker.upgrade() {
+            Some(w) => w,
+            None => return,
+        };
+        let mut pack = match make_channel_pack_by_rawdata(
+            CHANNEL_PACK_TYPE::AMOP_RESP,
+            &pack_amop(&msg.topic.clone().into_bytes(), &data.to_vec()),
+        ) {
+            Some(p) => p,
+            None => return,
+        };
+        pack.seq = msg.seq;
+        if let Err(e) = bcos_channel_threads_worker::send_packet(&worker, &pack) {
+            log::warn!("send amop response error {:?}", e);
+        }
+    }
+}
+
+impl IChannelPushHandlerFacade for AmopPushHandler {
+    fn handle(&self, pack: &ChannelPack) {
+        if pack.packtype == CHANNEL_PACK_TYPE::AMOP_RESP as u16 {
+            let mut pending = self.shared.pending();
+            if let Some(slot) = pending.get_mut(&pack.seq) {
+                *slot = Some(pack.clone());
+            }
+            return;
+        }
+        let (topic, data) = unpack_amop(&pack.data);
+        let msg = AmopMessage {
+            topic: String::from_utf8_lossy(&topic).to_string(),
+            data,
+            seq: pack.seq,
+            is_broadcast: pack.packtype == CHANNEL_PACK_TYPE::TOPIC_MULTICAST as u16,
+        };
+        //先取出回调再调用，回调里可以再订阅或发消息
+        let callback = self.shared.callbacks().get(&msg.topic).cloned();
+        let callback = match callback {
+            Some(cb) => cb,
+            None => {
+                log::warn!("no amop callback for topic {}", msg.topic);
+                return;
+            }
+        };
+        let response = match catch_unwind(AssertUnwindSafe(|| callback(&msg))) {
+            Ok(response) => response,
+            Err(_) => {
+                log::warn!("amop callback panicked on topic {}", msg.topic);
+                return;
+            }
+        };
+        if let (Some(data), false) = (response, msg.is_broadcast) {
+            self.reply(&msg, &data);
+        }
+    }
+}
+
+fn make_amop_pack(packtype: CHANNEL_PACK_TYPE, topic: &str, data: &[u8]) -> ChannelPack {
+    let body = pack_amop(&topic.to_string().into_bytes(), &data.to_vec());
+    make_channel_pack_by_rawdata(packtype, &body).unwrap()
+}
+
+///2.x AMOP的发布订阅接口
+pub struct BcosAmop {
+    pub worker: Arc<Mutex<BcosChannelWorker>>,
+    shared: Arc<AmopShared>,
+}
+
+impl BcosAmop {
+    pub fn new(configfile: &str) -> Result<BcosAmop, KissError> {
+        let worker = BcosChannelWorker {
+            bcossdk: Bcos2Client::new_from_config(configfile)?,
+            handlemanager: ChannelPushHandlerManager::default(),
+            is_working: true,
+        };
+        BcosAmop::from_worker(Arc::new(Mutex::new(worker)))
+    }
+
+    ///在已有的worker上使用AMOP，可以和EventHandler共用一个连接
+    pub fn from_worker(worker: Arc<Mutex<BcosChannelWorker>>) -> Result<BcosAmop, KissError> {
+        let shared = Arc::new(AmopShared::default());
+        {
+            let mut w = lock_worker(&worker);
+            if w.bcossdk.config.bcos2.protocol != BcosClientProtocol::CHANNEL {
+                return kisserr!(KissErrKind::EArgument, "AMOP needs the CHANNEL protocol");
+            }
+            let handler: HANDLE_FACADE_OBJ = Arc::new(Mutex::new(AmopPushHandler {
+                shared: shared.clone(),
+                worker: Arc::downgrade(&worker),
+            }));
+            for packtype in [
+                CHANNEL_PACK_TYPE::AMOP_REQ,
+                CHANNEL_PACK_TYPE::AMOP_RESP,
+                CHANNEL_PACK_TYPE::TOPIC_MULTICAST,
+            ] {
+                w.handlemanager
+                    .set_handle(&(packtype as u16), handler.clone());
+            }
+        }
+        Ok(BcosAmop { worker, shared })
+    }
+
+    ///订阅topic，收到消息时调用callback。同一个topic再次订阅时替换callback
+    pub fn subscribe(&self, topic: &str, callback: AmopCallback) -> Result<(), KissError> {
+        self.shared.callbacks().insert(topic.to_string(), callback);
+        let mut worker = lock_worker(&self.worker);
+        let client = &mut worker.bcossdk.netclient.channel_client;
+        //连接上的topic还可能有出块通知等，只增减自己的
+        let mut topics = client.topics.clone();
+        if !topics.iter().any(|t| t == topic) {
+            topics.push(topic.to_string());
+        }
+        client.report_topics(&topics)
+    }
+
+    pub fn unsubscribe(&self, topic: &str) -> Result<(), KissError> {
+        self.shared.callbacks().remove(topic);
+        let mut worker = lock_worker(&self.worker);
+        let client = &mut worker.bcossdk.netclient.channel_client;
+        let topics: Vec<String> = client
+            .topics
+            .iter()
+            .filter(|t| t.as_str() != topic)
+            .cloned()
+            .collect();
+        client.report_topics(&topics)
+    }
+
+    pub fn subscribed_topics(&self) -> Vec<String> {
+        self.shared.callbacks().keys().cloned().collect()
+    }
+
+    fn send_amop(
+        &self,
+        packtype: CHANNEL_PACK_TYPE,
+        topic: &str,
+        data: &[u8],
+    ) -> Result<ChannelPack, KissError> {
+        let pack = make_amop_pack(packtype, topic, data);
+        bcos_channel_threads_worker::send_packet(&self.worker, &pack)?;
+        Ok(pack)
+    }
+
+    ///读一次连接上的数据并分发给handler，没有启动worker线程时由调用者定时调用
+    pub fn poll(&self) -> Result<usize, KissError> {
+        let packs = bcos_channel_threads_worker::read_packets(&self.worker)?;
+        for pack in packs.iter() {
+            bcos_channel_threads_worker::handle_packet(&self.worker, pack);
+        }
+        Ok(packs.len())
+    }
+
+    ///单播：发给topic的一个订阅方，等待对方回包，返回回包的数据
+    pub fn request(
+        &self,
+        topic: &str,
+        data: &[u8],
+        timeout_sec: u64,
+    ) -> Result<Vec<u8>, KissError> {
+        //先登记seq再发送，否则worker线程可能在登记前收到回包并把它丢掉
+        let pack = make_amop_pack(CHANNEL_PACK_TYPE::AMOP_REQ, topic, data);
+        self.shared.pending().insert(pack.seq, None);
+        if let Err(e) = bcos_channel_threads_worker::send_packet(&self.worker, &pack) {
+            self.shared.pending().remove(&pack.seq);
+            return Err(e);
+        }
+        let start = time::now();
+        let response = loop {
+            if let Some(Some(resp)) = self.shared.pending().get(&pack.seq) {
+                break Some(resp.clone());
+            }
+            if time::now() - start >= chrono::Duration::seconds(timeout_sec as i64) {
+                break None;
+            }
+            //后台worker线程在读包时这里读到的是空，不影响
+            if let Err(e) = self.poll() {
+                self.shared.pending().remove(&pack.seq);
+                return Err(e);
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        };
+        self.shared.pending().remove(&pack.seq);
+        let response = match response {
+            Some(r) => r,
+            None => {
+                return kisserr!(
+                    KissErrKind::ETimeout,
+                    "amop request to topic {} timeout",
+                    topic
+                )
+            }
+        };
+        if response.result != 0 {
+            //100:没有订阅方，其他为节点转发失败
+            return kisserrcode!(
+                KissErrKind::ENetwork,
+                response.result as i64,
+                "amop request to topic {} failed, result {}",
+                topic,
+                response.result
+            );
+        }
+        let (_, body) = unpack_amop(&response.data);
+        Ok(body)
+    }
+
+    ///广播：topic的所有订阅方都会收到，没有回包
+    pub fn broadcast(&self, topic: &str, data: &[u8]) -> Result<(), KissError> {
+        self.send_amop(CHANNEL_PACK_TYPE::TOPIC_MULTICAST, topic, data)?;
+        Ok(())
+    }
+
+    pub fn finish(&self) {
+        let mut worker = lock_worker(&self.worker);
+        worker.is_working = false;
+        worker.bcossdk.finish();
+    }
+}
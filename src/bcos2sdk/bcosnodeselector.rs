@@ -0,0 +1,174 @@
+/*
+  FISCO BCOS/rust-SDK is a rust client for FISCO BCOS2.0 (https://github.com/FISCO-BCOS/)
+  FISCO BCOS/rust-SDK is free software: you can redistribute it and/or modify it under the
+  terms of the MIT License as published by the Free Software Foundation. This project is
+  distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even
+  the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+  @author: kentzhang
+  @date: 2021-07
+*/
+#![allow(
+    clippy::unreadable_literal,
+    clippy::upper_case_acronyms,
+    dead_code,
+    non_camel_case_types,
+    non_snake_case,
+    non_upper_case_globals,
+    overflowing_literals,
+    unused_variables,
+    unused_assignments
+)]
+
+/*
+多节点的选择和健康状态：
+节点出现网络错误后标记为down，node_retry_sec秒内不再优先使用，过了这个时间重新放回候选列表试探，成功则恢复。
+没有请求时由BcosRPC::probe_down_nodes(心跳线程定时调用)主动探测到了重试时间的节点，恢复的节点不用等下一个请求。
+STICKY：一直使用当前节点，出错后切到下一个可用节点并停留在那里；
+ROUNDROBIN：每次请求从下一个节点开始。
+所有节点都不可用时，仍然按down的先后顺序尝试一遍，而不是直接返回错误
+*/
+use crate::bcossdkutil::bcosclientconfig::{BcosClientProtocol, BcosNodePolicy, ClientConfig};
+use crate::bcossdkutil::kisserror::{KissErrKind, KissError};
+
+#[derive(Debug, Clone)]
+pub struct BcosNodeState {
+    ///channel为"ip:port"，rpc为url
+    pub endpoint: String,
+    pub is_down: bool,
+    ///连续失败次数，成功后清零
+    pub fail_count: u32,
+    pub down_time: Option<time::Tm>,
+}
+
+#[derive(Debug, Clone)]
+pub struct BcosNodeSelector {
+    pub nodes: Vec<BcosNodeState>,
+    pub policy: BcosNodePolicy,
+    pub retry_sec: u32,
+    ///STICKY时为当前节点，ROUNDROBIN时为下一个请求开始的位置
+    pub cursor: usize,
+}
+
+impl BcosNodeSelector {
+    pub fn new(endpoints: &[String], policy: &BcosNodePolicy, retry_sec: u32) -> BcosNodeSelector {
+        let mut nodes: Vec<BcosNodeState> = vec![];
+        for e in endpoints.iter() {
+            let e = e.trim();
+            if e.is_empty() || nodes.iter().any(|n| n.endpoint == e) {
+                continue;
+            }
+            nodes.push(BcosNodeState {
+                endpoint: e.to_string(),
+                is_down: false,
+                fail_count: 0,
+                down_time: None,
+            });
+        }
+        BcosNodeSelector {
+            nodes,
+            policy: policy.clone(),
+            retry_sec,
+            cursor: 0,
+        }
+    }
+
+    ///按协议从配置里取节点列表：channel为ip:port加上nodes，rpc为url加上urls
+    pub fn from_config(config: &ClientConfig) -> BcosNodeSelector {
+        let mut endpoints = vec![];
+        match config.bcos2.protocol {
+            BcosClientProtocol::CHANNEL => {
+                if !config.channel.ip.is_empty() {
+                    endpoints.push(format!("{}:{}", config.channel.ip, config.channel.port));
+                }
+                endpoints.extend(config.channel.nodes.iter().cloned());
+            }
+            _ => {
+                endpoints.push(config.rpc.url.clone());
+                endpoints.extend(config.rpc.urls.iter().cloned());
+            }
+        }
+        BcosNodeSelector::new(
+            &endpoints,
+            &config.bcos2.node_policy,
+            config.bcos2.node_retry_sec,
+        )
+    }
+
+    fn is_available(&self, node: &BcosNodeState) -> bool {
+        match (node.is_down, node.down_time) {
+            (false, _) => true,
+            (true, Some(t)) => time::now() - t >= chrono::Duration::seconds(self.retry_sec as i64),
+            (true, None) => true,
+        }
+    }
+
+    ///本次请求依次尝试的节点下标：先是可用的(含到了重试时间的)节点，按策略排序，最后是其他down的节点
+    pub fn candidates(&mut self) -> Vec<usize> {
+        let count = self.nodes.len();
+        if count == 0 {
+            return vec![];
+        }
+        let start = self.cursor % count;
+        if self.policy == BcosNodePolicy::ROUNDROBIN {
+            self.cursor = (start + 1) % count;
+        }
+        let order: Vec<usize> = (0..count).map(|i| (start + i) % count).collect();
+        let mut result: Vec<usize> = order
+            .iter()
+            .filter(|i| self.is_available(&self.nodes[**i]))
+            .cloned()
+            .collect();
+        let mut rest: Vec<usize> = order
+            .iter()
+            .filter(|i| !result.contains(i))
+            .cloned()
+            .collect();
+        rest.sort_by_key(|i| self.nodes[*i].down_time.map(|t| t.to_timespec()));
+        result.extend(rest);
+        result
+    }
+
+    ///down了并且已经到了重试时间的节点，用于后台探测
+    pub fn due_for_probe(&self) -> Vec<usize> {
+        (0..self.nodes.len())
+            .filter(|i| self.nodes[*i].is_down && self.is_available(&self.nodes[*i]))
+            .collect()
+    }
+
+    ///节点恢复可用，不改变当前节点
+    pub fn mark_recovered(&mut self, index: usize) {
+        if let Some(node) = self.nodes.get_mut(index) {
+            if node.is_down {
+                log::info!("node {} is back", node.endpoint);
+            }
+            node.is_down = false;
+            node.fail_count = 0;
+            node.down_time = None;
+        }
+    }
+
+    pub fn mark_ok(&mut self, index: usize) {
+        self.mark_recovered(index);
+        if self.policy == BcosNodePolicy::STICKY {
+            self.cursor = index;
+        }
+    }
+
+    pub fn mark_down(&mut self, index: usize, reason: &KissError) {
+        if let Some(node) = self.nodes.get_mut(index) {
+            log::warn!("node {} is down: {:?}", node.endpoint, reason);
+            node.is_down = true;
+            node.fail_count += 1;
+            node.down_time = Some(time::now());
+        }
+    }
+
+    pub fn endpoint(&self, index: usize) -> &str {
+        self.nodes[index].endpoint.as_str()
+    }
+
+    ///网络和超时错误才切换节点，其他错误(如参数、格式错误)换节点也没用
+    pub fn is_failover_error(e: &KissError) -> bool {
+        e.kind == KissErrKind::ENetwork || e.kind == KissErrKind::ETimeout
+    }
+}
@@ -18,12 +18,17 @@
     unused_variables,
     unused_assignments
 )]
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Result as JsonResult, Value as JsonValue};
 
 use crate::bcos2sdk::bcos_channel_client::{BcosChannelClient, IBcosChannel};
 use crate::bcos2sdk::bcoshttpclient::HttpJsonRpcClient;
-use crate::bcossdkutil::bcosclientconfig::{BcosClientProtocol, ClientConfig};
+use crate::bcos2sdk::bcosnodeselector::BcosNodeSelector;
+use crate::bcossdkutil::bcosclientconfig::{
+    BcosClientProtocol, BcosNodePolicy, ChannelConfig, ClientConfig,
+};
 use crate::bcossdkutil::kisserror::{KissErrKind, KissError};
 use crate::{kisserr, printlnex};
 
@@ -69,12 +74,19 @@ impl RpcRequestData {
 
 //-----------------------------------------------------------------------------------
 ///统一对外暴露这个实现,封装向网络提交请求的部分
+///配置了多个节点时，请求按node_policy选择节点，网络错误时自动切换到其他节点
 #[derive()]
 pub struct BcosRPC {
     //http rpc 的实现
     pub config: ClientConfig,
     pub jsonrpc_client: HttpJsonRpcClient,
+    ///当前节点的channel连接，推送(事件、amop等)也从这个连接读取
     pub channel_client: BcosChannelClient,
+    pub selector: BcosNodeSelector,
+    ///jsonrpc_client/channel_client当前对应的节点下标，channel未连接时为None
+    pub current_node: Option<usize>,
+    ///ROUNDROBIN时其他节点已建立的channel连接，切换节点时复用
+    pub idle_channels: HashMap<usize, BcosChannelClient>,
 }
 //unsafe impl Send for BcosRPC{}
 //unsafe impl Sync for BcosRPC{}
@@ -85,37 +97,235 @@ impl BcosRPC {
         let mut jsonrpc_client = HttpJsonRpcClient::new();
         jsonrpc_client.target_url = config.rpc.url.clone();
         jsonrpc_client.timeout = config.rpc.timeout;
-        let channel_client: BcosChannelClient;
+        let mut rpc = BcosRPC {
+            config: config.clone(),
+            jsonrpc_client,
+            channel_client: BcosChannelClient::default(&config.channel),
+            selector: BcosNodeSelector::from_config(config),
+            current_node: None,
+            idle_channels: HashMap::new(),
+        };
         if config.bcos2.protocol == BcosClientProtocol::CHANNEL {
-            channel_client = BcosChannelClient::new(&config.channel)?;
+            //创建时就连上一个节点，都连不上则返回最后一个错误
+            let mut lasterr = None;
+            for index in rpc.selector.candidates() {
+                match rpc.use_node(index) {
+                    Ok(()) => {
+                        rpc.selector.mark_ok(index);
+                        //第一个请求从已连上的节点开始
+                        rpc.selector.cursor = index;
+                        return Ok(rpc);
+                    }
+                    Err(e) => {
+                        rpc.selector.mark_down(index, &e);
+                        lasterr = Some(e);
+                    }
+                }
+            }
+            return match lasterr {
+                Some(e) => Err(e),
+                None => kisserr!(KissErrKind::EArgument, "no channel node configured"),
+            };
+        }
+        printlnex!("done channel_client");
+        Ok(rpc)
+    }
+
+    ///把当前连接切换到第index个节点，channel方式需要时会建立连接
+    pub fn use_node(&mut self, index: usize) -> Result<(), KissError> {
+        if self.current_node == Some(index) {
+            return Ok(());
+        }
+        let endpoint = self.selector.endpoint(index).to_string();
+        if self.config.bcos2.protocol != BcosClientProtocol::CHANNEL {
+            self.jsonrpc_client.target_url = endpoint;
+            self.current_node = Some(index);
+            return Ok(());
+        }
+        let client = match self.idle_channels.remove(&index) {
+            Some(c) => c,
+            None => BcosChannelClient::new(&channel_config_for(&self.config, endpoint.as_str())?)?,
+        };
+        let mut old = std::mem::replace(&mut self.channel_client, client);
+        if self.config.bcos2.node_policy == BcosNodePolicy::ROUNDROBIN {
+            if let Some(oldindex) = self.current_node {
+                self.idle_channels.insert(oldindex, old);
+            }
         } else {
-            channel_client = BcosChannelClient::default(&config.channel);
-            printlnex!("done channel_client");
+            //STICKY时推送都在当前连接上，把订阅的topic和事件转到新节点
+            old.finish();
+            if !old.topics.is_empty() || !old.eventfilters.is_empty() {
+                self.channel_client.topics = old.topics;
+                self.channel_client.eventfilters = old.eventfilters;
+                self.channel_client.resubscribe()?;
+            }
+        }
+        self.current_node = Some(index);
+        Ok(())
+    }
+
+    ///心跳线程分三步用，避免重连和探测时一直占着锁：在锁内用keep_alive_task发心跳、决定要做什么，
+    ///在锁外KeepAliveTask::run建连接，再回到锁内用apply_keep_alive换上结果
+    pub fn keep_alive_task(&mut self) -> KeepAliveTask {
+        let reconnect = match self.current_node {
+            Some(index)
+                if self.config.bcos2.protocol == BcosClientProtocol::CHANNEL
+                    && self.channel_client.check_heartbeat() =>
+            {
+                Some((index, self.channel_client.config.clone()))
+            }
+            _ => None,
+        };
+        KeepAliveTask {
+            reconnect,
+            probes: self.due_probes(),
+            config: self.config.clone(),
+        }
+    }
+
+    ///换上KeepAliveTask::run的结果，返回channel是否重连过
+    pub fn apply_keep_alive(&mut self, result: KeepAliveResult) -> Result<bool, KissError> {
+        self.apply_probes(result.probes);
+        match result.reconnect {
+            //锁外重连期间当前节点可能已被切换，这时新连接不再需要
+            Some((index, Ok(mut fresh))) if self.current_node != Some(index) => {
+                fresh.finish();
+                Ok(false)
+            }
+            Some((index, Ok(fresh))) => {
+                self.channel_client.adopt(fresh)?;
+                Ok(true)
+            }
+            Some((index, Err(e))) => Err(e),
+            None => Ok(false),
+        }
+    }
+
+    ///心跳：当前channel连接按heartbeat_sec发心跳，断开时重连，再探测down的节点。返回channel是否重连过
+    pub fn keep_alive(&mut self) -> Result<bool, KissError> {
+        let result = self.keep_alive_task().run();
+        self.apply_keep_alive(result)
+    }
+
+    ///主动探测down了并且到了重试时间的节点，成功的恢复为可用，失败的重新计时，返回恢复的节点数。
+    ///没有请求时down的节点不会被candidates重试，由心跳线程定时调用
+    pub fn probe_down_nodes(&mut self) -> usize {
+        let task = KeepAliveTask {
+            reconnect: None,
+            probes: self.due_probes(),
+            config: self.config.clone(),
+        };
+        self.apply_probes(task.run().probes)
+    }
+
+    //到了重试时间、又不是当前连接的down节点
+    fn due_probes(&self) -> Vec<(usize, String)> {
+        self.selector
+            .due_for_probe()
+            .into_iter()
+            .filter(|index| self.current_node != Some(*index))
+            .map(|index| (index, self.selector.endpoint(index).to_string()))
+            .collect()
+    }
+
+    //ROUNDROBIN时保留探测时建好的channel连接备用，返回恢复的节点数
+    fn apply_probes(
+        &mut self,
+        probes: Vec<(usize, Result<Option<BcosChannelClient>, KissError>)>,
+    ) -> usize {
+        let mut recovered = 0;
+        for (index, res) in probes {
+            match res {
+                Ok(client) => {
+                    if let Some(mut client) = client {
+                        if self.config.bcos2.node_policy == BcosNodePolicy::ROUNDROBIN
+                            && self.current_node != Some(index)
+                        {
+                            if let Some(mut old) = self.idle_channels.insert(index, client) {
+                                old.finish();
+                            }
+                        } else {
+                            client.finish();
+                        }
+                    }
+                    self.selector.mark_recovered(index);
+                    recovered += 1;
+                }
+                Err(e) => self.selector.mark_down(index, &e),
+            }
+        }
+        recovered
+    }
+
+    ///当前节点出错，断开连接，下次请求时重新选择
+    fn drop_node(&mut self, index: usize, e: &KissError) {
+        self.selector.mark_down(index, e);
+        if let Some(mut c) = self.idle_channels.remove(&index) {
+            c.finish();
+        }
+        if self.current_node == Some(index) {
+            if self.config.bcos2.protocol == BcosClientProtocol::CHANNEL {
+                self.channel_client.finish();
+            }
+            self.current_node = None;
         }
-        Ok(BcosRPC {
-            config: config.clone(),
-            jsonrpc_client: jsonrpc_client,
-            channel_client: channel_client,
-        })
     }
 
     pub fn finish(&mut self) {
         self.channel_client.finish();
+        for (_, c) in self.idle_channels.iter_mut() {
+            c.finish();
+        }
+        self.idle_channels.clear();
+        self.current_node = None;
     }
-    pub fn switch_rpc_request_sync(&mut self, outbuffer: &String) -> Result<String, KissError> {
-        //let mut response_text =String::default();
+
+    fn request_current(&mut self, outbuffer: &str) -> Result<String, KissError> {
         match self.config.bcos2.protocol {
-            BcosClientProtocol::RPC => self.jsonrpc_client.request_sync(&outbuffer),
-            BcosClientProtocol::CHANNEL => self.channel_client.request_sync(&outbuffer),
-            _ => {
-                return kisserr!(
-                    KissErrKind::EArgument,
-                    "unhandled protocal {:?}",
-                    self.config.bcos2.protocol
-                )
+            BcosClientProtocol::RPC => self.jsonrpc_client.request_sync(outbuffer),
+            BcosClientProtocol::CHANNEL => self.channel_client.request_sync(outbuffer),
+            _ => kisserr!(
+                KissErrKind::EArgument,
+                "unhandled protocal {:?}",
+                self.config.bcos2.protocol
+            ),
+        }
+    }
+
+    ///按节点选择策略发送请求，网络错误或超时时把节点标记为down，换下一个节点重试
+    pub fn switch_rpc_request_sync(&mut self, outbuffer: &str) -> Result<String, KissError> {
+        if self.config.bcos2.protocol == BcosClientProtocol::BCOS3 {
+            return self.request_current(outbuffer);
+        }
+        let mut lasterr = None;
+        for index in self.selector.candidates() {
+            if let Err(e) = self.use_node(index) {
+                self.drop_node(index, &e);
+                lasterr = Some(e);
+                continue;
             }
+            match self.request_current(outbuffer) {
+                Ok(response) => {
+                    self.selector.mark_ok(index);
+                    return Ok(response);
+                }
+                Err(e) => {
+                    if !BcosNodeSelector::is_failover_error(&e) {
+                        return Err(e);
+                    }
+                    log::warn!(
+                        "request to node {} failed, try next node",
+                        self.selector.endpoint(index)
+                    );
+                    self.drop_node(index, &e);
+                    lasterr = Some(e);
+                }
+            }
+        }
+        match lasterr {
+            Some(e) => Err(e),
+            None => kisserr!(KissErrKind::EArgument, "no node configured"),
         }
-        //Ok(response_text);
     }
 
     ///同步调用的客户端请求，输入cmd，如 getBlockNumber，value:参数，参考bcos rpc接口文档，参数中应包含groupid
@@ -168,3 +378,79 @@ pub fn test_json_rpc() {
     let response = client.rpc_request_sync("getBlockNumber", params);
     println!("{:?}", response);
 }
+
+///endpoint为"ip:port"，其他channel配置和配置文件相同
+fn channel_config_for(config: &ClientConfig, endpoint: &str) -> Result<ChannelConfig, KissError> {
+    let mut channelconfig = config.channel.clone();
+    let pos = match endpoint.rfind(':') {
+        Some(p) => p,
+        None => return kisserr!(KissErrKind::EArgument, "bad node address {}", endpoint),
+    };
+    channelconfig.ip = endpoint[..pos].to_string();
+    channelconfig.port = match endpoint[pos + 1..].parse() {
+        Ok(p) => p,
+        Err(e) => return kisserr!(KissErrKind::EArgument, "bad node port {}", endpoint),
+    };
+    Ok(channelconfig)
+}
+
+///心跳要做的重连和探测，由BcosRPC::keep_alive_task生成，run时不需要持有BcosRPC
+pub struct KeepAliveTask {
+    ///要重连的当前节点下标和它的channel配置
+    pub reconnect: Option<(usize, ChannelConfig)>,
+    ///要探测的down节点下标和endpoint
+    pub probes: Vec<(usize, String)>,
+    config: ClientConfig,
+}
+
+pub struct KeepAliveResult {
+    reconnect: Option<(usize, Result<BcosChannelClient, KissError>)>,
+    probes: Vec<(usize, Result<Option<BcosChannelClient>, KissError>)>,
+}
+
+impl KeepAliveTask {
+    pub fn is_empty(&self) -> bool {
+        self.reconnect.is_none() && self.probes.is_empty()
+    }
+
+    ///建立连接和探测，可能阻塞到连接超时
+    pub fn run(self) -> KeepAliveResult {
+        let reconnect = self.reconnect.as_ref().map(|(index, channelconfig)| {
+            log::warn!(
+                "reconnect channel {}:{}",
+                channelconfig.ip.as_str(),
+                channelconfig.port
+            );
+            (*index, BcosChannelClient::new(channelconfig))
+        });
+        let probes = self
+            .probes
+            .iter()
+            .map(|(index, endpoint)| (*index, self.probe(endpoint)))
+            .collect();
+        KeepAliveResult { reconnect, probes }
+    }
+
+    //用getClientVersion探测一个节点，channel方式时返回建好的连接
+    fn probe(&self, endpoint: &str) -> Result<Option<BcosChannelClient>, KissError> {
+        let req = RpcRequestData {
+            method: "getClientVersion".to_string(),
+            params: json!([self.config.bcos2.groupid]),
+            ..RpcRequestData::default()
+        };
+        let outbuffer = req.encode()?;
+        if self.config.bcos2.protocol != BcosClientProtocol::CHANNEL {
+            let mut client = HttpJsonRpcClient::new();
+            client.target_url = endpoint.to_string();
+            client.timeout = self.config.rpc.timeout;
+            client.request_sync(outbuffer.as_str())?;
+            return Ok(None);
+        }
+        let mut client = BcosChannelClient::new(&channel_config_for(&self.config, endpoint)?)?;
+        if let Err(e) = client.request_sync(outbuffer.as_str()) {
+            client.finish();
+            return Err(e);
+        }
+        Ok(Some(client))
+    }
+}
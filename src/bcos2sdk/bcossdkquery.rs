@@ -26,9 +26,12 @@ use ethabi::Log as ReceiptLog;
 use serde_json::{json, Value as JsonValue};
 
 use crate::bcos2sdk::bcos2client::Bcos2Client;
+use crate::bcossdkutil::blocksignature;
+use crate::bcossdkutil::blocksignature::BlockSignatureReport;
 use crate::bcossdkutil::commonhash::HashType;
 use crate::bcossdkutil::contractabi::ContractABI;
 use crate::bcossdkutil::kisserror::{KissErrKind, KissError};
+use crate::bcossdkutil::merkleproof;
 use crate::{kisserr, printlnex};
 use std::thread;
 use std::time::Duration;
@@ -257,6 +260,20 @@ impl Bcos2Client {
         self.netclient.rpc_request_sync(cmd, &paramobj)
     }
 
+    ///取带签名列表的区块头，用当前的共识节点列表校验区块头hash和共识签名，返回签名的共识节点
+    pub fn verify_block_signatures(&mut self, num: u32) -> Result<BlockSignatureReport, KissError> {
+        let header = self.getBlockHeaderByNumber(num, true)?;
+        if header["result"].is_null() {
+            return kisserr!(KissErrKind::Error, "block {} not found", num);
+        }
+        let sealers = self.getSealerList(self.config.bcos2.groupid)?;
+        blocksignature::verify_block_signatures_v2(
+            &header["result"],
+            &sealers["result"],
+            &self.hashtype,
+        )
+    }
+
     ///https://fisco-bcos-documentation.readthedocs.io/zh_CN/latest/docs/api.html#gettransactionreceipt
     pub fn getTransactionReceipt(&mut self, txhash: &str) -> Result<JsonValue, KissError> {
         let groupid = self.config.bcos2.groupid;
@@ -357,6 +374,70 @@ impl Bcos2Client {
         self.netclient.rpc_request_sync(cmd, &paramobj)
     }
 
+    ///取交易所在块的块头字段，如transactionsRoot，receiptsRoot
+    fn block_field_of(&mut self, v: &JsonValue, name: &str) -> Result<String, KissError> {
+        let num = json_hextoint(&v["blockNumber"])?;
+        let block = self.getBlockByNumber(num as u32, false)?;
+        match block["result"][name].as_str() {
+            Some(root) => Ok(root.to_string()),
+            None => kisserr!(KissErrKind::EFormat, "{} of block {} not found", name, num),
+        }
+    }
+
+    ///用getTransactionByHashWithProof的证明和块头里的transactionsRoot校验交易确实在块里
+    pub fn verify_transaction_proof(&mut self, txhash: &str) -> Result<bool, KissError> {
+        let response = self.getTransactionByHashWithProof(txhash)?;
+        let result = &response["result"];
+        if result.is_null() {
+            return kisserr!(KissErrKind::Error, "transaction {} not found", txhash);
+        }
+        let tx = &result["transaction"];
+        if !merkleproof::is_same_txhash(tx, txhash) {
+            return Ok(false);
+        }
+        let root = self.block_field_of(tx, "transactionsRoot")?;
+        merkleproof::verify_transaction_proof_v2(tx, &result["txProof"], &root, &self.hashtype)
+    }
+
+    ///用getTransactionReceiptByHashWithProof的证明和块头里的receiptsRoot校验回执没有被篡改
+    pub fn verify_receipt_proof(&mut self, txhash: &str) -> Result<bool, KissError> {
+        let response = self.getTransactionReceiptByHashWithProof(txhash)?;
+        let result = &response["result"];
+        if result.is_null() {
+            return kisserr!(KissErrKind::Error, "receipt of {} not found", txhash);
+        }
+        let receipt = &result["transactionReceipt"];
+        if !merkleproof::is_same_txhash(receipt, txhash) {
+            return Ok(false);
+        }
+        let root = self.block_field_of(receipt, "receiptsRoot")?;
+        merkleproof::verify_receipt_proof_v2(
+            receipt,
+            &result["receiptProof"],
+            &root,
+            &self.hashtype,
+        )
+    }
+
+    ///校验sendRawTransactionAndGetProof推送的回执，回执里带txProof和receiptProof
+    pub fn verify_receipt_with_proofs(&mut self, receipt: &JsonValue) -> Result<bool, KissError> {
+        let txs_root = self.block_field_of(receipt, "transactionsRoot")?;
+        let receipts_root = self.block_field_of(receipt, "receiptsRoot")?;
+        let tx_ok = merkleproof::verify_transaction_proof_v2(
+            receipt,
+            &receipt["txProof"],
+            &txs_root,
+            &self.hashtype,
+        )?;
+        let receipt_ok = merkleproof::verify_receipt_proof_v2(
+            receipt,
+            &receipt["receiptProof"],
+            &receipts_root,
+            &self.hashtype,
+        )?;
+        Ok(tx_ok && receipt_ok)
+    }
+
     ///https://fisco-bcos-documentation.readthedocs.io/zh_CN/latest/docs/api.html#getpendingtransactions
     pub fn getPendingTransactions(&mut self, groupid: u32) -> Result<JsonValue, KissError> {
         let cmd = "getPendingTransactions";
@@ -26,9 +26,9 @@ use rlp::{self, DecoderError, Rlp, RlpStream};
 use crate::bcossdkutil::accountutil::{EcdsaAccountUtil, IBcosAccountUtil};
 use crate::bcossdkutil::commonhash::{CommonHash, HashType};
 use crate::bcossdkutil::commonsigner::{CommonSignature, CommonSignerWeDPR_Secp256, ICommonSigner};
-use crate::bcossdkutil::kisserror::KissError;
-use crate::printlnex;
-use std::convert::TryInto;
+use crate::bcossdkutil::contractabi::{function_input, ContractABI};
+use crate::bcossdkutil::kisserror::{KissErrKind, KissError};
+use crate::{kisserr, printlnex};
 
 ///fisco bcos的交易结构，重点关注random_id,block_limit,chain_id,grou_id
 #[derive(Debug, Clone)]
@@ -63,6 +63,32 @@ impl BcosTransaction {
         }
     }
 
+    ///构建未签名的交易，block_limit、chainid、groupid都由调用者给出，不访问网络，可用于离线签名。
+    ///部署合约时to_address为空，data为合约代码加构造参数
+    pub fn new_unsigned(
+        to_address: &str,
+        data: Vec<u8>,
+        block_limit: u64,
+        chainid: u32,
+        groupid: u32,
+        hashtype: &HashType,
+    ) -> BcosTransaction {
+        let randid: u64 = rand::random();
+        BcosTransaction {
+            to_address: encode_address(to_address),
+            random_id: U256::from(randid),
+            gas_price: U256::from(30000000),
+            gas_limit: U256::from(30000000),
+            block_limit: U256::from(block_limit),
+            value: U256::from(0),
+            data,
+            fisco_chain_id: U256::from(chainid),
+            group_id: U256::from(groupid),
+            extra_data: b"".to_vec(),
+            hashtype: hashtype.clone(),
+        }
+    }
+
     pub fn rlp_append_tx_elements(&self, stream: &mut RlpStream) {
         stream.append(&self.random_id);
         stream.append(&self.gas_price);
@@ -145,12 +171,15 @@ impl BcosTransactionWithSig {
             &self.signature.s.len()
         );
         // printlnex!("v{:?},r{:?},s{:?}",&self.signature.v,&self.signature.r,&self.signature.s);
-        if self.signature.v.len() == 8
-        // ecdsa
+        if self.signature.v.len() <= 8
+        // ecdsa，签名时v是8字节，从rlp解码出来的v是去掉了前导0的整数
         {
             printlnex!("is a ecdsa sig {:?}", self.signature.v);
-            let u64v: u64 =
-                u64::from_be_bytes(self.signature.v.as_slice()[0..8].try_into().unwrap());
+            let u64v: u64 = self
+                .signature
+                .v
+                .iter()
+                .fold(0u64, |acc, b| (acc << 8) | *b as u64);
             stream.append(&u64v);
         } else {
             let h512v = H512::from_slice(self.signature.v.as_slice());
@@ -168,7 +197,7 @@ impl BcosTransactionWithSig {
             return Err(DecoderError::RlpIncorrectListLen);
         }
         //let hash = keccak(d.as_raw());
-        let transaction = BcosTransaction::decode_rlp(d, 0).unwrap();
+        let transaction = BcosTransaction::decode_rlp(d, 0)?;
 
         let mut is_signed = false;
         let mut signature = CommonSignature::default();
@@ -209,6 +238,91 @@ impl BcosTransactionWithSig {
             Err(e) => Err(e),
         }
     }
+
+    ///签名并编码，返回的数据即sendRawTransaction的参数
+    pub fn sign_and_encode(
+        signer: &dyn ICommonSigner,
+        tx: &BcosTransaction,
+    ) -> Result<Vec<u8>, KissError> {
+        Ok(BcosTransactionWithSig::sign(signer, tx)?.encode())
+    }
+
+    ///被签名的hash，即未签名交易的rlp编码的hash
+    pub fn sign_hash(&self, hashtype: &HashType) -> H256 {
+        let mut tx = self.transaction.clone();
+        tx.hashtype = hashtype.clone();
+        tx.hash()
+    }
+
+    ///交易hash，和节点返回的txhash一致：签名交易的rlp编码的hash
+    pub fn txhash(&self, hashtype: &HashType) -> H256 {
+        CommonHash::hash_to_h256(&self.encode(), hashtype)
+    }
+
+    ///从签名恢复发送者地址，国密交易会用交易里带的公钥校验签名
+    pub fn recover_sender(&self, hashtype: &HashType) -> Result<Address, KissError> {
+        if !self.is_signed {
+            return kisserr!(KissErrKind::ESign, "transaction is not signed");
+        }
+        let hash = self.sign_hash(hashtype);
+        let address = self.signature.recover_address(hash.as_bytes(), hashtype)?;
+        Ok(Address::from_slice(&address))
+    }
+}
+
+///解码后的签名交易，用于审计工具等需要确认"谁发了什么交易"的场景
+#[derive(Debug, Clone)]
+pub struct DecodedSignedTransaction {
+    pub transaction: BcosTransactionWithSig,
+    ///和节点返回的一致的交易hash
+    pub txhash: String,
+    ///从签名恢复出的发送者地址
+    pub sender: String,
+    ///部署交易的to为空
+    pub to: String,
+    ///按abi解码出的方法和参数，没有传abi、部署交易或者abi里找不到方法时为None
+    pub call: Option<function_input>,
+}
+
+///解码签名交易(sendRawTransaction的数据)，恢复发送者，并按abi解码调用的方法和参数
+pub fn decode_signed_transaction(
+    rawtx: &[u8],
+    hashtype: &HashType,
+    contract: Option<&ContractABI>,
+) -> Result<DecodedSignedTransaction, KissError> {
+    let mut tx = match BcosTransactionWithSig::decode_rlp(&Rlp::new(rawtx)) {
+        Ok(t) => t,
+        Err(e) => {
+            return kisserr!(
+                KissErrKind::EFormat,
+                "decode signed transaction error {:?}",
+                e
+            )
+        }
+    };
+    tx.transaction.hashtype = hashtype.clone();
+    let sender = tx.recover_sender(hashtype)?;
+    let to = if tx.transaction.to_address.is_empty() {
+        "".to_string()
+    } else {
+        format!("0x{}", hex::encode(&tx.transaction.to_address))
+    };
+    let call = match contract {
+        Some(c) if !to.is_empty() && tx.transaction.data.len() >= 4 => c
+            .decode_input_for_tx(hex::encode(&tx.transaction.data).as_str())
+            .ok(),
+        _ => None,
+    };
+    Ok(DecodedSignedTransaction {
+        txhash: format!(
+            "0x{}",
+            hex::encode(CommonHash::hash(&rawtx.to_vec(), hashtype))
+        ),
+        sender: format!("0x{}", hex::encode(sender.as_bytes())),
+        to,
+        call,
+        transaction: tx,
+    })
 }
 
 fn test_decode_tx_from_str(tx_data: &str) {
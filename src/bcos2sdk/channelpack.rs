@@ -33,7 +33,7 @@ use crate::kisserr;
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub enum CHANNEL_PACK_TYPE {
     RPC = 0x12,                       //JSONRPC 2.0格式	RPC接口消息包	SDK->节点
-    HEART_BEAT = 0x13, //json格式心跳包{"heartbeat":"0"}	心跳包	0:SDK->节点，1:节点->SDK
+    HEART_BEAT = 0x13, //心跳包，V1为"0"，V2起为json{"heartBeat":"0"}	心跳包	0:SDK->节点，1:节点->SDK
     HAND_SHAKE = 0x14, //SDK->节点的包体{"minimumSupport":version,"maximumSupport":version,"clientType":"client type"},节点->SDK的包体{"protocol":version,"nodeVersion":"fisco-bcos version"	握手包，json格式的协议版本协商	SDK<->节点，双向
     CLIENT_REGISTER_EVENT_LOG = 0x15, // 注册消息监听
     AMOP_REQ = 0x30,   //AMOP消息包包体	AMOP请求包	SDK<->节点，双向
@@ -45,6 +45,23 @@ pub enum CHANNEL_PACK_TYPE {
     EVENT_LOG_PUSH = 0x1002,
 }
 
+///channel协议版本，连接后用HAND_SHAKE和节点协商，取双方都支持的最高版本
+pub const CHANNEL_PROTOCOL_V1: i64 = 1;
+pub const CHANNEL_PROTOCOL_V2: i64 = 2;
+///从V3开始节点支持事件推送(CLIENT_REGISTER_EVENT_LOG/EVENT_LOG_PUSH)
+pub const CHANNEL_PROTOCOL_V3: i64 = 3;
+pub const CHANNEL_PROTOCOL_MIN: i64 = CHANNEL_PROTOCOL_V1;
+pub const CHANNEL_PROTOCOL_MAX: i64 = CHANNEL_PROTOCOL_V3;
+
+///按协议版本生成心跳包体，value为"0"(SDK->节点)或"1"(节点->SDK)，未握手时按V1
+pub fn make_heartbeat_data(protocol: i64, value: &str) -> String {
+    if protocol >= CHANNEL_PROTOCOL_V2 {
+        format!("{{\"heartBeat\":\"{}\"}}", value)
+    } else {
+        value.to_string()
+    }
+}
+
 ///https://fisco-bcos-documentation.readthedocs.io/zh_CN/latest/docs/design/protocol_description.html#channelmessage-v2
 ///
 ///length	uint32_t	4	数据包长度，含包头和数据，大端
@@ -187,7 +204,7 @@ pub fn test_channelpack() {
 https://fisco-bcos-documentation.readthedocs.io/zh_CN/latest/docs/design/protocol_description.html#id5
 
 0x12	JSONRPC 2.0格式	RPC接口消息包	SDK->节点
-0x13	json格式心跳包{"heartbeat":"0"}	心跳包	0:SDK->节点，1:节点->SDK
+0x13	心跳包，V1为"0"，V2起为json{"heartBeat":"0"}	心跳包	0:SDK->节点，1:节点->SDK
 0x14	SDK->节点的包体{"minimumSupport":version,"maximumSupport":version,"clientType":"client type"},节点->SDK的包体{"protocol":version,"nodeVersion":"fisco-bcos version"	握手包，json格式的协议版本协商	SDK<->节点，双向
 0x30	AMOP消息包包体	AMOP请求包	SDK<->节点，双向
 0x31	失败的AMOP消息的包体	AMOP失败响应包	节点->SDK或节点->节点
@@ -136,14 +136,13 @@ impl EventHandler {
         println!("register amop data : {:?}", registerreqdata);
         //let (amoptopic ,amopdata ) = channelpack::unpack_amop(&registerreqdata);
         //println!("amop data unpack {}",String::from_utf8(amopdata).unwrap());
-        let pack = channelpack::make_channel_pack_by_rawdata(
-            CHANNEL_PACK_TYPE::CLIENT_REGISTER_EVENT_LOG,
-            &registerreqdata,
-        );
-        let packdata = pack.unwrap().pack();
-        // 发送请求包
+        // 发送请求包，channel_client会记录下来，重连后重新注册
         let mut worker = self.worker.lock().unwrap();
-        let res = worker.bcossdk.netclient.channel_client.try_send(&packdata);
+        let res = worker
+            .bcossdk
+            .netclient
+            .channel_client
+            .register_event_filter(&registerreqdata);
 
         //println!("register event result {:?},packdata {:?}",res,packdata);
     }
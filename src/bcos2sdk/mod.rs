@@ -10,7 +10,9 @@
 
 pub mod bcos2client;
 
+pub mod bcosamop;
 pub mod bcoshttpclient;
+pub mod bcosnodeselector;
 pub mod bcosrpcwraper;
 pub mod bcossdkquery;
 
@@ -23,6 +25,8 @@ pub mod bcostransaction;
 
 pub mod bcos2_ssl_ffi;
 pub mod bcos_channel_handler_manager;
+pub mod bcos_channel_mocknode;
 pub mod bcos_channel_tassl_sock_ffi;
 pub mod channelpack;
 pub mod eventhandler;
+pub mod precompiled;
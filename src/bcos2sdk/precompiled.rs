@@ -0,0 +1,456 @@
+/*
+  FISCO BCOS/rust-SDK is a rust client for FISCO BCOS2.0 (https://github.com/FISCO-BCOS/)
+  FISCO BCOS/rust-SDK is free software: you can redistribute it and/or modify it under the
+  terms of the MIT License as published by the Free Software Foundation. This project is
+  distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even
+  the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+  @author: kentzhang
+  @date: 2021-07
+*/
+#![allow(
+    clippy::unreadable_literal,
+    clippy::upper_case_acronyms,
+    dead_code,
+    non_camel_case_types,
+    non_snake_case,
+    non_upper_case_globals,
+    overflowing_literals,
+    unused_variables,
+    unused_assignments
+)]
+
+/*
+FISCO BCOS 2.x的预编译合约：系统配置、节点管理、CNS、权限、合约生命周期
+https://fisco-bcos-documentation.readthedocs.io/zh_CN/latest/docs/manual/precompiled_contract_api.html
+- 预编译合约地址固定，abi内置在这里，不需要合约文件
+- 写操作是交易，回执的output是int256的返回码，>=0为成功(一般是影响的行数)，<0为错误码，转成EPrecompiled
+- 读操作用call，CNS和权限的查询结果是json字符串，解析后返回
+*/
+use ethabi::{Address, Token};
+use serde_json::Value as JsonValue;
+
+use crate::bcos2sdk::bcos2client::Bcos2Client;
+use crate::bcossdkutil::contractabi::ContractABI;
+use crate::bcossdkutil::kisserror::{KissErrKind, KissError};
+use crate::{kisserr, kisserrcode};
+
+pub const SYS_CONFIG_ADDRESS: &str = "0x0000000000000000000000000000000000001000";
+pub const CONSENSUS_ADDRESS: &str = "0x0000000000000000000000000000000000001003";
+pub const CNS_ADDRESS: &str = "0x0000000000000000000000000000000000001004";
+pub const PERMISSION_ADDRESS: &str = "0x0000000000000000000000000000000000001005";
+pub const CONTRACT_LIFECYCLE_ADDRESS: &str = "0x0000000000000000000000000000000000001007";
+
+pub const SYS_CONFIG_ABI: &str = r#"[
+{"constant":false,"inputs":[{"name":"key","type":"string"},{"name":"value","type":"string"}],"name":"setValueByKey","outputs":[{"name":"","type":"int256"}],"payable":false,"stateMutability":"nonpayable","type":"function"}]"#;
+
+pub const CONSENSUS_ABI: &str = r#"[
+{"constant":false,"inputs":[{"name":"nodeID","type":"string"}],"name":"addSealer","outputs":[{"name":"","type":"int256"}],"payable":false,"stateMutability":"nonpayable","type":"function"},
+{"constant":false,"inputs":[{"name":"nodeID","type":"string"}],"name":"addObserver","outputs":[{"name":"","type":"int256"}],"payable":false,"stateMutability":"nonpayable","type":"function"},
+{"constant":false,"inputs":[{"name":"nodeID","type":"string"}],"name":"remove","outputs":[{"name":"","type":"int256"}],"payable":false,"stateMutability":"nonpayable","type":"function"}]"#;
+
+pub const CNS_ABI: &str = r#"[
+{"constant":false,"inputs":[{"name":"name","type":"string"},{"name":"version","type":"string"},{"name":"addr","type":"string"},{"name":"abi","type":"string"}],"name":"insert","outputs":[{"name":"","type":"int256"}],"payable":false,"stateMutability":"nonpayable","type":"function"},
+{"constant":true,"inputs":[{"name":"name","type":"string"}],"name":"selectByName","outputs":[{"name":"","type":"string"}],"payable":false,"stateMutability":"view","type":"function"},
+{"constant":true,"inputs":[{"name":"name","type":"string"},{"name":"version","type":"string"}],"name":"selectByNameAndVersion","outputs":[{"name":"","type":"string"}],"payable":false,"stateMutability":"view","type":"function"},
+{"constant":true,"inputs":[{"name":"name","type":"string"},{"name":"version","type":"string"}],"name":"getContractAddress","outputs":[{"name":"","type":"address"}],"payable":false,"stateMutability":"view","type":"function"}]"#;
+
+pub const PERMISSION_ABI: &str = r#"[
+{"constant":false,"inputs":[{"name":"table_name","type":"string"},{"name":"addr","type":"string"}],"name":"insert","outputs":[{"name":"","type":"int256"}],"payable":false,"stateMutability":"nonpayable","type":"function"},
+{"constant":false,"inputs":[{"name":"table_name","type":"string"},{"name":"addr","type":"string"}],"name":"remove","outputs":[{"name":"","type":"int256"}],"payable":false,"stateMutability":"nonpayable","type":"function"},
+{"constant":true,"inputs":[{"name":"table_name","type":"string"}],"name":"queryByName","outputs":[{"name":"","type":"string"}],"payable":false,"stateMutability":"view","type":"function"},
+{"constant":false,"inputs":[{"name":"contractAddr","type":"address"},{"name":"user","type":"address"}],"name":"grantWrite","outputs":[{"name":"","type":"int256"}],"payable":false,"stateMutability":"nonpayable","type":"function"},
+{"constant":false,"inputs":[{"name":"contractAddr","type":"address"},{"name":"user","type":"address"}],"name":"revokeWrite","outputs":[{"name":"","type":"int256"}],"payable":false,"stateMutability":"nonpayable","type":"function"},
+{"constant":true,"inputs":[{"name":"contractAddr","type":"address"}],"name":"queryPermission","outputs":[{"name":"","type":"string"}],"payable":false,"stateMutability":"view","type":"function"}]"#;
+
+pub const CONTRACT_LIFECYCLE_ABI: &str = r#"[
+{"constant":false,"inputs":[{"name":"addr","type":"address"}],"name":"freeze","outputs":[{"name":"","type":"int256"}],"payable":false,"stateMutability":"nonpayable","type":"function"},
+{"constant":false,"inputs":[{"name":"addr","type":"address"}],"name":"unfreeze","outputs":[{"name":"","type":"int256"}],"payable":false,"stateMutability":"nonpayable","type":"function"},
+{"constant":false,"inputs":[{"name":"contractAddr","type":"address"},{"name":"userAddr","type":"address"}],"name":"grantManager","outputs":[{"name":"","type":"int256"}],"payable":false,"stateMutability":"nonpayable","type":"function"},
+{"constant":true,"inputs":[{"name":"addr","type":"address"}],"name":"getStatus","outputs":[{"name":"","type":"int256"},{"name":"","type":"string"}],"payable":false,"stateMutability":"view","type":"function"},
+{"constant":true,"inputs":[{"name":"addr","type":"address"}],"name":"listManager","outputs":[{"name":"","type":"int256"},{"name":"","type":"address[]"}],"payable":false,"stateMutability":"view","type":"function"}]"#;
+
+//权限表，grant_permission/revoke_permission/list_permission的table参数。用户表为"_user_"+表名
+///部署合约和建表
+pub const PERMISSION_TABLE_DEPLOY_AND_CREATE: &str = "_sys_tables_";
+///管理权限本身
+pub const PERMISSION_TABLE_PERMISSION: &str = "_sys_table_access_";
+///节点管理(addSealer/addObserver/remove)
+pub const PERMISSION_TABLE_NODE: &str = "_sys_consensus_";
+///CNS注册
+pub const PERMISSION_TABLE_CNS: &str = "_sys_cns_";
+///系统配置
+pub const PERMISSION_TABLE_SYS_CONFIG: &str = "_sys_config_";
+
+///预编译合约的错误码，见FISCO BCOS 2.x的PrecompiledErrorCode
+pub fn precompiled_error_message(code: i64) -> &'static str {
+    match code {
+        -50000 => "permission denied",
+        -50001 => "table name already exists",
+        -50002 => "table name length overflow",
+        -50003 => "table field name length overflow",
+        -50004 => "table field total length overflow",
+        -50005 => "table key value length overflow",
+        -50006 => "table field value length overflow",
+        -50007 => "duplicate table field",
+        -50008 => "invalid table field",
+        -50100 => "table does not exist",
+        -50101 => "undefined function",
+        -50102 => "invalid address format",
+        -51000 => "table name and address already exist",
+        -51001 => "table name and address do not exist",
+        -51002 => "table name length overflow",
+        -51003 => "contract does not exist",
+        -51004 => "permission is controlled by committee, use chain governance instead",
+        -51100 => "invalid node ID",
+        -51101 => "the last sealer cannot be removed",
+        -51102 => "the node is not reachable",
+        -51103 => "the node is not a group peer",
+        -51104 => "the node is already in the sealer list",
+        -51105 => "the node is already in the observer list",
+        -51200 => "contract name and version already exist",
+        -51201 => "version length overflow",
+        -51300 => "invalid configuration value",
+        -51500 => "table key cannot be updated",
+        -51900 => "the contract has been frozen",
+        -51901 => "the contract is available",
+        -51902 => "the contract has been granted to the same user",
+        -51903 => "invalid contract address",
+        -51904 => "the contract address does not exist",
+        -51905 => "no permission to access the contract",
+        _ => "unknown precompiled error",
+    }
+}
+
+///返回码<0时转成EPrecompiled，code为返回码
+pub fn check_precompiled_code(code: i64, op: &str) -> Result<i64, KissError> {
+    if code >= 0 {
+        return Ok(code);
+    }
+    kisserrcode!(
+        KissErrKind::EPrecompiled,
+        code,
+        "{} failed: {} ({})",
+        op,
+        precompiled_error_message(code),
+        code
+    )
+}
+
+//int256/uint256的返回码取低64位，负数是补码，低64位即i64的补码
+fn token_to_code(token: &Token) -> Option<i64> {
+    match token {
+        Token::Int(v) | Token::Uint(v) => Some(v.low_u64() as i64),
+        _ => None,
+    }
+}
+
+fn parse_address(address: &str) -> Result<Token, KissError> {
+    let bytes = match hex::decode(address.trim().trim_start_matches("0x")) {
+        Ok(b) if b.len() == 20 => b,
+        _ => return kisserr!(KissErrKind::EArgument, "invalid address {}", address),
+    };
+    Ok(Token::Address(Address::from_slice(&bytes)))
+}
+
+fn decode_output(
+    contract: &ContractABI,
+    method: &str,
+    output: &str,
+) -> Result<Vec<Token>, KissError> {
+    match contract.decode_output_byname(method, output) {
+        Ok(tokens) => Ok(tokens),
+        Err(e) => kisserr!(
+            KissErrKind::EFormat,
+            "decode {} output error {:?}",
+            method,
+            e
+        ),
+    }
+}
+
+//查询结果是json字符串，不是json时原样返回字符串
+fn parse_json_text(text: &str) -> JsonValue {
+    match serde_json::from_str(text) {
+        Ok(v) => v,
+        Err(_) => JsonValue::String(text.to_string()),
+    }
+}
+
+impl Bcos2Client {
+    ///发交易调用预编译合约，返回output里的返回码，<0时返回EPrecompiled
+    pub fn precompiled_send(
+        &mut self,
+        address: &str,
+        abi: &str,
+        method: &str,
+        params: &[Token],
+    ) -> Result<i64, KissError> {
+        let contract = ContractABI::new_from_text(abi, &self.hashtype)?;
+        let receipt =
+            self.sendRawTransactionGetReceiptWithTokenParam(&contract, address, method, params)?;
+        let output = receipt["result"]["output"].as_str().unwrap_or("");
+        let tokens = decode_output(&contract, method, output)?;
+        match tokens.first().and_then(token_to_code) {
+            Some(code) => check_precompiled_code(code, method),
+            None => kisserr!(
+                KissErrKind::EFormat,
+                "{} output is not a return code: {}",
+                method,
+                output
+            ),
+        }
+    }
+
+    ///call预编译合约的只读方法，返回解码后的输出
+    pub fn precompiled_call(
+        &mut self,
+        address: &str,
+        abi: &str,
+        method: &str,
+        params: &[Token],
+    ) -> Result<Vec<Token>, KissError> {
+        let contract = ContractABI::new_from_text(abi, &self.hashtype)?;
+        let response = self.call_with_tokenparam(&contract, address, method, params)?;
+        let result = &response["result"];
+        let status = result["status"].as_str().unwrap_or("0x0");
+        if status != "0x0" {
+            return kisserr!(
+                KissErrKind::Error,
+                "call {} error, status {}, output {}",
+                method,
+                status,
+                result["output"]
+            );
+        }
+        decode_output(&contract, method, result["output"].as_str().unwrap_or(""))
+    }
+
+    //只有一个string返回值的查询
+    fn precompiled_call_json(
+        &mut self,
+        address: &str,
+        abi: &str,
+        method: &str,
+        params: &[Token],
+    ) -> Result<JsonValue, KissError> {
+        let tokens = self.precompiled_call(address, abi, method, params)?;
+        match tokens.first() {
+            Some(Token::String(s)) => Ok(parse_json_text(s.as_str())),
+            _ => kisserr!(KissErrKind::EFormat, "{} output is not string", method),
+        }
+    }
+
+    //---------------------------SystemConfig 0x1000--------------------------------
+    ///key如tx_count_limit、tx_gas_limit、rpbft_epoch_sealer_num、consensus_timeout，查询用getSystemConfigByKey
+    pub fn set_system_config(&mut self, key: &str, value: &str) -> Result<i64, KissError> {
+        self.precompiled_send(
+            SYS_CONFIG_ADDRESS,
+            SYS_CONFIG_ABI,
+            "setValueByKey",
+            &[
+                Token::String(key.to_string()),
+                Token::String(value.to_string()),
+            ],
+        )
+    }
+
+    //---------------------------Consensus 0x1003--------------------------------
+    ///node_id为节点的公钥(128个hex字符)，可用getNodeIDList查到
+    pub fn add_sealer(&mut self, node_id: &str) -> Result<i64, KissError> {
+        self.consensus_send("addSealer", node_id)
+    }
+    pub fn add_observer(&mut self, node_id: &str) -> Result<i64, KissError> {
+        self.consensus_send("addObserver", node_id)
+    }
+    ///把节点设为游离节点，不再参与共识和同步
+    pub fn remove_node(&mut self, node_id: &str) -> Result<i64, KissError> {
+        self.consensus_send("remove", node_id)
+    }
+    fn consensus_send(&mut self, method: &str, node_id: &str) -> Result<i64, KissError> {
+        self.precompiled_send(
+            CONSENSUS_ADDRESS,
+            CONSENSUS_ABI,
+            method,
+            &[Token::String(node_id.to_string())],
+        )
+    }
+
+    //---------------------------CNS 0x1004--------------------------------
+    pub fn cns_register(
+        &mut self,
+        name: &str,
+        version: &str,
+        address: &str,
+        abi: &str,
+    ) -> Result<i64, KissError> {
+        self.precompiled_send(
+            CNS_ADDRESS,
+            CNS_ABI,
+            "insert",
+            &[
+                Token::String(name.to_string()),
+                Token::String(version.to_string()),
+                Token::String(address.to_string()),
+                Token::String(abi.to_string()),
+            ],
+        )
+    }
+
+    ///返回json数组，元素为{"name","version","address","abi"}，version为None时返回所有版本
+    pub fn cns_query(&mut self, name: &str, version: Option<&str>) -> Result<JsonValue, KissError> {
+        match version {
+            Some(v) => self.precompiled_call_json(
+                CNS_ADDRESS,
+                CNS_ABI,
+                "selectByNameAndVersion",
+                &[
+                    Token::String(name.to_string()),
+                    Token::String(v.to_string()),
+                ],
+            ),
+            None => self.precompiled_call_json(
+                CNS_ADDRESS,
+                CNS_ABI,
+                "selectByName",
+                &[Token::String(name.to_string())],
+            ),
+        }
+    }
+
+    //---------------------------Permission 0x1005--------------------------------
+    ///table为PERMISSION_TABLE_*或用户表"_user_"+表名
+    pub fn grant_permission(&mut self, table: &str, account: &str) -> Result<i64, KissError> {
+        self.precompiled_send(
+            PERMISSION_ADDRESS,
+            PERMISSION_ABI,
+            "insert",
+            &[
+                Token::String(table.to_string()),
+                Token::String(account.to_string()),
+            ],
+        )
+    }
+    pub fn revoke_permission(&mut self, table: &str, account: &str) -> Result<i64, KissError> {
+        self.precompiled_send(
+            PERMISSION_ADDRESS,
+            PERMISSION_ABI,
+            "remove",
+            &[
+                Token::String(table.to_string()),
+                Token::String(account.to_string()),
+            ],
+        )
+    }
+    ///返回json数组，元素为{"address","enable_num","table_name"}
+    pub fn list_permission(&mut self, table: &str) -> Result<JsonValue, KissError> {
+        self.precompiled_call_json(
+            PERMISSION_ADDRESS,
+            PERMISSION_ABI,
+            "queryByName",
+            &[Token::String(table.to_string())],
+        )
+    }
+    ///合约的写权限(合约所用表的写权限)
+    pub fn grant_contract_write(
+        &mut self,
+        contract: &str,
+        account: &str,
+    ) -> Result<i64, KissError> {
+        let params = [parse_address(contract)?, parse_address(account)?];
+        self.precompiled_send(PERMISSION_ADDRESS, PERMISSION_ABI, "grantWrite", &params)
+    }
+    pub fn revoke_contract_write(
+        &mut self,
+        contract: &str,
+        account: &str,
+    ) -> Result<i64, KissError> {
+        let params = [parse_address(contract)?, parse_address(account)?];
+        self.precompiled_send(PERMISSION_ADDRESS, PERMISSION_ABI, "revokeWrite", &params)
+    }
+    pub fn list_contract_write(&mut self, contract: &str) -> Result<JsonValue, KissError> {
+        let params = [parse_address(contract)?];
+        self.precompiled_call_json(
+            PERMISSION_ADDRESS,
+            PERMISSION_ABI,
+            "queryPermission",
+            &params,
+        )
+    }
+
+    //---------------------------ContractLifeCycle 0x1007--------------------------------
+    pub fn freeze_contract(&mut self, contract: &str) -> Result<i64, KissError> {
+        let params = [parse_address(contract)?];
+        self.precompiled_send(
+            CONTRACT_LIFECYCLE_ADDRESS,
+            CONTRACT_LIFECYCLE_ABI,
+            "freeze",
+            &params,
+        )
+    }
+    pub fn unfreeze_contract(&mut self, contract: &str) -> Result<i64, KissError> {
+        let params = [parse_address(contract)?];
+        self.precompiled_send(
+            CONTRACT_LIFECYCLE_ADDRESS,
+            CONTRACT_LIFECYCLE_ABI,
+            "unfreeze",
+            &params,
+        )
+    }
+    ///授权account管理合约(冻结/解冻)，默认只有部署者是管理者
+    pub fn grant_contract_manager(
+        &mut self,
+        contract: &str,
+        account: &str,
+    ) -> Result<i64, KissError> {
+        let params = [parse_address(contract)?, parse_address(account)?];
+        self.precompiled_send(
+            CONTRACT_LIFECYCLE_ADDRESS,
+            CONTRACT_LIFECYCLE_ABI,
+            "grantManager",
+            &params,
+        )
+    }
+    ///返回合约的状态说明，如"The contract is available."，查询失败时返回EPrecompiled
+    pub fn get_contract_status(&mut self, contract: &str) -> Result<String, KissError> {
+        let params = [parse_address(contract)?];
+        let tokens = self.precompiled_call(
+            CONTRACT_LIFECYCLE_ADDRESS,
+            CONTRACT_LIFECYCLE_ABI,
+            "getStatus",
+            &params,
+        )?;
+        match (tokens.first().and_then(token_to_code), tokens.get(1)) {
+            (Some(code), Some(Token::String(msg))) => {
+                check_precompiled_code(code, "getStatus")?;
+                Ok(msg.clone())
+            }
+            _ => kisserr!(KissErrKind::EFormat, "getStatus output error {:?}", tokens),
+        }
+    }
+    pub fn list_contract_manager(&mut self, contract: &str) -> Result<Vec<String>, KissError> {
+        let params = [parse_address(contract)?];
+        let tokens = self.precompiled_call(
+            CONTRACT_LIFECYCLE_ADDRESS,
+            CONTRACT_LIFECYCLE_ABI,
+            "listManager",
+            &params,
+        )?;
+        match (tokens.first().and_then(token_to_code), tokens.get(1)) {
+            (Some(code), Some(Token::Array(managers))) => {
+                check_precompiled_code(code, "listManager")?;
+                Ok(managers
+                    .iter()
+                    .filter_map(|t| match t {
+                        Token::Address(a) => Some(format!("0x{}", hex::encode(a.as_bytes()))),
+                        _ => None,
+                    })
+                    .collect())
+            }
+            _ => kisserr!(
+                KissErrKind::EFormat,
+                "listManager output error {:?}",
+                tokens
+            ),
+        }
+    }
+}
@@ -4,30 +4,49 @@
 
 use std::ffi::{CStr, CString};
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Arc};
 
 use encoding::{DecoderTrap, Encoding};
 use encoding::all::GBK;
-use libc::{c_char, c_int, c_longlong, c_void};
+use libc::{c_char, c_int, c_longlong, c_uint, c_ulong, c_void};
+use ethabi::Token;
 use serde_json::Value as JsonValue;
 use time::{Duration, Tm};
 
 use crate::{kisserr, kisserrcode, str2p};
 use crate::bcos3sdk::bcos3sdk_ini::Bcos3sdkIni;
+use crate::bcos3sdk::bcos3sdkamop;
+use crate::bcos3sdk::bcos3sdkamop::Bcos3AmopMessage;
+use crate::bcos3sdk::bcos3sdkevent;
+use crate::bcos3sdk::bcos3sdkevent::{Bcos3EventReceiver, Bcos3EventSubscription};
 use crate::bcos3sdk::bcos3sdkfuture::Bcos3SDKFuture;
+use crate::bcos3sdk::bcos3sdkresponse::{bcos_sdk_c_struct_response, Bcos3SDKResponse};
 use crate::bcos3sdk::bcos3sdkwrapper::*;
 use crate::bcos3sdk::bcos3sdkwrapper::bcos3sdk_def::*;
-use crate::bcossdkutil::accountutil::{account_from_pem, BcosAccount};
+use crate::bcos3sdk::bcos3transaction::{bcos3_receipt_hash, Bcos3Transaction, Bcos3TransactionData};
+use crate::bcossdkutil::accountutil::{account_from_config, BcosAccount};
 use crate::bcossdkutil::bcosclientconfig::{BcosCryptoKind, ClientConfig};
+use crate::bcossdkutil::blocksignature;
+use crate::bcossdkutil::blocksignature::BlockSignatureReport;
 use crate::bcossdkutil::commonhash::{CommonHash, HashType};
+use crate::bcossdkutil::commonsigner::{CommonSignerWeDPR_Secp256, CommonSignerWeDPR_SM2, ICommonSigner};
 use crate::bcossdkutil::contractabi::ContractABI;
+use crate::bcossdkutil::externalsigner::ExternalSigner;
 use crate::bcossdkutil::fileutils;
 use crate::bcossdkutil::kisserror::{KissErrKind, KissError};
+use crate::bcossdkutil::merkleproof;
+use crate::bcossdkutil::txstatus::check_bcos3_receipt;
+use ethabi::Log as ReceiptLog;
 
 //定义一个结构体，简单包装sdk指针，有待扩展
 pub struct Bcos3Client {
     pub crytotype: i32,
     pub hashtype: HashType,
-    pub keypair: *const c_void,
+    //交易在rust里编码和签名，私钥不再传给c sdk
+    pub ecdsasigner: Option<CommonSignerWeDPR_Secp256>,
+    pub gmsigner: Option<CommonSignerWeDPR_SM2>,
+    ///外部或自定义的签名器，设置后优先于ecdsasigner/gmsigner
+    pub signer: Option<Box<dyn ICommonSigner + Send + Sync>>,
     pub account: BcosAccount,
     pub config: ClientConfig,
     pub bcos3sdkini: Bcos3sdkIni,
@@ -86,8 +105,36 @@ impl Bcos3Client {
         }
     }
     pub fn new(configfile: &str) -> Result<Self, KissError> {
+        let config = ClientConfig::load(configfile)?;
+        //配置了外部签名服务时，账户的公钥和地址从签名服务获取
+        match ExternalSigner::from_config(&config.common)? {
+            Some(signer) => {
+                let account = signer.account.clone();
+                Bcos3Client::build(config, account, Some(Box::new(signer)))
+            }
+            None => {
+                let account = account_from_config(&config.common)?;
+                Bcos3Client::build(config, account, None)
+            }
+        }
+    }
+
+    ///使用任意的签名器创建client，account为签名器对应的账户，私钥可以为空
+    pub fn new_with_signer(
+        configfile: &str,
+        signer: Box<dyn ICommonSigner + Send + Sync>,
+        account: BcosAccount,
+    ) -> Result<Self, KissError> {
+        let config = ClientConfig::load(configfile)?;
+        Bcos3Client::build(config, account, Some(signer))
+    }
+
+    fn build(
+        config: ClientConfig,
+        account: BcosAccount,
+        signer: Option<Box<dyn ICommonSigner + Send + Sync>>,
+    ) -> Result<Self, KissError> {
         unsafe {
-            let config = ClientConfig::load(configfile).unwrap();
             let sdk = init_bcos3sdk_lib(config.bcos3.sdk_config_file.as_str());
             if sdk == 0 as *const c_void {
                 return kisserr!(KissErrKind::Error,"BCOS3 C LIB is NOT init;ERROR:{}:{}",bcos_sdk_get_last_error(),Bcos3Client::getLastErrMessage());
@@ -97,21 +144,29 @@ impl Bcos3Client {
             }
             let bcos3sdkini = Bcos3sdkIni::load(config.bcos3.sdk_config_file.as_str())?;
             let mut cryptotype = 0;
-            let account =
-                account_from_pem(config.common.accountpem.as_str(), &config.common.crypto)?;
-            let privkey = hex::encode(&account.privkey);
             let hashtype = CommonHash::crypto_to_hashtype(&config.common.crypto);
-
+            let mut ecdsasigner = Option::None;
+            let mut gmsigner = Option::None;
             match &config.common.crypto {
                 BcosCryptoKind::ECDSA => {
                     cryptotype = 0;
+                    if signer.is_none() {
+                        ecdsasigner = Option::from(CommonSignerWeDPR_Secp256 {
+                            account: account.clone(),
+                            ..Default::default()
+                        });
+                    }
                 }
                 BcosCryptoKind::GM => {
                     cryptotype = 1;
+                    if signer.is_none() {
+                        gmsigner = Option::from(CommonSignerWeDPR_SM2 {
+                            account: account.clone(),
+                            ..Default::default()
+                        });
+                    }
                 }
             }
-            let keypair =
-                bcos_sdk_create_keypair_by_hex_private_key(cryptotype, str2p!(privkey.as_str()));
 
             let client = Bcos3Client {
                 clientname: "BCOS3".to_string(),
@@ -122,7 +177,9 @@ impl Bcos3Client {
                 chainid: "chain0".to_string(),
                 config: config,
                 bcos3sdkini: bcos3sdkini,
-                keypair: keypair,
+                ecdsasigner,
+                gmsigner,
+                signer,
                 account: account,
                 node: "".to_string(),
                 reqcounter: AtomicU64::new(0),
@@ -138,6 +195,8 @@ impl Bcos3Client {
             if self.sdk == 0 as *const c_void {
                 return;
             }
+            bcos3sdkamop::unregister_sdk(self.sdk);
+            bcos3sdkevent::unregister_sdk(self.sdk);
             bcos_sdk_stop(self.sdk);
             bcos_sdk_destroy(self.sdk);
             self.sdk = 0 as *const c_void;
@@ -500,6 +559,46 @@ impl Bcos3Client {
         }
     }
 
+    //3.x的交易json里没有块高，从回执里取，再查块头里的根
+    fn block_root_of(&self, hash: &str, name: &str) -> Result<String, KissError> {
+        let receipt = self.getTransactionReceipt(hash, 0)?;
+        let num = match receipt["blockNumber"].as_u64() {
+            Some(n) => n,
+            None => return kisserr!(KissErrKind::Error, "receipt of {} not found", hash),
+        };
+        let header = self.getBlockByNumber(num, 1, 1)?;
+        match header[name].as_str() {
+            Some(root) => Ok(root.to_string()),
+            None => kisserr!(KissErrKind::EFormat, "{} of block {} not found", name, num),
+        }
+    }
+
+    ///用交易的txProof和块头里的txsRoot校验交易确实在块里
+    pub fn verify_transaction_proof(&self, hash: &str) -> Result<bool, KissError> {
+        let tx = self.getTransactionByHash(hash, 1)?;
+        let root = self.block_root_of(hash, "txsRoot")?;
+        let data_hash = Bcos3TransactionData::from_json(&tx, &self.hashtype)?.hash();
+        merkleproof::verify_transaction_proof_v3(&tx, hash, &data_hash, &root, &self.hashtype)
+    }
+
+    ///用回执的receiptProof和块头里的receiptsRoot校验回执没有被篡改
+    pub fn verify_receipt_proof(&self, hash: &str) -> Result<bool, KissError> {
+        let receipt = self.getTransactionReceipt(hash, 1)?;
+        let root = self.block_root_of(hash, "receiptsRoot")?;
+        let receipt_hash = bcos3_receipt_hash(&receipt, &self.hashtype)?;
+        merkleproof::verify_receipt_proof_v3(&receipt, hash, &receipt_hash, &root, &self.hashtype)
+    }
+
+    ///取区块头，用当前的共识节点列表校验区块头hash和共识签名，返回签名的共识节点
+    pub fn verify_block_signatures(&self, num: u64) -> Result<BlockSignatureReport, KissError> {
+        let header = self.getBlockByNumber(num, 1, 1)?;
+        if header.is_null() {
+            return kisserr!(KissErrKind::Error, "block {} not found", num);
+        }
+        let sealers = self.getSealerList()?;
+        blocksignature::verify_block_signatures_v3(&header, &sealers, &self.hashtype)
+    }
+
     pub fn getPendingTxSize(&self) -> Result<JsonValue, KissError> {
         self.reqcounter.fetch_add(1, Ordering::Relaxed);
         unsafe {
@@ -562,13 +661,34 @@ impl Bcos3Client {
         funcname: &str,
         paramsvec: &Vec<String>,
         abi: &ContractABI,
+    ) -> Result<JsonValue, KissError> {
+        let functiondata = abi.encode_function_input_to_abi(funcname, &paramsvec, true)?;
+        self.call_rawdata(to, funcname, functiondata.as_str())
+    }
+
+    ///输入已经解析好的param，调用合约的只读方法
+    pub fn call_with_tokenparam(
+        &self,
+        to: &str,
+        funcname: &str,
+        params: &[Token],
+        abi: &ContractABI,
+    ) -> Result<JsonValue, KissError> {
+        let function = abi.find_function_unwrap(funcname)?;
+        let functiondata =
+            ContractABI::encode_function_input_to_abi_by_tokens(&function, params, &self.hashtype)?;
+        self.call_rawdata(to, funcname, hex::encode(functiondata).as_str())
+    }
+
+    ///functiondata为已经编码好的abi hex
+    pub fn call_rawdata(
+        &self,
+        to: &str,
+        funcname: &str,
+        functiondata: &str,
     ) -> Result<JsonValue, KissError> {
         self.reqcounter.fetch_add(1, Ordering::Relaxed);
         unsafe {
-            let functiondata = abi
-                .encode_function_input_to_abi(funcname, &paramsvec, true)
-                .unwrap();
-            let seq = 0;
             let cbfuture = Bcos3SDKFuture::create(Bcos3SDKFuture::next_seq(), funcname, "do call");
             bcos_rpc_call(
                 self.sdk,
@@ -585,65 +705,109 @@ impl Bcos3Client {
         }
     }
 
+    ///根据配置选择签名算法实现
+    pub fn pick_signer(&self) -> &dyn ICommonSigner {
+        if let Some(signer) = &self.signer {
+            return signer.as_ref();
+        }
+        match self.config.common.crypto {
+            BcosCryptoKind::ECDSA => self.ecdsasigner.as_ref().unwrap(),
+            BcosCryptoKind::GM => self.gmsigner.as_ref().unwrap(),
+        }
+    }
+
+    ///构造并签名交易，返回的交易encode_hex后即可发送，txhash_hex为交易hash。
+    ///blocklimit取自节点，离线构建时直接用Bcos3TransactionData::new指定blocklimit、chainid和group
+    pub fn make_signed_transaction(
+        &mut self,
+        to_address: &str,
+        functiondata: &str,
+    ) -> Result<Bcos3Transaction, KissError> {
+        let input = match hex::decode(functiondata.trim().trim_start_matches("0x")) {
+            Ok(data) => data,
+            Err(e) => {
+                return kisserr!(KissErrKind::EFormat, "function data is not hex {:?}", e);
+            }
+        };
+        let blocklimit = self.getBlocklimit()?;
+        let txdata = Bcos3TransactionData::new(
+            self.chainid.as_str(),
+            self.group.as_str(),
+            blocklimit as i64,
+            to_address,
+            input,
+            "",
+            &self.hashtype,
+        );
+        Bcos3Transaction::sign(self.pick_signer(), &txdata)
+    }
+
     pub fn sendRawTransaction(
         &mut self,
         to_address: &str,
         methodname: &str,
         functiondata: &str,
+    ) -> Result<JsonValue, KissError> {
+        let signedtx = self.make_signed_transaction(to_address, functiondata)?;
+        self.send_signed_transaction(signedtx.encode_hex().as_str(), methodname)
+    }
+
+    ///发送已经签名好的交易(Bcos3Transaction::encode_hex的结果，如离线签名的交易)，等待并返回回执。
+    ///交易上链但执行失败(status非0)时返回ETxFailed，msg里带解析后的状态和revert原因
+    pub fn send_signed_transaction(
+        &mut self,
+        signedtx_hex: &str,
+        methodname: &str,
     ) -> Result<JsonValue, KissError> {
         self.reqcounter.fetch_add(1, Ordering::Relaxed);
+        let signedtx_hex = match CString::new(signedtx_hex.trim().trim_start_matches("0x")) {
+            Ok(s) => s,
+            Err(e) => return kisserr!(KissErrKind::EArgument, "signed tx is not a valid string {:?}", e),
+        };
         unsafe {
-            let cbfuture = Bcos3SDKFuture::create(
+            let mut cbfuture = Bcos3SDKFuture::create(
                 Bcos3SDKFuture::next_seq(),
                 "sendTransction",
                 format!("{}", methodname).as_str(),
             );
-
-            //println!("function data len {}, {}", functiondata.len(), functiondata);
-            let p_txhash = Box::into_raw(Box::new(0 as *mut c_char));
-            let p_signed_tx = Box::into_raw(Box::new(0 as *mut c_char));
-            let blocklimit = self.getBlocklimit()?;
-
-            bcos_sdk_create_signed_transaction(
-                self.keypair,
-                str2p!(self.group.as_str()),
-                str2p!(self.chainid.as_str()),
-                str2p!(to_address),
-                str2p!(functiondata),
-                str2p!(""),
-                blocklimit as c_longlong,
-                0,
-                p_txhash,
-                p_signed_tx,
-            );
-            let lasterr = Bcos3Client::getLastError();
-            if lasterr != 0 {
-                let last_err_msg = Bcos3Client::getLastErrMessage();
-                return kisserrcode!(KissErrKind::Error, lasterr as i64, "{}", last_err_msg);
-            }
-            let txhash_str = CStr::from_ptr(*p_txhash);
-            let signed_tx_str = CStr::from_ptr(*p_signed_tx);
-
-            //println!("txhash {:?}", txhash_str);
-            //println!("signed_tx {:?}", signed_tx_str);
+            //c sdk在交易上链后才回调，等待时间和bcos2一样取receipt_timeout_sec
+            cbfuture.timeout = self.config.bcos2.receipt_timeout_sec as u64;
             bcos_rpc_send_transaction(
                 self.sdk,
                 str2p!(self.group.as_str()),
                 0 as *const c_char,
-                *p_signed_tx,
+                signedtx_hex.as_ptr(),
                 0,
                 Bcos3SDKFuture::bcos_callback as BCOS3SDK_CALLBACK_FUNC,
                 Bcos3SDKFuture::to_c_ptr(&cbfuture),
             );
-            bcos_sdk_c_free(*p_txhash as *const c_void);
-            bcos_sdk_c_free(*p_signed_tx as *const c_void);
-
             let result = cbfuture.wait_result()?;
-
+            check_bcos3_receipt(&result)?;
             Ok(result)
         }
     }
 
+    ///发送已经签名好的交易，不等回执，返回交易hash(从交易里解出)，回执用getTransactionReceipt查。
+    ///回调不带context，节点拒绝交易时只记日志，交易会一直查不到回执
+    pub fn send_signed_transaction_nowait(&mut self, signedtx_hex: &str) -> Result<String, KissError> {
+        let signedtx_hex = signedtx_hex.trim().trim_start_matches("0x");
+        let txhash = Bcos3Transaction::decode_hex(signedtx_hex)?.txhash_hex();
+        self.reqcounter.fetch_add(1, Ordering::Relaxed);
+        let csignedtx = CString::new(signedtx_hex).unwrap();
+        unsafe {
+            bcos_rpc_send_transaction(
+                self.sdk,
+                str2p!(self.group.as_str()),
+                std::ptr::null(),
+                csignedtx.as_ptr(),
+                0,
+                nowait_callback as BCOS3SDK_CALLBACK_FUNC,
+                std::ptr::null(),
+            );
+        }
+        Ok(txhash)
+    }
+
     pub fn sendTransaction(
         &mut self,
         to_address: &str,
@@ -708,4 +872,183 @@ impl Bcos3Client {
         );
         self.deploy_file(binfile.as_str(), paramcode.as_str())
     }
+
+    //-----------------------------------------------------------------------------------
+    //AMOP，回调用闭包传入，路由见bcos3sdkamop.rs
+    fn check_last_error(opr: &str) -> Result<(), KissError> {
+        unsafe {
+            if bcos_sdk_get_last_error() != 0 {
+                return kisserrcode!(KissErrKind::Error, bcos_sdk_get_last_error() as i64, "{} error: {}", opr, Bcos3Client::getLastErrMessage());
+            }
+        }
+        Ok(())
+    }
+
+    ///订阅topic，收到消息时调用callback，callback返回Some时作为回包发给请求方
+    pub fn amop_subscribe<F>(&self, topic: &str, callback: F) -> Result<(), KissError>
+    where
+        F: Fn(&Bcos3AmopMessage) -> Option<Vec<u8>> + Send + Sync + 'static,
+    {
+        self.reqcounter.fetch_add(1, Ordering::Relaxed);
+        let id = bcos3sdkamop::register_subscriber(self.sdk, topic, Arc::new(callback));
+        unsafe {
+            bcos_amop_subscribe_topic_with_cb(
+                self.sdk,
+                str2p!(topic),
+                bcos3sdkamop::amop_sub_callback as BCOS3SDK_AMOP_SUB_CALLBACK_FUNC,
+                bcos3sdkamop::context_ptr(id),
+            );
+        }
+        if let Err(e) = Bcos3Client::check_last_error("amop subscribe") {
+            bcos3sdkamop::unregister_subscriber(self.sdk, topic);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    pub fn amop_unsubscribe(&self, topic: &str) -> Result<(), KissError> {
+        self.reqcounter.fetch_add(1, Ordering::Relaxed);
+        bcos3sdkamop::unregister_subscriber(self.sdk, topic);
+        let ctopic = match CString::new(topic) {
+            Ok(t) => t,
+            Err(e) => return kisserr!(KissErrKind::EArgument, "bad amop topic {} {:?}", topic, e),
+        };
+        let topics = [ctopic.as_ptr()];
+        unsafe {
+            bcos_amop_unsubscribe_topic(self.sdk, topics.as_ptr(), 1);
+        }
+        Bcos3Client::check_last_error("amop unsubscribe")
+    }
+
+    pub fn amop_subscribed_topics(&self) -> Vec<String> {
+        bcos3sdkamop::subscribed_topics(self.sdk)
+    }
+
+    ///单播给topic的一个订阅方，应答(或超时、出错)时调用callback，不阻塞
+    pub fn amop_publish<F>(&self, topic: &str, data: &[u8], timeout_ms: u32, callback: F) -> Result<(), KissError>
+    where
+        F: FnOnce(Result<Vec<u8>, KissError>) + Send + 'static,
+    {
+        self.reqcounter.fetch_add(1, Ordering::Relaxed);
+        let id = bcos3sdkamop::register_publish(Box::new(callback));
+        unsafe {
+            bcos_amop_publish(
+                self.sdk,
+                str2p!(topic),
+                data.as_ptr() as *const c_void,
+                data.len() as c_ulong,
+                timeout_ms as c_uint,
+                bcos3sdkamop::amop_publish_callback as BCOS3SDK_CALLBACK_FUNC,
+                bcos3sdkamop::context_ptr(id),
+            );
+        }
+        if let Err(e) = Bcos3Client::check_last_error("amop publish") {
+            bcos3sdkamop::unregister_publish(id);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    ///同步版的amop_publish，返回对方的回包
+    pub fn amop_request(&self, topic: &str, data: &[u8], timeout_ms: u32) -> Result<Vec<u8>, KissError> {
+        let (tx, rx) = mpsc::channel();
+        self.amop_publish(topic, data, timeout_ms, move |res| {
+            let _ = tx.send(res);
+        })?;
+        //c sdk超时后也会回调，这里多等一会，防止c sdk没有回调时一直阻塞
+        match rx.recv_timeout(std::time::Duration::from_millis(timeout_ms as u64 + 1000)) {
+            Ok(res) => res,
+            Err(e) => kisserr!(
+                KissErrKind::ETimeout,
+                "amop request to topic {} timeout {:?}",
+                topic,
+                e
+            ),
+        }
+    }
+
+    ///广播给topic的全部订阅方，没有应答
+    pub fn amop_broadcast(&self, topic: &str, data: &[u8]) -> Result<(), KissError> {
+        self.reqcounter.fetch_add(1, Ordering::Relaxed);
+        unsafe {
+            bcos_amop_broadcast(self.sdk, str2p!(topic), data.as_ptr() as *const c_void, data.len() as c_ulong);
+        }
+        Bcos3Client::check_last_error("amop broadcast")
+    }
+
+    ///给收到的消息回包，endpoint和seq取自Bcos3AmopMessage。订阅回调里返回Some时会自动回包，不需要再调用
+    pub fn amop_send_response(&self, endpoint: &str, seq: &str, data: &[u8]) -> Result<(), KissError> {
+        self.reqcounter.fetch_add(1, Ordering::Relaxed);
+        unsafe {
+            bcos_amop_send_response(
+                self.sdk,
+                str2p!(endpoint),
+                str2p!(seq),
+                data.as_ptr() as *const c_void,
+                data.len() as c_ulong,
+            );
+        }
+        Bcos3Client::check_last_error("amop send response")
+    }
+
+    //-----------------------------------------------------------------------------------
+    //事件订阅，日志按contract的abi解析后送给闭包，路由见bcos3sdkevent.rs
+    ///订阅合约事件，每次推送调用一次callback。返回的句柄用于取消订阅，finish时自动全部取消
+    pub fn subscribe_event<F>(
+        &self,
+        param: &EventSubParam,
+        contract: &ContractABI,
+        callback: F,
+    ) -> Result<Bcos3EventSubscription, KissError>
+    where
+        F: Fn(Result<Vec<ReceiptLog>, KissError>) + Send + Sync + 'static,
+    {
+        self.reqcounter.fetch_add(1, Ordering::Relaxed);
+        let paramstr = match serde_json::to_string(param) {
+            Ok(s) => s,
+            Err(e) => return kisserr!(KissErrKind::EFormat, "event sub param {:?}", e),
+        };
+        let id = bcos3sdkevent::register_subscriber(self.sdk, contract, Arc::new(callback));
+        let task_id = unsafe {
+            let ptask = bcos_event_sub_subscribe_event(
+                self.sdk,
+                str2p!(self.group.as_str()),
+                str2p!(paramstr.as_str()),
+                bcos3sdkevent::event_sub_callback as BCOS3SDK_CALLBACK_FUNC,
+                bcos3sdkevent::context_ptr(id),
+            );
+            if ptask.is_null() {
+                "".to_string()
+            } else {
+                CStr::from_ptr(ptask).to_string_lossy().to_string()
+            }
+        };
+        if let Err(e) = Bcos3Client::check_last_error("event subscribe") {
+            bcos3sdkevent::unregister_subscriber(id);
+            return Err(e);
+        }
+        Ok(Bcos3EventSubscription::new(self.sdk, id, task_id.as_str()))
+    }
+
+    ///订阅合约事件，推送的日志从返回的tokio channel里读取，适合在async代码里使用
+    pub fn subscribe_event_stream(
+        &self,
+        param: &EventSubParam,
+        contract: &ContractABI,
+    ) -> Result<(Bcos3EventSubscription, Bcos3EventReceiver), KissError> {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let subscription = self.subscribe_event(param, contract, move |logs| {
+            //接收方已经drop时丢弃
+            let _ = tx.send(logs);
+        })?;
+        Ok((subscription, rx))
+    }
+}
+
+//不等回执的交易，回调里只检查有没有出错
+extern "C" fn nowait_callback(resp: *const bcos_sdk_c_struct_response) {
+    let response = Bcos3SDKResponse::from_callback(resp);
+    if let Err(e) = response.get_result() {
+        log::warn!("send transaction without waiting error {:?}", e);
+    }
 }
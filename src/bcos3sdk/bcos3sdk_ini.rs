@@ -8,6 +8,14 @@ use crate::kisserr;
 pub struct Bcos3sdkIni {
     pub values: toml::Value,
     pub peers: HashMap<String, String>,
+    //以下配置项在bcos3sdk_native的纯rust实现里使用，c sdk自己会读ini
+    pub disable_ssl: bool,
+    pub message_timeout_ms: u64,
+    pub ssl_type: String,
+    pub ca_path: String,
+    pub ca_cert: String,
+    pub sdk_key: String,
+    pub sdk_cert: String,
 }
 
 impl Bcos3sdkIni {
@@ -15,6 +23,13 @@ impl Bcos3sdkIni {
         let mut sdkini = Bcos3sdkIni {
             values: toml::Value::String("".to_string()),
             peers: HashMap::new(),
+            disable_ssl: false,
+            message_timeout_ms: 10000,
+            ssl_type: "ssl".to_string(),
+            ca_path: "./conf".to_string(),
+            ca_cert: "ca.crt".to_string(),
+            sdk_key: "sdk.key".to_string(),
+            sdk_cert: "sdk.crt".to_string(),
         };
         let confres = Ini::load_from_file(config_file);
         if confres.is_err() {
@@ -28,7 +43,27 @@ impl Bcos3sdkIni {
                 sdkini.peers.insert(key.to_string(), value.to_string());
             }
         }
-
+        if let Some(common) = config.section(Some("common")) {
+            if let Some(v) = common.get("disable_ssl") {
+                sdkini.disable_ssl = v.trim() == "true";
+            }
+            if let Some(v) = common.get("message_timeout_ms") {
+                sdkini.message_timeout_ms = v.trim().parse().unwrap_or(sdkini.message_timeout_ms);
+            }
+        }
+        if let Some(cert) = config.section(Some("cert")) {
+            for (key, value) in cert.iter() {
+                let value = value.trim().to_string();
+                match key {
+                    "ssl_type" => sdkini.ssl_type = value,
+                    "ca_path" => sdkini.ca_path = value,
+                    "ca_cert" => sdkini.ca_cert = value,
+                    "sdk_key" => sdkini.sdk_key = value,
+                    "sdk_cert" => sdkini.sdk_cert = value,
+                    _ => {}
+                }
+            }
+        }
         Ok(sdkini)
     }
 }
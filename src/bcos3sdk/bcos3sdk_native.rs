@@ -0,0 +1,747 @@
+/*
+  FISCO BCOS/rust-SDK is a rust client for FISCO BCOS2.0 (https://github.com/FISCO-BCOS/)
+  FISCO BCOS/rust-SDK is free software: you can redistribute it and/or modify it under the
+  terms of the MIT License as published by the Free Software Foundation. This project is
+  distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even
+  the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+  @author: kentzhang
+  @date: 2021-07
+*/
+#![allow(
+    clippy::unreadable_literal,
+    clippy::upper_case_acronyms,
+    clippy::missing_safety_doc,
+    clippy::not_unsafe_ptr_arg_deref,
+    clippy::too_many_arguments,
+    dead_code,
+    non_camel_case_types,
+    non_snake_case,
+    non_upper_case_globals,
+    overflowing_literals,
+    unused_variables,
+    unused_assignments
+)]
+
+/*
+bcos3sdk_native特性下，用纯rust实现和bcos3sdk_def(c sdk的ffi映射)同名同参数的接口，
+Bcos3Client等上层代码无需修改，也不再需要随平台分发bcos-c-sdk动态库。
+sdk指针指向Bcos3NativeSdk，内部是Bcos3WsClient；回调在调用线程里同步触发，
+Bcos3SDKFuture的mpsc channel可以先fire再wait，所以上层的同步等待逻辑照常工作。
+限制：同步实现没有后台读线程，收不到节点主动推送的事件和AMOP消息，所以事件订阅和AMOP接口
+不可用，调用时设置ERR_NOT_IMPLEMENTED，Bcos3Client的订阅/发送接口据此立即返回错误。
+需要事件订阅或AMOP时请用bcos3sdk_ffi特性(c sdk)。
+*/
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ffi::{c_longlong, CStr, CString};
+use std::sync::Mutex;
+
+use libc::{c_char, c_int, c_uint, c_ulong, c_void};
+use serde_json::{json, Value as JsonValue};
+
+use crate::bcos3sdk::bcos3sdk_ini::Bcos3sdkIni;
+use crate::bcos3sdk::bcos3sdkresponse::bcos_sdk_c_struct_response;
+use crate::bcos3sdk::bcos3sdkwrapper::{BCOS3SDK_AMOP_SUB_CALLBACK_FUNC, BCOS3SDK_CALLBACK_FUNC};
+use crate::bcos3sdk::bcos3transaction::{Bcos3Transaction, Bcos3TransactionData};
+use crate::bcos3sdk::bcos3wsclient::Bcos3WsClient;
+use crate::bcossdkutil::commonhash::HashType;
+use crate::bcossdkutil::commonsigner::{
+    CommonSignerWeDPR_SM2, CommonSignerWeDPR_Secp256, ICommonSigner,
+};
+use crate::bcossdkutil::kisserror::{KissErrKind, KissError};
+use crate::kisserr;
+
+//和c sdk一样，blocklimit为当前块高加上这个值
+pub const BCOS3_BLOCK_LIMIT_RANGE: u64 = 500;
+const ERR_NOT_IMPLEMENTED: c_int = -32999;
+
+static NATIVE_SDK_VERSION: &str = concat!("bcos3sdk_native ", env!("CARGO_PKG_VERSION"), "\0");
+
+thread_local! {
+    static LAST_ERROR: RefCell<(c_int, CString)> = RefCell::new((0, CString::default()));
+}
+
+fn set_last_error(code: c_int, msg: &str) {
+    let cmsg = CString::new(msg.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|e| *e.borrow_mut() = (code, cmsg));
+}
+
+fn clear_last_error() {
+    set_last_error(0, "");
+}
+
+pub struct Bcos3NativeSdk {
+    pub client: Mutex<Bcos3WsClient>,
+    //bcos_sdk_get_group_chain_id返回的字符串由sdk持有，和c sdk一样在destroy之前一直有效
+    chain_ids: Mutex<HashMap<String, CString>>,
+}
+
+//bcos_sdk_create_keypair_by_hex_private_key返回的对象，签名时使用
+#[derive(Debug, Clone)]
+pub struct Bcos3NativeKeyPair {
+    pub crypto_type: i32,
+    pub privkey: Vec<u8>,
+}
+
+unsafe fn p2s(p: *const c_char) -> String {
+    if p.is_null() {
+        return "".to_string();
+    }
+    CStr::from_ptr(p).to_string_lossy().to_string()
+}
+
+unsafe fn native_sdk<'a>(sdk: *const c_void) -> Result<&'a Bcos3NativeSdk, KissError> {
+    if sdk.is_null() {
+        return kisserr!(KissErrKind::EArgument, "sdk is null");
+    }
+    Ok(&*(sdk as *const Bcos3NativeSdk))
+}
+
+///把结果按c sdk的回调结构体传给回调函数，data为节点返回的jsonrpc应答原文
+unsafe fn fire_callback(
+    result: Result<String, KissError>,
+    callback: BCOS3SDK_CALLBACK_FUNC,
+    context: *const c_void,
+) {
+    let (error, desc, data) = match result {
+        Ok(data) => (0, "".to_string(), data),
+        Err(e) => {
+            let code = if e.code == 0 { -1 } else { e.code as i32 };
+            (code, e.msg, "".to_string())
+        }
+    };
+    let cdesc = CString::new(desc.replace('\0', " ")).unwrap_or_default();
+    let resp = bcos_sdk_c_struct_response {
+        error,
+        desc: cdesc.as_ptr(),
+        data: if data.is_empty() {
+            std::ptr::null()
+        } else {
+            data.as_ptr() as *const c_void
+        },
+        size: data.len(),
+        context,
+    };
+    callback(&resp as *const bcos_sdk_c_struct_response);
+}
+
+///发一个jsonrpc请求，锁只在请求期间持有，回调在释放锁之后触发，避免回调里再调用sdk时死锁
+unsafe fn rpc_call(
+    sdk: *const c_void,
+    method: &str,
+    params: JsonValue,
+    callback: BCOS3SDK_CALLBACK_FUNC,
+    context: *const c_void,
+) {
+    let result = native_sdk(sdk).and_then(|s| match s.client.lock() {
+        Ok(mut client) => client.rpc_request_sync(method, &params),
+        Err(e) => kisserr!(KissErrKind::Error, "sdk lock error {:?}", e),
+    });
+    fire_callback(result, callback, context);
+}
+
+pub unsafe fn bcos_sdk_version() -> *const c_char {
+    NATIVE_SDK_VERSION.as_ptr() as *const c_char
+}
+pub unsafe fn bcos_sdk_create_by_config_file(configfile: *const c_char) -> *const c_void {
+    clear_last_error();
+    let configfile = p2s(configfile);
+    let sdkini = match Bcos3sdkIni::load(configfile.as_str()) {
+        Ok(ini) => ini,
+        Err(e) => {
+            set_last_error(-1, e.msg.as_str());
+            return std::ptr::null();
+        }
+    };
+    match Bcos3WsClient::connect(&sdkini) {
+        Ok(client) => {
+            let sdk = Box::new(Bcos3NativeSdk {
+                client: Mutex::new(client),
+                chain_ids: Mutex::new(HashMap::new()),
+            });
+            Box::into_raw(sdk) as *const c_void
+        }
+        Err(e) => {
+            set_last_error(-1, format!("{:?}:{}", e.kind, e.msg).as_str());
+            std::ptr::null()
+        }
+    }
+}
+//连接在create时已经建立，这里无需再做什么
+pub unsafe fn bcos_sdk_start(sdk: *const c_void) {}
+pub unsafe fn bcos_sdk_stop(sdk: *const c_void) {
+    if let Ok(s) = native_sdk(sdk) {
+        if let Ok(mut client) = s.client.lock() {
+            client.finish();
+        }
+    }
+}
+pub unsafe fn bcos_sdk_destroy(sdk: *const c_void) {
+    if !sdk.is_null() {
+        drop(Box::from_raw(sdk as *mut Bcos3NativeSdk));
+    }
+}
+pub unsafe fn bcos_sdk_get_last_error_msg() -> *const c_char {
+    LAST_ERROR.with(|e| e.borrow().1.as_ptr())
+}
+pub unsafe fn bcos_sdk_is_last_opr_success() -> c_int {
+    LAST_ERROR.with(|e| (e.borrow().0 == 0) as c_int)
+}
+pub unsafe fn bcos_sdk_get_last_error() -> c_int {
+    LAST_ERROR.with(|e| e.borrow().0)
+}
+pub unsafe fn bcos_rpc_get_group_info(
+    sdk: *const c_void,
+    group: *const c_char,
+    callback: BCOS3SDK_CALLBACK_FUNC,
+    context: *const c_void,
+) {
+    rpc_call(sdk, "getGroupInfo", json!([p2s(group)]), callback, context);
+}
+pub unsafe fn bcos_rpc_get_group_list(
+    sdk: *const c_void,
+    callback: BCOS3SDK_CALLBACK_FUNC,
+    context: *const c_void,
+) {
+    rpc_call(sdk, "getGroupList", json!([]), callback, context);
+}
+pub unsafe fn bcos_rpc_get_group_info_list(
+    sdk: *const c_void,
+    callback: BCOS3SDK_CALLBACK_FUNC,
+    context: *const c_void,
+) {
+    rpc_call(sdk, "getGroupInfoList", json!([]), callback, context);
+}
+pub unsafe fn bcos_rpc_get_group_node_info(
+    sdk: *const c_void,
+    group: *const c_char,
+    node: *const c_char,
+    callback: BCOS3SDK_CALLBACK_FUNC,
+    context: *const c_void,
+) {
+    let params = json!([p2s(group), p2s(node)]);
+    rpc_call(sdk, "getGroupNodeInfo", params, callback, context);
+}
+pub unsafe fn bcos_rpc_get_transaction(
+    sdk: *const c_void,
+    group: *const c_char,
+    node: *const c_char,
+    tx_hash: *const c_char,
+    proof: c_int,
+    callback: BCOS3SDK_CALLBACK_FUNC,
+    context: *const c_void,
+) {
+    let params = json!([p2s(group), p2s(node), p2s(tx_hash), proof != 0]);
+    rpc_call(sdk, "getTransaction", params, callback, context);
+}
+pub unsafe fn bcos_rpc_get_transaction_receipt(
+    sdk: *const c_void,
+    group: *const c_char,
+    node: *const c_char,
+    tx_hash: *const c_char,
+    proof: c_int,
+    callback: BCOS3SDK_CALLBACK_FUNC,
+    context: *const c_void,
+) {
+    let params = json!([p2s(group), p2s(node), p2s(tx_hash), proof != 0]);
+    rpc_call(sdk, "getTransactionReceipt", params, callback, context);
+}
+pub unsafe fn bcos_rpc_get_block_by_hash(
+    sdk: *const c_void,
+    group: *const c_char,
+    node: *const c_char,
+    block_hash: *const c_char,
+    only_header: c_int,
+    only_tx_hash: c_int,
+    callback: BCOS3SDK_CALLBACK_FUNC,
+    context: *const c_void,
+) {
+    let params = json!([
+        p2s(group),
+        p2s(node),
+        p2s(block_hash),
+        only_header != 0,
+        only_tx_hash != 0
+    ]);
+    rpc_call(sdk, "getBlockByHash", params, callback, context);
+}
+pub unsafe fn bcos_rpc_get_block_by_number(
+    sdk: *const c_void,
+    group: *const c_char,
+    node: *const c_char,
+    block_number: c_longlong,
+    only_header: c_int,
+    only_tx_hash: c_int,
+    callback: BCOS3SDK_CALLBACK_FUNC,
+    context: *const c_void,
+) {
+    let params = json!([
+        p2s(group),
+        p2s(node),
+        block_number,
+        only_header != 0,
+        only_tx_hash != 0
+    ]);
+    rpc_call(sdk, "getBlockByNumber", params, callback, context);
+}
+pub unsafe fn bcos_rpc_get_block_hash_by_number(
+    sdk: *const c_void,
+    group: *const c_char,
+    node: *const c_char,
+    block_number: c_longlong,
+    callback: BCOS3SDK_CALLBACK_FUNC,
+    context: *const c_void,
+) {
+    let params = json!([p2s(group), p2s(node), block_number]);
+    rpc_call(sdk, "getBlockHashByNumber", params, callback, context);
+}
+///优先用握手和新区块通知缓存的块高，没有的话去节点查一次
+pub unsafe fn bcos_rpc_get_block_limit(sdk: *const c_void, group: *const c_char) -> c_longlong {
+    let group = p2s(group);
+    let s = match native_sdk(sdk) {
+        Ok(s) => s,
+        Err(e) => return -1,
+    };
+    let mut client = match s.client.lock() {
+        Ok(c) => c,
+        Err(e) => return -1,
+    };
+    let num = match client.blocknumber(group.as_str()) {
+        Ok(n) => n,
+        Err(e) => {
+            set_last_error(-1, e.msg.as_str());
+            return -1;
+        }
+    };
+    (num + BCOS3_BLOCK_LIMIT_RANGE) as c_longlong
+}
+pub unsafe fn bcos_rpc_get_block_number(
+    sdk: *const c_void,
+    group: *const c_char,
+    node: *const c_char,
+    callback: BCOS3SDK_CALLBACK_FUNC,
+    context: *const c_void,
+) {
+    rpc_call(
+        sdk,
+        "getBlockNumber",
+        json!([p2s(group), p2s(node)]),
+        callback,
+        context,
+    );
+}
+pub unsafe fn bcos_rpc_get_code(
+    sdk: *const c_void,
+    group: *const c_char,
+    node: *const c_char,
+    address: *const c_char,
+    callback: BCOS3SDK_CALLBACK_FUNC,
+    context: *const c_void,
+) {
+    let params = json!([p2s(group), p2s(node), p2s(address)]);
+    rpc_call(sdk, "getCode", params, callback, context);
+}
+pub unsafe fn bcos_rpc_get_sealer_list(
+    sdk: *const c_void,
+    group: *const c_char,
+    node: *const c_char,
+    callback: BCOS3SDK_CALLBACK_FUNC,
+    context: *const c_void,
+) {
+    rpc_call(
+        sdk,
+        "getSealerList",
+        json!([p2s(group), p2s(node)]),
+        callback,
+        context,
+    );
+}
+pub unsafe fn bcos_rpc_get_observer_list(
+    sdk: *const c_void,
+    group: *const c_char,
+    node: *const c_char,
+    callback: BCOS3SDK_CALLBACK_FUNC,
+    context: *const c_void,
+) {
+    rpc_call(
+        sdk,
+        "getObserverList",
+        json!([p2s(group), p2s(node)]),
+        callback,
+        context,
+    );
+}
+pub unsafe fn bcos_rpc_get_pbft_view(
+    sdk: *const c_void,
+    group: *const c_char,
+    node: *const c_char,
+    callback: BCOS3SDK_CALLBACK_FUNC,
+    context: *const c_void,
+) {
+    rpc_call(
+        sdk,
+        "getPbftView",
+        json!([p2s(group), p2s(node)]),
+        callback,
+        context,
+    );
+}
+pub unsafe fn bcos_rpc_get_pending_tx_size(
+    sdk: *const c_void,
+    group: *const c_char,
+    node: *const c_char,
+    callback: BCOS3SDK_CALLBACK_FUNC,
+    context: *const c_void,
+) {
+    rpc_call(
+        sdk,
+        "getPendingTxSize",
+        json!([p2s(group), p2s(node)]),
+        callback,
+        context,
+    );
+}
+pub unsafe fn bcos_rpc_get_sync_status(
+    sdk: *const c_void,
+    group: *const c_char,
+    node: *const c_char,
+    callback: BCOS3SDK_CALLBACK_FUNC,
+    context: *const c_void,
+) {
+    rpc_call(
+        sdk,
+        "getSyncStatus",
+        json!([p2s(group), p2s(node)]),
+        callback,
+        context,
+    );
+}
+pub unsafe fn bcos_rpc_get_consensus_status(
+    sdk: *const c_void,
+    group: *const c_char,
+    node: *const c_char,
+    callback: BCOS3SDK_CALLBACK_FUNC,
+    context: *const c_void,
+) {
+    rpc_call(
+        sdk,
+        "getConsensusStatus",
+        json!([p2s(group), p2s(node)]),
+        callback,
+        context,
+    );
+}
+pub unsafe fn bcos_rpc_get_system_config_by_key(
+    sdk: *const c_void,
+    group: *const c_char,
+    node: *const c_char,
+    key: *const c_char,
+    callback: BCOS3SDK_CALLBACK_FUNC,
+    context: *const c_void,
+) {
+    let params = json!([p2s(group), p2s(node), p2s(key)]);
+    rpc_call(sdk, "getSystemConfigByKey", params, callback, context);
+}
+pub unsafe fn bcos_rpc_get_total_transaction_count(
+    sdk: *const c_void,
+    group: *const c_char,
+    node: *const c_char,
+    callback: BCOS3SDK_CALLBACK_FUNC,
+    context: *const c_void,
+) {
+    let params = json!([p2s(group), p2s(node)]);
+    rpc_call(sdk, "getTotalTransactionCount", params, callback, context);
+}
+pub unsafe fn bcos_rpc_get_group_peers(
+    sdk: *const c_void,
+    group: *const c_char,
+    callback: BCOS3SDK_CALLBACK_FUNC,
+    context: *const c_void,
+) {
+    rpc_call(sdk, "getGroupPeers", json!([p2s(group)]), callback, context);
+}
+pub unsafe fn bcos_rpc_get_peers(
+    sdk: *const c_void,
+    callback: BCOS3SDK_CALLBACK_FUNC,
+    context: *const c_void,
+) {
+    rpc_call(sdk, "getPeers", json!([]), callback, context);
+}
+
+//Event和AMOP需要接收节点推送，native实现不支持，见文件头说明。订阅时直接报错，
+//取消订阅时本来就没有订阅成功的任务，什么都不做
+const EVENT_NOT_SUPPORTED: &str =
+    "event subscribe is not supported by bcos3sdk_native, build with feature bcos3sdk_ffi to use it";
+const AMOP_NOT_SUPPORTED: &str =
+    "amop is not supported by bcos3sdk_native, build with feature bcos3sdk_ffi to use it";
+
+pub unsafe fn bcos_event_sub_subscribe_event(
+    sdk: *const c_void,
+    group: *const c_char,
+    param: *const c_char,
+    callback: BCOS3SDK_CALLBACK_FUNC,
+    context: *const c_void,
+) -> *const c_char {
+    set_last_error(ERR_NOT_IMPLEMENTED, EVENT_NOT_SUPPORTED);
+    std::ptr::null()
+}
+pub unsafe fn bcos_event_sub_unsubscribe_event(sdk: *const c_void, group: *const c_char) {}
+
+pub unsafe fn bcos_amop_subscribe_topic(
+    sdk: *const c_void,
+    topics: *const *const c_char,
+    count: c_ulong,
+) {
+    set_last_error(ERR_NOT_IMPLEMENTED, AMOP_NOT_SUPPORTED);
+}
+pub unsafe fn bcos_amop_subscribe_topic_with_cb(
+    sdk: *const c_void,
+    topic: *const c_char,
+    callback: BCOS3SDK_AMOP_SUB_CALLBACK_FUNC,
+    context: *const c_void,
+) {
+    set_last_error(ERR_NOT_IMPLEMENTED, AMOP_NOT_SUPPORTED);
+}
+pub unsafe fn bcos_amop_unsubscribe_topic(
+    sdk: *const c_void,
+    topics: *const *const c_char,
+    count: c_ulong,
+) {
+    clear_last_error();
+}
+pub unsafe fn bcos_amop_publish(
+    sdk: *const c_void,
+    topic: *const c_char,
+    data: *const c_void,
+    size: c_ulong,
+    timeout: c_uint,
+    callback: BCOS3SDK_CALLBACK_FUNC,
+    context: *const c_void,
+) {
+    set_last_error(ERR_NOT_IMPLEMENTED, AMOP_NOT_SUPPORTED);
+}
+pub unsafe fn bcos_amop_broadcast(
+    sdk: *const c_void,
+    topic: *const c_char,
+    data: *const c_void,
+    size: c_ulong,
+) {
+    set_last_error(ERR_NOT_IMPLEMENTED, AMOP_NOT_SUPPORTED);
+}
+pub unsafe fn bcos_amop_send_response(
+    sdk: *const c_void,
+    peer: *const c_char,
+    seq: *const c_char,
+    data: *const c_void,
+    size: c_ulong,
+) {
+    set_last_error(ERR_NOT_IMPLEMENTED, AMOP_NOT_SUPPORTED);
+}
+
+//密钥对只是保存私钥，供构造签名交易时使用。crypto_type: 0 ecdsa, 1 国密
+pub unsafe fn bcos_sdk_create_keypair(crypto_type: c_int) -> *const c_void {
+    let privkey: [u8; 32] = rand::random();
+    let keypair = Bcos3NativeKeyPair {
+        crypto_type,
+        privkey: privkey.to_vec(),
+    };
+    Box::into_raw(Box::new(keypair)) as *const c_void
+}
+pub unsafe fn bcos_sdk_create_keypair_by_private_key(
+    crypto_type: c_int,
+    private_key: *const c_char,
+) {
+}
+pub unsafe fn bcos_sdk_create_keypair_by_hex_private_key(
+    crypto_type: c_int,
+    private_key: *const c_char,
+) -> *const c_void {
+    let privkey = match hex::decode(p2s(private_key).trim_start_matches("0x")) {
+        Ok(k) => k,
+        Err(e) => {
+            set_last_error(-1, format!("private key format error {:?}", e).as_str());
+            return std::ptr::null();
+        }
+    };
+    let keypair = Bcos3NativeKeyPair {
+        crypto_type,
+        privkey,
+    };
+    Box::into_raw(Box::new(keypair)) as *const c_void
+}
+pub unsafe fn bcos_sdk_get_keypair_type(private_key: *const c_char) {}
+pub unsafe fn bcos_sdk_get_keypair_public_key(key_pair: *const c_void) {}
+pub unsafe fn bcos_sdk_get_keypair_private_key(key_pair: *const c_void) {}
+///从握手时节点返回的群组信息里取chainID
+pub unsafe fn bcos_sdk_get_group_chain_id(
+    sdk: *const c_void,
+    group: *const c_char,
+) -> *const c_char {
+    clear_last_error();
+    let group = p2s(group);
+    let sdk = match native_sdk(sdk) {
+        Ok(s) => s,
+        Err(e) => {
+            set_last_error(-1, e.msg.as_str());
+            return std::ptr::null();
+        }
+    };
+    let info = match sdk.client.lock() {
+        Ok(client) => client.find_group_info(group.as_str()),
+        Err(_) => None,
+    };
+    let chain_id = match info.as_ref().and_then(|i| i["chainID"].as_str()) {
+        Some(id) => id.to_string(),
+        None => {
+            set_last_error(
+                -1,
+                format!("chain id of group {} not found", group).as_str(),
+            );
+            return std::ptr::null();
+        }
+    };
+    let mut chain_ids = match sdk.chain_ids.lock() {
+        Ok(c) => c,
+        Err(_) => {
+            set_last_error(-1, "sdk lock error");
+            return std::ptr::null();
+        }
+    };
+    //已经返回过的指针要保持有效，同一群组只在第一次时插入
+    chain_ids
+        .entry(group)
+        .or_insert_with(|| CString::new(chain_id).unwrap_or_default())
+        .as_ptr()
+}
+///从握手时节点返回的群组信息里取genesisConfig
+pub unsafe fn bcos_sdk_get_group_wasm_and_crypto(
+    sdk: *const c_void,
+    group: *const c_char,
+    wasm: *mut c_int,
+    sm_cryto: *mut c_int,
+) {
+    let group = p2s(group);
+    let info = match native_sdk(sdk) {
+        Ok(s) => match s.client.lock() {
+            Ok(client) => client.find_group_info(group.as_str()),
+            Err(_) => None,
+        },
+        Err(_) => None,
+    };
+    let info = match info {
+        Some(i) => i,
+        None => {
+            set_last_error(-1, format!("group {} not found", group).as_str());
+            return;
+        }
+    };
+    if !wasm.is_null() {
+        *wasm = info["genesisConfig"]["isWasm"].as_bool().unwrap_or(false) as c_int;
+    }
+    if !sm_cryto.is_null() {
+        *sm_cryto = info["genesisConfig"]["smCrypto"].as_bool().unwrap_or(false) as c_int;
+    }
+}
+
+pub unsafe fn bcos_rpc_send_transaction(
+    sdk: *const c_void,
+    group: *const c_char,
+    node: *const c_char,
+    data: *const c_char,
+    proof: c_int,
+    callback: BCOS3SDK_CALLBACK_FUNC,
+    context: *const c_void,
+) {
+    let params = json!([p2s(group), p2s(node), p2s(data), proof != 0]);
+    rpc_call(sdk, "sendTransaction", params, callback, context);
+}
+pub unsafe fn bcos_rpc_call(
+    sdk: *const c_void,
+    group: *const c_char,
+    node: *const c_char,
+    to: *const c_char,
+    data: *const c_char,
+    callback: BCOS3SDK_CALLBACK_FUNC,
+    context: *const c_void,
+) {
+    let params = json!([p2s(group), p2s(node), p2s(to), p2s(data)]);
+    rpc_call(sdk, "call", params, callback, context);
+}
+
+//用bcos3transaction的rust实现编码和签名，输出的字符串由调用方用bcos_sdk_c_free释放
+pub unsafe fn bcos_sdk_create_signed_transaction(
+    key_pair: *const c_void,
+    group_id: *const c_char,
+    chain_id: *const c_char,
+    to: *const c_char,
+    data: *const c_char,
+    abi: *const c_char,
+    blocklimit: c_longlong,
+    attribute: c_int,
+    tx_hash: *mut *mut c_char,
+    signed_hash: *mut *mut c_char,
+) {
+    clear_last_error();
+    if key_pair.is_null() {
+        set_last_error(-1, "keypair is null");
+        return;
+    }
+    let keypair = &*(key_pair as *const Bcos3NativeKeyPair);
+    let input = match hex::decode(p2s(data).trim().trim_start_matches("0x")) {
+        Ok(d) => d,
+        Err(e) => {
+            set_last_error(-1, format!("transaction data is not hex {:?}", e).as_str());
+            return;
+        }
+    };
+    let hashtype = if keypair.crypto_type == 1 {
+        HashType::WEDRP_SM3
+    } else {
+        HashType::WEDPR_KECCAK
+    };
+    let txdata = Bcos3TransactionData::new(
+        p2s(chain_id).as_str(),
+        p2s(group_id).as_str(),
+        blocklimit,
+        p2s(to).as_str(),
+        input,
+        p2s(abi).as_str(),
+        &hashtype,
+    );
+    let result = if keypair.crypto_type == 1 {
+        let mut signer = CommonSignerWeDPR_SM2::default();
+        signer.key_from_bytes(&keypair.privkey);
+        sign_native_transaction(&signer, txdata, attribute)
+    } else {
+        let mut signer = CommonSignerWeDPR_Secp256::default();
+        signer.key_from_bytes(&keypair.privkey);
+        sign_native_transaction(&signer, txdata, attribute)
+    };
+    match result {
+        Ok(tx) => {
+            if !tx_hash.is_null() {
+                *tx_hash = CString::new(hex::encode(&tx.data_hash)).unwrap().into_raw();
+            }
+            if !signed_hash.is_null() {
+                *signed_hash = CString::new(tx.encode_hex()).unwrap().into_raw();
+            }
+        }
+        Err(e) => set_last_error(-1, e.msg.as_str()),
+    }
+}
+
+fn sign_native_transaction(
+    signer: &dyn ICommonSigner,
+    txdata: Bcos3TransactionData,
+    attribute: c_int,
+) -> Result<Bcos3Transaction, KissError> {
+    let mut tx = Bcos3Transaction::sign(signer, &txdata)?;
+    tx.attribute = attribute;
+    Ok(tx)
+}
+//由本模块分配给调用方的字符串，用CString::into_raw分配，这里对应释放
+pub unsafe fn bcos_sdk_c_free(p: *const c_void) {
+    if !p.is_null() {
+        drop(CString::from_raw(p as *mut c_char));
+    }
+}
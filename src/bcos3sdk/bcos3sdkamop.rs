@@ -1,28 +1,219 @@
-/*amop回调示例，未完整实现
+/*amop回调的rust封装，用户只需传入闭包，不用自己写extern "C"函数
 参见： https://fisco-bcos-doc.readthedocs.io/zh_CN/latest/docs/develop/sdk/c_sdk/api.html#bcos-amop-subscribe-topic-with-cb
+
+c sdk的回调只带回一个context指针，这里不把rust对象的指针交给c sdk(生命周期不可控)，
+而是给每个订阅/每次publish分配一个u64的id作为context，在全局表里按id找到对应的闭包，
+这样同一个进程里多个Bcos3Client、多个topic的回调都能送到正确的订阅者。
+回调是c sdk的线程调用的，用户闭包里的panic不能穿过c的栈帧，用catch_unwind截住并记日志；
+全局表只在增删查时持锁，锁被毒化时表里的数据仍然完整，照常使用。
 */
-use std::ffi::{c_char, CStr};
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+
+use std::collections::HashMap;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use lazy_static::lazy_static;
+use libc::{c_ulong, c_void};
+
+use crate::bcos3sdk::bcos3sdkresponse::bcos_sdk_c_struct_response;
+use crate::bcos3sdk::bcos3sdkwrapper::bcos3sdk_def::bcos_amop_send_response;
+use crate::bcossdkutil::kisserror::{KissErrKind, KissError};
+use crate::kisserrcode;
+
+///收到的AMOP消息，endpoint和seq用于回包
+#[derive(Debug, Clone)]
+pub struct Bcos3AmopMessage {
+    pub topic: String,
+    pub endpoint: String,
+    pub seq: String,
+    pub data: Vec<u8>,
+}
+
+///订阅回调，返回Some时自动用bcos_amop_send_response回包给请求方
+pub type Bcos3AmopSubCallback = Arc<dyn Fn(&Bcos3AmopMessage) -> Option<Vec<u8>> + Send + Sync>;
+///publish的应答回调，超时或出错时得到Err
+pub type Bcos3AmopPublishCallback = Box<dyn FnOnce(Result<Vec<u8>, KissError>) + Send>;
+
+struct AmopSubscriber {
+    //c sdk指针转成usize保存，回包时用
+    sdk: usize,
+    topic: String,
+    callback: Bcos3AmopSubCallback,
+}
+
+lazy_static! {
+    static ref AMOP_SUBSCRIBERS: Mutex<HashMap<u64, AmopSubscriber>> = Mutex::new(HashMap::new());
+    static ref AMOP_PUBLISHES: Mutex<HashMap<u64, Bcos3AmopPublishCallback>> =
+        Mutex::new(HashMap::new());
+}
+static AMOP_CONTEXT_SEQ: AtomicU64 = AtomicU64::new(1);
 
-use crate::bcos3sdk::bcos3sdkresponse::{bcos_sdk_c_struct_response, Bcos3SDKResponse};
+fn subscribers() -> MutexGuard<'static, HashMap<u64, AmopSubscriber>> {
+    AMOP_SUBSCRIBERS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
+fn publishes() -> MutexGuard<'static, HashMap<u64, Bcos3AmopPublishCallback>> {
+    AMOP_PUBLISHES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
+///id作为context传给c sdk，回调时再转回来
+pub fn context_ptr(id: u64) -> *const c_void {
+    id as usize as *const c_void
+}
+
+pub fn register_subscriber(sdk: *const c_void, topic: &str, callback: Bcos3AmopSubCallback) -> u64 {
+    let id = AMOP_CONTEXT_SEQ.fetch_add(1, Ordering::Relaxed);
+    subscribers().insert(
+        id,
+        AmopSubscriber {
+            sdk: sdk as usize,
+            topic: topic.to_string(),
+            callback,
+        },
+    );
+    id
+}
+
+///去掉某个sdk上某个topic的订阅，返回去掉的个数
+pub fn unregister_subscriber(sdk: *const c_void, topic: &str) -> usize {
+    let mut subscribers = subscribers();
+    let before = subscribers.len();
+    subscribers.retain(|_, s| !(s.sdk == sdk as usize && s.topic == topic));
+    before - subscribers.len()
+}
+
+///sdk销毁时去掉它的全部订阅
+pub fn unregister_sdk(sdk: *const c_void) {
+    subscribers().retain(|_, s| s.sdk != sdk as usize);
+}
 
+pub fn subscribed_topics(sdk: *const c_void) -> Vec<String> {
+    let mut topics: Vec<String> = subscribers()
+        .values()
+        .filter(|s| s.sdk == sdk as usize)
+        .map(|s| s.topic.clone())
+        .collect();
+    topics.sort();
+    topics.dedup();
+    topics
+}
+
+pub fn register_publish(callback: Bcos3AmopPublishCallback) -> u64 {
+    let id = AMOP_CONTEXT_SEQ.fetch_add(1, Ordering::Relaxed);
+    publishes().insert(id, callback);
+    id
+}
+
+///取消等待中的publish(如同步等待已超时)，之后到达的应答会被丢弃
+pub fn unregister_publish(id: u64) -> bool {
+    publishes().remove(&id).is_some()
+}
+
+unsafe fn cstr_to_string(p: *const c_char) -> String {
+    if p.is_null() {
+        return "".to_string();
+    }
+    CStr::from_ptr(p).to_string_lossy().to_string()
+}
+
+///AMOP的数据是二进制的，不能像Bcos3SDKResponse::from_callback那样转成String
+unsafe fn response_to_result(
+    resp: *const bcos_sdk_c_struct_response,
+) -> Result<Vec<u8>, KissError> {
+    if resp.is_null() {
+        return kisserrcode!(KissErrKind::EArgument, -1, "amop response is null");
+    }
+    let resp = &*resp;
+    if resp.error != 0 {
+        return kisserrcode!(
+            KissErrKind::Error,
+            resp.error as i64,
+            "{}",
+            cstr_to_string(resp.desc)
+        );
+    }
+    if resp.data.is_null() || resp.size == 0 {
+        return Ok(vec![]);
+    }
+    Ok(std::slice::from_raw_parts(resp.data as *const u8, resp.size).to_vec())
+}
+
+///传给bcos_amop_subscribe_topic_with_cb的回调，按context找到订阅者
 pub extern "C" fn amop_sub_callback(
     endpoint: *const c_char,
     seq: *const c_char,
     resp: *const bcos_sdk_c_struct_response,
 ) {
     unsafe {
-        let mut endpointStr = "".to_string();
-        let mut seqStr = "".to_string();
-        if !endpoint.is_null() {
-            endpointStr = CStr::from_ptr(endpoint.clone())
-                .to_str()
-                .unwrap()
-                .to_string();
+        if resp.is_null() {
+            return;
+        }
+        let id = (*resp).context as usize as u64;
+        //先取出闭包再调用，闭包里可以再订阅或publish
+        let subscriber = subscribers()
+            .get(&id)
+            .map(|s| (s.sdk, s.topic.clone(), s.callback.clone()));
+        let (sdk, topic, callback) = match subscriber {
+            Some(s) => s,
+            None => {
+                log::warn!("amop message for unknown subscriber {}", id);
+                return;
+            }
+        };
+        let data = match response_to_result(resp) {
+            Ok(d) => d,
+            Err(e) => {
+                log::warn!("amop message error on topic {}: {:?}", topic, e);
+                return;
+            }
+        };
+        let msg = Bcos3AmopMessage {
+            topic,
+            endpoint: cstr_to_string(endpoint),
+            seq: cstr_to_string(seq),
+            data,
+        };
+        let reply = match catch_unwind(AssertUnwindSafe(|| callback(&msg))) {
+            Ok(reply) => reply,
+            Err(_) => {
+                log::warn!("amop subscriber callback panicked on topic {}", msg.topic);
+                return;
+            }
+        };
+        if let Some(reply) = reply {
+            let cendpoint = CString::new(msg.endpoint.as_str()).unwrap_or_default();
+            let cseq = CString::new(msg.seq.as_str()).unwrap_or_default();
+            bcos_amop_send_response(
+                sdk as *const c_void,
+                cendpoint.as_ptr(),
+                cseq.as_ptr(),
+                reply.as_ptr() as *const c_void,
+                reply.len() as c_ulong,
+            );
+        }
+    }
+}
+
+///传给bcos_amop_publish的回调，按context找到publish时传入的闭包，只调用一次
+pub extern "C" fn amop_publish_callback(resp: *const bcos_sdk_c_struct_response) {
+    unsafe {
+        if resp.is_null() {
+            return;
         }
-        if !seq.is_null() {
-            seqStr = CStr::from_ptr(seq.clone()).to_str().unwrap().to_string();
+        let id = (*resp).context as usize as u64;
+        let callback = publishes().remove(&id);
+        if let Some(callback) = callback {
+            let result = response_to_result(resp);
+            if catch_unwind(AssertUnwindSafe(|| callback(result))).is_err() {
+                log::warn!("amop publish callback panicked");
+            }
         }
-        let response = Bcos3SDKResponse::from_callback(resp);
-        //println!("endpoint {},seq {}", endpointStr, seqStr);
     }
 }
@@ -0,0 +1,183 @@
+/*事件订阅回调的rust封装，用户传入ContractABI和闭包，回调里拿到的是已经解析好的Log
+参见： https://fisco-bcos-doc.readthedocs.io/zh_CN/latest/docs/develop/sdk/c_sdk/api.html#bcos-event-sub-subscribe-event
+
+和bcos3sdkamop.rs一样，不把rust对象的指针交给c sdk，而是给每个订阅分配一个u64的id作为context，
+回调时在全局表里按id找到订阅时传入的ContractABI和闭包。闭包里的panic和毒化的锁同bcos3sdkamop.rs处理。
+*/
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+
+use std::collections::HashMap;
+use std::ffi::CString;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use lazy_static::lazy_static;
+use libc::c_void;
+use serde_json::Value as JsonValue;
+
+use crate::bcos3sdk::bcos3sdkresponse::{bcos_sdk_c_struct_response, Bcos3SDKResponse};
+use crate::bcos3sdk::bcos3sdkwrapper::bcos3sdk_def::bcos_event_sub_unsubscribe_event;
+use crate::bcossdkutil::contractabi::ContractABI;
+use crate::bcossdkutil::kisserror::{KissErrKind, KissError};
+use crate::kisserrcode;
+use ethabi::Log as ReceiptLog;
+
+///事件回调，每次推送调用一次，出错(如节点返回错误状态、日志解析失败)时得到Err
+pub type Bcos3EventCallback = Arc<dyn Fn(Result<Vec<ReceiptLog>, KissError>) + Send + Sync>;
+///Bcos3Client::subscribe_event_stream返回的接收端
+pub type Bcos3EventReceiver =
+    tokio::sync::mpsc::UnboundedReceiver<Result<Vec<ReceiptLog>, KissError>>;
+
+struct EventSubscriber {
+    sdk: usize,
+    contract: Arc<ContractABI>,
+    callback: Bcos3EventCallback,
+}
+
+lazy_static! {
+    static ref EVENT_SUBSCRIBERS: Mutex<HashMap<u64, EventSubscriber>> = Mutex::new(HashMap::new());
+}
+static EVENT_CONTEXT_SEQ: AtomicU64 = AtomicU64::new(1);
+
+fn subscribers() -> MutexGuard<'static, HashMap<u64, EventSubscriber>> {
+    EVENT_SUBSCRIBERS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
+///id作为context传给c sdk，回调时再转回来
+pub fn context_ptr(id: u64) -> *const c_void {
+    id as usize as *const c_void
+}
+
+pub fn register_subscriber(
+    sdk: *const c_void,
+    contract: &ContractABI,
+    callback: Bcos3EventCallback,
+) -> u64 {
+    let id = EVENT_CONTEXT_SEQ.fetch_add(1, Ordering::Relaxed);
+    subscribers().insert(
+        id,
+        EventSubscriber {
+            sdk: sdk as usize,
+            contract: Arc::new(contract.clone()),
+            callback,
+        },
+    );
+    id
+}
+
+pub fn unregister_subscriber(id: u64) -> bool {
+    subscribers().remove(&id).is_some()
+}
+
+///sdk销毁时去掉它的全部订阅
+pub fn unregister_sdk(sdk: *const c_void) {
+    subscribers().retain(|_, s| s.sdk != sdk as usize);
+}
+
+pub fn subscriber_count(sdk: *const c_void) -> usize {
+    subscribers()
+        .values()
+        .filter(|s| s.sdk == sdk as usize)
+        .count()
+}
+
+///订阅的句柄，由Bcos3Client::subscribe_event返回
+#[derive(Debug, Clone)]
+pub struct Bcos3EventSubscription {
+    pub id: u64,
+    ///c sdk返回的订阅任务id，取消订阅时用
+    pub task_id: String,
+    sdk: usize,
+}
+
+impl Bcos3EventSubscription {
+    pub fn new(sdk: *const c_void, id: u64, task_id: &str) -> Self {
+        Bcos3EventSubscription {
+            id,
+            task_id: task_id.to_string(),
+            sdk: sdk as usize,
+        }
+    }
+
+    ///取消订阅，之后到达的推送被丢弃。sdk已经finish或已取消过时返回false
+    pub fn unsubscribe(&self) -> bool {
+        if !unregister_subscriber(self.id) {
+            return false;
+        }
+        if !self.task_id.is_empty() {
+            let ctaskid = CString::new(self.task_id.as_str()).unwrap_or_default();
+            unsafe {
+                bcos_event_sub_unsubscribe_event(self.sdk as *const c_void, ctaskid.as_ptr());
+            }
+        }
+        true
+    }
+}
+
+//推送的数据形如{"id":"..","status":0,"result":[log,...]}，订阅成功的应答里没有日志
+fn parse_event_response(
+    response: &Bcos3SDKResponse,
+    contract: &ContractABI,
+) -> Option<Result<Vec<ReceiptLog>, KissError>> {
+    if response.error != 0 {
+        return Some(kisserrcode!(
+            KissErrKind::Error,
+            response.error as i64,
+            "event push error: {}",
+            response.desc
+        ));
+    }
+    let v: JsonValue = match serde_json::from_str(response.data.as_str()) {
+        Ok(v) => v,
+        Err(e) => {
+            return Some(kisserrcode!(
+                KissErrKind::EFormat,
+                -1,
+                "event push format: {:?}",
+                e
+            ))
+        }
+    };
+    if let Some(status) = v["status"].as_i64() {
+        if status != 0 {
+            return Some(kisserrcode!(
+                KissErrKind::Error,
+                status,
+                "event push status {}",
+                status
+            ));
+        }
+    }
+    match v["result"].as_array() {
+        Some(logs) if !logs.is_empty() => Some(contract.parse_receipt_logs(&v["result"])),
+        _ => None,
+    }
+}
+
+///传给bcos_event_sub_subscribe_event的回调，按context找到订阅者，解析日志后调用闭包
+pub extern "C" fn event_sub_callback(resp: *const bcos_sdk_c_struct_response) {
+    if resp.is_null() {
+        return;
+    }
+    let response = Bcos3SDKResponse::from_callback(resp);
+    let id = response.context_pointer as usize as u64;
+    //先取出闭包再调用，闭包里可以取消订阅
+    let subscriber = subscribers()
+        .get(&id)
+        .map(|s| (s.contract.clone(), s.callback.clone()));
+    let (contract, callback) = match subscriber {
+        Some(s) => s,
+        None => {
+            log::warn!("event push for unknown subscriber {}", id);
+            return;
+        }
+    };
+    if let Some(result) = parse_event_response(&response, &contract) {
+        if catch_unwind(AssertUnwindSafe(|| callback(result))).is_err() {
+            log::warn!("event subscriber callback panicked");
+        }
+    }
+}
@@ -42,7 +42,7 @@ pub mod bcos3sdk_def {
     use crate::bcos3sdk::bcos3sdkwrapper::{
         BCOS3SDK_AMOP_SUB_CALLBACK_FUNC, BCOS3SDK_CALLBACK_FUNC,
     };
-    use libc::{c_char, c_int, c_ulong};
+    use libc::{c_char, c_int, c_uint, c_ulong};
     use std::ffi::{c_longlong, c_void};
 
     #[link(name = "bcos-c-sdk")]
@@ -232,6 +232,38 @@ pub mod bcos3sdk_def {
             callback: BCOS3SDK_AMOP_SUB_CALLBACK_FUNC,
             context: *const c_void,
         );
+        // void bcos_amop_unsubscribe_topic(void* sdk, char** topics, size_t count)
+        pub fn bcos_amop_unsubscribe_topic(
+            sdk: *const c_void,
+            topics: *const *const c_char,
+            count: c_ulong,
+        );
+        // void bcos_amop_publish(void* sdk, const char* topic, void* data, size_t size, uint32_t timeout,
+        //     bcos_sdk_c_amop_publish_cb cb, void* context)
+        pub fn bcos_amop_publish(
+            sdk: *const c_void,
+            topic: *const c_char,
+            data: *const c_void,
+            size: c_ulong,
+            timeout: c_uint,
+            callback: BCOS3SDK_CALLBACK_FUNC,
+            context: *const c_void,
+        );
+        // void bcos_amop_broadcast(void* sdk, const char* topic, void* data, size_t size)
+        pub fn bcos_amop_broadcast(
+            sdk: *const c_void,
+            topic: *const c_char,
+            data: *const c_void,
+            size: c_ulong,
+        );
+        // void bcos_amop_send_response(void* sdk, const char* peer, const char* seq, void* data, size_t size)
+        pub fn bcos_amop_send_response(
+            sdk: *const c_void,
+            peer: *const c_char,
+            seq: *const c_char,
+            data: *const c_void,
+            size: c_ulong,
+        );
 
         // void* bcos_sdk_create_keypair(int crypto_type); 1: ecdsa 2: sm
         pub fn bcos_sdk_create_keypair(crypto_type: c_int) -> *const c_void;
@@ -314,12 +346,16 @@ pub mod bcos3sdk_def {
 
 //----------------------------------------------------------
 //当未定义bcos3sdk_ffi时，声明一些unsafe的C语言库接口的“桩”方法，"骗过编"译器，实际上等于没有链接库，什么都做不了
-#[cfg(not(feature = "bcos3sdk_ffi"))]
+//bcos3sdk_native特性：用纯rust的websocket客户端实现同样的接口，见bcos3sdk_native.rs
+#[cfg(all(feature = "bcos3sdk_native", not(feature = "bcos3sdk_ffi")))]
+pub use crate::bcos3sdk::bcos3sdk_native as bcos3sdk_def;
+
+#[cfg(not(any(feature = "bcos3sdk_ffi", feature = "bcos3sdk_native")))]
 pub mod bcos3sdk_def {
     use crate::bcos3sdk::bcos3sdkwrapper::{
         BCOS3SDK_AMOP_SUB_CALLBACK_FUNC, BCOS3SDK_CALLBACK_FUNC,
     };
-    use libc::{c_char, c_int, c_ulong};
+    use libc::{c_char, c_int, c_uint, c_ulong};
     use std::ffi::{c_longlong, c_void};
 
     pub unsafe fn bcos_sdk_version() -> *const c_char {
@@ -544,6 +580,54 @@ pub mod bcos3sdk_def {
         context: *const c_void,
     ) {
     }
+    /// void bcos_amop_unsubscribe_topic(void* sdk, char** topics, size_t count)
+    ///
+    /// # Safety
+    /// 空实现不访问指针；和c sdk版本一样，调用方应保证topics指向count个有效的c字符串
+    pub unsafe fn bcos_amop_unsubscribe_topic(
+        sdk: *const c_void,
+        topics: *const *const c_char,
+        count: c_ulong,
+    ) {
+    }
+    /// void bcos_amop_publish(void* sdk, const char* topic, void* data, size_t size, uint32_t timeout,
+    ///     bcos_sdk_c_amop_publish_cb cb, void* context)
+    ///
+    /// # Safety
+    /// 空实现不访问指针；和c sdk版本一样，调用方应保证topic为c字符串、data指向size字节
+    pub unsafe fn bcos_amop_publish(
+        sdk: *const c_void,
+        topic: *const c_char,
+        data: *const c_void,
+        size: c_ulong,
+        timeout: c_uint,
+        callback: BCOS3SDK_CALLBACK_FUNC,
+        context: *const c_void,
+    ) {
+    }
+    /// void bcos_amop_broadcast(void* sdk, const char* topic, void* data, size_t size)
+    ///
+    /// # Safety
+    /// 空实现不访问指针；和c sdk版本一样，调用方应保证topic为c字符串、data指向size字节
+    pub unsafe fn bcos_amop_broadcast(
+        sdk: *const c_void,
+        topic: *const c_char,
+        data: *const c_void,
+        size: c_ulong,
+    ) {
+    }
+    /// void bcos_amop_send_response(void* sdk, const char* peer, const char* seq, void* data, size_t size)
+    ///
+    /// # Safety
+    /// 空实现不访问指针；和c sdk版本一样，调用方应保证peer、seq为c字符串、data指向size字节
+    pub unsafe fn bcos_amop_send_response(
+        sdk: *const c_void,
+        peer: *const c_char,
+        seq: *const c_char,
+        data: *const c_void,
+        size: c_ulong,
+    ) {
+    }
 
     // void* bcos_sdk_create_keypair(int crypto_type); 1: ecdsa 2: sm
     pub unsafe fn bcos_sdk_create_keypair(crypto_type: c_int) -> *const c_void {
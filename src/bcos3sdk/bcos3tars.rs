@@ -0,0 +1,313 @@
+/*
+  FISCO BCOS/rust-SDK is a rust client for FISCO BCOS2.0 (https://github.com/FISCO-BCOS/)
+  FISCO BCOS/rust-SDK is free software: you can redistribute it and/or modify it under the
+  terms of the MIT License as published by the Free Software Foundation. This project is
+  distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even
+  the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+  @author: kentzhang
+  @date: 2021-07
+*/
+#![allow(
+    clippy::unreadable_literal,
+    clippy::upper_case_acronyms,
+    dead_code,
+    non_camel_case_types,
+    non_snake_case,
+    non_upper_case_globals,
+    overflowing_literals,
+    unused_variables,
+    unused_assignments
+)]
+
+/*
+FISCO BCOS3.0的交易、回执等协议结构用TARS编码(bcos-tars-protocol)，这里实现其中用到的子集：
+整数、字符串、vector<byte>、嵌套结构体。每个字段先写一个head：
+tag<15时为一个字节 (tag<<4)|type，否则为两个字节 0xF0|type, tag。
+整数按值的大小压缩成最短的类型，0值只写head(ZERO_TAG)，数字均为大端。
+参见：https://github.com/TarsCloud/TarsCpp/blob/master/servant/tup/Tars.h
+*/
+use std::convert::TryInto;
+
+use crate::bcossdkutil::kisserror::{KissErrKind, KissError};
+use crate::kisserr;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum TARS_TYPE {
+    INT1 = 0,
+    INT2 = 1,
+    INT4 = 2,
+    INT8 = 3,
+    FLOAT = 4,
+    DOUBLE = 5,
+    STRING1 = 6,
+    STRING4 = 7,
+    MAP = 8,
+    LIST = 9,
+    STRUCT_BEGIN = 10,
+    STRUCT_END = 11,
+    ZERO_TAG = 12,
+    SIMPLE_LIST = 13,
+}
+
+impl TARS_TYPE {
+    pub fn from_u8(v: u8) -> Result<TARS_TYPE, KissError> {
+        let t = match v {
+            0 => TARS_TYPE::INT1,
+            1 => TARS_TYPE::INT2,
+            2 => TARS_TYPE::INT4,
+            3 => TARS_TYPE::INT8,
+            4 => TARS_TYPE::FLOAT,
+            5 => TARS_TYPE::DOUBLE,
+            6 => TARS_TYPE::STRING1,
+            7 => TARS_TYPE::STRING4,
+            8 => TARS_TYPE::MAP,
+            9 => TARS_TYPE::LIST,
+            10 => TARS_TYPE::STRUCT_BEGIN,
+            11 => TARS_TYPE::STRUCT_END,
+            12 => TARS_TYPE::ZERO_TAG,
+            13 => TARS_TYPE::SIMPLE_LIST,
+            _ => return kisserr!(KissErrKind::EFormat, "unknown tars type {}", v),
+        };
+        Ok(t)
+    }
+}
+
+#[derive(Default, Debug, Clone)]
+pub struct TarsWriter {
+    pub buffer: Vec<u8>,
+}
+
+impl TarsWriter {
+    pub fn new() -> Self {
+        TarsWriter { buffer: vec![] }
+    }
+
+    pub fn write_head(&mut self, tag: u8, t: TARS_TYPE) {
+        if tag < 15 {
+            self.buffer.push((tag << 4) | t as u8);
+        } else {
+            self.buffer.push(0xF0 | t as u8);
+            self.buffer.push(tag);
+        }
+    }
+
+    pub fn write_int(&mut self, tag: u8, v: i64) {
+        if v == 0 {
+            self.write_head(tag, TARS_TYPE::ZERO_TAG);
+        } else if v >= i8::MIN as i64 && v <= i8::MAX as i64 {
+            self.write_head(tag, TARS_TYPE::INT1);
+            self.buffer.push(v as i8 as u8);
+        } else if v >= i16::MIN as i64 && v <= i16::MAX as i64 {
+            self.write_head(tag, TARS_TYPE::INT2);
+            self.buffer.extend_from_slice(&(v as i16).to_be_bytes());
+        } else if v >= i32::MIN as i64 && v <= i32::MAX as i64 {
+            self.write_head(tag, TARS_TYPE::INT4);
+            self.buffer.extend_from_slice(&(v as i32).to_be_bytes());
+        } else {
+            self.write_head(tag, TARS_TYPE::INT8);
+            self.buffer.extend_from_slice(&v.to_be_bytes());
+        }
+    }
+
+    pub fn write_string(&mut self, tag: u8, s: &str) {
+        let data = s.as_bytes();
+        if data.len() > 255 {
+            self.write_head(tag, TARS_TYPE::STRING4);
+            self.buffer
+                .extend_from_slice(&(data.len() as u32).to_be_bytes());
+        } else {
+            self.write_head(tag, TARS_TYPE::STRING1);
+            self.buffer.push(data.len() as u8);
+        }
+        self.buffer.extend_from_slice(data);
+    }
+
+    ///vector<byte>用SIMPLE_LIST编码：head, 元素类型head(INT1,tag 0), 长度(tag 0的整数), 数据
+    pub fn write_bytes(&mut self, tag: u8, data: &[u8]) {
+        self.write_head(tag, TARS_TYPE::SIMPLE_LIST);
+        self.write_head(0, TARS_TYPE::INT1);
+        self.write_int(0, data.len() as i64);
+        self.buffer.extend_from_slice(data);
+    }
+
+    ///嵌套结构体，内容由调用方用同一个writer写完后调用write_struct_end
+    pub fn write_struct_begin(&mut self, tag: u8) {
+        self.write_head(tag, TARS_TYPE::STRUCT_BEGIN);
+    }
+
+    pub fn write_struct_end(&mut self) {
+        self.write_head(0, TARS_TYPE::STRUCT_END);
+    }
+
+    //optional字段等于默认值时不写，和tars2cpp生成的代码行为一致
+    pub fn write_opt_int(&mut self, tag: u8, v: i64) {
+        if v != 0 {
+            self.write_int(tag, v);
+        }
+    }
+
+    pub fn write_opt_string(&mut self, tag: u8, s: &str) {
+        if !s.is_empty() {
+            self.write_string(tag, s);
+        }
+    }
+
+    pub fn write_opt_bytes(&mut self, tag: u8, data: &[u8]) {
+        if !data.is_empty() {
+            self.write_bytes(tag, data);
+        }
+    }
+}
+
+///读取到的一个字段值，结构体的内容不在这里展开，由调用方继续读
+#[derive(Debug, Clone, PartialEq)]
+pub enum TarsValue {
+    Int(i64),
+    Str(String),
+    Bytes(Vec<u8>),
+    StructBegin,
+    StructEnd,
+}
+
+#[derive(Debug, Clone)]
+pub struct TarsReader<'a> {
+    pub data: &'a [u8],
+    pub pos: usize,
+}
+
+impl<'a> TarsReader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        TarsReader { data, pos: 0 }
+    }
+
+    pub fn is_end(&self) -> bool {
+        self.pos >= self.data.len()
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], KissError> {
+        if self.pos + len > self.data.len() {
+            return kisserr!(
+                KissErrKind::EFormat,
+                "tars data too short, need {} bytes at {}, total {}",
+                len,
+                self.pos,
+                self.data.len()
+            );
+        }
+        let s = &self.data[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(s)
+    }
+
+    pub fn read_head(&mut self) -> Result<(u8, TARS_TYPE), KissError> {
+        let b = self.take(1)?[0];
+        let t = TARS_TYPE::from_u8(b & 0x0F)?;
+        let mut tag = b >> 4;
+        if tag == 15 {
+            tag = self.take(1)?[0];
+        }
+        Ok((tag, t))
+    }
+
+    fn read_int_body(&mut self, t: TARS_TYPE) -> Result<i64, KissError> {
+        let v = match t {
+            TARS_TYPE::ZERO_TAG => 0,
+            TARS_TYPE::INT1 => self.take(1)?[0] as i8 as i64,
+            TARS_TYPE::INT2 => i16::from_be_bytes(self.take(2)?.try_into().unwrap()) as i64,
+            TARS_TYPE::INT4 => i32::from_be_bytes(self.take(4)?.try_into().unwrap()) as i64,
+            TARS_TYPE::INT8 => i64::from_be_bytes(self.take(8)?.try_into().unwrap()),
+            _ => return kisserr!(KissErrKind::EFormat, "tars type {:?} is not int", t),
+        };
+        Ok(v)
+    }
+
+    ///读一个字段，返回tag和值
+    pub fn read_field(&mut self) -> Result<(u8, TarsValue), KissError> {
+        let (tag, t) = self.read_head()?;
+        let value = match t {
+            TARS_TYPE::ZERO_TAG
+            | TARS_TYPE::INT1
+            | TARS_TYPE::INT2
+            | TARS_TYPE::INT4
+            | TARS_TYPE::INT8 => TarsValue::Int(self.read_int_body(t)?),
+            TARS_TYPE::STRING1 | TARS_TYPE::STRING4 => {
+                let len = if t == TARS_TYPE::STRING1 {
+                    self.take(1)?[0] as usize
+                } else {
+                    u32::from_be_bytes(self.take(4)?.try_into().unwrap()) as usize
+                };
+                TarsValue::Str(String::from_utf8_lossy(self.take(len)?).to_string())
+            }
+            TARS_TYPE::SIMPLE_LIST => {
+                let (_, elemtype) = self.read_head()?;
+                if elemtype != TARS_TYPE::INT1 {
+                    return kisserr!(
+                        KissErrKind::EFormat,
+                        "tars simple list element type {:?} not supported",
+                        elemtype
+                    );
+                }
+                let (_, lentype) = self.read_head()?;
+                let len = self.read_int_body(lentype)?;
+                if len < 0 {
+                    return kisserr!(KissErrKind::EFormat, "tars simple list length {}", len);
+                }
+                TarsValue::Bytes(self.take(len as usize)?.to_vec())
+            }
+            TARS_TYPE::STRUCT_BEGIN => TarsValue::StructBegin,
+            TARS_TYPE::STRUCT_END => TarsValue::StructEnd,
+            _ => {
+                return kisserr!(
+                    KissErrKind::EFormat,
+                    "tars type {:?} at tag {} not supported",
+                    t,
+                    tag
+                )
+            }
+        };
+        Ok((tag, value))
+    }
+
+    ///跳过一个嵌套结构体的剩余内容(已读过STRUCT_BEGIN)，用于忽略不认识的字段
+    pub fn skip_struct(&mut self) -> Result<(), KissError> {
+        loop {
+            match self.read_field()?.1 {
+                TarsValue::StructBegin => self.skip_struct()?,
+                TarsValue::StructEnd => return Ok(()),
+                _ => {}
+            }
+        }
+    }
+}
+
+///从字段值里取出需要的类型，类型不符时报错
+pub fn tars_to_int(tag: u8, v: &TarsValue) -> Result<i64, KissError> {
+    match v {
+        TarsValue::Int(i) => Ok(*i),
+        _ => kisserr!(KissErrKind::EFormat, "tars tag {} is not int: {:?}", tag, v),
+    }
+}
+
+pub fn tars_to_string(tag: u8, v: TarsValue) -> Result<String, KissError> {
+    match v {
+        TarsValue::Str(s) => Ok(s),
+        _ => kisserr!(
+            KissErrKind::EFormat,
+            "tars tag {} is not string: {:?}",
+            tag,
+            v
+        ),
+    }
+}
+
+pub fn tars_to_bytes(tag: u8, v: TarsValue) -> Result<Vec<u8>, KissError> {
+    match v {
+        TarsValue::Bytes(b) => Ok(b),
+        _ => kisserr!(
+            KissErrKind::EFormat,
+            "tars tag {} is not bytes: {:?}",
+            tag,
+            v
+        ),
+    }
+}
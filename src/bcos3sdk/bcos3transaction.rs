@@ -0,0 +1,409 @@
+/*
+  FISCO BCOS/rust-SDK is a rust client for FISCO BCOS2.0 (https://github.com/FISCO-BCOS/)
+  FISCO BCOS/rust-SDK is free software: you can redistribute it and/or modify it under the
+  terms of the MIT License as published by the Free Software Foundation. This project is
+  distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even
+  the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+  @author: kentzhang
+  @date: 2021-07
+*/
+#![allow(
+    clippy::unreadable_literal,
+    clippy::upper_case_acronyms,
+    dead_code,
+    non_camel_case_types,
+    non_snake_case,
+    non_upper_case_globals,
+    overflowing_literals,
+    unused_variables,
+    unused_assignments
+)]
+
+/*
+FISCO BCOS3.0的交易结构，rust实现tars编码、hash和签名，不依赖c sdk的keypair，可以离线签名。
+对应bcos-tars-protocol的Transaction.tars：
+
+struct TransactionData {            struct Transaction {
+    1 optional int version;             1 optional TransactionData data;
+    2 optional string chainID;          2 optional vector<byte> dataHash;
+    3 optional string groupID;          3 optional vector<byte> signature;
+    4 optional long blockLimit;         4 optional long importTime;
+    5 optional string nonce;            5 optional int attribute;
+    6 optional string to;               7 optional vector<byte> sender;
+    7 optional vector<byte> input;      8 optional string extraData;
+    8 optional string abi;          };
+    9-13 value,gasPrice,gasLimit,maxFeePerGas,maxPriorityFeePerGas (version>=1)
+    14 optional vector<byte> extension (version>=2)
+};
+
+dataHash不是对tars编码结果做hash，而是按字段顺序拼接后hash(和节点、c sdk的calculateHash一致)，
+int/long字段按大端拼入。dataHash就是交易hash，签名即对dataHash签名：
+ecdsa为r(32)+s(32)+v(1,取值0/1)，国密为r(32)+s(32)+公钥(64)
+*/
+use serde_json::{json, Value as JsonValue};
+
+use crate::bcos3sdk::bcos3tars::{
+    tars_to_bytes, tars_to_int, tars_to_string, TarsReader, TarsValue, TarsWriter,
+};
+use crate::bcossdkutil::commonhash::{CommonHash, HashType};
+use crate::bcossdkutil::commonsigner::{CommonSignature, ICommonSigner, Secp256Signature};
+use crate::bcossdkutil::kisserror::{KissErrKind, KissError};
+use crate::bcossdkutil::merkleproof::{decode_hex, json_hex, json_u256};
+use crate::{kisserr, printlnex};
+use std::convert::TryInto;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bcos3TransactionData {
+    pub version: i32,
+    pub chain_id: String,
+    pub group_id: String,
+    pub block_limit: i64,
+    pub nonce: String,
+    pub to: String,
+    pub input: Vec<u8>,
+    pub abi: String,
+    //以下为version 1增加的字段
+    pub value: String,
+    pub gas_price: String,
+    pub gas_limit: i64,
+    pub max_fee_per_gas: String,
+    pub max_priority_fee_per_gas: String,
+    //version 2增加
+    pub extension: Vec<u8>,
+    pub hashtype: HashType, //由调用者指定hash算法
+}
+
+impl Default for Bcos3TransactionData {
+    fn default() -> Self {
+        Bcos3TransactionData {
+            version: 0,
+            chain_id: "".to_string(),
+            group_id: "".to_string(),
+            block_limit: 0,
+            nonce: "".to_string(),
+            to: "".to_string(),
+            input: vec![],
+            abi: "".to_string(),
+            value: "".to_string(),
+            gas_price: "".to_string(),
+            gas_limit: 0,
+            max_fee_per_gas: "".to_string(),
+            max_priority_fee_per_gas: "".to_string(),
+            extension: vec![],
+            hashtype: HashType::Unknow,
+        }
+    }
+}
+
+impl Bcos3TransactionData {
+    ///构造version 0的交易，nonce随机生成。部署合约时to为空，input为合约代码加构造参数
+    pub fn new(
+        chain_id: &str,
+        group_id: &str,
+        block_limit: i64,
+        to: &str,
+        input: Vec<u8>,
+        abi: &str,
+        hashtype: &HashType,
+    ) -> Self {
+        Bcos3TransactionData {
+            chain_id: chain_id.to_string(),
+            group_id: group_id.to_string(),
+            block_limit,
+            nonce: Bcos3TransactionData::make_nonce(),
+            to: to.to_string(),
+            input,
+            abi: abi.to_string(),
+            hashtype: hashtype.clone(),
+            ..Default::default()
+        }
+    }
+
+    ///节点用nonce去重，c sdk和java sdk都是一个随机大数的十进制字符串
+    pub fn make_nonce() -> String {
+        let n: u128 = rand::random();
+        n.to_string()
+    }
+
+    pub fn write_fields(&self, writer: &mut TarsWriter) {
+        writer.write_opt_int(1, self.version as i64);
+        writer.write_opt_string(2, &self.chain_id);
+        writer.write_opt_string(3, &self.group_id);
+        writer.write_opt_int(4, self.block_limit);
+        writer.write_opt_string(5, &self.nonce);
+        writer.write_opt_string(6, &self.to);
+        writer.write_opt_bytes(7, &self.input);
+        writer.write_opt_string(8, &self.abi);
+        writer.write_opt_string(9, &self.value);
+        writer.write_opt_string(10, &self.gas_price);
+        writer.write_opt_int(11, self.gas_limit);
+        writer.write_opt_string(12, &self.max_fee_per_gas);
+        writer.write_opt_string(13, &self.max_priority_fee_per_gas);
+        writer.write_opt_bytes(14, &self.extension);
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut writer = TarsWriter::new();
+        self.write_fields(&mut writer);
+        writer.buffer
+    }
+
+    ///读取字段直到STRUCT_END或数据结束，嵌套在Transaction里时由调用方读掉STRUCT_BEGIN
+    pub fn read_fields(reader: &mut TarsReader) -> Result<Bcos3TransactionData, KissError> {
+        let mut txdata = Bcos3TransactionData::default();
+        while !reader.is_end() {
+            let (tag, v) = reader.read_field()?;
+            match (tag, v) {
+                (_, TarsValue::StructEnd) => break,
+                (_, TarsValue::StructBegin) => reader.skip_struct()?,
+                (1, v) => txdata.version = tars_to_int(tag, &v)? as i32,
+                (2, v) => txdata.chain_id = tars_to_string(tag, v)?,
+                (3, v) => txdata.group_id = tars_to_string(tag, v)?,
+                (4, v) => txdata.block_limit = tars_to_int(tag, &v)?,
+                (5, v) => txdata.nonce = tars_to_string(tag, v)?,
+                (6, v) => txdata.to = tars_to_string(tag, v)?,
+                (7, v) => txdata.input = tars_to_bytes(tag, v)?,
+                (8, v) => txdata.abi = tars_to_string(tag, v)?,
+                (9, v) => txdata.value = tars_to_string(tag, v)?,
+                (10, v) => txdata.gas_price = tars_to_string(tag, v)?,
+                (11, v) => txdata.gas_limit = tars_to_int(tag, &v)?,
+                (12, v) => txdata.max_fee_per_gas = tars_to_string(tag, v)?,
+                (13, v) => txdata.max_priority_fee_per_gas = tars_to_string(tag, v)?,
+                (14, v) => txdata.extension = tars_to_bytes(tag, v)?,
+                _ => {}
+            }
+        }
+        Ok(txdata)
+    }
+
+    pub fn decode_bytes(data: &[u8]) -> Result<Bcos3TransactionData, KissError> {
+        Bcos3TransactionData::read_fields(&mut TarsReader::new(data))
+    }
+
+    ///按字段拼接后hash，这就是交易hash
+    pub fn hash(&self) -> Vec<u8> {
+        let mut buffer: Vec<u8> = vec![];
+        buffer.extend_from_slice(&self.version.to_be_bytes());
+        buffer.extend_from_slice(self.chain_id.as_bytes());
+        buffer.extend_from_slice(self.group_id.as_bytes());
+        buffer.extend_from_slice(&self.block_limit.to_be_bytes());
+        buffer.extend_from_slice(self.nonce.as_bytes());
+        buffer.extend_from_slice(self.to.as_bytes());
+        buffer.extend_from_slice(&self.input);
+        buffer.extend_from_slice(self.abi.as_bytes());
+        if self.version >= 1 {
+            buffer.extend_from_slice(self.value.as_bytes());
+            buffer.extend_from_slice(self.gas_price.as_bytes());
+            buffer.extend_from_slice(&self.gas_limit.to_be_bytes());
+            buffer.extend_from_slice(self.max_fee_per_gas.as_bytes());
+            buffer.extend_from_slice(self.max_priority_fee_per_gas.as_bytes());
+        }
+        if self.version >= 2 {
+            buffer.extend_from_slice(&self.extension);
+        }
+        CommonHash::hash(&buffer, &self.hashtype)
+    }
+
+    ///从节点返回的交易json还原交易数据，用来自己计算交易hash，不信任json里的hash字段
+    pub fn from_json(
+        tx: &JsonValue,
+        hashtype: &HashType,
+    ) -> Result<Bcos3TransactionData, KissError> {
+        Ok(Bcos3TransactionData {
+            version: json_u256(tx, "version")
+                .map(|v| v.low_u32() as i32)
+                .unwrap_or(0),
+            chain_id: json_text(tx, "chainID"),
+            group_id: json_text(tx, "groupID"),
+            block_limit: json_u256(tx, "blockLimit")?.low_u64() as i64,
+            nonce: json_text(tx, "nonce"),
+            to: json_text(tx, "to"),
+            input: json_hex(tx, "input")?,
+            abi: json_text(tx, "abi"),
+            value: json_text(tx, "value"),
+            gas_price: json_text(tx, "gasPrice"),
+            gas_limit: json_u256(tx, "gasLimit")
+                .map(|v| v.low_u64() as i64)
+                .unwrap_or(0),
+            max_fee_per_gas: json_text(tx, "maxFeePerGas"),
+            max_priority_fee_per_gas: json_text(tx, "maxPriorityFeePerGas"),
+            extension: json_hex(tx, "extension").unwrap_or_default(),
+            hashtype: hashtype.clone(),
+        })
+    }
+
+    pub fn to_json(&self) -> JsonValue {
+        json!({
+            "version": self.version,
+            "chainID": self.chain_id,
+            "groupID": self.group_id,
+            "blockLimit": self.block_limit,
+            "nonce": self.nonce,
+            "to": self.to,
+            "input": format!("0x{}", hex::encode(&self.input)),
+            "abi": self.abi,
+        })
+    }
+}
+
+//字符串字段，缺少时为空串(tars里optional的默认值)
+fn json_text(v: &JsonValue, name: &str) -> String {
+    match &v[name] {
+        JsonValue::String(s) => s.clone(),
+        JsonValue::Number(n) => n.to_string(),
+        _ => "".to_string(),
+    }
+}
+
+/*
+按回执字段计算回执hash，对应bcos-tars-protocol里TransactionReceiptData的hash：
+version(int,大端) gasUsed contractAddress status(int,大端) output
+每条log的address、各个topic、data，blockNumber(long,大端)，version>=1时再加effectiveGasPrice
+*/
+pub fn bcos3_receipt_hash(receipt: &JsonValue, hashtype: &HashType) -> Result<Vec<u8>, KissError> {
+    let version = json_u256(receipt, "version")
+        .map(|v| v.low_u32() as i32)
+        .unwrap_or(0);
+    let mut buffer: Vec<u8> = vec![];
+    buffer.extend_from_slice(&version.to_be_bytes());
+    buffer.extend_from_slice(json_text(receipt, "gasUsed").as_bytes());
+    buffer.extend_from_slice(json_text(receipt, "contractAddress").as_bytes());
+    let status = json_u256(receipt, "status")?.low_u32() as i32;
+    buffer.extend_from_slice(&status.to_be_bytes());
+    buffer.extend(json_hex(receipt, "output").unwrap_or_default());
+    let logs = receipt["logEntries"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    for log in logs.iter() {
+        buffer.extend_from_slice(json_text(log, "address").as_bytes());
+        for topic in log["topics"].as_array().cloned().unwrap_or_default().iter() {
+            buffer.extend(decode_hex(topic.as_str().unwrap_or_default())?);
+        }
+        buffer.extend(json_hex(log, "data").unwrap_or_default());
+    }
+    let block_number = json_u256(receipt, "blockNumber")?.low_u64() as i64;
+    buffer.extend_from_slice(&block_number.to_be_bytes());
+    if version >= 1 {
+        buffer.extend_from_slice(json_text(receipt, "effectiveGasPrice").as_bytes());
+    }
+    Ok(CommonHash::hash(&buffer, hashtype))
+}
+
+///把ICommonSigner的签名结果转成3.0节点要求的格式：ecdsa的v是8字节且加了27，这里转回1个字节的0/1
+pub fn bcos3_signature_bytes(sig: &CommonSignature) -> Vec<u8> {
+    let mut buffer: Vec<u8> = vec![];
+    buffer.extend_from_slice(&sig.r);
+    buffer.extend_from_slice(&sig.s);
+    match sig.v.len() {
+        8 => {
+            let v = u64::from_be_bytes(sig.v.as_slice().try_into().unwrap());
+            buffer.push(Secp256Signature::make_stand_v(v) as u8);
+        }
+        1 => buffer.push(Secp256Signature::make_stand_v(sig.v[0] as u64) as u8),
+        _ => buffer.extend_from_slice(&sig.v), //国密，v是公钥
+    }
+    buffer
+}
+
+///携带hash和签名的完整交易，encode后的hex字符串即sendTransaction的参数
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Bcos3Transaction {
+    pub data: Bcos3TransactionData,
+    pub data_hash: Vec<u8>,
+    pub signature: Vec<u8>,
+    pub import_time: i64,
+    pub attribute: i32,
+    pub sender: Vec<u8>,
+    pub extra_data: String,
+}
+
+impl Bcos3Transaction {
+    ///计算交易hash并签名，签名算法由signer决定，hash算法由txdata.hashtype决定，两者要匹配
+    pub fn sign(
+        signer: &dyn ICommonSigner,
+        txdata: &Bcos3TransactionData,
+    ) -> Result<Bcos3Transaction, KissError> {
+        let data_hash = txdata.hash();
+        if data_hash.is_empty() {
+            return kisserr!(
+                KissErrKind::EArgument,
+                "hash type {:?} of transaction is unknown",
+                txdata.hashtype
+            );
+        }
+        let sig = signer.sign(data_hash.clone())?;
+        printlnex!("sign bcos3 tx ok : {:?}", &sig.detail());
+        Ok(Bcos3Transaction {
+            data: txdata.clone(),
+            data_hash,
+            signature: bcos3_signature_bytes(&sig),
+            ..Default::default()
+        })
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut writer = TarsWriter::new();
+        writer.write_struct_begin(1);
+        self.data.write_fields(&mut writer);
+        writer.write_struct_end();
+        writer.write_opt_bytes(2, &self.data_hash);
+        writer.write_opt_bytes(3, &self.signature);
+        writer.write_opt_int(4, self.import_time);
+        writer.write_opt_int(5, self.attribute as i64);
+        writer.write_opt_bytes(7, &self.sender);
+        writer.write_opt_string(8, &self.extra_data);
+        writer.buffer
+    }
+
+    ///不带0x的hex，和c sdk的bcos_sdk_create_signed_transaction输出一致
+    pub fn encode_hex(&self) -> String {
+        hex::encode(self.encode())
+    }
+
+    ///解码tars数据，hashtype无法从数据里得到，解出来是Unknow，需要时由调用方设置
+    pub fn decode_bytes(data: &[u8]) -> Result<Bcos3Transaction, KissError> {
+        let mut reader = TarsReader::new(data);
+        let mut tx = Bcos3Transaction::default();
+        while !reader.is_end() {
+            let (tag, v) = reader.read_field()?;
+            match (tag, v) {
+                (1, TarsValue::StructBegin) => {
+                    tx.data = Bcos3TransactionData::read_fields(&mut reader)?
+                }
+                (_, TarsValue::StructBegin) => reader.skip_struct()?,
+                (2, v) => tx.data_hash = tars_to_bytes(tag, v)?,
+                (3, v) => tx.signature = tars_to_bytes(tag, v)?,
+                (4, v) => tx.import_time = tars_to_int(tag, &v)?,
+                (5, v) => tx.attribute = tars_to_int(tag, &v)? as i32,
+                (7, v) => tx.sender = tars_to_bytes(tag, v)?,
+                (8, v) => tx.extra_data = tars_to_string(tag, v)?,
+                _ => {}
+            }
+        }
+        Ok(tx)
+    }
+
+    pub fn decode_hex(hexstr: &str) -> Result<Bcos3Transaction, KissError> {
+        match hex::decode(hexstr.trim().trim_start_matches("0x")) {
+            Ok(data) => Bcos3Transaction::decode_bytes(&data),
+            Err(e) => kisserr!(KissErrKind::EFormat, "transaction hex decode error {:?}", e),
+        }
+    }
+
+    pub fn txhash_hex(&self) -> String {
+        format!("0x{}", hex::encode(&self.data_hash))
+    }
+
+    pub fn to_json(&self) -> JsonValue {
+        json!({
+            "data": self.data.to_json(),
+            "dataHash": self.txhash_hex(),
+            "signature": format!("0x{}", hex::encode(&self.signature)),
+            "importTime": self.import_time,
+            "attribute": self.attribute,
+            "sender": format!("0x{}", hex::encode(&self.sender)),
+            "extraData": self.extra_data,
+        })
+    }
+}
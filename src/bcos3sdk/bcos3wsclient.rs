@@ -0,0 +1,379 @@
+/*
+  FISCO BCOS/rust-SDK is a rust client for FISCO BCOS2.0 (https://github.com/FISCO-BCOS/)
+  FISCO BCOS/rust-SDK is free software: you can redistribute it and/or modify it under the
+  terms of the MIT License as published by the Free Software Foundation. This project is
+  distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even
+  the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+  @author: kentzhang
+  @date: 2021-07
+*/
+#![allow(
+    clippy::unreadable_literal,
+    clippy::upper_case_acronyms,
+    dead_code,
+    non_camel_case_types,
+    non_snake_case,
+    non_upper_case_globals,
+    overflowing_literals,
+    unused_variables,
+    unused_assignments
+)]
+
+/*
+纯rust实现的FISCO BCOS3.0 websocket客户端，替代c sdk的网络层。
+同步实现：发出请求后，在当前线程读websocket消息直到seq匹配，期间收到的新区块通知用来更新本地缓存的块高，
+其他推送(AMOP、事件)native实现不支持，直接丢弃。缓存的块高超过blocknumber_max_age时重新查询，
+避免长时间空闲后用过期的blocklimit签名交易。
+tls部分和bcos_ssl_normal.rs一样用openssl，只支持非国密(ssl_type=ssl)，也可以在ini里disable_ssl
+*/
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use openssl::ssl::{SslConnector, SslFiletype, SslMethod, SslStream, SslVerifyMode};
+use serde_json::{json, Value as JsonValue};
+use tungstenite::{HandshakeError, Message, WebSocket};
+
+use crate::bcos3sdk::bcos3sdk_ini::Bcos3sdkIni;
+use crate::bcos3sdk::bcos3wsmessage::{Bcos3WsMessage, BCOS3_WS_MSG_TYPE};
+use crate::bcossdkutil::kisserror::{KissErrKind, KissError};
+use crate::{kisserr, kisserrcode, printlnex};
+
+//缓存的块高超过这个时间没有更新就重新查询
+const BLOCKNUMBER_MAX_AGE_SEC: u64 = 5;
+
+pub enum Bcos3WsStream {
+    Plain(TcpStream),
+    Tls(SslStream<TcpStream>),
+}
+
+impl Bcos3WsStream {
+    pub fn tcp(&self) -> &TcpStream {
+        match self {
+            Bcos3WsStream::Plain(s) => s,
+            Bcos3WsStream::Tls(s) => s.get_ref(),
+        }
+    }
+}
+
+impl Read for Bcos3WsStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Bcos3WsStream::Plain(s) => s.read(buf),
+            Bcos3WsStream::Tls(s) => s.read(buf),
+        }
+    }
+}
+
+impl Write for Bcos3WsStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Bcos3WsStream::Plain(s) => s.write(buf),
+            Bcos3WsStream::Tls(s) => s.write(buf),
+        }
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Bcos3WsStream::Plain(s) => s.flush(),
+            Bcos3WsStream::Tls(s) => s.flush(),
+        }
+    }
+}
+
+pub struct Bcos3WsClient {
+    pub peer: String,
+    pub timeout: Duration,
+    //握手时节点返回的协议版本和群组信息
+    pub protocol_version: i64,
+    pub handshake_result: JsonValue,
+    //各群组的块高和更新时间，握手、新区块通知和getBlockNumber时更新
+    pub blocknumbers: HashMap<String, (u64, Instant)>,
+    pub blocknumber_max_age: Duration,
+    socket: Option<WebSocket<Bcos3WsStream>>,
+    reqid: AtomicU64,
+}
+
+impl Bcos3WsClient {
+    ///按ini里的[peers]顺序逐个尝试连接，连上一个即可
+    pub fn connect(sdkini: &Bcos3sdkIni) -> Result<Bcos3WsClient, KissError> {
+        let mut peers: Vec<(&String, &String)> = sdkini.peers.iter().collect();
+        peers.sort();
+        let mut lasterr = KissError::new(KissErrKind::EArgument, -1, "no peers in config");
+        for (_, peer) in peers {
+            match Bcos3WsClient::connect_peer(peer.trim(), sdkini) {
+                Ok(client) => return Ok(client),
+                Err(e) => {
+                    printlnex!("connect to {} error {:?}", peer, e);
+                    lasterr = e;
+                }
+            }
+        }
+        Err(lasterr)
+    }
+
+    pub fn connect_peer(peer: &str, sdkini: &Bcos3sdkIni) -> Result<Bcos3WsClient, KissError> {
+        let timeout = Duration::from_millis(sdkini.message_timeout_ms);
+        let tcp_stream = match TcpStream::connect(peer) {
+            Ok(s) => s,
+            Err(e) => return kisserr!(KissErrKind::ENetwork, "connect {} error {:?}", peer, e),
+        };
+        let _ = tcp_stream.set_read_timeout(Some(timeout));
+        let _ = tcp_stream.set_nodelay(true);
+        let (stream, url) = if sdkini.disable_ssl {
+            (Bcos3WsStream::Plain(tcp_stream), format!("ws://{}/", peer))
+        } else {
+            let ssl_stream = Bcos3WsClient::ssl_connect(peer, tcp_stream, sdkini)?;
+            (Bcos3WsStream::Tls(ssl_stream), format!("wss://{}/", peer))
+        };
+        let socket = match tungstenite::client(url.as_str(), stream) {
+            Ok((socket, _response)) => socket,
+            Err(HandshakeError::Failure(e)) => {
+                return kisserr!(KissErrKind::ENetwork, "websocket handshake error {:?}", e)
+            }
+            Err(HandshakeError::Interrupted(_)) => {
+                return kisserr!(KissErrKind::ETimeout, "websocket handshake interrupted")
+            }
+        };
+        let mut client = Bcos3WsClient {
+            peer: peer.to_string(),
+            timeout,
+            protocol_version: 0,
+            handshake_result: JsonValue::Null,
+            blocknumbers: HashMap::new(),
+            blocknumber_max_age: Duration::from_secs(BLOCKNUMBER_MAX_AGE_SEC),
+            socket: Option::from(socket),
+            reqid: AtomicU64::new(1),
+        };
+        client.handshake()?;
+        Ok(client)
+    }
+
+    fn ssl_connect(
+        peer: &str,
+        tcp_stream: TcpStream,
+        sdkini: &Bcos3sdkIni,
+    ) -> Result<SslStream<TcpStream>, KissError> {
+        if sdkini.ssl_type != "ssl" {
+            return kisserr!(
+                KissErrKind::EArgument,
+                "ssl_type {} is not supported by bcos3sdk_native",
+                sdkini.ssl_type
+            );
+        }
+        let mut ctx = match SslConnector::builder(SslMethod::tls_client()) {
+            Ok(c) => c,
+            Err(e) => {
+                return kisserr!(KissErrKind::ENetwork, "sslconnector builder error {:?}", e);
+            }
+        };
+        let certpath = Path::new(sdkini.ca_path.as_str());
+        let res = ctx
+            .set_ca_file(certpath.join(&sdkini.ca_cert))
+            .and_then(|_| ctx.set_certificate_chain_file(certpath.join(&sdkini.sdk_cert)))
+            .and_then(|_| {
+                ctx.set_private_key_file(certpath.join(&sdkini.sdk_key), SslFiletype::PEM)
+            })
+            .and_then(|_| ctx.check_private_key());
+        if let Err(e) = res {
+            return kisserr!(KissErrKind::ENetwork, "set client certs error {:?}", e);
+        }
+        ctx.set_verify(SslVerifyMode::NONE);
+        let host = peer.split(':').next().unwrap_or(peer);
+        let ssl = match ctx.build().configure().and_then(|c| c.into_ssl(host)) {
+            Ok(s) => s,
+            Err(e) => return kisserr!(KissErrKind::ENetwork, "ssl_ctx into ssl {:?}", e),
+        };
+        let mut ssl_stream = match SslStream::new(ssl, tcp_stream) {
+            Ok(s) => s,
+            Err(e) => {
+                return kisserr!(
+                    KissErrKind::ENetwork,
+                    "SslStream new from TcpStream error {:?}",
+                    e
+                )
+            }
+        };
+        match ssl_stream.connect() {
+            Ok(_) => Ok(ssl_stream),
+            Err(e) => kisserr!(KissErrKind::ENetwork, "SslStream connect error {:?}", e),
+        }
+    }
+
+    ///协商协议版本，节点返回{"protocolVersion":1,"groupInfoList":[..],"groupBlockNumber":[{"group0":1}]}
+    fn handshake(&mut self) -> Result<(), KissError> {
+        let req = json!({"minVersion":1,"maxVersion":1,"clientType":"rust-gears-sdk"});
+        let msg = Bcos3WsMessage::new(BCOS3_WS_MSG_TYPE::HANDSHAKE, req.to_string().as_bytes());
+        let resp = self.request_sync(&msg)?;
+        let v: JsonValue = match serde_json::from_slice(&resp.payload) {
+            Ok(v) => v,
+            Err(e) => return kisserr!(KissErrKind::EFormat, "handshake response error {:?}", e),
+        };
+        self.protocol_version = v["protocolVersion"].as_i64().unwrap_or(1);
+        if let Some(list) = v["groupBlockNumber"].as_array() {
+            for item in list {
+                if let Some(obj) = item.as_object() {
+                    for (group, num) in obj {
+                        if let Some(n) = num.as_u64() {
+                            self.blocknumbers.insert(group.clone(), (n, Instant::now()));
+                        }
+                    }
+                }
+            }
+        }
+        self.handshake_result = v;
+        Ok(())
+    }
+
+    pub fn is_connect(&self) -> bool {
+        self.socket.is_some()
+    }
+
+    pub fn finish(&mut self) {
+        if let Some(mut socket) = self.socket.take() {
+            let _ = socket.close(None);
+            let _ = socket.write_pending();
+        }
+    }
+
+    pub fn send(&mut self, msg: &Bcos3WsMessage) -> Result<(), KissError> {
+        let socket = match self.socket.as_mut() {
+            Some(s) => s,
+            None => return kisserr!(KissErrKind::ENetwork, "websocket is not connected"),
+        };
+        printlnex!("ws send {}", msg.detail());
+        match socket.write_message(Message::Binary(msg.encode())) {
+            Ok(_) => Ok(()),
+            Err(e) => kisserr!(KissErrKind::ENetwork, "websocket send error {:?}", e),
+        }
+    }
+
+    ///读一个消息，超时返回None
+    pub fn recv(&mut self) -> Result<Option<Bcos3WsMessage>, KissError> {
+        let socket = match self.socket.as_mut() {
+            Some(s) => s,
+            None => return kisserr!(KissErrKind::ENetwork, "websocket is not connected"),
+        };
+        match socket.read_message() {
+            Ok(Message::Binary(data)) => {
+                let msg = Bcos3WsMessage::decode(&data)?;
+                printlnex!("ws recv {}", msg.detail());
+                Ok(Some(msg))
+            }
+            Ok(Message::Close(_)) => {
+                self.socket = None;
+                kisserr!(KissErrKind::ENetwork, "websocket closed by peer")
+            }
+            //ping/pong/text帧不是sdk协议的内容，忽略
+            Ok(_) => Ok(None),
+            Err(tungstenite::Error::Io(e))
+                if e.kind() == io::ErrorKind::WouldBlock || e.kind() == io::ErrorKind::TimedOut =>
+            {
+                Ok(None)
+            }
+            Err(e) => {
+                self.socket = None;
+                kisserr!(KissErrKind::ENetwork, "websocket recv error {:?}", e)
+            }
+        }
+    }
+
+    ///处理非应答类的消息
+    pub fn handle_push(&mut self, msg: Bcos3WsMessage) {
+        if msg.msgtype == BCOS3_WS_MSG_TYPE::BLOCK_NOTIFY as u16 {
+            if let Ok(v) = serde_json::from_slice::<JsonValue>(&msg.payload) {
+                if let (Some(group), Some(num)) = (v["group"].as_str(), v["blockNumber"].as_u64()) {
+                    self.blocknumbers
+                        .insert(group.to_string(), (num, Instant::now()));
+                }
+            }
+            return;
+        }
+        log::debug!("drop unsupported push message {}", msg.detail());
+    }
+
+    ///发送并等待seq相同的应答
+    pub fn request_sync(&mut self, msg: &Bcos3WsMessage) -> Result<Bcos3WsMessage, KissError> {
+        self.send(msg)?;
+        let start = Instant::now();
+        while start.elapsed() < self.timeout {
+            if let Some(resp) = self.recv()? {
+                if resp.seq == msg.seq {
+                    if resp.status != 0 {
+                        return kisserrcode!(
+                            KissErrKind::Error,
+                            resp.status as i64,
+                            "ws response status {} : {}",
+                            resp.status,
+                            String::from_utf8_lossy(&resp.payload)
+                        );
+                    }
+                    return Ok(resp);
+                }
+                self.handle_push(resp);
+            }
+        }
+        kisserr!(
+            KissErrKind::ETimeout,
+            "wait response for seq {} timeout",
+            msg.seq
+        )
+    }
+
+    ///jsonrpc请求，返回节点应答的json原文(含id,jsonrpc,result或error)
+    pub fn rpc_request_sync(
+        &mut self,
+        method: &str,
+        params: &JsonValue,
+    ) -> Result<String, KissError> {
+        let req = json!({
+            "jsonrpc":"2.0",
+            "method":method,
+            "params":params,
+            "id":self.reqid.fetch_add(1, Ordering::Relaxed)
+        });
+        let msg = Bcos3WsMessage::new(BCOS3_WS_MSG_TYPE::RPC_REQUEST, req.to_string().as_bytes());
+        let resp = self.request_sync(&msg)?;
+        Ok(String::from_utf8_lossy(&resp.payload).to_string())
+    }
+
+    pub fn get_blocknumber(&mut self, group: &str) -> Result<u64, KissError> {
+        let text = self.rpc_request_sync("getBlockNumber", &json!([group, ""]))?;
+        let v: JsonValue = match serde_json::from_str(text.as_str()) {
+            Ok(v) => v,
+            Err(e) => return kisserr!(KissErrKind::EFormat, "getBlockNumber response {:?}", e),
+        };
+        match v["result"].as_u64() {
+            Some(num) => {
+                self.blocknumbers
+                    .insert(group.to_string(), (num, Instant::now()));
+                Ok(num)
+            }
+            None => kisserr!(KissErrKind::EFormat, "getBlockNumber response {}", text),
+        }
+    }
+
+    ///缓存的块高，没有或超过blocknumber_max_age时向节点查询
+    pub fn blocknumber(&mut self, group: &str) -> Result<u64, KissError> {
+        match self.blocknumbers.get(group) {
+            Some((num, updated)) if updated.elapsed() < self.blocknumber_max_age => Ok(*num),
+            _ => self.get_blocknumber(group),
+        }
+    }
+
+    ///握手返回的群组信息
+    pub fn find_group_info(&self, group: &str) -> Option<JsonValue> {
+        let list = self.handshake_result["groupInfoList"].as_array()?;
+        list.iter()
+            .find(|g| g["groupID"].as_str() == Some(group))
+            .cloned()
+    }
+}
+
+impl Drop for Bcos3WsClient {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
@@ -0,0 +1,144 @@
+/*
+  FISCO BCOS/rust-SDK is a rust client for FISCO BCOS2.0 (https://github.com/FISCO-BCOS/)
+  FISCO BCOS/rust-SDK is free software: you can redistribute it and/or modify it under the
+  terms of the MIT License as published by the Free Software Foundation. This project is
+  distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even
+  the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+  @author: kentzhang
+  @date: 2021-07
+*/
+#![allow(
+    clippy::unreadable_literal,
+    clippy::upper_case_acronyms,
+    dead_code,
+    non_camel_case_types,
+    non_snake_case,
+    non_upper_case_globals,
+    overflowing_literals,
+    unused_variables,
+    unused_assignments
+)]
+
+/*
+FISCO BCOS3.0 节点和sdk之间的websocket消息格式(bcos-boostssl的WsMessage)，
+每个websocket binary帧承载一个消息，数字均为大端：
+
+version	uint16_t	2	协议版本
+type	uint16_t	2	消息类型，见BCOS3_WS_MSG_TYPE
+status	int16_t	2	错误码，0为成功
+seqlen	uint16_t	2	seq长度
+seq	string	seqlen	消息序列号，sdk一般用32字节的uuid，应答和请求的seq相同
+ext	uint16_t	2	扩展标志位
+payload	bytes	剩余部分	消息体，rpc类的消息为jsonrpc 2.0格式
+
+参见：https://github.com/FISCO-BCOS/bcos-boostssl
+*/
+use std::convert::TryInto;
+
+use crate::bcossdkutil::kisserror::{KissErrKind, KissError};
+use crate::kisserr;
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum BCOS3_WS_MSG_TYPE {
+    HANDSHAKE = 0x100,         //握手，协商协议版本，节点返回群组信息
+    BLOCK_NOTIFY = 0x101,      //新区块通知 节点->SDK
+    RPC_REQUEST = 0x102,       //jsonrpc请求，应答的type相同
+    GROUP_NOTIFY = 0x103,      //群组信息变更通知 节点->SDK
+    AMOP_SUBTOPIC = 0x110,     //AMOP订阅topic
+    AMOP_REQUEST = 0x111,      //AMOP单播请求
+    AMOP_BROADCAST = 0x112,    //AMOP广播
+    AMOP_RESPONSE = 0x113,     //AMOP应答
+    EVENT_SUBSCRIBE = 0x120,   //订阅event
+    EVENT_UNSUBSCRIBE = 0x121, //取消订阅event
+    EVENT_LOG_PUSH = 0x122,    //event推送 节点->SDK
+}
+
+pub const BCOS3_WS_MSG_VERSION: u16 = 1;
+//version,type,status,seqlen,ext
+const WS_MSG_FIXED_LEN: usize = 10;
+
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct Bcos3WsMessage {
+    pub version: u16,
+    pub msgtype: u16,
+    pub status: i16,
+    pub seq: String,
+    pub ext: u16,
+    pub payload: Vec<u8>,
+}
+
+impl Bcos3WsMessage {
+    pub fn new(msgtype: BCOS3_WS_MSG_TYPE, payload: &[u8]) -> Self {
+        Bcos3WsMessage {
+            version: BCOS3_WS_MSG_VERSION,
+            msgtype: msgtype as u16,
+            status: 0,
+            seq: Bcos3WsMessage::make_seq(),
+            ext: 0,
+            payload: payload.to_vec(),
+        }
+    }
+
+    ///32字节的随机hex字符串，和c sdk的uuid去掉'-'后的长度一致
+    pub fn make_seq() -> String {
+        let v: [u8; 16] = rand::random();
+        hex::encode(v)
+    }
+
+    pub fn detail(&self) -> String {
+        format!(
+            "ver:{},type:0x{:X},status:{},seq:{},ext:{},payload:{}",
+            self.version,
+            self.msgtype,
+            self.status,
+            self.seq,
+            self.ext,
+            String::from_utf8_lossy(&self.payload)
+        )
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buffer: Vec<u8> =
+            Vec::with_capacity(WS_MSG_FIXED_LEN + self.seq.len() + self.payload.len());
+        buffer.extend_from_slice(&self.version.to_be_bytes());
+        buffer.extend_from_slice(&self.msgtype.to_be_bytes());
+        buffer.extend_from_slice(&self.status.to_be_bytes());
+        buffer.extend_from_slice(&(self.seq.len() as u16).to_be_bytes());
+        buffer.extend_from_slice(self.seq.as_bytes());
+        buffer.extend_from_slice(&self.ext.to_be_bytes());
+        buffer.extend_from_slice(&self.payload);
+        buffer
+    }
+
+    pub fn decode(data: &[u8]) -> Result<Bcos3WsMessage, KissError> {
+        if data.len() < WS_MSG_FIXED_LEN {
+            return kisserr!(
+                KissErrKind::EFormat,
+                "ws message data length too short: {}",
+                data.len()
+            );
+        }
+        let mut msg = Bcos3WsMessage {
+            version: u16::from_be_bytes(data[0..2].try_into().unwrap()),
+            msgtype: u16::from_be_bytes(data[2..4].try_into().unwrap()),
+            status: i16::from_be_bytes(data[4..6].try_into().unwrap()),
+            ..Default::default()
+        };
+        let seqlen = u16::from_be_bytes(data[6..8].try_into().unwrap()) as usize;
+        let mut start = 8;
+        if data.len() < WS_MSG_FIXED_LEN + seqlen {
+            return kisserr!(
+                KissErrKind::EFormat,
+                "ws message seq length {} exceed data length {}",
+                seqlen,
+                data.len()
+            );
+        }
+        msg.seq = String::from_utf8_lossy(&data[start..start + seqlen]).to_string();
+        start += seqlen;
+        msg.ext = u16::from_be_bytes(data[start..start + 2].try_into().unwrap());
+        start += 2;
+        msg.payload = data[start..].to_vec();
+        Ok(msg)
+    }
+}
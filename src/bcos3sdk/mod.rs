@@ -9,7 +9,16 @@
 */
 pub mod bcos3client;
 pub mod bcos3sdkamop;
+pub mod bcos3sdkevent;
 pub mod bcos3sdkfuture;
 pub mod bcos3sdkresponse;
 pub mod bcos3sdkwrapper;
 pub mod bcos3sdk_ini;
+pub mod bcos3tars;
+pub mod bcos3transaction;
+#[cfg(feature = "bcos3sdk_native")]
+pub mod bcos3sdk_native;
+#[cfg(feature = "bcos3sdk_native")]
+pub mod bcos3wsclient;
+#[cfg(feature = "bcos3sdk_native")]
+pub mod bcos3wsmessage;
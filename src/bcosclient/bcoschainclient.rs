@@ -0,0 +1,592 @@
+/*
+  FISCO BCOS/rust-SDK is a rust client for FISCO BCOS2.0 (https://github.com/FISCO-BCOS/)
+  FISCO BCOS/rust-SDK is free software: you can redistribute it and/or modify it under the
+  terms of the MIT License as published by the Free Software Foundation. This project is
+  distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even
+  the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+  @author: kentzhang
+  @date: 2021-07
+*/
+#![allow(
+    clippy::unreadable_literal,
+    clippy::upper_case_acronyms,
+    dead_code,
+    non_camel_case_types,
+    non_snake_case,
+    non_upper_case_globals,
+    overflowing_literals,
+    unused_variables,
+    unused_assignments
+)]
+
+/*
+Bcos2Client和Bcos3Client的方法名相近，但参数、返回值(u32/u64，json结构)、可变性都不一样，
+这里定义一个与链版本无关的trait，统一用u64和归一化的数据结构，应用代码只写一次，
+由create_chain_client根据配置在运行时选择后端：
+配置文件[bcos2]段的protocol为"BCOS3"时使用Bcos3Client，为"RPC"/"CHANNEL"时使用Bcos2Client
+*/
+use std::thread;
+use std::time::Duration;
+
+use ethabi::Token;
+use serde_json::Value as JsonValue;
+
+use crate::bcos2sdk::bcos2client::Bcos2Client;
+use crate::bcos3sdk::bcos3client::Bcos3Client;
+use crate::bcosclient::bcoschaintypes::{
+    BcosBlockInfo, BcosCallResult, BcosReceiptInfo, BcosTxInfo,
+};
+use crate::bcossdkutil::accountutil::BcosAccount;
+use crate::bcossdkutil::bcosclientconfig::{BcosClientProtocol, ClientConfig};
+use crate::bcossdkutil::commonhash::HashType;
+use crate::bcossdkutil::contractabi::ContractABI;
+use crate::bcossdkutil::kisserror::{KissErrKind, KissError};
+use crate::{kisserr, kisserrcode};
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum BcosChainVersion {
+    V2,
+    V3,
+}
+
+///与链版本无关的客户端接口，网络相关的方法一律用&mut self，因为bcos2的客户端需要可变引用
+pub trait IBcosChainClient {
+    fn chain_version(&self) -> BcosChainVersion;
+    ///用于合约历史记录(contracthistory.toml)里区分不同链的名字
+    fn get_full_name(&self) -> String;
+    fn get_info(&self) -> String;
+    fn get_config(&self) -> &ClientConfig;
+    fn get_account(&self) -> &BcosAccount;
+    fn get_hashtype(&self) -> &HashType;
+
+    fn get_block_number(&mut self) -> Result<u64, KissError>;
+    fn get_block_limit(&mut self) -> Result<u64, KissError>;
+    ///include_txs为false时，区块里只有交易hash(tx_hashes)
+    fn get_block_by_number(
+        &mut self,
+        num: u64,
+        include_txs: bool,
+    ) -> Result<BcosBlockInfo, KissError>;
+    fn get_block_by_hash(
+        &mut self,
+        hash: &str,
+        include_txs: bool,
+    ) -> Result<BcosBlockInfo, KissError>;
+    fn get_transaction_by_hash(&mut self, hash: &str) -> Result<BcosTxInfo, KissError>;
+    ///交易还没上链时返回Ok(None)
+    fn get_transaction_receipt(&mut self, hash: &str)
+        -> Result<Option<BcosReceiptInfo>, KissError>;
+
+    fn call(
+        &mut self,
+        contract: &ContractABI,
+        address: &str,
+        method: &str,
+        params: &[String],
+    ) -> Result<BcosCallResult, KissError>;
+    ///发送交易并等待回执
+    fn send_transaction(
+        &mut self,
+        contract: &ContractABI,
+        address: &str,
+        method: &str,
+        params: &[String],
+    ) -> Result<BcosReceiptInfo, KissError>;
+    ///按合约名从配置的contractpath加载abi和bin，部署并等待回执，新地址在回执的contract_address里
+    fn deploy(
+        &mut self,
+        contractname: &str,
+        params: &[String],
+    ) -> Result<BcosReceiptInfo, KissError>;
+    //以下三个方法的参数是已经按abi类型构造好的token或编码好的hex，供生成的合约绑定代码使用
+    fn call_with_tokens(
+        &mut self,
+        contract: &ContractABI,
+        address: &str,
+        method: &str,
+        params: &[Token],
+    ) -> Result<BcosCallResult, KissError>;
+    fn send_transaction_with_tokens(
+        &mut self,
+        contract: &ContractABI,
+        address: &str,
+        method: &str,
+        params: &[Token],
+    ) -> Result<BcosReceiptInfo, KissError>;
+    ///hexcode为合约代码，已追加编码好的构造函数参数
+    fn deploy_hexcode(&mut self, hexcode: &str) -> Result<BcosReceiptInfo, KissError>;
+    ///发送已经签名好的交易(hex)并等待回执，用于广播离线签名的交易
+    fn send_signed_transaction(&mut self, rawtx_hex: &str) -> Result<BcosReceiptInfo, KissError> {
+        kisserr!(
+            KissErrKind::Error,
+            "{} does not support sending signed transaction",
+            self.get_full_name()
+        )
+    }
+
+    ///是否实现了send_signed_transaction_nowait
+    fn supports_send_nowait(&self) -> bool {
+        false
+    }
+    ///只发送签名好的交易，不等回执，返回交易hash。和collect_receipts配合，可以在一个连接上连续发送多笔交易
+    fn send_signed_transaction_nowait(&mut self, rawtx_hex: &str) -> Result<String, KissError> {
+        kisserr!(
+            KissErrKind::Error,
+            "{} does not support sending signed transaction without waiting",
+            self.get_full_name()
+        )
+    }
+
+    ///收取pending中已经上链的交易回执，返回(交易hash,回执)，还没上链的不返回。
+    ///默认逐个查询回执，能收到上链推送的客户端可以覆盖
+    fn collect_receipts(
+        &mut self,
+        pending: &[String],
+    ) -> Result<Vec<(String, BcosReceiptInfo)>, KissError> {
+        poll_receipts(self, pending)
+    }
+
+    fn finish(&mut self);
+
+    ///发交易后等待回执的超时时间，取配置的[bcos2]receipt_timeout_sec，bcos3发交易时也用这个值
+    fn receipt_timeout_sec(&self) -> i64 {
+        self.get_config().bcos2.receipt_timeout_sec as i64
+    }
+
+    ///轮询获取回执，直到超时
+    fn wait_transaction_receipt(
+        &mut self,
+        hash: &str,
+        timeoutsec: i64,
+    ) -> Result<BcosReceiptInfo, KissError> {
+        let start = time::now();
+        while time::now() - start < chrono::Duration::seconds(timeoutsec) {
+            if let Some(receipt) = self.get_transaction_receipt(hash)? {
+                return Ok(receipt);
+            }
+            thread::sleep(Duration::from_millis(200));
+        }
+        kisserr!(
+            KissErrKind::ETimeout,
+            "wait receipt for {} timeout ({}s)",
+            hash,
+            timeoutsec
+        )
+    }
+
+    ///等待块高超过after，返回最新块高，超时时返回的块高可能不大于after。
+    ///默认轮询块高，能收到新块推送的客户端可以覆盖
+    fn wait_new_block(&mut self, after: u64, timeout_ms: u64) -> Result<u64, KissError> {
+        poll_new_block(self, after, timeout_ms)
+    }
+}
+
+///轮询块高，直到超过after或超时
+pub fn poll_new_block<C: IBcosChainClient + ?Sized>(
+    client: &mut C,
+    after: u64,
+    timeout_ms: u64,
+) -> Result<u64, KissError> {
+    let start = time::now();
+    loop {
+        let current = client.get_block_number()?;
+        if current > after
+            || time::now() - start >= chrono::Duration::milliseconds(timeout_ms as i64)
+        {
+            return Ok(current);
+        }
+        thread::sleep(Duration::from_millis(200));
+    }
+}
+
+///逐个查询pending的回执，一个都没查到时等一会，避免空转
+pub fn poll_receipts<C: IBcosChainClient + ?Sized>(
+    client: &mut C,
+    pending: &[String],
+) -> Result<Vec<(String, BcosReceiptInfo)>, KissError> {
+    let mut receipts = vec![];
+    for hash in pending {
+        if let Some(receipt) = client.get_transaction_receipt(hash)? {
+            receipts.push((hash.clone(), receipt));
+        }
+    }
+    if receipts.is_empty() {
+        thread::sleep(Duration::from_millis(200));
+    }
+    Ok(receipts)
+}
+
+///根据配置文件创建客户端
+pub fn create_chain_client(configfile: &str) -> Result<Box<dyn IBcosChainClient>, KissError> {
+    let config = ClientConfig::load(configfile)?;
+    match config.bcos2.protocol {
+        BcosClientProtocol::BCOS3 => Ok(Box::new(Bcos3Client::new(configfile)?)),
+        _ => Ok(Box::new(Bcos2Client::new_from_config(configfile)?)),
+    }
+}
+
+///bcos2的rpc返回完整的jsonrpc应答，取出result，或把error转成KissError
+fn bcos2_result(response: JsonValue) -> Result<JsonValue, KissError> {
+    if let Some(err) = response.get("error") {
+        let code = err["code"].as_i64().unwrap_or(-1);
+        let msg = err["message"].as_str().unwrap_or("");
+        return kisserrcode!(KissErrKind::Error, code, "{}", msg);
+    }
+    match response.get("result") {
+        Some(v) => Ok(v.clone()),
+        None => kisserr!(KissErrKind::EFormat, "no result in response {:?}", response),
+    }
+}
+
+fn tx_not_found(hash: &str, v: &JsonValue) -> Result<BcosTxInfo, KissError> {
+    if v.is_null() {
+        return kisserr!(KissErrKind::Error, "transaction {} not found", hash);
+    }
+    BcosTxInfo::from_json(v)
+}
+
+impl IBcosChainClient for Bcos2Client {
+    fn chain_version(&self) -> BcosChainVersion {
+        BcosChainVersion::V2
+    }
+    fn get_full_name(&self) -> String {
+        "bcos2".to_string()
+    }
+    fn get_info(&self) -> String {
+        self.to_summary()
+    }
+    fn get_config(&self) -> &ClientConfig {
+        &self.config
+    }
+    fn get_account(&self) -> &BcosAccount {
+        &self.account
+    }
+    fn get_hashtype(&self) -> &HashType {
+        &self.hashtype
+    }
+
+    fn get_block_number(&mut self) -> Result<u64, KissError> {
+        Ok(self.getBlockNumber()? as u64)
+    }
+    fn get_block_limit(&mut self) -> Result<u64, KissError> {
+        Ok(self.getBlockLimit()? as u64)
+    }
+    fn get_block_by_number(
+        &mut self,
+        num: u64,
+        include_txs: bool,
+    ) -> Result<BcosBlockInfo, KissError> {
+        let v = bcos2_result(self.getBlockByNumber(num as u32, include_txs)?)?;
+        BcosBlockInfo::from_json(&v)
+    }
+    fn get_block_by_hash(
+        &mut self,
+        hash: &str,
+        include_txs: bool,
+    ) -> Result<BcosBlockInfo, KissError> {
+        let v = bcos2_result(self.getBlockByHash(hash, include_txs)?)?;
+        BcosBlockInfo::from_json(&v)
+    }
+    fn get_transaction_by_hash(&mut self, hash: &str) -> Result<BcosTxInfo, KissError> {
+        let v = bcos2_result(self.getTransactionByHash(hash)?)?;
+        tx_not_found(hash, &v)
+    }
+    fn get_transaction_receipt(
+        &mut self,
+        hash: &str,
+    ) -> Result<Option<BcosReceiptInfo>, KissError> {
+        let v = bcos2_result(self.getTransactionReceipt(hash)?)?;
+        if v.is_null() {
+            return Ok(None);
+        }
+        Ok(Some(BcosReceiptInfo::from_json(&v)?))
+    }
+
+    fn call(
+        &mut self,
+        contract: &ContractABI,
+        address: &str,
+        method: &str,
+        params: &[String],
+    ) -> Result<BcosCallResult, KissError> {
+        let v = bcos2_result(Bcos2Client::call(self, contract, address, method, params)?)?;
+        BcosCallResult::from_json(&v)
+    }
+    fn send_transaction(
+        &mut self,
+        contract: &ContractABI,
+        address: &str,
+        method: &str,
+        params: &[String],
+    ) -> Result<BcosReceiptInfo, KissError> {
+        let response = self.send_raw_transaction(contract, address, method, params)?;
+        let txhash = bcos2_result(response)?;
+        match txhash.as_str() {
+            Some(h) => self
+                .wait_transaction_receipt(h, self.receipt_timeout_sec())?
+                .check_status(&BcosChainVersion::V2),
+            None => kisserr!(KissErrKind::EFormat, "txhash not found {:?}", txhash),
+        }
+    }
+    fn deploy(
+        &mut self,
+        contractname: &str,
+        params: &[String],
+    ) -> Result<BcosReceiptInfo, KissError> {
+        let response = self.deploy_withparam(contractname, params)?;
+        let txhash = bcos2_result(response)?;
+        match txhash.as_str() {
+            Some(h) => self
+                .wait_transaction_receipt(h, self.receipt_timeout_sec())?
+                .check_status(&BcosChainVersion::V2),
+            None => kisserr!(KissErrKind::EFormat, "txhash not found {:?}", txhash),
+        }
+    }
+    fn call_with_tokens(
+        &mut self,
+        contract: &ContractABI,
+        address: &str,
+        method: &str,
+        params: &[Token],
+    ) -> Result<BcosCallResult, KissError> {
+        let response = self.call_with_tokenparam(contract, address, method, params)?;
+        BcosCallResult::from_json(&bcos2_result(response)?)
+    }
+    fn send_transaction_with_tokens(
+        &mut self,
+        contract: &ContractABI,
+        address: &str,
+        method: &str,
+        params: &[Token],
+    ) -> Result<BcosReceiptInfo, KissError> {
+        let response =
+            self.send_raw_transaction_withtokenparam(contract, address, method, params)?;
+        let txhash = bcos2_result(response)?;
+        match txhash.as_str() {
+            Some(h) => self
+                .wait_transaction_receipt(h, self.receipt_timeout_sec())?
+                .check_status(&BcosChainVersion::V2),
+            None => kisserr!(KissErrKind::EFormat, "txhash not found {:?}", txhash),
+        }
+    }
+    fn deploy_hexcode(&mut self, hexcode: &str) -> Result<BcosReceiptInfo, KissError> {
+        let response = Bcos2Client::deploy_hexcode(self, hexcode)?;
+        let txhash = bcos2_result(response)?;
+        match txhash.as_str() {
+            Some(h) => self
+                .wait_transaction_receipt(h, self.receipt_timeout_sec())?
+                .check_status(&BcosChainVersion::V2),
+            None => kisserr!(KissErrKind::EFormat, "txhash not found {:?}", txhash),
+        }
+    }
+
+    fn send_signed_transaction(&mut self, rawtx_hex: &str) -> Result<BcosReceiptInfo, KissError> {
+        let rawtx = match hex::decode(rawtx_hex.trim().trim_start_matches("0x")) {
+            Ok(data) => data,
+            Err(e) => return kisserr!(KissErrKind::EFormat, "signed tx is not hex {:?}", e),
+        };
+        let timeout = self.receipt_timeout_sec();
+        let response = self.send_signed_tx_get_receipt(&rawtx, timeout)?;
+        BcosReceiptInfo::from_json(&bcos2_result(response)?)?.check_status(&BcosChainVersion::V2)
+    }
+
+    fn supports_send_nowait(&self) -> bool {
+        true
+    }
+    fn send_signed_transaction_nowait(&mut self, rawtx_hex: &str) -> Result<String, KissError> {
+        let rawtx = match hex::decode(rawtx_hex.trim().trim_start_matches("0x")) {
+            Ok(data) => data,
+            Err(e) => return kisserr!(KissErrKind::EFormat, "signed tx is not hex {:?}", e),
+        };
+        self.send_signed_tx(&rawtx)
+    }
+
+    //channel协议下收节点推送的TX_COMMITTED，按回执里的transactionHash对应到pending
+    fn collect_receipts(
+        &mut self,
+        pending: &[String],
+    ) -> Result<Vec<(String, BcosReceiptInfo)>, KissError> {
+        if self.config.bcos2.protocol != BcosClientProtocol::CHANNEL {
+            return poll_receipts(self, pending);
+        }
+        let mut receipts = vec![];
+        for text in self.netclient.channel_client.take_tx_committed(200)? {
+            let v: JsonValue = match serde_json::from_str(text.as_str()) {
+                Ok(v) => v,
+                Err(e) => {
+                    log::warn!("tx committed data is not json {},{:?}", text, e);
+                    continue;
+                }
+            };
+            let hash = v["transactionHash"].as_str().unwrap_or_default();
+            if let Some(h) = pending.iter().find(|h| h.eq_ignore_ascii_case(hash)) {
+                receipts.push((h.clone(), BcosReceiptInfo::from_json(&v)?));
+            }
+        }
+        Ok(receipts)
+    }
+
+    fn finish(&mut self) {
+        Bcos2Client::finish(self)
+    }
+
+    //channel协议下订阅_block_notify_{groupid}，由节点推送新块，不用轮询
+    fn wait_new_block(&mut self, after: u64, timeout_ms: u64) -> Result<u64, KissError> {
+        if self.config.bcos2.protocol != BcosClientProtocol::CHANNEL {
+            return poll_new_block(self, after, timeout_ms);
+        }
+        let topic = format!("_block_notify_{}", self.config.bcos2.groupid);
+        let channel = &mut self.netclient.channel_client;
+        if !channel.topics.contains(&topic) {
+            let mut topics = channel.topics.clone();
+            topics.push(topic);
+            channel.report_topics(&topics)?;
+        }
+        //订阅生效前出的块收不到通知，先查一次
+        let current = self.get_block_number()?;
+        if current > after {
+            return Ok(current);
+        }
+        match self
+            .netclient
+            .channel_client
+            .wait_block_notify(timeout_ms)?
+        {
+            Some(n) if n > current => Ok(n),
+            _ => Ok(current),
+        }
+    }
+}
+
+//bcos3的c sdk在发交易时已经等待了回执，所以send_transaction和deploy直接解析返回值即可
+impl IBcosChainClient for Bcos3Client {
+    fn chain_version(&self) -> BcosChainVersion {
+        BcosChainVersion::V3
+    }
+    fn get_full_name(&self) -> String {
+        Bcos3Client::get_full_name(self)
+    }
+    fn get_info(&self) -> String {
+        Bcos3Client::get_info(self)
+    }
+    fn get_config(&self) -> &ClientConfig {
+        &self.config
+    }
+    fn get_account(&self) -> &BcosAccount {
+        &self.account
+    }
+    fn get_hashtype(&self) -> &HashType {
+        &self.hashtype
+    }
+
+    fn get_block_number(&mut self) -> Result<u64, KissError> {
+        self.getBlockNumber()
+    }
+    fn get_block_limit(&mut self) -> Result<u64, KissError> {
+        self.getBlocklimit()
+    }
+    fn get_block_by_number(
+        &mut self,
+        num: u64,
+        include_txs: bool,
+    ) -> Result<BcosBlockInfo, KissError> {
+        let only_tx_hash = if include_txs { 0 } else { 1 };
+        let v = self.getBlockByNumber(num, 0, only_tx_hash)?;
+        BcosBlockInfo::from_json(&v)
+    }
+    fn get_block_by_hash(
+        &mut self,
+        hash: &str,
+        include_txs: bool,
+    ) -> Result<BcosBlockInfo, KissError> {
+        let only_tx_hash = if include_txs { 0 } else { 1 };
+        let v = self.getBlockByHash(hash, 0, only_tx_hash)?;
+        BcosBlockInfo::from_json(&v)
+    }
+    fn get_transaction_by_hash(&mut self, hash: &str) -> Result<BcosTxInfo, KissError> {
+        let v = self.getTransactionByHash(hash, 0)?;
+        tx_not_found(hash, &v)
+    }
+    fn get_transaction_receipt(
+        &mut self,
+        hash: &str,
+    ) -> Result<Option<BcosReceiptInfo>, KissError> {
+        let v = self.getTransactionReceipt(hash, 0)?;
+        if v.is_null() {
+            return Ok(None);
+        }
+        Ok(Some(BcosReceiptInfo::from_json(&v)?))
+    }
+
+    fn call(
+        &mut self,
+        contract: &ContractABI,
+        address: &str,
+        method: &str,
+        params: &[String],
+    ) -> Result<BcosCallResult, KissError> {
+        let v = Bcos3Client::call(self, address, method, &params.to_vec(), contract)?;
+        BcosCallResult::from_json(&v)
+    }
+    fn send_transaction(
+        &mut self,
+        contract: &ContractABI,
+        address: &str,
+        method: &str,
+        params: &[String],
+    ) -> Result<BcosReceiptInfo, KissError> {
+        let v = self.sendTransaction(address, method, params, contract)?;
+        BcosReceiptInfo::from_json(&v)
+    }
+    fn deploy(
+        &mut self,
+        contractname: &str,
+        params: &[String],
+    ) -> Result<BcosReceiptInfo, KissError> {
+        let v = self.deploy_withparam(contractname, params)?;
+        BcosReceiptInfo::from_json(&v)
+    }
+    fn call_with_tokens(
+        &mut self,
+        contract: &ContractABI,
+        address: &str,
+        method: &str,
+        params: &[Token],
+    ) -> Result<BcosCallResult, KissError> {
+        let v = self.call_with_tokenparam(address, method, params, contract)?;
+        BcosCallResult::from_json(&v)
+    }
+    fn send_transaction_with_tokens(
+        &mut self,
+        contract: &ContractABI,
+        address: &str,
+        method: &str,
+        params: &[Token],
+    ) -> Result<BcosReceiptInfo, KissError> {
+        let function = contract.find_function_unwrap(method)?;
+        let functiondata =
+            ContractABI::encode_function_input_to_abi_by_tokens(&function, params, &self.hashtype)?;
+        let v = self.sendRawTransaction(address, method, hex::encode(functiondata).as_str())?;
+        BcosReceiptInfo::from_json(&v)
+    }
+    fn deploy_hexcode(&mut self, hexcode: &str) -> Result<BcosReceiptInfo, KissError> {
+        let v = Bcos3Client::deploy_hexcode(self, hexcode)?;
+        BcosReceiptInfo::from_json(&v)
+    }
+
+    fn send_signed_transaction(&mut self, rawtx_hex: &str) -> Result<BcosReceiptInfo, KissError> {
+        let v = Bcos3Client::send_signed_transaction(self, rawtx_hex, "")?;
+        BcosReceiptInfo::from_json(&v)
+    }
+
+    fn supports_send_nowait(&self) -> bool {
+        true
+    }
+    fn send_signed_transaction_nowait(&mut self, rawtx_hex: &str) -> Result<String, KissError> {
+        Bcos3Client::send_signed_transaction_nowait(self, rawtx_hex)
+    }
+
+    fn finish(&mut self) {
+        Bcos3Client::finish(self)
+    }
+}
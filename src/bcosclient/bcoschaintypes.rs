@@ -0,0 +1,302 @@
+/*
+  FISCO BCOS/rust-SDK is a rust client for FISCO BCOS2.0 (https://github.com/FISCO-BCOS/)
+  FISCO BCOS/rust-SDK is free software: you can redistribute it and/or modify it under the
+  terms of the MIT License as published by the Free Software Foundation. This project is
+  distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even
+  the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+  @author: kentzhang
+  @date: 2021-07
+*/
+#![allow(
+    clippy::unreadable_literal,
+    clippy::upper_case_acronyms,
+    dead_code,
+    non_camel_case_types,
+    non_snake_case,
+    non_upper_case_globals,
+    overflowing_literals,
+    unused_variables,
+    unused_assignments
+)]
+
+/*
+归一化的区块、交易、回执数据结构。
+bcos2的rpc返回的数字大多是"0x1a"这样的hex字符串，字段名如transactionsRoot,logs；
+bcos3的c sdk返回的是json数字，字段名如txsRoot,logEntries。
+这里的from_json同时兼容两种格式，常用字段直接取出，其余字段可以从raw里找
+*/
+use serde_json::Value as JsonValue;
+
+use crate::bcosclient::bcoschainclient::BcosChainVersion;
+use crate::bcossdkutil::kisserror::{KissErrKind, KissError};
+use crate::bcossdkutil::txstatus::ReceiptStatus;
+use crate::kisserr;
+
+///把json里的数字统一转成u64，兼容json数字、"0x"开头的hex字符串、十进制字符串
+pub fn json_to_u64(v: &JsonValue) -> Option<u64> {
+    match v {
+        JsonValue::Number(n) => match n.as_u64() {
+            Some(u) => Some(u),
+            None => n.as_i64().map(|i| i as u64),
+        },
+        JsonValue::String(s) => {
+            let s = s.trim();
+            if s.starts_with("0x") || s.starts_with("0X") {
+                u64::from_str_radix(&s[2..], 16).ok()
+            } else {
+                s.parse::<u64>().ok()
+            }
+        }
+        _ => None,
+    }
+}
+
+fn field_u64(v: &JsonValue, names: &[&str]) -> Option<u64> {
+    for name in names {
+        if let Some(num) = v.get(*name).and_then(json_to_u64) {
+            return Some(num);
+        }
+    }
+    None
+}
+
+//状态码缺失或无法解析时报错，不能当作成功(0)
+fn field_status(v: &JsonValue, name: &str) -> Result<i64, KissError> {
+    match field_u64(v, &["status"]) {
+        Some(status) => Ok(status as i64),
+        None => kisserr!(
+            KissErrKind::EFormat,
+            "{} has no valid status : {:?}",
+            name,
+            v["status"]
+        ),
+    }
+}
+
+fn field_str(v: &JsonValue, names: &[&str]) -> String {
+    for name in names {
+        if let Some(s) = v.get(*name).and_then(|x| x.as_str()) {
+            return s.to_string();
+        }
+    }
+    "".to_string()
+}
+
+fn str_list(v: &JsonValue) -> Vec<String> {
+    match v.as_array() {
+        Some(arr) => arr
+            .iter()
+            .filter_map(|x| x.as_str().map(|s| s.to_string()))
+            .collect(),
+        None => vec![],
+    }
+}
+
+fn check_object(v: &JsonValue, name: &str) -> Result<(), KissError> {
+    if !v.is_object() {
+        return kisserr!(
+            KissErrKind::EFormat,
+            "{} is not a json object : {:?}",
+            name,
+            v
+        );
+    }
+    Ok(())
+}
+
+///回执里的一条log,bcos2为logs，bcos3为logEntries
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BcosLogInfo {
+    pub address: String,
+    pub topics: Vec<String>,
+    pub data: String,
+}
+
+impl BcosLogInfo {
+    pub fn from_json(v: &JsonValue) -> Self {
+        BcosLogInfo {
+            address: field_str(v, &["address"]),
+            topics: str_list(&v["topics"]),
+            data: field_str(v, &["data"]),
+        }
+    }
+}
+
+///交易回执
+#[derive(Debug, Clone, Default)]
+pub struct BcosReceiptInfo {
+    pub transaction_hash: String,
+    pub block_number: u64,
+    pub status: i64,
+    pub from: String,
+    pub to: String,
+    pub contract_address: String,
+    pub input: String,
+    pub output: String,
+    pub gas_used: u64,
+    pub logs: Vec<BcosLogInfo>,
+    //节点返回的原始json，归一化之外的字段（如proof，message，version等）从这里取
+    pub raw: JsonValue,
+}
+
+impl BcosReceiptInfo {
+    pub fn from_json(v: &JsonValue) -> Result<Self, KissError> {
+        check_object(v, "receipt")?;
+        let mut loglist = &v["logs"];
+        if loglist.is_null() {
+            loglist = &v["logEntries"];
+        }
+        let logs = match loglist.as_array() {
+            Some(arr) => arr.iter().map(BcosLogInfo::from_json).collect(),
+            None => vec![],
+        };
+        Ok(BcosReceiptInfo {
+            transaction_hash: field_str(v, &["transactionHash"]),
+            block_number: field_u64(v, &["blockNumber"]).unwrap_or(0),
+            status: field_status(v, "receipt")?,
+            from: field_str(v, &["from"]),
+            to: field_str(v, &["to"]),
+            contract_address: field_str(v, &["contractAddress"]),
+            input: field_str(v, &["input"]),
+            output: field_str(v, &["output"]),
+            gas_used: field_u64(v, &["gasUsed"]).unwrap_or(0),
+            logs,
+            raw: v.clone(),
+        })
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.status == 0
+    }
+
+    ///按链版本解析状态码，失败时从output解析revert原因
+    pub fn receipt_status(&self, version: &BcosChainVersion) -> ReceiptStatus {
+        match version {
+            BcosChainVersion::V2 => ReceiptStatus::from_bcos2(self.status, self.output.as_str()),
+            BcosChainVersion::V3 => ReceiptStatus::from_bcos3(self.status, self.output.as_str()),
+        }
+    }
+
+    ///交易执行失败时返回ETxFailed，成功时原样返回
+    pub fn check_status(self, version: &BcosChainVersion) -> Result<Self, KissError> {
+        self.receipt_status(version)
+            .check(self.transaction_hash.as_str())?;
+        Ok(self)
+    }
+}
+
+///交易详情，bcos2的交易带blockNumber，bcos3的交易带blockLimit，所以都是可选
+#[derive(Debug, Clone, Default)]
+pub struct BcosTxInfo {
+    pub hash: String,
+    pub from: String,
+    pub to: String,
+    pub input: String,
+    pub nonce: String,
+    pub block_number: Option<u64>,
+    pub block_limit: Option<u64>,
+    pub raw: JsonValue,
+}
+
+impl BcosTxInfo {
+    pub fn from_json(v: &JsonValue) -> Result<Self, KissError> {
+        check_object(v, "transaction")?;
+        Ok(BcosTxInfo {
+            hash: field_str(v, &["hash", "transactionHash"]),
+            from: field_str(v, &["from"]),
+            to: field_str(v, &["to"]),
+            input: field_str(v, &["input"]),
+            nonce: match &v["nonce"] {
+                JsonValue::String(s) => s.clone(),
+                JsonValue::Number(n) => n.to_string(),
+                _ => "".to_string(),
+            },
+            block_number: field_u64(v, &["blockNumber"]),
+            block_limit: field_u64(v, &["blockLimit"]),
+            raw: v.clone(),
+        })
+    }
+}
+
+///区块，transactions字段在查询时如果只要hash，则transactions为空，只填tx_hashes
+#[derive(Debug, Clone, Default)]
+pub struct BcosBlockInfo {
+    pub number: u64,
+    pub hash: String,
+    pub parent_hash: String,
+    pub timestamp: u64,
+    pub sealer: u64,
+    pub sealer_list: Vec<String>,
+    pub tx_hashes: Vec<String>,
+    pub transactions: Vec<BcosTxInfo>,
+    pub txs_root: String,
+    pub receipts_root: String,
+    pub state_root: String,
+    pub raw: JsonValue,
+}
+
+impl BcosBlockInfo {
+    pub fn from_json(v: &JsonValue) -> Result<Self, KissError> {
+        check_object(v, "block")?;
+        //bcos3的父区块信息在parentInfo数组里
+        let mut parent_hash = field_str(v, &["parentHash"]);
+        if parent_hash.is_empty() {
+            if let Some(parent) = v["parentInfo"].as_array().and_then(|a| a.first()) {
+                parent_hash = field_str(parent, &["blockHash"]);
+            }
+        }
+        let mut tx_hashes = vec![];
+        let mut transactions = vec![];
+        if let Some(txlist) = v["transactions"].as_array() {
+            for item in txlist {
+                match item {
+                    JsonValue::String(h) => tx_hashes.push(h.clone()),
+                    _ => {
+                        let tx = BcosTxInfo::from_json(item)?;
+                        tx_hashes.push(tx.hash.clone());
+                        transactions.push(tx);
+                    }
+                }
+            }
+        }
+        Ok(BcosBlockInfo {
+            number: field_u64(v, &["number"]).unwrap_or(0),
+            hash: field_str(v, &["hash"]),
+            parent_hash,
+            timestamp: field_u64(v, &["timestamp"]).unwrap_or(0),
+            sealer: field_u64(v, &["sealer"]).unwrap_or(0),
+            sealer_list: str_list(&v["sealerList"]),
+            tx_hashes,
+            transactions,
+            txs_root: field_str(v, &["transactionsRoot", "txsRoot"]),
+            receipts_root: field_str(v, &["receiptsRoot"]),
+            state_root: field_str(v, &["stateRoot"]),
+            raw: v.clone(),
+        })
+    }
+}
+
+///call的结果，output是abi编码的hex字符串，可以用ContractABI::decode_output_byname解析
+#[derive(Debug, Clone, Default)]
+pub struct BcosCallResult {
+    pub status: i64,
+    pub output: String,
+    pub block_number: u64,
+    pub raw: JsonValue,
+}
+
+impl BcosCallResult {
+    pub fn from_json(v: &JsonValue) -> Result<Self, KissError> {
+        check_object(v, "call result")?;
+        Ok(BcosCallResult {
+            status: field_status(v, "call result")?,
+            output: field_str(v, &["output"]),
+            block_number: field_u64(v, &["currentBlockNumber", "blockNumber"]).unwrap_or(0),
+            raw: v.clone(),
+        })
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.status == 0
+    }
+}
@@ -0,0 +1,264 @@
+/*
+  FISCO BCOS/rust-SDK is a rust client for FISCO BCOS2.0 (https://github.com/FISCO-BCOS/)
+  FISCO BCOS/rust-SDK is free software: you can redistribute it and/or modify it under the
+  terms of the MIT License as published by the Free Software Foundation. This project is
+  distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even
+  the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+  @author: kentzhang
+  @date: 2021-07
+*/
+#![allow(
+    clippy::unreadable_literal,
+    clippy::upper_case_acronyms,
+    dead_code,
+    non_camel_case_types,
+    non_snake_case,
+    non_upper_case_globals,
+    overflowing_literals,
+    unused_variables,
+    unused_assignments
+)]
+
+/*
+bcoscontractgen生成的合约绑定代码在运行时依赖的部分：
+BcosContract持有合约地址和abi，通过IBcosChainClient调用，所以同一份绑定代码可以用于bcos2和bcos3；
+token_into_xxx把解码出来的Token转成rust类型，类型不符时返回EFormat错误。
+生成的代码只引用这个模块，Address/Int/Uint/Token在这里re-export，使用方不必直接依赖ethabi
+*/
+pub use ethabi::{Address, Int, Log, Token, Uint};
+
+use crate::bcosclient::bcoschainclient::IBcosChainClient;
+use crate::bcosclient::bcoschaintypes::BcosReceiptInfo;
+use crate::bcossdkutil::commonhash::HashType;
+use crate::bcossdkutil::contractabi::ContractABI;
+use crate::bcossdkutil::kisserror::{KissErrKind, KissError};
+use crate::{kisserr, kisserrcode};
+
+#[derive(Debug, Clone)]
+pub struct BcosContract {
+    pub name: String,
+    pub address: String,
+    pub abi: ContractABI,
+}
+
+impl BcosContract {
+    pub fn new(
+        name: &str,
+        abitext: &str,
+        address: &str,
+        hashtype: &HashType,
+    ) -> Result<BcosContract, KissError> {
+        Ok(BcosContract {
+            name: name.to_string(),
+            address: address.to_string(),
+            abi: ContractABI::new_from_text(abitext, hashtype)?,
+        })
+    }
+
+    ///部署合约，bin为合约代码的hex，params为构造函数的参数，部署成功后地址取自回执
+    pub fn deploy(
+        client: &mut dyn IBcosChainClient,
+        name: &str,
+        abitext: &str,
+        bin: &str,
+        params: &[Token],
+    ) -> Result<(BcosContract, BcosReceiptInfo), KissError> {
+        let mut contract = BcosContract::new(name, abitext, "", client.get_hashtype())?;
+        let code = match hex::decode(bin.trim().trim_start_matches("0x")) {
+            Ok(c) => c,
+            Err(e) => return kisserr!(KissErrKind::EFormat, "{} bin is not hex {:?}", name, e),
+        };
+        let data = match contract.abi.contract.constructor() {
+            Some(cons) => match cons.encode_input(code, params) {
+                Ok(d) => d,
+                Err(e) => {
+                    return kisserr!(
+                        KissErrKind::EFormat,
+                        "encode {} constructor error {:?}",
+                        name,
+                        e
+                    )
+                }
+            },
+            None => code,
+        };
+        let receipt = client.deploy_hexcode(hex::encode(data).as_str())?;
+        if !receipt.is_success() || receipt.contract_address.is_empty() {
+            return kisserrcode!(
+                KissErrKind::Error,
+                receipt.status,
+                "deploy {} failed, status {}, output {}",
+                name,
+                receipt.status,
+                receipt.output
+            );
+        }
+        contract.address = receipt.contract_address.clone();
+        Ok((contract, receipt))
+    }
+
+    ///调用只读方法，返回解码后的输出
+    pub fn call(
+        &self,
+        client: &mut dyn IBcosChainClient,
+        method: &str,
+        params: &[Token],
+    ) -> Result<Vec<Token>, KissError> {
+        let result = client.call_with_tokens(&self.abi, &self.address, method, params)?;
+        if !result.is_success() {
+            return kisserrcode!(
+                KissErrKind::Error,
+                result.status,
+                "call {}.{} failed, status {}, output {}",
+                self.name,
+                method,
+                result.status,
+                result.output
+            );
+        }
+        self.decode_output(method, result.output.as_str())
+    }
+
+    ///按方法的outputs解码call或交易回执里的output
+    pub fn decode_output(&self, method: &str, output: &str) -> Result<Vec<Token>, KissError> {
+        let function = self.abi.find_function_unwrap(method)?;
+        match self.abi.decode_function_output(&function, output) {
+            Ok(tokens) => Ok(tokens),
+            Err(e) => kisserr!(
+                KissErrKind::EFormat,
+                "decode {}.{} output error {:?}",
+                self.name,
+                method,
+                e
+            ),
+        }
+    }
+
+    ///发送交易并等待回执，回执状态由调用方判断
+    pub fn send(
+        &self,
+        client: &mut dyn IBcosChainClient,
+        method: &str,
+        params: &[Token],
+    ) -> Result<BcosReceiptInfo, KissError> {
+        client.send_transaction_with_tokens(&self.abi, &self.address, method, params)
+    }
+
+    ///解析回执里本合约的某个event，非匿名event按topic[0]过滤，匿名event逐个尝试解析
+    pub fn parse_events(
+        &self,
+        receipt: &BcosReceiptInfo,
+        eventname: &str,
+    ) -> Result<Vec<Log>, KissError> {
+        let event = match self.abi.find_event_by_name(eventname) {
+            Some(e) => e.clone(),
+            None => return kisserr!(KissErrKind::EArgument, "event {} not found", eventname),
+        };
+        let signature = self.abi.event_abi_utils.event_signature(&event);
+        let mut logs = vec![];
+        for item in receipt.logs.iter() {
+            if !self.address.is_empty()
+                && !item.address.is_empty()
+                && item.address.trim_start_matches("0x").to_lowercase()
+                    != self.address.trim_start_matches("0x").to_lowercase()
+            {
+                continue;
+            }
+            let mut topics = vec![];
+            for t in item.topics.iter() {
+                match t.trim_start_matches("0x").parse() {
+                    Ok(h) => topics.push(h),
+                    Err(e) => return kisserr!(KissErrKind::EFormat, "topic {} error {:?}", t, e),
+                }
+            }
+            if !event.anonymous && topics.first() != Some(&signature) {
+                continue;
+            }
+            let data = match hex::decode(item.data.trim_start_matches("0x")) {
+                Ok(d) => d,
+                Err(e) => return kisserr!(KissErrKind::EFormat, "log data error {:?}", e),
+            };
+            let rawlog = ethabi::RawLog { topics, data };
+            match self.abi.event_abi_utils.parse_log(&event, rawlog) {
+                Ok(log) => logs.push(log),
+                Err(e) => {
+                    if !event.anonymous {
+                        return Err(e);
+                    }
+                }
+            }
+        }
+        Ok(logs)
+    }
+}
+
+///从解码结果里按顺序取下一个token
+pub fn next_token<I: Iterator<Item = Token>>(iter: &mut I) -> Result<Token, KissError> {
+    match iter.next() {
+        Some(t) => Ok(t),
+        None => kisserr!(KissErrKind::EFormat, "not enough tokens in result"),
+    }
+}
+
+fn token_type_error<T>(expect: &str, t: &Token) -> Result<T, KissError> {
+    kisserr!(KissErrKind::EFormat, "expect {} token, got {:?}", expect, t)
+}
+
+pub fn token_into_address(t: Token) -> Result<Address, KissError> {
+    match t {
+        Token::Address(v) => Ok(v),
+        _ => token_type_error("address", &t),
+    }
+}
+
+pub fn token_into_bool(t: Token) -> Result<bool, KissError> {
+    match t {
+        Token::Bool(v) => Ok(v),
+        _ => token_type_error("bool", &t),
+    }
+}
+
+pub fn token_into_string(t: Token) -> Result<String, KissError> {
+    match t {
+        Token::String(v) => Ok(v),
+        _ => token_type_error("string", &t),
+    }
+}
+
+///bytes和bytesN都转成Vec<u8>
+pub fn token_into_bytes(t: Token) -> Result<Vec<u8>, KissError> {
+    match t {
+        Token::Bytes(v) | Token::FixedBytes(v) => Ok(v),
+        _ => token_type_error("bytes", &t),
+    }
+}
+
+///intN是补码形式的U256，uintN是U256
+pub fn token_into_int(t: Token) -> Result<Int, KissError> {
+    match t {
+        Token::Int(v) | Token::Uint(v) => Ok(v),
+        _ => token_type_error("int", &t),
+    }
+}
+
+pub fn token_into_uint(t: Token) -> Result<Uint, KissError> {
+    match t {
+        Token::Uint(v) | Token::Int(v) => Ok(v),
+        _ => token_type_error("uint", &t),
+    }
+}
+
+pub fn token_into_array(t: Token) -> Result<Vec<Token>, KissError> {
+    match t {
+        Token::Array(v) | Token::FixedArray(v) => Ok(v),
+        _ => token_type_error("array", &t),
+    }
+}
+
+///struct对应tuple，检查成员个数
+pub fn token_into_tuple(t: Token, size: usize) -> Result<Vec<Token>, KissError> {
+    match t {
+        Token::Tuple(v) if v.len() == size => Ok(v),
+        _ => token_type_error(format!("tuple({})", size).as_str(), &t),
+    }
+}
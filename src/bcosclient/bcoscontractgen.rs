@@ -0,0 +1,544 @@
+/*
+  FISCO BCOS/rust-SDK is a rust client for FISCO BCOS2.0 (https://github.com/FISCO-BCOS/)
+  FISCO BCOS/rust-SDK is free software: you can redistribute it and/or modify it under the
+  terms of the MIT License as published by the Free Software Foundation. This project is
+  distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even
+  the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+  @author: kentzhang
+  @date: 2021-07
+*/
+#![allow(
+    clippy::unreadable_literal,
+    clippy::upper_case_acronyms,
+    dead_code,
+    non_camel_case_types,
+    non_snake_case,
+    non_upper_case_globals,
+    overflowing_literals,
+    unused_variables,
+    unused_assignments
+)]
+
+/*
+根据合约的.abi(和.bin)生成强类型的rust绑定代码，每个合约一个struct：
+只读方法(view/pure)用call，返回解码后的rust类型；其他方法发送交易，返回回执；
+每个event生成一个struct和parse_xxx_events方法；有.bin时生成带类型参数的deploy。
+生成的代码基于IBcosChainClient，bcos2和bcos3都可以用。
+
+类型映射：address->Address, bool->bool, string->String, bytes/bytesN->Vec<u8>,
+intN->Int, uintN->Uint(都是U256，int为补码), T[]/T[N]->Vec<T>, struct(tuple)->rust元组。
+indexed的string/bytes/数组/struct在log里只有hash，对应字段为Vec<u8>。
+
+每个合约生成一个文件(合约名小写.rs)，内容是同名的mod。典型用法是在build.rs里生成，再include到代码里：
+    //build.rs
+    let outdir = std::env::var("OUT_DIR").unwrap();
+    BcosContractGen::new().generate_from_config("conf/config.toml", &outdir).unwrap();
+    //代码里
+    include!(concat!(env!("OUT_DIR"), "/helloworld.rs"));
+    use helloworld::HelloWorld;
+    let (hello, receipt) = HelloWorld::deploy(client.as_mut())?;
+    hello.set(client.as_mut(), "hi".to_string())?;
+    let name: String = hello.get(client.as_mut())?;
+*/
+use std::fs;
+use std::path::PathBuf;
+
+use ethabi::ParamType;
+use serde_json::Value as JsonValue;
+
+use crate::bcossdkutil::bcosclientconfig::ClientConfig;
+use crate::bcossdkutil::commonhash::HashType;
+use crate::bcossdkutil::contractabi::ContractABI;
+use crate::bcossdkutil::fileutils;
+use crate::bcossdkutil::kisserror::{KissErrKind, KissError};
+use crate::kisserr;
+
+static RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "extern", "false",
+    "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref",
+    "return", "static", "struct", "trait", "true", "type", "unsafe", "use", "where", "while",
+    "abstract", "become", "box", "do", "final", "macro", "override", "priv", "try", "typeof",
+    "unsized", "virtual", "yield",
+];
+
+///把abi里的名字转成合法的rust标识符，空名字用default_name
+pub fn rust_ident(name: &str, default_name: &str) -> String {
+    let mut ident: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if ident.is_empty() {
+        return default_name.to_string();
+    }
+    if ident.starts_with(|c: char| c.is_ascii_digit()) {
+        ident = format!("_{}", ident);
+    }
+    match ident.as_str() {
+        //不能用r#的关键字，以及生成代码里自己用到的参数名
+        "self" | "Self" | "super" | "crate" | "_" | "client" => format!("{}_", ident),
+        s if RUST_KEYWORDS.contains(&s) => format!("r#{}", ident),
+        _ => ident,
+    }
+}
+
+///on_set -> OnSet
+pub fn camel_name(name: &str) -> String {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            let mut chars = s.chars();
+            match chars.next() {
+                Some(first) => format!("{}{}", first.to_ascii_uppercase(), chars.as_str()),
+                None => "".to_string(),
+            }
+        })
+        .collect()
+}
+
+fn is_dynamic_topic(kind: &ParamType) -> bool {
+    matches!(
+        kind,
+        ParamType::String
+            | ParamType::Bytes
+            | ParamType::Array(_)
+            | ParamType::FixedArray(_, _)
+            | ParamType::Tuple(_)
+    )
+}
+
+fn is_constant_function(item: &JsonValue) -> bool {
+    if item["constant"].as_bool() == Some(true) {
+        return true;
+    }
+    matches!(
+        item["stateMutability"].as_str(),
+        Some("view") | Some("pure")
+    )
+}
+
+fn tuple_type(items: Vec<String>) -> String {
+    match items.len() {
+        0 => "()".to_string(),
+        1 => format!("({},)", items[0]),
+        _ => format!("({})", items.join(", ")),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BcosContractGen {
+    ///生成的代码里引用本sdk用的路径，在本crate内部使用时可以设为"crate"
+    pub crate_path: String,
+}
+
+impl Default for BcosContractGen {
+    fn default() -> Self {
+        BcosContractGen::new()
+    }
+}
+
+impl BcosContractGen {
+    pub fn new() -> Self {
+        BcosContractGen {
+            crate_path: "fisco_bcos_rust_gears_sdk".to_string(),
+        }
+    }
+
+    ///abi类型对应的rust类型
+    pub fn rust_type(&self, kind: &ParamType) -> String {
+        match kind {
+            ParamType::Address => "Address".to_string(),
+            ParamType::Bytes | ParamType::FixedBytes(_) => "Vec<u8>".to_string(),
+            ParamType::Int(_) => "Int".to_string(),
+            ParamType::Uint(_) => "Uint".to_string(),
+            ParamType::Bool => "bool".to_string(),
+            ParamType::String => "String".to_string(),
+            ParamType::Array(t) | ParamType::FixedArray(t, _) => {
+                format!("Vec<{}>", self.rust_type(t))
+            }
+            ParamType::Tuple(ts) => tuple_type(ts.iter().map(|t| self.rust_type(t)).collect()),
+        }
+    }
+
+    ///把rust表达式转成Token的代码
+    pub fn to_token_code(&self, kind: &ParamType, expr: &str, depth: usize) -> String {
+        match kind {
+            ParamType::Address => format!("Token::Address({})", expr),
+            ParamType::Bytes => format!("Token::Bytes({})", expr),
+            ParamType::FixedBytes(_) => format!("Token::FixedBytes({})", expr),
+            ParamType::Int(_) => format!("Token::Int({})", expr),
+            ParamType::Uint(_) => format!("Token::Uint({})", expr),
+            ParamType::Bool => format!("Token::Bool({})", expr),
+            ParamType::String => format!("Token::String({})", expr),
+            ParamType::Array(t) | ParamType::FixedArray(t, _) => {
+                let variant = if let ParamType::Array(_) = kind {
+                    "Array"
+                } else {
+                    "FixedArray"
+                };
+                let v = format!("v{}", depth);
+                format!(
+                    "Token::{}({}.into_iter().map(|{}| {}).collect())",
+                    variant,
+                    expr,
+                    v,
+                    self.to_token_code(t, v.as_str(), depth + 1)
+                )
+            }
+            ParamType::Tuple(ts) => {
+                let names: Vec<String> =
+                    (0..ts.len()).map(|i| format!("t{}_{}", depth, i)).collect();
+                let tokens: Vec<String> = ts
+                    .iter()
+                    .zip(names.iter())
+                    .map(|(t, n)| self.to_token_code(t, n, depth + 1))
+                    .collect();
+                format!(
+                    "{{ let {} = {}; Token::Tuple(vec![{}]) }}",
+                    tuple_type(names),
+                    expr,
+                    tokens.join(", ")
+                )
+            }
+        }
+    }
+
+    ///把Token表达式转成rust值的代码，生成的代码用?返回错误
+    pub fn from_token_code(&self, kind: &ParamType, expr: &str, depth: usize) -> String {
+        match kind {
+            ParamType::Address => format!("token_into_address({})?", expr),
+            ParamType::Bytes | ParamType::FixedBytes(_) => {
+                format!("token_into_bytes({})?", expr)
+            }
+            ParamType::Int(_) => format!("token_into_int({})?", expr),
+            ParamType::Uint(_) => format!("token_into_uint({})?", expr),
+            ParamType::Bool => format!("token_into_bool({})?", expr),
+            ParamType::String => format!("token_into_string({})?", expr),
+            ParamType::Array(t) | ParamType::FixedArray(t, _) => {
+                let v = format!("v{}", depth);
+                format!(
+                    "token_into_array({})?.into_iter().map(|{}| -> Result<_, KissError> {{ Ok({}) }}).collect::<Result<Vec<_>, KissError>>()?",
+                    expr,
+                    v,
+                    self.from_token_code(t, v.as_str(), depth + 1)
+                )
+            }
+            ParamType::Tuple(ts) => {
+                let it = format!("it{}", depth);
+                let next = format!("next_token(&mut {})?", it);
+                let items: Vec<String> = ts
+                    .iter()
+                    .map(|t| self.from_token_code(t, next.as_str(), depth + 1))
+                    .collect();
+                format!(
+                    "{{ let mut {} = token_into_tuple({}, {})?.into_iter(); {} }}",
+                    it,
+                    expr,
+                    ts.len(),
+                    tuple_type(items)
+                )
+            }
+        }
+    }
+
+    fn params_code(&self, params: &[(String, ParamType)]) -> (String, String) {
+        let decl: Vec<String> = params
+            .iter()
+            .map(|(n, k)| format!(", {}: {}", n, self.rust_type(k)))
+            .collect();
+        let tokens: Vec<String> = params
+            .iter()
+            .map(|(n, k)| self.to_token_code(k, n, 0))
+            .collect();
+        (decl.join(""), tokens.join(", "))
+    }
+
+    fn outputs_code(&self, outputs: &[ParamType], tokens_expr: &str) -> (String, String) {
+        let next = "next_token(&mut outputs)?";
+        let types: Vec<String> = outputs.iter().map(|k| self.rust_type(k)).collect();
+        let values: Vec<String> = outputs
+            .iter()
+            .map(|k| self.from_token_code(k, next, 0))
+            .collect();
+        let (rettype, retvalue) = match outputs.len() {
+            1 => (types[0].clone(), values[0].clone()),
+            _ => (tuple_type(types), tuple_type(values)),
+        };
+        let body = format!(
+            "        let mut outputs = {}.into_iter();\n        Ok({})\n",
+            tokens_expr, retvalue
+        );
+        (rettype, body)
+    }
+
+    ///生成一个合约的绑定代码，bin为None时不生成deploy
+    pub fn generate_code(
+        &self,
+        name: &str,
+        abitext: &str,
+        bin: Option<&str>,
+    ) -> Result<String, KissError> {
+        //只用来解析结构，hash算法无关
+        let abi = ContractABI::new_from_text(abitext, &HashType::WEDPR_KECCAK)?;
+        let (client, receipt, err) = ("IBcosChainClient", "BcosReceiptInfo", "KissError");
+        let structname = rust_ident(&camel_name(name), "Contract");
+        let modname = rust_ident(&name.to_lowercase(), "contract");
+
+        //abi压缩成一行放在raw string里，生成的代码整体缩进时不会改变abi的内容
+        let abiline = abi.abiparser.abiobj.to_string();
+        let mut hashes = "#".to_string();
+        while abiline.contains(format!("\"{}", hashes).as_str()) {
+            hashes.push('#');
+        }
+        let mut code = format!(
+            "#[derive(Debug, Clone)]\npub struct {} {{\n    pub contract: BcosContract,\n}}\n\nimpl {} {{\n",
+            structname, structname
+        );
+        code += &format!("    pub const NAME: &'static str = \"{}\";\n", name);
+        code += &format!(
+            "    pub const ABI: &'static str = r{}\"{}\"{};\n",
+            hashes, abiline, hashes
+        );
+        if let Some(b) = bin {
+            code += &format!("    pub const BIN: &'static str = \"{}\";\n", b.trim());
+        }
+        code += &format!(
+            "\n    ///已部署在address的合约\n    pub fn at(client: &dyn {}, address: &str) -> Result<Self, {}> {{\n        Ok({} {{\n            contract: BcosContract::new(Self::NAME, Self::ABI, address, client.get_hashtype())?,\n        }})\n    }}\n",
+            client, err, structname
+        );
+
+        if bin.is_some() {
+            let params: Vec<(String, ParamType)> = match abi.contract.constructor() {
+                Some(cons) => cons
+                    .inputs
+                    .iter()
+                    .enumerate()
+                    .map(|(i, p)| (rust_ident(&p.name, &format!("arg{}", i)), p.kind.clone()))
+                    .collect(),
+                None => vec![],
+            };
+            let (decl, tokens) = self.params_code(&params);
+            code += &format!(
+                "\n    ///部署合约，新合约的地址在回执的contract_address里\n    pub fn deploy(client: &mut dyn {}{}) -> Result<(Self, {}), {}> {{\n        let (contract, receipt) = BcosContract::deploy(client, Self::NAME, Self::ABI, Self::BIN, &[{}])?;\n        Ok(({} {{ contract }}, receipt))\n    }}\n",
+                client, decl, receipt, err, tokens, structname
+            );
+        }
+
+        //按abi文件里的顺序生成，同名函数(重载)第k个对应ethabi里的第k个
+        let mut seen: std::collections::HashMap<String, usize> = std::collections::HashMap::new();
+        let items = abi.abiparser.abiobj.as_array().cloned().unwrap_or_default();
+        for item in items
+            .iter()
+            .filter(|i| i["type"].as_str() == Some("function"))
+        {
+            let fname = item["name"].as_str().unwrap_or_default();
+            let overloads = abi
+                .contract
+                .functions_by_name(fname)
+                .map(|f| f.len())
+                .unwrap_or(0);
+            let index = *seen.get(fname).unwrap_or(&0);
+            seen.insert(fname.to_string(), index + 1);
+            let function = match abi.contract.functions_by_name(fname) {
+                Ok(fs) if index < fs.len() => fs[index].clone(),
+                _ => return kisserr!(KissErrKind::EFormat, "function {} not found in abi", fname),
+            };
+            let (method, key) = if overloads > 1 {
+                let m = if index == 0 {
+                    rust_ident(fname, "function")
+                } else {
+                    rust_ident(&format!("{}_{}", fname, index), "function")
+                };
+                (m, function.signature())
+            } else {
+                (rust_ident(fname, "function"), fname.to_string())
+            };
+            let params: Vec<(String, ParamType)> = function
+                .inputs
+                .iter()
+                .enumerate()
+                .map(|(i, p)| (rust_ident(&p.name, &format!("arg{}", i)), p.kind.clone()))
+                .collect();
+            let outputs: Vec<ParamType> = function.outputs.iter().map(|p| p.kind.clone()).collect();
+            let (decl, tokens) = self.params_code(&params);
+            let sig = function.signature();
+            if is_constant_function(item) {
+                let (rettype, body) = if outputs.is_empty() {
+                    (
+                        "()".to_string(),
+                        format!(
+                            "        self.contract.call(client, \"{}\", &[{}])?;\n        Ok(())\n",
+                            key, tokens
+                        ),
+                    )
+                } else {
+                    self.outputs_code(
+                        &outputs,
+                        format!("self.contract.call(client, \"{}\", &[{}])?", key, tokens).as_str(),
+                    )
+                };
+                code += &format!(
+                    "\n    ///{} (call)\n    pub fn {}(&self, client: &mut dyn {}{}) -> Result<{}, {}> {{\n{}    }}\n",
+                    sig, method, client, decl, rettype, err, body
+                );
+            } else {
+                code += &format!(
+                    "\n    ///{} (发送交易)\n    pub fn {}(&self, client: &mut dyn {}{}) -> Result<{}, {}> {{\n        self.contract.send(client, \"{}\", &[{}])\n    }}\n",
+                    sig, method, client, decl, receipt, err, key, tokens
+                );
+                if !outputs.is_empty() {
+                    let (rettype, body) = self.outputs_code(
+                        &outputs,
+                        format!(
+                            "self.contract.decode_output(\"{}\", receipt.output.as_str())?",
+                            key
+                        )
+                        .as_str(),
+                    );
+                    let output_method = rust_ident(
+                        &format!("{}_output", method.trim_start_matches("r#")),
+                        "function_output",
+                    );
+                    code += &format!(
+                        "\n    ///解码{}交易回执里的返回值\n    pub fn {}(&self, receipt: &{}) -> Result<{}, {}> {{\n{}    }}\n",
+                        fname, output_method, receipt, rettype, err, body
+                    );
+                }
+            }
+        }
+
+        //event
+        let mut eventstructs = String::new();
+        for event in abi.abiparser.events.iter() {
+            let evstruct = format!("{}{}Event", structname, camel_name(&event.name));
+            let mut fields = String::new();
+            let mut values = String::new();
+            let next = "next_token(&mut params)?";
+            for (i, p) in event.inputs.iter().enumerate() {
+                let kind = if p.indexed && is_dynamic_topic(&p.kind) {
+                    ParamType::FixedBytes(32)
+                } else {
+                    p.kind.clone()
+                };
+                let fname = rust_ident(&p.name, &format!("arg{}", i));
+                fields += &format!("    pub {}: {},\n", fname, self.rust_type(&kind));
+                values += &format!(
+                    "                {}: {},\n",
+                    fname,
+                    self.from_token_code(&kind, next, 0)
+                );
+            }
+            eventstructs += &format!(
+                "\n#[derive(Debug, Clone, PartialEq)]\npub struct {} {{\n{}}}\n",
+                evstruct, fields
+            );
+            let parse_method = rust_ident(&format!("parse_{}_events", event.name), "parse_events");
+            code += &format!(
+                "\n    ///解析回执里的{}事件\n    pub fn {}(&self, receipt: &{}) -> Result<Vec<{}>, {}> {{\n        let mut events = vec![];\n        for log in self.contract.parse_events(receipt, \"{}\")? {{\n            let mut params = log.params.into_iter().map(|p| p.value);\n            events.push({} {{\n{}            }});\n        }}\n        Ok(events)\n    }}\n",
+                event.name, parse_method, receipt, evstruct, err, event.name, evstruct, values
+            );
+        }
+        code += "}\n";
+        code += &eventstructs;
+
+        //放在以合约名小写命名的mod里，include!到任何位置都不会和使用方的名字冲突
+        let mut output = format!(
+            "// 由bcoscontractgen根据{}.abi生成，不要手工修改\n#[allow(non_snake_case, non_camel_case_types, dead_code, unused_mut, unused_imports, clippy::all)]\npub mod {} {{\n",
+            name, modname
+        );
+        for usepath in [
+            "bcosclient::bcoschainclient::IBcosChainClient",
+            "bcosclient::bcoschaintypes::BcosReceiptInfo",
+            "bcosclient::bcoscontract::*",
+            "bcossdkutil::kisserror::KissError",
+        ]
+        .iter()
+        {
+            output += &format!("    use {}::{};\n", self.crate_path, usepath);
+        }
+        output += "\n";
+        for line in code.lines() {
+            if !line.is_empty() {
+                output += "    ";
+                output += line;
+            }
+            output += "\n";
+        }
+        output += "}\n";
+        Ok(output)
+    }
+
+    ///读取contractpath下的name.abi和name.bin(可选)，生成outdir/name小写.rs，返回生成的文件名
+    pub fn generate_file(
+        &self,
+        contractpath: &str,
+        name: &str,
+        outdir: &str,
+    ) -> Result<String, KissError> {
+        let base = PathBuf::from(contractpath);
+        let abifile = base.join(format!("{}.abi", name));
+        let abitext = fileutils::readstring(abifile.to_str().unwrap())?;
+        let binfile = base.join(format!("{}.bin", name));
+        let bin = if binfile.exists() {
+            Some(fileutils::readstring(binfile.to_str().unwrap())?)
+        } else {
+            None
+        };
+        let code = self.generate_code(name, abitext.as_str(), bin.as_deref())?;
+        let outfile = PathBuf::from(outdir).join(format!("{}.rs", name.to_lowercase()));
+        if let Err(e) = fs::create_dir_all(outdir) {
+            return kisserr!(
+                KissErrKind::EFileWrite,
+                "create dir {} error {:?}",
+                outdir,
+                e
+            );
+        }
+        if let Err(e) = fs::write(&outfile, code) {
+            return kisserr!(KissErrKind::EFileWrite, "write {:?} error {:?}", outfile, e);
+        }
+        Ok(outfile.to_str().unwrap().to_string())
+    }
+
+    ///为contractpath下所有的.abi生成绑定代码
+    pub fn generate_dir(&self, contractpath: &str, outdir: &str) -> Result<Vec<String>, KissError> {
+        let entries = match fs::read_dir(contractpath) {
+            Ok(e) => e,
+            Err(e) => {
+                return kisserr!(
+                    KissErrKind::EFileMiss,
+                    "read dir {} error {:?}",
+                    contractpath,
+                    e
+                )
+            }
+        };
+        let mut names: Vec<String> = entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| p.extension().map(|x| x == "abi").unwrap_or(false))
+            .filter_map(|p| p.file_stem().map(|s| s.to_string_lossy().to_string()))
+            .collect();
+        names.sort();
+        let mut files = vec![];
+        for name in names {
+            files.push(self.generate_file(contractpath, name.as_str(), outdir)?);
+        }
+        Ok(files)
+    }
+
+    ///为配置文件里[common]段contractpath下的所有合约生成绑定代码
+    pub fn generate_from_config(
+        &self,
+        configfile: &str,
+        outdir: &str,
+    ) -> Result<Vec<String>, KissError> {
+        let config = ClientConfig::load(configfile)?;
+        self.generate_dir(config.common.contractpath.as_str(), outdir)
+    }
+}
@@ -0,0 +1,349 @@
+/*
+  FISCO BCOS/rust-SDK is a rust client for FISCO BCOS2.0 (https://github.com/FISCO-BCOS/)
+  FISCO BCOS/rust-SDK is free software: you can redistribute it and/or modify it under the
+  terms of the MIT License as published by the Free Software Foundation. This project is
+  distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even
+  the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+  @author: kentzhang
+  @date: 2021-07
+*/
+#![allow(
+    clippy::unreadable_literal,
+    clippy::upper_case_acronyms,
+    dead_code,
+    non_camel_case_types,
+    non_snake_case,
+    non_upper_case_globals,
+    overflowing_literals,
+    unused_variables,
+    unused_assignments
+)]
+
+/*
+可断点续传的事件索引器：按块扫描指定合约的event log，解析后写入sink，每处理完一个块就把块高记到checkpoint文件。
+进程重启后从checkpoint的下一个块继续，不会漏掉事件。
+扫描用IBcosChainClient的get_block_by_number和get_transaction_receipt，所以2.x和3.x都能用。
+两种模式：
+CatchUp：落后于链上块高时，按batch_size一批批追赶
+Live：追上以后等待新块(2.x的channel协议由节点推送新块通知，其他情况轮询块高)，有新块再扫描
+不直接用节点的事件推送，因为推送的日志没法和checkpoint对齐，断线期间的事件会丢。
+写sink和记checkpoint不是原子的，中断时同一个块的事件可能被写两次(at least once)，
+sink可以用(block_number,transaction_hash,log_index)去重。
+*/
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use ethabi::Log as ReceiptLog;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value as JsonValue};
+
+use crate::bcosclient::bcoschainclient::IBcosChainClient;
+use crate::bcosclient::bcoschaintypes::{BcosLogInfo, BcosReceiptInfo};
+use crate::bcossdkutil::contractabi::ContractABI;
+use crate::bcossdkutil::fileutils;
+use crate::bcossdkutil::kisserror::{KissErrKind, KissError};
+use crate::kisserr;
+
+///索引到的一条事件
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct IndexedEvent {
+    pub block_number: u64,
+    pub transaction_hash: String,
+    ///在交易回执logs里的序号
+    pub log_index: usize,
+    pub address: String,
+    ///事件名，abi里找不到对应的事件时为空，只保留原始的topics和data
+    pub event: String,
+    ///参数名 -> 参数值
+    pub params: Map<String, JsonValue>,
+    pub topics: Vec<String>,
+    pub data: String,
+}
+
+///索引结果的输出，可以实现为写文件、数据库、消息队列等
+pub trait IEventSink {
+    ///写入一个块里的事件(可能为空)，返回Ok后才会更新checkpoint
+    fn write_block(&mut self, block_number: u64, events: &[IndexedEvent]) -> Result<(), KissError>;
+}
+
+///默认的sink，每个事件一行json，追加到文件
+pub struct JsonLinesSink {
+    pub path: String,
+}
+
+impl JsonLinesSink {
+    pub fn new(path: &str) -> Self {
+        JsonLinesSink {
+            path: path.to_string(),
+        }
+    }
+}
+
+impl IEventSink for JsonLinesSink {
+    fn write_block(&mut self, block_number: u64, events: &[IndexedEvent]) -> Result<(), KissError> {
+        if events.is_empty() {
+            return Ok(());
+        }
+        let mut lines = String::new();
+        for e in events {
+            match serde_json::to_string(e) {
+                Ok(s) => {
+                    lines.push_str(s.as_str());
+                    lines.push('\n');
+                }
+                Err(e) => return kisserr!(KissErrKind::EFormat, "format event error {:?}", e),
+            }
+        }
+        let mut file = match OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+        {
+            Ok(f) => f,
+            Err(e) => {
+                return kisserr!(KissErrKind::EFileOpen, "open {} error {:?}", self.path, e);
+            }
+        };
+        match file
+            .write_all(lines.as_bytes())
+            .and_then(|_| file.sync_data())
+        {
+            Ok(_) => Ok(()),
+            Err(e) => kisserr!(KissErrKind::EFileWrite, "write {} error {:?}", self.path, e),
+        }
+    }
+}
+
+///checkpoint文件的内容
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IndexerCheckpoint {
+    ///已经处理完的最后一个块
+    pub last_block: u64,
+}
+
+impl IndexerCheckpoint {
+    ///文件不存在时返回None
+    pub fn load(path: &str) -> Result<Option<IndexerCheckpoint>, KissError> {
+        if !fileutils::is_file_exist(path) {
+            return Ok(None);
+        }
+        let content = fileutils::readstring(path)?;
+        match serde_json::from_str(content.as_str()) {
+            Ok(c) => Ok(Some(c)),
+            Err(e) => kisserr!(KissErrKind::EFormat, "checkpoint {} error {:?}", path, e),
+        }
+    }
+
+    ///先写临时文件再改名，写到一半中断时不会留下损坏的checkpoint
+    pub fn save(&self, path: &str) -> Result<(), KissError> {
+        let tmpfile = format!("{}.tmp", path);
+        fileutils::writestring(tmpfile.as_str(), serde_json::to_string(self).unwrap())?;
+        match std::fs::rename(&tmpfile, path) {
+            Ok(_) => Ok(()),
+            Err(e) => kisserr!(
+                KissErrKind::EFileWrite,
+                "save checkpoint {} error {:?}",
+                path,
+                e
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum IndexerMode {
+    CatchUp,
+    Live,
+}
+
+pub struct EventIndexer {
+    ///小写的合约地址 -> abi，只索引这些合约的事件
+    pub contracts: HashMap<String, ContractABI>,
+    pub sink: Box<dyn IEventSink + Send>,
+    pub checkpoint_file: String,
+    ///没有checkpoint时从这个块开始
+    pub start_block: u64,
+    ///追赶模式下每次run_once最多扫描的块数
+    pub batch_size: u64,
+    ///实时模式下每次等待新块的时间
+    pub live_wait_ms: u64,
+    pub mode: IndexerMode,
+    ///已经处理完的最后一个块，从checkpoint加载
+    pub last_block: Option<u64>,
+}
+
+impl EventIndexer {
+    ///加载checkpoint，文件不存在时从start_block(默认0)开始
+    pub fn new(checkpoint_file: &str, sink: Box<dyn IEventSink + Send>) -> Result<Self, KissError> {
+        let checkpoint = IndexerCheckpoint::load(checkpoint_file)?;
+        Ok(EventIndexer {
+            contracts: HashMap::new(),
+            sink,
+            checkpoint_file: checkpoint_file.to_string(),
+            start_block: 0,
+            batch_size: 100,
+            live_wait_ms: 3000,
+            mode: IndexerMode::CatchUp,
+            last_block: checkpoint.map(|c| c.last_block),
+        })
+    }
+
+    pub fn add_contract(&mut self, address: &str, contract: &ContractABI) {
+        self.contracts
+            .insert(address.to_lowercase(), contract.clone());
+    }
+
+    ///下一个要处理的块
+    pub fn next_block(&self) -> u64 {
+        match self.last_block {
+            Some(n) => n + 1,
+            None => self.start_block,
+        }
+    }
+
+    fn decode_log(
+        &self,
+        contract: &ContractABI,
+        receipt: &BcosReceiptInfo,
+        index: usize,
+        log: &BcosLogInfo,
+    ) -> IndexedEvent {
+        let mut event = IndexedEvent {
+            block_number: receipt.block_number,
+            transaction_hash: receipt.transaction_hash.clone(),
+            log_index: index,
+            address: log.address.clone(),
+            topics: log.topics.clone(),
+            data: log.data.clone(),
+            ..Default::default()
+        };
+        //匿名事件没有topic，无法按签名找到abi
+        if log.topics.is_empty() {
+            return event;
+        }
+        let rawlogs = json!([{"topics": log.topics, "data": log.data}]);
+        match contract.parse_receipt_logs(&rawlogs) {
+            Ok(parsed) => {
+                if let Some(decoded) = parsed.first() {
+                    if let Some(e) = contract.find_event_by_hash(log_topic0(log)) {
+                        event.event = e.name.clone();
+                    }
+                    event.params = log_params(decoded);
+                }
+            }
+            Err(e) => {
+                log::warn!(
+                    "decode log {} of tx {} error {:?}",
+                    index,
+                    receipt.transaction_hash,
+                    e
+                );
+            }
+        }
+        event
+    }
+
+    ///扫描一个块里所有交易的回执，写入sink并更新checkpoint，返回索引到的事件数
+    pub fn index_block(
+        &mut self,
+        client: &mut dyn IBcosChainClient,
+        number: u64,
+    ) -> Result<usize, KissError> {
+        let block = client.get_block_by_number(number, false)?;
+        let mut events: Vec<IndexedEvent> = vec![];
+        for txhash in block.tx_hashes.iter() {
+            let mut receipt = match client.get_transaction_receipt(txhash.as_str())? {
+                Some(r) => r,
+                None => {
+                    return kisserr!(
+                        KissErrKind::Error,
+                        "receipt of {} in block {} not found",
+                        txhash,
+                        number
+                    )
+                }
+            };
+            receipt.block_number = number;
+            for (index, log) in receipt.logs.iter().enumerate() {
+                if let Some(contract) = self.contracts.get(&log.address.to_lowercase()) {
+                    events.push(self.decode_log(contract, &receipt, index, log));
+                }
+            }
+        }
+        self.sink.write_block(number, &events)?;
+        IndexerCheckpoint { last_block: number }.save(self.checkpoint_file.as_str())?;
+        self.last_block = Some(number);
+        Ok(events.len())
+    }
+
+    fn index_range(
+        &mut self,
+        client: &mut dyn IBcosChainClient,
+        to: u64,
+    ) -> Result<usize, KissError> {
+        let mut total = 0;
+        while self.next_block() <= to {
+            total += self.index_block(client, self.next_block())?;
+        }
+        Ok(total)
+    }
+
+    fn switch_mode(&mut self, mode: IndexerMode) {
+        if self.mode != mode {
+            log::info!(
+                "event indexer switch to {:?} mode at block {}",
+                mode,
+                self.next_block()
+            );
+            self.mode = mode;
+        }
+    }
+
+    ///推进一步：落后时追赶最多batch_size个块，已追上时等待新块后扫描，返回索引到的事件数
+    pub fn run_once(&mut self, client: &mut dyn IBcosChainClient) -> Result<usize, KissError> {
+        let head = client.get_block_number()?;
+        let next = self.next_block();
+        if next <= head {
+            self.switch_mode(IndexerMode::CatchUp);
+            let to = head.min(next + self.batch_size.max(1) - 1);
+            let total = self.index_range(client, to)?;
+            if self.next_block() > head {
+                self.switch_mode(IndexerMode::Live);
+            }
+            return Ok(total);
+        }
+        self.switch_mode(IndexerMode::Live);
+        //next>head>=0，所以next>=1
+        let head = client.wait_new_block(next - 1, self.live_wait_ms)?;
+        self.index_range(client, head)
+    }
+
+    ///一直运行到stop被置为true
+    pub fn run(
+        &mut self,
+        client: &mut dyn IBcosChainClient,
+        stop: &AtomicBool,
+    ) -> Result<(), KissError> {
+        while !stop.load(Ordering::SeqCst) {
+            self.run_once(client)?;
+        }
+        Ok(())
+    }
+}
+
+fn log_topic0(log: &BcosLogInfo) -> ethabi::Hash {
+    log.topics[0]
+        .trim_start_matches("0x")
+        .parse()
+        .unwrap_or_default()
+}
+
+fn log_params(log: &ReceiptLog) -> Map<String, JsonValue> {
+    let mut params = Map::new();
+    for p in log.params.iter() {
+        params.insert(p.name.clone(), JsonValue::String(format!("{}", p.value)));
+    }
+    params
+}
@@ -0,0 +1,342 @@
+/*
+  FISCO BCOS/rust-SDK is a rust client for FISCO BCOS2.0 (https://github.com/FISCO-BCOS/)
+  FISCO BCOS/rust-SDK is free software: you can redistribute it and/or modify it under the
+  terms of the MIT License as published by the Free Software Foundation. This project is
+  distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even
+  the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+  @author: kentzhang
+  @date: 2021-07
+*/
+#![allow(
+    clippy::unreadable_literal,
+    clippy::upper_case_acronyms,
+    dead_code,
+    non_camel_case_types,
+    non_snake_case,
+    non_upper_case_globals,
+    overflowing_literals,
+    unused_variables,
+    unused_assignments
+)]
+
+/*
+压测：先用OfflineTxBuilder签好N笔交易，签名耗时单独统计，不计入发送；
+再开connections个线程，每个线程创建自己的客户端(即一个独立的连接)，在连接上流水线发送分到的交易：
+最多window笔交易在途(已发送未收到回执)，回执通过IBcosChainClient::collect_receipts收取
+(bcos2 channel协议收TX_COMMITTED推送，其他轮询回执)，每笔交易的延迟从它发送时算起。
+window为1或客户端不支持只发送不等回执时，逐笔发送并等待回执。
+所有线程连接好以后一起开始计时，统计成功/失败数、TPS(成功数/发送总耗时)、回执延迟的分位数和失败原因。
+bcos2和bcos3都通过IBcosChainClient发送，按配置文件选择客户端。
+交易的blocklimit取签名时节点给的值(当前块高+500左右)，出块超过这个数的长时间压测要分批进行
+*/
+use std::collections::BTreeMap;
+use std::sync::{Arc, Barrier};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde_json::{json, Value as JsonValue};
+
+use crate::bcosclient::bcoschainclient::{create_chain_client, IBcosChainClient};
+use crate::bcosclient::bcosofflinetx::{signer_from_config, OfflineTxBuilder};
+use crate::bcossdkutil::contractabi::ContractABI;
+use crate::bcossdkutil::kisserror::{KissErrKind, KissError};
+use crate::bcossdkutil::stattool::LatencyStat;
+use crate::kisserr;
+
+///压测结果
+#[derive(Debug, Clone, Default)]
+pub struct LoadTestReport {
+    pub total: usize,
+    pub success: usize,
+    pub failed: usize,
+    pub connections: usize,
+    ///预签名的耗时
+    pub sign_ms: u64,
+    ///从所有连接开始发送到最后一个回执返回的耗时
+    pub elapsed_ms: u64,
+    ///收到回执的交易(不论执行状态)的延迟
+    pub latency: LatencyStat,
+    ///失败原因计数：回执状态非0时为"status:{状态码}"，发送出错时为"error:{错误码或错误类型}"
+    pub failures: BTreeMap<String, usize>,
+}
+
+impl LoadTestReport {
+    pub fn tps(&self) -> f64 {
+        if self.elapsed_ms == 0 {
+            return 0.0;
+        }
+        self.success as f64 * 1000.0 / self.elapsed_ms as f64
+    }
+
+    fn fail(&mut self, reason: String) {
+        self.failed += 1;
+        *self.failures.entry(reason).or_insert(0) += 1;
+    }
+
+    fn merge(&mut self, other: &LoadTestReport) {
+        self.total += other.total;
+        self.success += other.success;
+        self.failed += other.failed;
+        self.latency.merge(&other.latency);
+        for (reason, count) in other.failures.iter() {
+            *self.failures.entry(reason.clone()).or_insert(0) += count;
+        }
+    }
+
+    pub fn to_json(&self) -> JsonValue {
+        json!({
+            "total": self.total,
+            "success": self.success,
+            "failed": self.failed,
+            "connections": self.connections,
+            "signMs": self.sign_ms,
+            "elapsedMs": self.elapsed_ms,
+            "tps": self.tps(),
+            "latencyMs": {
+                "min": self.latency.min_ms(),
+                "avg": self.latency.avg_ms(),
+                "p50": self.latency.percentile_ms(50.0),
+                "p90": self.latency.percentile_ms(90.0),
+                "p99": self.latency.percentile_ms(99.0),
+                "max": self.latency.max_ms(),
+            },
+            "failures": self.failures,
+        })
+    }
+
+    pub fn summary(&self) -> String {
+        let mut text = format!(
+            "total {}, success {}, failed {}, connections {}\n\
+             sign {} ms, send {} ms, TPS {:.2}\n\
+             latency(ms) min {:.2}, avg {:.2}, p50 {:.2}, p90 {:.2}, p99 {:.2}, max {:.2}",
+            self.total,
+            self.success,
+            self.failed,
+            self.connections,
+            self.sign_ms,
+            self.elapsed_ms,
+            self.tps(),
+            self.latency.min_ms(),
+            self.latency.avg_ms(),
+            self.latency.percentile_ms(50.0),
+            self.latency.percentile_ms(90.0),
+            self.latency.percentile_ms(99.0),
+            self.latency.max_ms()
+        );
+        for (reason, count) in self.failures.iter() {
+            text.push_str(format!("\nfailure {} : {}", reason, count).as_str());
+        }
+        text
+    }
+}
+
+fn error_reason(e: &KissError) -> String {
+    if e.code != -1 {
+        format!("error:{}", e.code)
+    } else {
+        format!("error:{:?}", e.kind)
+    }
+}
+
+//按回执的状态计数，已上链但执行失败的也收到了回执
+fn count_receipt(report: &mut LoadTestReport, status: i64, start: Instant) {
+    report.latency.add(start.elapsed());
+    if status == 0 {
+        report.success += 1;
+    } else {
+        report.fail(format!("status:{}", status));
+    }
+}
+
+///一个连接上逐笔发送分到的交易，每笔都等回执
+fn send_all(client: &mut dyn IBcosChainClient, rawtxs: &[String]) -> LoadTestReport {
+    let mut report = LoadTestReport {
+        total: rawtxs.len(),
+        ..Default::default()
+    };
+    for rawtx in rawtxs {
+        let start = Instant::now();
+        match client.send_signed_transaction(rawtx.as_str()) {
+            Ok(receipt) => count_receipt(&mut report, receipt.status, start),
+            Err(e) if e.kind == KissErrKind::ETxFailed => count_receipt(&mut report, e.code, start),
+            Err(e) => {
+                log::warn!("load test send tx error {:?}", e);
+                report.fail(error_reason(&e));
+            }
+        }
+    }
+    report
+}
+
+///一个连接上流水线发送分到的交易，最多window笔在途。
+///在途超过receipt_timeout_sec的交易最后再查一次回执，还没有就算超时失败
+fn send_pipelined(
+    client: &mut dyn IBcosChainClient,
+    rawtxs: &[String],
+    window: usize,
+) -> LoadTestReport {
+    let mut report = LoadTestReport {
+        total: rawtxs.len(),
+        ..Default::default()
+    };
+    let timeout = Duration::from_secs(client.receipt_timeout_sec().max(0) as u64);
+    let mut pending: Vec<(String, Instant)> = vec![];
+    let mut next = 0;
+    while next < rawtxs.len() || !pending.is_empty() {
+        while next < rawtxs.len() && pending.len() < window {
+            let start = Instant::now();
+            match client.send_signed_transaction_nowait(rawtxs[next].as_str()) {
+                Ok(txhash) => pending.push((txhash, start)),
+                Err(e) => {
+                    log::warn!("load test send tx error {:?}", e);
+                    report.fail(error_reason(&e));
+                }
+            }
+            next += 1;
+        }
+        if pending.is_empty() {
+            continue;
+        }
+        let hashes: Vec<String> = pending.iter().map(|(h, _)| h.clone()).collect();
+        let receipts = match client.collect_receipts(&hashes) {
+            Ok(r) => r,
+            Err(e) => {
+                log::warn!("load test collect receipts error {:?}", e);
+                vec![]
+            }
+        };
+        for (txhash, receipt) in receipts {
+            if let Some(pos) = pending.iter().position(|(h, _)| *h == txhash) {
+                let (_, start) = pending.remove(pos);
+                count_receipt(&mut report, receipt.status, start);
+            }
+        }
+        let mut i = 0;
+        while i < pending.len() {
+            if pending[i].1.elapsed() < timeout {
+                i += 1;
+                continue;
+            }
+            let (txhash, start) = pending.remove(i);
+            match client.get_transaction_receipt(txhash.as_str()) {
+                Ok(Some(receipt)) => count_receipt(&mut report, receipt.status, start),
+                Ok(None) => report.fail(format!("error:{:?}", KissErrKind::ETimeout)),
+                Err(e) => report.fail(error_reason(&e)),
+            }
+        }
+    }
+    report
+}
+
+pub struct BcosLoadTest {
+    pub configfile: String,
+    pub connections: usize,
+    ///每个连接上最多同时在途(已发送未收到回执)的交易数，为1时逐笔发送并等待回执
+    pub window: usize,
+    ///签好的交易，不带0x的hex
+    pub rawtxs: Vec<String>,
+    pub sign_ms: u64,
+}
+
+impl BcosLoadTest {
+    pub fn new(configfile: &str, connections: usize) -> Self {
+        BcosLoadTest {
+            configfile: configfile.to_string(),
+            connections: connections.max(1),
+            window: 1,
+            rawtxs: vec![],
+            sign_ms: 0,
+        }
+    }
+
+    ///从client取blocklimit，按client的配置签好count笔调用method的交易，每笔交易的nonce不同
+    pub fn presign(
+        &mut self,
+        client: &mut dyn IBcosChainClient,
+        contract: &ContractABI,
+        address: &str,
+        method: &str,
+        params: &[String],
+        count: usize,
+    ) -> Result<(), KissError> {
+        let start = Instant::now();
+        let block_limit = client.get_block_limit()?;
+        let config = client.get_config();
+        let builder = OfflineTxBuilder::from_config(config, block_limit);
+        let signer = signer_from_config(&config.common)?;
+        let input = contract.encode_function_input_to_abi(method, params, true)?;
+        let input = hex::decode(input).unwrap();
+        self.rawtxs.clear();
+        for _ in 0..count {
+            self.rawtxs
+                .push(builder.sign_raw(signer.as_ref(), address, &input)?);
+        }
+        self.sign_ms = start.elapsed().as_millis() as u64;
+        Ok(())
+    }
+
+    ///交易按序号轮流分给各个连接，所有连接建立后同时开始发送
+    pub fn run(&self) -> Result<LoadTestReport, KissError> {
+        if self.rawtxs.is_empty() {
+            return kisserr!(
+                KissErrKind::EArgument,
+                "no transaction to send, presign first"
+            );
+        }
+        let connections = self.connections.min(self.rawtxs.len());
+        let barrier = Arc::new(Barrier::new(connections + 1));
+        let mut workers = vec![];
+        for i in 0..connections {
+            let rawtxs: Vec<String> = self
+                .rawtxs
+                .iter()
+                .skip(i)
+                .step_by(connections)
+                .cloned()
+                .collect();
+            let configfile = self.configfile.clone();
+            let barrier = barrier.clone();
+            let window = self.window.max(1);
+            workers.push(thread::spawn(move || {
+                let client = create_chain_client(configfile.as_str());
+                //连接失败也要等在barrier上，否则其他线程会一直等
+                barrier.wait();
+                let mut client = client?;
+                let report = if window > 1 && client.supports_send_nowait() {
+                    send_pipelined(client.as_mut(), &rawtxs, window)
+                } else {
+                    send_all(client.as_mut(), &rawtxs)
+                };
+                client.finish();
+                Ok(report)
+            }));
+        }
+        barrier.wait();
+        let start = Instant::now();
+        let mut report = LoadTestReport {
+            connections,
+            sign_ms: self.sign_ms,
+            ..Default::default()
+        };
+        let mut first_error = None;
+        for worker in workers {
+            let result: Result<LoadTestReport, KissError> = match worker.join() {
+                Ok(r) => r,
+                Err(_) => kisserr!(KissErrKind::Error, "load test worker panicked"),
+            };
+            match result {
+                Ok(r) => report.merge(&r),
+                Err(e) => {
+                    if first_error.is_none() {
+                        first_error = Some(e);
+                    }
+                }
+            }
+        }
+        report.elapsed_ms = start.elapsed().as_millis() as u64;
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(report),
+        }
+    }
+}
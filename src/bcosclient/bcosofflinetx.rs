@@ -0,0 +1,547 @@
+/*
+  FISCO BCOS/rust-SDK is a rust client for FISCO BCOS2.0 (https://github.com/FISCO-BCOS/)
+  FISCO BCOS/rust-SDK is free software: you can redistribute it and/or modify it under the
+  terms of the MIT License as published by the Free Software Foundation. This project is
+  distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even
+  the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+  @author: kentzhang
+  @date: 2021-07
+*/
+#![allow(
+    clippy::unreadable_literal,
+    clippy::upper_case_acronyms,
+    dead_code,
+    non_camel_case_types,
+    non_snake_case,
+    non_upper_case_globals,
+    overflowing_literals,
+    unused_variables,
+    unused_assignments
+)]
+
+/*
+离线签名、在线广播：
+在不联网的机器上用OfflineTxBuilder构建并签名交易，blocklimit、chainid、group都显式给出，
+签名结果保存为json文件(SignedTxFile)，可以直接查看发给谁、调用什么方法、blocklimit是多少；
+拷到联网的机器上用broadcast发送。发送前会重新解码rawTransaction，确认文件里的摘要字段没有被改过，
+并检查blocklimit：节点只接受blocklimit在(当前块高, 当前块高+1000]范围内的交易，过期的交易只能重新签名。
+
+文件格式：
+{
+  "format": "bcos-signed-tx", "chain": "bcos2"|"bcos3", "crypto": "ECDSA"|"GM",
+  "chainId": "1", "groupId": "1", "blockLimit": 600,
+  "txhash": "0x..", "from": "0x..", "to": "0x..", "input": "0x..",
+  "method": "set", "params": ["..."],
+  "rawTransaction": "f8..."
+}
+*/
+use serde_json::{json, Value as JsonValue};
+
+use crate::bcos2sdk::bcostransaction::{self, BcosTransaction, BcosTransactionWithSig};
+use crate::bcos3sdk::bcos3transaction::{Bcos3Transaction, Bcos3TransactionData};
+use crate::bcosclient::bcoschainclient::{BcosChainVersion, IBcosChainClient};
+use crate::bcosclient::bcoschaintypes::BcosReceiptInfo;
+use crate::bcossdkutil::accountutil::account_from_config;
+use crate::bcossdkutil::bcosclientconfig::{
+    BcosClientProtocol, BcosCryptoKind, ClientConfig, CommonConfig,
+};
+use crate::bcossdkutil::commonhash::{CommonHash, HashType};
+use crate::bcossdkutil::commonsigner::{
+    CommonSignature, CommonSignerWeDPR_SM2, CommonSignerWeDPR_Secp256, ICommonSigner,
+};
+use crate::bcossdkutil::contractabi::ContractABI;
+use crate::bcossdkutil::externalsigner::ExternalSigner;
+use crate::bcossdkutil::kisserror::{KissErrKind, KissError};
+use crate::kisserr;
+
+pub const SIGNED_TX_FORMAT: &str = "bcos-signed-tx";
+///节点允许的blocklimit最大超前块数
+pub const MAX_BLOCK_LIMIT_AHEAD: u64 = 1000;
+///Bcos3Client固定使用的chainid
+pub const BCOS3_DEFAULT_CHAINID: &str = "chain0";
+
+fn chain_name(chain: &BcosChainVersion) -> &'static str {
+    match chain {
+        BcosChainVersion::V2 => "bcos2",
+        BcosChainVersion::V3 => "bcos3",
+    }
+}
+
+fn crypto_name(crypto: &BcosCryptoKind) -> &'static str {
+    match crypto {
+        BcosCryptoKind::ECDSA => "ECDSA",
+        BcosCryptoKind::GM => "GM",
+    }
+}
+
+///地址统一成小写、带0x的格式，部署交易为空
+fn normalize_address(addr: &str) -> String {
+    let addr = addr.trim().trim_start_matches("0x").to_lowercase();
+    if addr.is_empty() {
+        return addr;
+    }
+    format!("0x{}", addr)
+}
+
+///根据配置创建签名器：配置了外部签名服务时用签名服务，否则从accountpem加载私钥
+pub fn signer_from_config(
+    config: &CommonConfig,
+) -> Result<Box<dyn ICommonSigner + Send + Sync>, KissError> {
+    if let Some(signer) = ExternalSigner::from_config(config)? {
+        return Ok(Box::new(signer));
+    }
+    let account = account_from_config(config)?;
+    match config.crypto {
+        BcosCryptoKind::ECDSA => Ok(Box::new(CommonSignerWeDPR_Secp256 {
+            account,
+            ..Default::default()
+        })),
+        BcosCryptoKind::GM => Ok(Box::new(CommonSignerWeDPR_SM2 {
+            account,
+            ..Default::default()
+        })),
+    }
+}
+
+///blocklimit相对当前块高的状态
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum BlockLimitStatus {
+    ///还可以发送，remaining为距离过期还剩的块数
+    Valid { remaining: u64 },
+    ///块高已经达到blocklimit，节点会拒绝，需要重新签名
+    Expired { blocknumber: u64 },
+    ///blocklimit超过当前块高+1000，节点同样会拒绝，等块高涨上来再发
+    TooFar { blocknumber: u64 },
+}
+
+impl BlockLimitStatus {
+    pub fn check(block_limit: u64, blocknumber: u64) -> Self {
+        if block_limit <= blocknumber {
+            BlockLimitStatus::Expired { blocknumber }
+        } else if block_limit > blocknumber + MAX_BLOCK_LIMIT_AHEAD {
+            BlockLimitStatus::TooFar { blocknumber }
+        } else {
+            BlockLimitStatus::Valid {
+                remaining: block_limit - blocknumber,
+            }
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        matches!(self, BlockLimitStatus::Valid { .. })
+    }
+}
+
+///签名好的交易及其可读的摘要，对应保存的json文件
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignedTxFile {
+    pub chain: BcosChainVersion,
+    pub crypto: BcosCryptoKind,
+    pub chainid: String,
+    pub groupid: String,
+    pub block_limit: u64,
+    pub txhash: String,
+    pub from: String,
+    pub to: String,
+    ///0x开头的hex
+    pub input: String,
+    ///按abi签名时的方法名和参数，仅用于查看，直接传input签名时为空
+    pub method: String,
+    pub params: Vec<String>,
+    ///不带0x的hex，即sendRawTransaction/sendTransaction的参数
+    pub rawtx: String,
+}
+
+impl SignedTxFile {
+    ///从签名交易解码出摘要字段，method和params无法从交易里得到，为空
+    pub fn decode(
+        chain: &BcosChainVersion,
+        crypto: &BcosCryptoKind,
+        rawtx: &str,
+    ) -> Result<SignedTxFile, KissError> {
+        let rawtx = rawtx.trim().trim_start_matches("0x").to_lowercase();
+        let data = match hex::decode(&rawtx) {
+            Ok(d) => d,
+            Err(e) => return kisserr!(KissErrKind::EFormat, "rawTransaction is not hex {:?}", e),
+        };
+        let hashtype = CommonHash::crypto_to_hashtype(crypto);
+        let mut txfile = SignedTxFile {
+            chain: chain.clone(),
+            crypto: crypto.clone(),
+            chainid: "".to_string(),
+            groupid: "".to_string(),
+            block_limit: 0,
+            txhash: "".to_string(),
+            from: "".to_string(),
+            to: "".to_string(),
+            input: "".to_string(),
+            method: "".to_string(),
+            params: vec![],
+            rawtx,
+        };
+        match chain {
+            BcosChainVersion::V2 => {
+                let decoded = bcostransaction::decode_signed_transaction(&data, &hashtype, None)?;
+                let tx = &decoded.transaction.transaction;
+                txfile.chainid = tx.fisco_chain_id.to_string();
+                txfile.groupid = tx.group_id.to_string();
+                txfile.block_limit = tx.block_limit.low_u64();
+                txfile.input = format!("0x{}", hex::encode(&tx.data));
+                txfile.txhash = decoded.txhash;
+                txfile.from = decoded.sender;
+                txfile.to = decoded.to;
+            }
+            BcosChainVersion::V3 => {
+                let mut tx = Bcos3Transaction::decode_bytes(&data)?;
+                tx.data.hashtype = hashtype.clone();
+                //dataHash由交易发送方给出，重新计算一次，确认和交易内容一致
+                if tx.data.hash() != tx.data_hash {
+                    return kisserr!(
+                        KissErrKind::ESign,
+                        "dataHash {} does not match transaction data",
+                        tx.txhash_hex()
+                    );
+                }
+                if tx.signature.len() < 65 {
+                    return kisserr!(KissErrKind::ESign, "transaction is not signed");
+                }
+                let sig = CommonSignature::from_vec(&tx.signature);
+                let sender = sig.recover_address(&tx.data_hash, &hashtype)?;
+                txfile.chainid = tx.data.chain_id.clone();
+                txfile.groupid = tx.data.group_id.clone();
+                txfile.block_limit = tx.data.block_limit as u64;
+                txfile.input = format!("0x{}", hex::encode(&tx.data.input));
+                txfile.txhash = tx.txhash_hex();
+                txfile.from = format!("0x{}", hex::encode(sender));
+                txfile.to = normalize_address(&tx.data.to);
+            }
+        }
+        Ok(txfile)
+    }
+
+    ///重新解码rawTransaction，检查签名和文件里的摘要字段是否一致，防止看到的和实际发送的不是同一笔交易
+    pub fn verify(&self) -> Result<(), KissError> {
+        let decoded = SignedTxFile::decode(&self.chain, &self.crypto, &self.rawtx)?;
+        let fields = [
+            ("chainId", &decoded.chainid, &self.chainid),
+            ("groupId", &decoded.groupid, &self.groupid),
+            ("txhash", &decoded.txhash, &self.txhash),
+            ("from", &decoded.from, &self.from),
+            ("to", &decoded.to, &self.to),
+            ("input", &decoded.input, &self.input),
+        ];
+        for (name, actual, claimed) in fields.iter() {
+            if actual.to_lowercase() != claimed.to_lowercase() {
+                return kisserr!(
+                    KissErrKind::EFormat,
+                    "{} in file is {} but the signed transaction has {}",
+                    name,
+                    claimed,
+                    actual
+                );
+            }
+        }
+        if decoded.block_limit != self.block_limit {
+            return kisserr!(
+                KissErrKind::EFormat,
+                "blockLimit in file is {} but the signed transaction has {}",
+                self.block_limit,
+                decoded.block_limit
+            );
+        }
+        Ok(())
+    }
+
+    pub fn check_block_limit(&self, blocknumber: u64) -> BlockLimitStatus {
+        BlockLimitStatus::check(self.block_limit, blocknumber)
+    }
+
+    pub fn to_json(&self) -> JsonValue {
+        json!({
+            "format": SIGNED_TX_FORMAT,
+            "chain": chain_name(&self.chain),
+            "crypto": crypto_name(&self.crypto),
+            "chainId": self.chainid,
+            "groupId": self.groupid,
+            "blockLimit": self.block_limit,
+            "txhash": self.txhash,
+            "from": self.from,
+            "to": self.to,
+            "input": self.input,
+            "method": self.method,
+            "params": self.params,
+            "rawTransaction": self.rawtx,
+        })
+    }
+
+    pub fn from_json(v: &JsonValue) -> Result<SignedTxFile, KissError> {
+        if v["format"].as_str() != Some(SIGNED_TX_FORMAT) {
+            return kisserr!(KissErrKind::EFormat, "not a signed transaction file");
+        }
+        let text = |name: &str| -> Result<String, KissError> {
+            match v[name].as_str() {
+                Some(s) => Ok(s.to_string()),
+                None => kisserr!(KissErrKind::EFormat, "signed tx file has no {}", name),
+            }
+        };
+        let chain = match text("chain")?.as_str() {
+            "bcos2" => BcosChainVersion::V2,
+            "bcos3" => BcosChainVersion::V3,
+            other => return kisserr!(KissErrKind::EFormat, "unknown chain {}", other),
+        };
+        let crypto = match text("crypto")?.as_str() {
+            "ECDSA" => BcosCryptoKind::ECDSA,
+            "GM" => BcosCryptoKind::GM,
+            other => return kisserr!(KissErrKind::EFormat, "unknown crypto {}", other),
+        };
+        let block_limit = match v["blockLimit"].as_u64() {
+            Some(n) => n,
+            None => return kisserr!(KissErrKind::EFormat, "signed tx file has no blockLimit"),
+        };
+        let params = match v["params"].as_array() {
+            Some(list) => list
+                .iter()
+                .map(|p| match p.as_str() {
+                    Some(s) => s.to_string(),
+                    None => p.to_string(),
+                })
+                .collect(),
+            None => vec![],
+        };
+        Ok(SignedTxFile {
+            chain,
+            crypto,
+            chainid: text("chainId")?,
+            groupid: text("groupId")?,
+            block_limit,
+            txhash: text("txhash")?,
+            from: text("from")?,
+            to: text("to")?,
+            input: text("input")?,
+            method: v["method"].as_str().unwrap_or_default().to_string(),
+            params,
+            rawtx: text("rawTransaction")?,
+        })
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), KissError> {
+        let text = serde_json::to_string_pretty(&self.to_json()).unwrap();
+        match std::fs::write(path, text) {
+            Ok(_) => Ok(()),
+            Err(e) => kisserr!(KissErrKind::EFileWrite, "write {} error {:?}", path, e),
+        }
+    }
+
+    pub fn load(path: &str) -> Result<SignedTxFile, KissError> {
+        let text = match std::fs::read_to_string(path) {
+            Ok(t) => t,
+            Err(e) => return kisserr!(KissErrKind::EFileRead, "read {} error {:?}", path, e),
+        };
+        match serde_json::from_str::<JsonValue>(text.as_str()) {
+            Ok(v) => SignedTxFile::from_json(&v),
+            Err(e) => kisserr!(KissErrKind::EFormat, "{} is not json {:?}", path, e),
+        }
+    }
+
+    ///广播前的检查：签名和摘要一致、链版本和chainid/group与客户端一致，返回blocklimit状态
+    pub fn check_with_client(
+        &self,
+        client: &mut dyn IBcosChainClient,
+    ) -> Result<BlockLimitStatus, KissError> {
+        self.verify()?;
+        if client.chain_version() != self.chain {
+            return kisserr!(
+                KissErrKind::EArgument,
+                "transaction is signed for {} but client is {}",
+                chain_name(&self.chain),
+                client.get_full_name()
+            );
+        }
+        let config = client.get_config();
+        if config.common.crypto != self.crypto {
+            return kisserr!(
+                KissErrKind::EArgument,
+                "transaction crypto is {} but client is {:?}",
+                crypto_name(&self.crypto),
+                config.common.crypto
+            );
+        }
+        let (chainid, groupid) = match self.chain {
+            BcosChainVersion::V2 => (
+                config.bcos2.chainid.to_string(),
+                config.bcos2.groupid.to_string(),
+            ),
+            BcosChainVersion::V3 => (
+                BCOS3_DEFAULT_CHAINID.to_string(),
+                config.bcos3.group.clone(),
+            ),
+        };
+        if chainid != self.chainid || groupid != self.groupid {
+            return kisserr!(
+                KissErrKind::EArgument,
+                "transaction is signed for chain {} group {}, client is on chain {} group {}",
+                self.chainid,
+                self.groupid,
+                chainid,
+                groupid
+            );
+        }
+        let blocknumber = client.get_block_number()?;
+        Ok(self.check_block_limit(blocknumber))
+    }
+
+    ///检查后发送并等待回执。blocklimit无效时默认不发送，force为true时照样发送(节点通常会拒绝)
+    pub fn broadcast(
+        &self,
+        client: &mut dyn IBcosChainClient,
+        force: bool,
+    ) -> Result<BcosReceiptInfo, KissError> {
+        let status = self.check_with_client(client)?;
+        if !status.is_valid() {
+            if !force {
+                return kisserr!(
+                    KissErrKind::EArgument,
+                    "blockLimit {} of {} is not acceptable: {:?}, sign it again",
+                    self.block_limit,
+                    self.txhash,
+                    status
+                );
+            }
+            log::warn!(
+                "broadcast {} with blockLimit {} anyway: {:?}",
+                self.txhash,
+                self.block_limit,
+                status
+            );
+        }
+        client.send_signed_transaction(self.rawtx.as_str())
+    }
+}
+
+///离线构建交易，所有链上参数都由调用者指定，不访问网络
+#[derive(Debug, Clone)]
+pub struct OfflineTxBuilder {
+    pub chain: BcosChainVersion,
+    pub crypto: BcosCryptoKind,
+    pub chainid: String,
+    pub groupid: String,
+    pub block_limit: u64,
+}
+
+impl OfflineTxBuilder {
+    pub fn new(
+        chain: BcosChainVersion,
+        crypto: &BcosCryptoKind,
+        chainid: &str,
+        groupid: &str,
+        block_limit: u64,
+    ) -> Self {
+        OfflineTxBuilder {
+            chain,
+            crypto: crypto.clone(),
+            chainid: chainid.to_string(),
+            groupid: groupid.to_string(),
+            block_limit,
+        }
+    }
+
+    ///链版本、chainid、group取自配置文件，和在线客户端一致；blocklimit仍需指定
+    pub fn from_config(config: &ClientConfig, block_limit: u64) -> Self {
+        match config.bcos2.protocol {
+            BcosClientProtocol::BCOS3 => OfflineTxBuilder::new(
+                BcosChainVersion::V3,
+                &config.common.crypto,
+                BCOS3_DEFAULT_CHAINID,
+                config.bcos3.group.as_str(),
+                block_limit,
+            ),
+            _ => OfflineTxBuilder::new(
+                BcosChainVersion::V2,
+                &config.common.crypto,
+                config.bcos2.chainid.to_string().as_str(),
+                config.bcos2.groupid.to_string().as_str(),
+                block_limit,
+            ),
+        }
+    }
+
+    pub fn hashtype(&self) -> HashType {
+        CommonHash::crypto_to_hashtype(&self.crypto)
+    }
+
+    fn parse_u32(name: &str, value: &str) -> Result<u32, KissError> {
+        match value.trim().parse::<u32>() {
+            Ok(n) => Ok(n),
+            Err(e) => kisserr!(
+                KissErrKind::EArgument,
+                "bcos2 {} {} is not number",
+                name,
+                value
+            ),
+        }
+    }
+
+    ///构建并签名交易，返回不带0x的hex，不做解码校验，用于批量签名
+    pub fn sign_raw(
+        &self,
+        signer: &dyn ICommonSigner,
+        to: &str,
+        input: &[u8],
+    ) -> Result<String, KissError> {
+        let hashtype = self.hashtype();
+        let rawtx = match self.chain {
+            BcosChainVersion::V2 => {
+                let tx = BcosTransaction::new_unsigned(
+                    to,
+                    input.to_vec(),
+                    self.block_limit,
+                    OfflineTxBuilder::parse_u32("chainid", &self.chainid)?,
+                    OfflineTxBuilder::parse_u32("groupid", &self.groupid)?,
+                    &hashtype,
+                );
+                hex::encode(BcosTransactionWithSig::sign_and_encode(signer, &tx)?)
+            }
+            BcosChainVersion::V3 => {
+                let txdata = Bcos3TransactionData::new(
+                    self.chainid.as_str(),
+                    self.groupid.as_str(),
+                    self.block_limit as i64,
+                    to,
+                    input.to_vec(),
+                    "",
+                    &hashtype,
+                );
+                Bcos3Transaction::sign(signer, &txdata)?.encode_hex()
+            }
+        };
+        Ok(rawtx)
+    }
+
+    ///构建并签名交易，to为空时是部署交易，input为合约代码加构造参数
+    pub fn sign(
+        &self,
+        signer: &dyn ICommonSigner,
+        to: &str,
+        input: &[u8],
+    ) -> Result<SignedTxFile, KissError> {
+        let rawtx = self.sign_raw(signer, to, input)?;
+        //从签名结果解码摘要，顺便确认签名器给出的签名是对的
+        SignedTxFile::decode(&self.chain, &self.crypto, rawtx.as_str())
+    }
+
+    ///按abi编码方法调用并签名，方法名和参数记录在文件里便于查看
+    pub fn sign_call(
+        &self,
+        signer: &dyn ICommonSigner,
+        contract: &ContractABI,
+        to: &str,
+        method: &str,
+        params: &[String],
+    ) -> Result<SignedTxFile, KissError> {
+        let input = contract.encode_function_input_to_abi(method, params, true)?;
+        let mut txfile = self.sign(signer, to, &hex::decode(input).unwrap())?;
+        txfile.method = method.to_string();
+        txfile.params = params.to_vec();
+        Ok(txfile)
+    }
+}
@@ -0,0 +1,17 @@
+/*
+  FISCO BCOS/rust-SDK is a rust client for FISCO BCOS2.0 (https://github.com/FISCO-BCOS/)
+  FISCO BCOS/rust-SDK is free software: you can redistribute it and/or modify it under the
+  terms of the MIT License as published by the Free Software Foundation. This project is
+  distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even
+  the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+  @author: kentzhang
+  @date: 2021-07
+*/
+//与链版本无关的客户端抽象，应用代码面向这里的trait和数据结构编程，运行时按配置选择bcos2或bcos3的实现
+pub mod bcoschainclient;
+pub mod bcoschaintypes;
+pub mod bcoscontract;
+pub mod bcoscontractgen;
+pub mod bcoseventindexer;
+pub mod bcosloadtest;
+pub mod bcosofflinetx;
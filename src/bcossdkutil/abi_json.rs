@@ -0,0 +1,113 @@
+/*
+  FISCO BCOS/rust-SDK is a rust client for FISCO BCOS2.0 (https://github.com/FISCO-BCOS/)
+  FISCO BCOS/rust-SDK is free software: you can redistribute it and/or modify it under the
+  terms of the MIT License as published by the Free Software Foundation. This project is
+  distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even
+  the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+  @author: kentzhang
+  @date: 2021-07
+*/
+#![allow(
+    clippy::unreadable_literal,
+    clippy::upper_case_acronyms,
+    dead_code,
+    non_camel_case_types,
+    non_snake_case,
+    non_upper_case_globals,
+    overflowing_literals,
+    unused_variables,
+    unused_assignments
+)]
+
+/*
+把解码出来的ethabi Token转成json，和ABIJsonTokenizer的输入格式对应，便于脚本(如jq)处理：
+- address、bytes、bytesN: 0x开头的hex字符串
+- uint/int: 在u64/i64范围内的是json数字，超出的是十进制字符串(避免json解析器丢精度)
+- 数组、定长数组: json数组
+- tuple和参数列表: 每个成员都有名字且不重名时是以名字为key的json对象，否则按顺序输出json数组
+成员名取自abi原文里的name和components(ethabi解析后的ParamType里没有名字)，没有abi定义时都按数组输出
+*/
+use ethabi::{Token, Uint};
+use serde_json::{Map, Value as JsonValue};
+use std::collections::HashSet;
+
+///abiparam是abi里这个参数的定义，如 {"name":"u","type":"tuple","components":[...]}
+pub fn token_to_json(token: &Token, abiparam: Option<&JsonValue>) -> JsonValue {
+    match token {
+        Token::Address(a) => JsonValue::String(format!("0x{}", hex::encode(a.as_bytes()))),
+        Token::Bytes(b) | Token::FixedBytes(b) => {
+            JsonValue::String(format!("0x{}", hex::encode(b)))
+        }
+        Token::String(s) => JsonValue::String(s.clone()),
+        Token::Bool(b) => JsonValue::Bool(*b),
+        Token::Uint(u) => uint_to_json(u),
+        Token::Int(i) => int_to_json(i),
+        //数组的components定义的是数组元素，原样传下去
+        Token::Array(items) | Token::FixedArray(items) => JsonValue::Array(
+            items
+                .iter()
+                .map(|item| token_to_json(item, abiparam))
+                .collect(),
+        ),
+        Token::Tuple(items) => params_to_json(items, abiparam.map(|p| &p["components"])),
+    }
+}
+
+///参数列表(方法的inputs/outputs，或者tuple的components)转json，abiparams是对应的json数组定义
+pub fn params_to_json(tokens: &[Token], abiparams: Option<&JsonValue>) -> JsonValue {
+    let abiparams = abiparams
+        .and_then(|p| p.as_array())
+        .filter(|p| p.len() == tokens.len());
+    let abiparam = |i: usize| abiparams.and_then(|p| p.get(i));
+    match abiparams.and_then(|p| param_names(p)) {
+        Some(names) => {
+            let mut obj = Map::new();
+            for (i, (name, token)) in names.into_iter().zip(tokens.iter()).enumerate() {
+                obj.insert(name, token_to_json(token, abiparam(i)));
+            }
+            JsonValue::Object(obj)
+        }
+        None => JsonValue::Array(
+            tokens
+                .iter()
+                .enumerate()
+                .map(|(i, token)| token_to_json(token, abiparam(i)))
+                .collect(),
+        ),
+    }
+}
+
+///所有成员都有名字且不重名时返回名字列表
+pub fn param_names(abiparams: &[JsonValue]) -> Option<Vec<String>> {
+    let mut names: Vec<String> = vec![];
+    let mut seen = HashSet::new();
+    for p in abiparams {
+        let name = p["name"].as_str().unwrap_or("");
+        if name.is_empty() || !seen.insert(name) {
+            return None;
+        }
+        names.push(name.to_string());
+    }
+    Some(names)
+}
+
+fn uint_to_json(u: &Uint) -> JsonValue {
+    if *u <= Uint::from(u64::MAX) {
+        JsonValue::from(u.low_u64())
+    } else {
+        JsonValue::String(u.to_string())
+    }
+}
+
+///int是补码形式的256位数，最高位为1时是负数
+fn int_to_json(i: &Uint) -> JsonValue {
+    if !i.bit(255) {
+        return uint_to_json(i);
+    }
+    let abs = !*i + 1;
+    if abs <= Uint::from(i64::MAX as u64) + 1 {
+        JsonValue::from((abs.low_u64() as i64).wrapping_neg())
+    } else {
+        JsonValue::String(format!("-{}", abs))
+    }
+}
@@ -64,6 +64,24 @@ impl ABIParser {
         Ok(parser)
     }
 
+    ///直接传入abi文本，比如生成的合约绑定代码里内嵌的abi
+    pub fn from_text(abitext: &str) -> Result<ABIParser, KissError> {
+        let abiobj: JsonValue = match serde_json::from_str(abitext) {
+            Ok(v) => v,
+            Err(e) => return kisserr!(KissErrKind::EFormat, "abi is not json {:?}", e),
+        };
+        if !abiobj.is_array() {
+            return kisserr!(KissErrKind::EFormat, "abi is not a json array");
+        }
+        let mut parser = ABIParser {
+            abitext: abitext.to_string(),
+            abiobj,
+            events: vec![],
+        };
+        parser.parse();
+        Ok(parser)
+    }
+
     ///解析结构体参数类型，type是 tuple,每个参数都是component
     pub fn parse_tuple_params(input: &JsonValue) -> ParamType {
         let mut tupleparams: Vec<Box<ParamType>> = vec![];
@@ -98,24 +116,39 @@ impl ABIParser {
             "int" => ParamType::Int(256),
             "tuple" => ABIParser::parse_tuple_params(input),
             "uint" => ParamType::Uint(256),
+            s if s.ends_with(']') => {
+                //数组类型，类似string[],string[3],tuple[],uint256[2][]等
+                //从最后一个[]拆开，前面的部分作为元素类型递归解析，[]里有数字则是定长数组
+                let pos = s.rfind('[').unwrap();
+                let paramtype = ABIParser::parse_param_type(input, &s[..pos])?;
+                let size = &s[pos + 1..s.len() - 1];
+                if size.is_empty() {
+                    ParamType::Array(Box::new(paramtype))
+                } else {
+                    match size.parse::<usize>() {
+                        Ok(len) => ParamType::FixedArray(Box::new(paramtype), len),
+                        Err(_) => {
+                            return kisserr!(
+                                KissErrKind::EArgument,
+                                "unknow typename {}",
+                                typename
+                            );
+                        }
+                    }
+                }
+            }
             s if s.starts_with("int") => {
-                let len = usize::from_str_radix(&s[3..], 10).unwrap();
+                let len = s[3..].parse::<usize>().unwrap();
                 ParamType::Int(len)
             }
             s if s.starts_with("uint") => {
-                let len = usize::from_str_radix(&s[4..], 10).unwrap();
+                let len = s[4..].parse::<usize>().unwrap();
                 ParamType::Uint(len)
             }
             s if s.starts_with("bytes") => {
-                let len = usize::from_str_radix(&s[5..], 10).unwrap();
+                let len = s[5..].parse::<usize>().unwrap();
                 ParamType::FixedBytes(len)
             }
-            s if s.ends_with(']') => {
-                //数组类型，类似string[],string[3],tuple[]等
-                let items: Vec<&str> = s.split("[").collect();
-                let paramtype = ABIParser::parse_param_type(input, &items[0]).unwrap();
-                ParamType::Array(Box::new(paramtype))
-            }
             _ => {
                 return kisserr!(KissErrKind::EArgument, "unknow typename {}", typename);
             }
@@ -1,5 +1,6 @@
 use crate::bcossdkutil::liteutils::split_param;
 use ethabi::{param_type::ParamType, token::Token, Error as ABIError, Uint};
+use serde_json::Value as JsonValue;
 
 pub trait ABITokenizer {
     /// Tries to parse a string as a token of given type.
@@ -237,3 +238,220 @@ impl ABITokenizer for ABILenientTokenizer {
         Ok(int.into())
     }
 }
+
+/*
+按json解析参数，json本身就带有结构，不需要像split_param那样猜测分隔符和括号，
+嵌套的tuple、tuple[]、定长数组等都可以准确的对应到ParamType上
+- uint/int: json数字，或字符串形式的十进制数(可带负号)，或0x开头的hex
+- address/bytes/bytesN: hex字符串，0x可带可不带，bytes也可以是0~255的数字数组
+- bool: true/false，也接受"true"/"false"/0/1
+- 数组/定长数组: json数组，定长数组的长度必须一致
+- tuple: 按成员顺序的json数组，或者以成员名为key的json对象(需要abi里的components定义)
+例：addUsers((string,uint256)[]) 的参数可以是 [{"name":"alice","age":25},["bob",30]]
+*/
+pub struct ABIJsonTokenizer;
+
+impl ABIJsonTokenizer {
+    pub fn tokenize(param: &ParamType, value: &JsonValue) -> Result<Token, ABIError> {
+        Self::tokenize_with_components(param, None, value)
+    }
+
+    ///components是abi里这个参数的"components"字段，数组类型的components定义的是数组元素，
+    /// 带上它才能用成员名作为key的json对象传入tuple
+    pub fn tokenize_with_components(
+        param: &ParamType,
+        components: Option<&JsonValue>,
+        value: &JsonValue,
+    ) -> Result<Token, ABIError> {
+        match *param {
+            ParamType::Address => Self::tokenize_address(value).map(|a| Token::Address(a.into())),
+            ParamType::String => Self::tokenize_string(value).map(Token::String),
+            ParamType::Bool => Self::tokenize_bool(value).map(Token::Bool),
+            ParamType::Bytes => Self::tokenize_bytes(value).map(Token::Bytes),
+            ParamType::FixedBytes(len) => {
+                let bytes = Self::tokenize_bytes(value)?;
+                match bytes.len() == len {
+                    true => Ok(Token::FixedBytes(bytes)),
+                    false => Err(ABIError::Other(format!(
+                        "bytes{} expects {} bytes, got {}",
+                        len,
+                        len,
+                        bytes.len()
+                    ))),
+                }
+            }
+            ParamType::Uint(_) => Self::tokenize_number(value, false)
+                .map(Into::into)
+                .map(Token::Uint),
+            ParamType::Int(_) => Self::tokenize_number(value, true)
+                .map(Into::into)
+                .map(Token::Int),
+            ParamType::Array(ref p) => Self::tokenize_array(value, p, components).map(Token::Array),
+            ParamType::FixedArray(ref p, len) => {
+                let result = Self::tokenize_array(value, p, components)?;
+                match result.len() == len {
+                    true => Ok(Token::FixedArray(result)),
+                    false => Err(ABIError::Other(format!(
+                        "fixed array expects {} items, got {}",
+                        len,
+                        result.len()
+                    ))),
+                }
+            }
+            ParamType::Tuple(ref p) => Self::tokenize_tuple(value, p, components).map(Token::Tuple),
+        }
+    }
+
+    fn tokenize_array(
+        value: &JsonValue,
+        param: &ParamType,
+        components: Option<&JsonValue>,
+    ) -> Result<Vec<Token>, ABIError> {
+        let items = match value.as_array() {
+            Some(a) => a,
+            None => return Err(ABIError::Other(format!("expect json array: {}", value))),
+        };
+        items
+            .iter()
+            .map(|item| Self::tokenize_with_components(param, components, item))
+            .collect()
+    }
+
+    fn tokenize_tuple(
+        value: &JsonValue,
+        paramtypes: &[Box<ParamType>],
+        components: Option<&JsonValue>,
+    ) -> Result<Vec<Token>, ABIError> {
+        let components = components.and_then(|c| c.as_array());
+        let component = |i: usize| components.and_then(|c| c.get(i));
+        if let Some(items) = value.as_array() {
+            if items.len() != paramtypes.len() {
+                return Err(ABIError::Other(format!(
+                    "tuple expects {} members, got {}",
+                    paramtypes.len(),
+                    items.len()
+                )));
+            }
+            let mut result: Vec<Token> = vec![];
+            for (i, (param, item)) in paramtypes.iter().zip(items.iter()).enumerate() {
+                let sub = component(i).map(|c| &c["components"]);
+                result.push(Self::tokenize_with_components(param, sub, item)?);
+            }
+            return Ok(result);
+        }
+        let obj = match value.as_object() {
+            Some(o) => o,
+            None => {
+                return Err(ABIError::Other(format!(
+                    "expect json array or object for tuple: {}",
+                    value
+                )))
+            }
+        };
+        //按成员名取值，必须有abi里的成员定义
+        if components.map(|c| c.len()) != Some(paramtypes.len()) {
+            return Err(ABIError::Other(
+                "tuple member names unknown, use json array instead of object".to_string(),
+            ));
+        }
+        let mut result: Vec<Token> = vec![];
+        for (i, param) in paramtypes.iter().enumerate() {
+            let c = component(i).unwrap();
+            let name = c["name"].as_str().unwrap_or("");
+            let item = match obj.get(name) {
+                Some(v) => v,
+                None => return Err(ABIError::Other(format!("tuple member {} missing", name))),
+            };
+            result.push(Self::tokenize_with_components(
+                param,
+                Some(&c["components"]),
+                item,
+            )?);
+        }
+        Ok(result)
+    }
+
+    fn tokenize_address(value: &JsonValue) -> Result<[u8; 20], ABIError> {
+        match value.as_str() {
+            Some(s) => ABIStrictTokenizer::tokenize_address(s.trim().trim_start_matches("0x")),
+            None => Err(ABIError::Other(format!("expect address string: {}", value))),
+        }
+    }
+
+    fn tokenize_string(value: &JsonValue) -> Result<String, ABIError> {
+        match value {
+            JsonValue::String(s) => Ok(s.clone()),
+            JsonValue::Number(_) | JsonValue::Bool(_) => Ok(value.to_string()),
+            _ => Err(ABIError::Other(format!("expect string: {}", value))),
+        }
+    }
+
+    fn tokenize_bool(value: &JsonValue) -> Result<bool, ABIError> {
+        match value {
+            JsonValue::Bool(b) => Ok(*b),
+            JsonValue::String(s) => ABIStrictTokenizer::tokenize_bool(s.trim()),
+            JsonValue::Number(_) => ABIStrictTokenizer::tokenize_bool(value.to_string().as_str()),
+            _ => Err(ABIError::Other(format!("expect bool: {}", value))),
+        }
+    }
+
+    fn tokenize_bytes(value: &JsonValue) -> Result<Vec<u8>, ABIError> {
+        match value {
+            JsonValue::String(s) => {
+                ABIStrictTokenizer::tokenize_bytes(s.trim().trim_start_matches("0x"))
+            }
+            JsonValue::Array(items) => items
+                .iter()
+                .map(|b| match b.as_u64() {
+                    Some(n) if n <= 255 => Ok(n as u8),
+                    _ => Err(ABIError::Other(format!("invalid byte: {}", b))),
+                })
+                .collect(),
+            _ => Err(ABIError::Other(format!(
+                "expect hex string for bytes: {}",
+                value
+            ))),
+        }
+    }
+
+    ///json数字超过u64/i64范围时会丢精度，大数请用字符串传入
+    fn tokenize_number(value: &JsonValue, signed: bool) -> Result<[u8; 32], ABIError> {
+        let text = match value {
+            JsonValue::Number(n) if n.is_u64() || n.is_i64() => n.to_string(),
+            JsonValue::String(s) => s.trim().to_string(),
+            _ => return Err(ABIError::Other(format!("expect integer: {}", value))),
+        };
+        if let Some(hexstr) = text.strip_prefix("0x") {
+            let hex = hex::decode(format!("{:0>64}", hexstr))
+                .map_err(|_| ABIError::Other(format!("invalid hex integer: {}", text)))?;
+            if hex.len() != 32 {
+                return Err(ABIError::Other(format!("integer too large: {}", text)));
+            }
+            let mut result = [0u8; 32];
+            result.copy_from_slice(&hex);
+            return Ok(result);
+        }
+        //十进制的不能走ABILenientTokenizer，64位的数字串会被当成hex
+        let abs = Uint::from_dec_str(text.trim_start_matches('-'))
+            .map_err(|e| ABIError::Other(format!("invalid integer {}: {:?}", text, e)))?;
+        if !text.starts_with('-') {
+            if signed && abs > Uint::max_value() / 2 {
+                return Err(ABIError::Other("int256 parse error: Overflow".into()));
+            }
+            return Ok(abs.into());
+        }
+        if !signed {
+            return Err(ABIError::Other(format!(
+                "negative value for uint: {}",
+                text
+            )));
+        }
+        if abs.is_zero() {
+            return Ok(abs.into());
+        }
+        if abs > Uint::max_value() / 2 + 1 {
+            return Err(ABIError::Other("int256 parse error: Underflow".into()));
+        }
+        Ok((!abs + 1).into())
+    }
+}
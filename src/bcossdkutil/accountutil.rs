@@ -27,9 +27,11 @@ use wedpr_l_crypto_signature_sm2::WedprSm2p256v1;
 use wedpr_l_libsm::sm2::signature::SigCtx;
 use wedpr_l_utils::traits::Signature;
 
-use crate::bcossdkutil::bcosclientconfig::BcosCryptoKind;
+use crate::bcossdkutil::bcosclientconfig::{BcosCryptoKind, CommonConfig};
 use crate::bcossdkutil::commonhash::{CommonHash, HashType};
 use crate::bcossdkutil::fileutils;
+use crate::bcossdkutil::hdwallet::HDWallet;
+use crate::bcossdkutil::keystore;
 use crate::bcossdkutil::kisserror::{KissErrKind, KissError};
 use pkcs8::PrivateKeyInfo;
 use std::convert::TryFrom;
@@ -107,7 +109,8 @@ pub fn try_from_fisco_pem_format(contents: Vec<u8>) -> Option<Vec<u8>> {
     None
 }
 
-fn address_from_pubkey(pubkey: &Vec<u8>, hashtype: &HashType) -> Vec<u8> {
+///公钥(64或65字节)转成地址：hash的后20字节
+pub fn address_from_pubkey(pubkey: &Vec<u8>, hashtype: &HashType) -> Vec<u8> {
     let mut actpubkey = pubkey.clone();
     if pubkey.len() == 65 {
         actpubkey = actpubkey[1..].to_vec(); //去掉头部的压缩标记
@@ -230,20 +233,32 @@ pub fn create_account(cryptokind: &BcosCryptoKind) -> BcosAccount {
     }
 }
 
+///明文pem直接加载，加密的pem/p12/json keystore需要用account_from_keystore传入密码
 pub fn account_from_pem(
     pemfile: &str,
     cryptokind: &BcosCryptoKind,
 ) -> Result<BcosAccount, KissError> {
-    match cryptokind {
-        BcosCryptoKind::ECDSA => {
-            let accountutil = EcdsaAccountUtil::default();
-            accountutil.from_pem(pemfile)
-        }
-        BcosCryptoKind::GM => {
-            let accountutil = GMAccountUtil::default();
-            accountutil.from_pem(pemfile)
-        }
-    }
+    account_from_keystore(pemfile, None, cryptokind)
+}
+
+///按文件格式加载账户，支持明文pem，加密pem，p12，web3 json keystore，见keystore模块
+pub fn account_from_keystore(
+    keyfile: &str,
+    password: Option<&str>,
+    cryptokind: &BcosCryptoKind,
+) -> Result<BcosAccount, KissError> {
+    let key = keystore::load_key(keyfile, password)?;
+    account_from_privkey(&key, cryptokind.clone())
+}
+
+///按配置的common.accountpem和common.accountpassword加载账户
+pub fn account_from_config(config: &CommonConfig) -> Result<BcosAccount, KissError> {
+    let password = config.account_password()?;
+    account_from_keystore(
+        config.accountpem.as_str(),
+        password.as_deref(),
+        &config.crypto,
+    )
 }
 
 pub fn account_from_privkey(
@@ -262,6 +277,16 @@ pub fn account_from_privkey(
     }
 }
 
+///从助记词按默认的BIP44路径派生第index个账户，见hdwallet模块
+pub fn account_from_mnemonic(
+    phrase: &str,
+    passphrase: &str,
+    index: u32,
+    cryptokind: &BcosCryptoKind,
+) -> Result<BcosAccount, KissError> {
+    HDWallet::from_mnemonic(phrase, passphrase, cryptokind)?.derive_account(index)
+}
+
 //测试代码开始--------------------------------------------
 pub fn test_account() {
     let fixkey = "82dcd33c98a23d5d06f9331554e14ab4044a1d71b169b7a38b61c214f0690f80";
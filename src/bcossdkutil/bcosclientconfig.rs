@@ -23,6 +23,7 @@ use serde_derive::Deserialize;
 use toml;
 
 use crate::bcossdkutil::fileutils;
+use crate::bcossdkutil::keystore;
 use crate::bcossdkutil::kisserror::{KissErrKind, KissError};
 
 #[derive(Deserialize, Debug, Clone, Eq, PartialEq)]
@@ -37,6 +38,26 @@ pub enum BcosClientProtocol {
     BCOS3,
 }
 
+///多节点时选择节点的方式，STICKY：一直用同一个节点，出错才切换；ROUNDROBIN：每个请求轮流发往各节点
+#[derive(Deserialize, Debug, Default, Clone, Eq, PartialEq)]
+pub enum BcosNodePolicy {
+    #[default]
+    STICKY,
+    ROUNDROBIN,
+}
+
+fn default_node_retry_sec() -> u32 {
+    30
+}
+
+fn default_receipt_timeout_sec() -> u32 {
+    30
+}
+
+fn default_heartbeat_sec() -> u32 {
+    10
+}
+
 impl BcosCryptoKind {
     pub fn default() -> Self {
         BcosCryptoKind::ECDSA
@@ -48,6 +69,14 @@ pub struct Bcos2ChainConfig {
     pub chainid: u32,
     pub groupid: u32,
     pub protocol: BcosClientProtocol,
+    #[serde(default)]
+    pub node_policy: BcosNodePolicy,
+    //被标记为不可用的节点，隔多少秒后重新尝试
+    #[serde(default = "default_node_retry_sec")]
+    pub node_retry_sec: u32,
+    //发交易后等待回执的最长时间，出块慢或压测时适当调大
+    #[serde(default = "default_receipt_timeout_sec")]
+    pub receipt_timeout_sec: u32,
 }
 //unsafe impl Send for ChainConfig{}
 //unsafe impl Sync for ChainConfig{}
@@ -58,6 +87,9 @@ impl Bcos2ChainConfig {
             chainid: 1,
             groupid: 1,
             protocol: BcosClientProtocol::RPC,
+            node_policy: BcosNodePolicy::STICKY,
+            node_retry_sec: default_node_retry_sec(),
+            receipt_timeout_sec: default_receipt_timeout_sec(),
         }
     }
 }
@@ -84,6 +116,9 @@ impl Bcos3Config {
 pub struct RpcConfig {
     pub url: String,
     pub timeout: u32, //in sec
+    //其他节点的url，和url一起组成节点列表
+    #[serde(default)]
+    pub urls: Vec<String>,
 }
 
 unsafe impl Send for RpcConfig {}
@@ -94,6 +129,7 @@ impl RpcConfig {
         RpcConfig {
             url: "".to_string(),
             timeout: 10,
+            urls: vec![],
         }
     }
 }
@@ -115,6 +151,12 @@ pub struct ChannelConfig {
     pub gmsdkkey: String,
     pub gmensdkcert: String,
     pub gmensdkkey: String,
+    //其他节点，格式为"ip:port"，和ip/port一起组成节点列表，证书配置共用
+    #[serde(default)]
+    pub nodes: Vec<String>,
+    //心跳间隔，空闲超过一个间隔时发心跳，心跳发出后2个间隔内没有收到任何数据就认为连接已断开，会自动重连。0表示不发心跳
+    #[serde(default = "default_heartbeat_sec")]
+    pub heartbeat_sec: u32,
 }
 unsafe impl Send for ChannelConfig {}
 unsafe impl Sync for ChannelConfig {}
@@ -135,6 +177,8 @@ impl ChannelConfig {
             gmensdkcert: "sdk/gmensdk.crt".to_string(),
             gmensdkkey: "sdk/gmensdk.key".to_string(),
             timeout: 10,
+            nodes: vec![],
+            heartbeat_sec: default_heartbeat_sec(),
         }
     }
 }
@@ -147,8 +191,29 @@ pub struct CommonConfig {
     pub contractpath: String,
     pub solc: String,   //solc编译器
     pub solcgm: String, //solc国密版本编译器
+    ///账户文件的密码来源，pass:明文/env:环境变量名/file:文件路径，为空表示账户文件未加密
+    #[serde(default)]
+    pub accountpassword: String,
+    ///外部签名服务地址(http(s)://,unix:,cmd:)，配置后交易由外部服务签名，不再加载accountpem，见externalsigner
+    #[serde(default)]
+    pub signer: String,
+    ///外部签名服务里的密钥标识
+    #[serde(default)]
+    pub signerkeyid: String,
 }
 unsafe impl Sync for CommonConfig {}
+
+impl CommonConfig {
+    ///按accountpassword读取账户文件的密码，没有配置时返回None
+    pub fn account_password(&self) -> Result<Option<String>, KissError> {
+        if self.accountpassword.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(keystore::read_password_source(
+            self.accountpassword.as_str(),
+        )?))
+    }
+}
 unsafe impl Send for CommonConfig {}
 
 #[derive(Deserialize, Debug, Clone)]
@@ -0,0 +1,348 @@
+/*
+  FISCO BCOS/rust-SDK is a rust client for FISCO BCOS2.0 (https://github.com/FISCO-BCOS/)
+  FISCO BCOS/rust-SDK is free software: you can redistribute it and/or modify it under the
+  terms of the MIT License as published by the Free Software Foundation. This project is
+  distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even
+  the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+  @author: kentzhang
+  @date: 2021-07
+*/
+#![allow(
+    clippy::unreadable_literal,
+    clippy::upper_case_acronyms,
+    dead_code,
+    non_camel_case_types,
+    non_snake_case,
+    non_upper_case_globals,
+    overflowing_literals,
+    unused_variables,
+    unused_assignments
+)]
+
+/*
+区块头的共识签名校验：按区块头字段重新计算区块hash，再用共识节点列表(getSealerList)校验signatureList里的签名，
+有效签名数达到PBFT的法定数(n-f，f=(n-1)/3)才认为区块可信。配合merkleproof，只需要信任共识节点列表。
+
+2.x区块头的hash = hash(rlp([parentHash,stateRoot,transactionsRoot,receiptsRoot,dbHash,logsBloom,
+  number,gasLimit,gasUsed,timestamp,[extraData..],sealer,[sealerList..]]))
+签名是对区块hash的签名，signatureList里每项为{"index":区块头sealerList里的序号,"signature":"0x.."}：
+  非国密：65字节 r,s,v(recovery id 0/1)
+  国密：128字节 r,s,公钥(64字节)，只用r,s和共识节点的公钥校验
+3.x区块头的hash不是对tars编码结果做hash，而是和交易一样按BlockHeaderData的字段顺序拼接后hash
+(和节点的BlockHeaderImpl::calculateHash一致)，int/long字段按大端拼入：
+  version(int) [parentInfo: blockNumber(long),blockHash..] txsRoot receiptsRoot stateRoot number(long)
+  gasUsed(字符串) timestamp(long) sealer(long) [sealerList..] extraData [consensusWeights(long)..]
+签名格式和2.x相同，signatureList里每项为{"sealerIndex":区块头sealerList里的序号,"signature":"0x.."}
+*/
+use ethereum_types::U256;
+use rlp::RlpStream;
+use serde_json::Value as JsonValue;
+use wedpr_l_crypto_signature_secp256k1::WedprSecp256k1Recover;
+use wedpr_l_crypto_signature_sm2::WedprSm2p256v1;
+use wedpr_l_utils::traits::Signature;
+
+use crate::bcossdkutil::accountutil::{EcdsaAccountUtil, GMAccountUtil, IBcosAccountUtil};
+use crate::bcossdkutil::commonhash::{CommonHash, HashType};
+use crate::bcossdkutil::kisserror::{KissErrKind, KissError};
+use crate::bcossdkutil::merkleproof::{decode_hex, json_hex, json_u256};
+
+///一个块的签名校验结果
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BlockSignatureReport {
+    pub block_number: u64,
+    ///按区块头字段重新计算的hash
+    pub hash: String,
+    ///节点返回的hash和重新计算的是否一致
+    pub hash_matched: bool,
+    ///可信的共识节点数和法定签名数
+    pub sealer_count: usize,
+    pub quorum: usize,
+    ///签名有效的共识节点id
+    pub signers: Vec<String>,
+    ///签名无效，或者签名者不在共识节点列表里的项在signatureList里的序号
+    pub invalid: Vec<usize>,
+}
+
+impl BlockSignatureReport {
+    pub fn is_valid(&self) -> bool {
+        self.hash_matched && self.sealer_count > 0 && self.signers.len() >= self.quorum
+    }
+}
+
+///PBFT的法定节点数：n个节点最多容忍f=(n-1)/3个作恶节点，需要n-f个签名
+pub fn pbft_quorum(sealer_count: usize) -> usize {
+    if sealer_count == 0 {
+        return 0;
+    }
+    sealer_count - (sealer_count - 1) / 3
+}
+
+///getSealerList的结果转成节点id列表，2.x是节点id的数组，3.x是{"nodeID":..,"weight":..}的数组
+pub fn sealer_ids(sealers: &JsonValue) -> Vec<String> {
+    sealers
+        .as_array()
+        .cloned()
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|s| match s {
+            JsonValue::String(id) => Some(normalize_nodeid(id)),
+            _ => s["nodeID"].as_str().map(normalize_nodeid),
+        })
+        .collect()
+}
+
+fn normalize_nodeid(id: &str) -> String {
+    id.trim_start_matches("0x").to_lowercase()
+}
+
+///2.x区块头的rlp编码，block可以是getBlockByNumber或getBlockHeaderByNumber的结果
+pub fn header_rlp_v2(block: &JsonValue) -> Result<Vec<u8>, KissError> {
+    let mut stream = RlpStream::new_list(13);
+    for name in [
+        "parentHash",
+        "stateRoot",
+        "transactionsRoot",
+        "receiptsRoot",
+        "dbHash",
+    ]
+    .iter()
+    {
+        stream.append(&json_hex(block, name)?);
+    }
+    stream.append(&json_hex(block, "logsBloom")?);
+    stream.append(&json_u256(block, "number")?);
+    stream.append(&json_u256(block, "gasLimit")?);
+    stream.append(&json_u256(block, "gasUsed")?);
+    stream.append(&json_u256(block, "timestamp")?);
+    let extra = block["extraData"].as_array().cloned().unwrap_or_default();
+    stream.begin_list(extra.len());
+    for e in extra.iter() {
+        stream.append(&decode_hex(e.as_str().unwrap_or_default())?);
+    }
+    stream.append(&json_u256(block, "sealer")?);
+    let sealers = block["sealerList"].as_array().cloned().unwrap_or_default();
+    stream.begin_list(sealers.len());
+    for s in sealers.iter() {
+        stream.append(&decode_hex(s.as_str().unwrap_or_default())?);
+    }
+    Ok(stream.out())
+}
+
+pub fn header_hash_v2(block: &JsonValue, hashtype: &HashType) -> Result<Vec<u8>, KissError> {
+    Ok(CommonHash::hash(&header_rlp_v2(block)?, hashtype))
+}
+
+//3.x的long字段，按大端拼入
+fn json_be_i64(v: &JsonValue, name: &str) -> Result<[u8; 8], KissError> {
+    Ok((json_u256(v, name)?.low_u64() as i64).to_be_bytes())
+}
+
+///3.x区块头按字段拼接后的hash，block是getBlockByNumber的结果(只取区块头字段)
+pub fn header_hash_v3(block: &JsonValue, hashtype: &HashType) -> Result<Vec<u8>, KissError> {
+    let version = json_u256(block, "version")
+        .map(|v| v.low_u32() as i32)
+        .unwrap_or(0);
+    let mut buffer: Vec<u8> = vec![];
+    buffer.extend_from_slice(&version.to_be_bytes());
+    for parent in block["parentInfo"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default()
+        .iter()
+    {
+        buffer.extend_from_slice(&json_be_i64(parent, "blockNumber")?);
+        buffer.extend(json_hex(parent, "blockHash")?);
+    }
+    buffer.extend(json_hex(block, "txsRoot")?);
+    buffer.extend(json_hex(block, "receiptsRoot")?);
+    buffer.extend(json_hex(block, "stateRoot")?);
+    buffer.extend_from_slice(&json_be_i64(block, "number")?);
+    let gas_used = match &block["gasUsed"] {
+        JsonValue::String(s) => s.clone(),
+        JsonValue::Number(n) => n.to_string(),
+        _ => "".to_string(),
+    };
+    buffer.extend_from_slice(gas_used.as_bytes());
+    buffer.extend_from_slice(&json_be_i64(block, "timestamp")?);
+    buffer.extend_from_slice(&json_be_i64(block, "sealer")?);
+    for s in block["sealerList"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default()
+        .iter()
+    {
+        buffer.extend(decode_hex(s.as_str().unwrap_or_default())?);
+    }
+    buffer.extend(json_hex(block, "extraData").unwrap_or_default());
+    for w in block["consensusWeights"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default()
+        .iter()
+    {
+        let weight = w.as_i64().unwrap_or_default();
+        buffer.extend_from_slice(&weight.to_be_bytes());
+    }
+    Ok(CommonHash::hash(&buffer, hashtype))
+}
+
+///用共识节点的公钥(64字节的节点id)校验对hash的签名，国密用SM2，否则用secp256k1
+pub fn verify_sealer_signature(
+    nodeid: &[u8],
+    hash: &[u8],
+    signature: &[u8],
+    hashtype: &HashType,
+) -> bool {
+    if signature.len() < 65 {
+        return false;
+    }
+    match hashtype {
+        HashType::WEDRP_SM3 => {
+            let mut pubkey = vec![4u8];
+            pubkey.extend_from_slice(nodeid);
+            WedprSm2p256v1::default().verify(&pubkey, &hash.to_vec(), &signature[0..64].to_vec())
+        }
+        _ => {
+            //兼容v为27/28的签名
+            let mut sig = signature[0..65].to_vec();
+            if sig[64] >= 27 {
+                sig[64] -= 27;
+            }
+            WedprSecp256k1Recover::default().verify(&nodeid.to_vec(), &hash.to_vec(), &sig)
+        }
+    }
+}
+
+///按共识节点的格式签名区块hash，一般只有节点会用到，sdk里用于测试和模拟节点
+pub fn sign_block_hash(
+    privkey: &[u8],
+    hash: &[u8],
+    hashtype: &HashType,
+) -> Result<Vec<u8>, KissError> {
+    let result = match hashtype {
+        HashType::WEDRP_SM3 => WedprSm2p256v1::default()
+            .sign(&privkey.to_vec(), &hash.to_vec())
+            .map(|mut sig| {
+                sig.extend(nodeid_from_privkey(privkey, hashtype).unwrap_or_default());
+                sig
+            }),
+        _ => WedprSecp256k1Recover::default().sign(&privkey.to_vec(), &hash.to_vec()),
+    };
+    match result {
+        Ok(sig) => Ok(sig),
+        Err(e) => kisserr!(KissErrKind::ESign, "sign block hash error {:?}", e),
+    }
+}
+
+///私钥对应的节点id(64字节公钥，不含04前缀)
+pub fn nodeid_from_privkey(privkey: &[u8], hashtype: &HashType) -> Result<Vec<u8>, KissError> {
+    let account = match hashtype {
+        HashType::WEDRP_SM3 => GMAccountUtil::default().from_privkey_bytes(&privkey.to_vec())?,
+        _ => EcdsaAccountUtil::default().from_privkey_bytes(&privkey.to_vec())?,
+    };
+    let pubkey = account.pubkey;
+    if pubkey.len() == 65 {
+        return Ok(pubkey[1..].to_vec());
+    }
+    Ok(pubkey)
+}
+
+///校验signatureList，header_sealers是区块头里的sealerList(签名的index指向它)，trusted是可信的共识节点列表。
+///同一个节点的多个签名只算一次。返回(有效签名的节点id，无效签名在列表里的序号)
+pub fn verify_signature_list(
+    hash: &[u8],
+    signature_list: &JsonValue,
+    header_sealers: &[String],
+    trusted: &[String],
+    hashtype: &HashType,
+) -> (Vec<String>, Vec<usize>) {
+    let mut signers: Vec<String> = vec![];
+    let mut invalid: Vec<usize> = vec![];
+    let list = signature_list.as_array().cloned().unwrap_or_default();
+    for (i, item) in list.iter().enumerate() {
+        //2.x是"index":"0x1"，3.x是"sealerIndex":1
+        let index = json_u256(item, "index")
+            .or_else(|_| json_u256(item, "sealerIndex"))
+            .ok()
+            .filter(|n| *n < U256::from(header_sealers.len()))
+            .map(|n| n.as_usize());
+        let nodeid = match index {
+            Some(n) => normalize_nodeid(&header_sealers[n]),
+            None => {
+                invalid.push(i);
+                continue;
+            }
+        };
+        let ok = trusted.contains(&nodeid)
+            && match (
+                decode_hex(&nodeid),
+                decode_hex(item["signature"].as_str().unwrap_or_default()),
+            ) {
+                (Ok(id), Ok(sig)) => verify_sealer_signature(&id, hash, &sig, hashtype),
+                _ => false,
+            };
+        if !ok {
+            invalid.push(i);
+        } else if !signers.contains(&nodeid) {
+            signers.push(nodeid);
+        }
+    }
+    (signers, invalid)
+}
+
+///校验2.x的区块头：重新计算hash，并用可信的共识节点列表(getSealerList的结果)校验签名
+pub fn verify_block_signatures_v2(
+    block: &JsonValue,
+    sealers: &JsonValue,
+    hashtype: &HashType,
+) -> Result<BlockSignatureReport, KissError> {
+    let hash = header_hash_v2(block, hashtype)?;
+    signature_report(block, &hash, sealers, hashtype)
+}
+
+///校验3.x的区块头，同verify_block_signatures_v2
+pub fn verify_block_signatures_v3(
+    block: &JsonValue,
+    sealers: &JsonValue,
+    hashtype: &HashType,
+) -> Result<BlockSignatureReport, KissError> {
+    let hash = header_hash_v3(block, hashtype)?;
+    signature_report(block, &hash, sealers, hashtype)
+}
+
+//hash是按区块头字段重新计算的，和节点返回的hash比较，并校验签名
+fn signature_report(
+    block: &JsonValue,
+    hash: &[u8],
+    sealers: &JsonValue,
+    hashtype: &HashType,
+) -> Result<BlockSignatureReport, KissError> {
+    let trusted = sealer_ids(sealers);
+    let header_sealers: Vec<String> = block["sealerList"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default()
+        .iter()
+        .map(|s| s.as_str().unwrap_or_default().to_string())
+        .collect();
+    let (signers, invalid) = verify_signature_list(
+        hash,
+        &block["signatureList"],
+        &header_sealers,
+        &trusted,
+        hashtype,
+    );
+    let hash_matched = match block["hash"].as_str() {
+        Some(h) => decode_hex(h).map(|h| h == hash).unwrap_or(false),
+        None => false,
+    };
+    Ok(BlockSignatureReport {
+        block_number: json_u256(block, "number")?.as_u64(),
+        hash: format!("0x{}", hex::encode(hash)),
+        hash_matched,
+        sealer_count: trusted.len(),
+        quorum: pbft_quorum(trusted.len()),
+        signers,
+        invalid,
+    })
+}
@@ -28,8 +28,11 @@ use wedpr_l_crypto_signature_sm2::WedprSm2p256v1;
 use wedpr_l_libsm::sm2::signature::Signature as WEDPRSM2Signature;
 use wedpr_l_utils::traits::Signature;
 
-use crate::bcossdkutil::accountutil::{BcosAccount, EcdsaAccountUtil, IBcosAccountUtil};
 use crate::bcossdkutil::accountutil::GMAccountUtil;
+use crate::bcossdkutil::accountutil::{
+    address_from_pubkey, BcosAccount, EcdsaAccountUtil, IBcosAccountUtil,
+};
+use crate::bcossdkutil::commonhash::HashType;
 use crate::bcossdkutil::kisserror::{KissErrKind, KissError};
 
 ///secp256原始方式的签名串, * Ecdsa的签名结构和国密略有不同,国密的v直接就是公钥
@@ -76,6 +79,82 @@ impl CommonSignature {
         buffer.append(&mut self.v.clone());
         buffer
     }
+
+    ///国密签名的v是64字节的公钥，ecdsa的v只有1~8个字节
+    pub fn is_sm2(&self) -> bool {
+        self.v.len() == 64
+    }
+
+    //r,s补齐到32字节，ecdsa的v转成recovery id(0/1)，得到wedpr要求的65字节签名
+    fn recoverable_bytes(&self) -> Result<Vec<u8>, KissError> {
+        if self.r.len() > 32 || self.s.len() > 32 || self.v.len() > 8 {
+            return kisserr!(
+                KissErrKind::EFormat,
+                "bad ecdsa signature {}",
+                self.detail()
+            );
+        }
+        let mut buffer = vec![0u8; 32 - self.r.len()];
+        buffer.extend_from_slice(&self.r);
+        buffer.extend(vec![0u8; 32 - self.s.len()]);
+        buffer.extend_from_slice(&self.s);
+        let v = self.v.iter().fold(0u64, |acc, b| (acc << 8) | *b as u64);
+        let v = if v <= 1 {
+            v
+        } else {
+            Secp256Signature::make_stand_v(v)
+        };
+        if v > 1 {
+            return kisserr!(KissErrKind::EFormat, "bad ecdsa signature v {:?}", self.v);
+        }
+        buffer.push(v as u8);
+        Ok(buffer)
+    }
+
+    ///从签名恢复出签名者的公钥(64字节，不含04前缀)，hash为被签名的hash。
+    ///ecdsa从r,s,v恢复；国密签名里自带公钥，签名校验通过才返回这个公钥
+    pub fn recover_pubkey(&self, hash: &[u8]) -> Result<Vec<u8>, KissError> {
+        if self.is_sm2() {
+            if self.verify(hash, &self.v) {
+                return Ok(self.v.clone());
+            }
+            return kisserr!(KissErrKind::ESign, "sm2 signature verify fail");
+        }
+        let sig = self.recoverable_bytes()?;
+        match WedprSecp256k1Recover::default().recover_public_key(&hash.to_vec(), &sig) {
+            Ok(pubkey) if pubkey.len() == 65 => Ok(pubkey[1..].to_vec()),
+            Ok(pubkey) => Ok(pubkey),
+            Err(e) => kisserr!(KissErrKind::ESign, "recover public key error {:?}", e),
+        }
+    }
+
+    ///用公钥(64或65字节)校验签名
+    pub fn verify(&self, hash: &[u8], pubkey: &[u8]) -> bool {
+        let pubkey = if pubkey.len() == 65 {
+            &pubkey[1..]
+        } else {
+            pubkey
+        };
+        if self.is_sm2() {
+            let mut sig = self.r.clone();
+            sig.extend_from_slice(&self.s);
+            let mut fullkey = vec![4u8];
+            fullkey.extend_from_slice(pubkey);
+            return WedprSm2p256v1::default().verify(&fullkey, &hash.to_vec(), &sig);
+        }
+        match self.recoverable_bytes() {
+            Ok(sig) => {
+                WedprSecp256k1Recover::default().verify(&pubkey.to_vec(), &hash.to_vec(), &sig)
+            }
+            Err(_) => false,
+        }
+    }
+
+    ///恢复签名者的地址，hashtype为链上计算地址用的hash算法(ecdsa为keccak，国密为sm3)
+    pub fn recover_address(&self, hash: &[u8], hashtype: &HashType) -> Result<Vec<u8>, KissError> {
+        let pubkey = self.recover_pubkey(hash)?;
+        Ok(address_from_pubkey(&pubkey, hashtype))
+    }
 }
 
 ///一些secp256的特有方法
@@ -39,10 +39,13 @@ use ethabi::{
 use hex::ToHex;
 use hex_literal::hex;
 use keccak_hash::keccak;
-use serde_json::Value as JsonValue;
+use serde_json::{json, Value as JsonValue};
 
+use crate::bcossdkutil::abi_json;
 use crate::bcossdkutil::abi_parser::ABIParser;
-use crate::bcossdkutil::abi_tokenizer::{ABILenientTokenizer, ABIStrictTokenizer, ABITokenizer};
+use crate::bcossdkutil::abi_tokenizer::{
+    ABIJsonTokenizer, ABILenientTokenizer, ABIStrictTokenizer, ABITokenizer,
+};
 use crate::bcossdkutil::commonhash::{CommonHash, HashType};
 use crate::bcossdkutil::event_utils;
 use crate::bcossdkutil::event_utils::EventABIUtils;
@@ -118,12 +121,85 @@ impl ContractABI {
             event_abi_utils: EventABIUtils::new(&hashtype),
             abiparser: abiparser,
         };
+        contract.fix_function_params();
         contract.map_event_to_hash();
         contract.map_function_to_selector();
 
         Ok(contract)
     }
 
+    ///从abi文本创建，abi_file为空
+    pub fn new_from_text(abitext: &str, hashtype: &HashType) -> Result<ContractABI, KissError> {
+        let abiparser = ABIParser::from_text(abitext)?;
+        let contract_obj = match Contract::load(abitext.as_bytes()) {
+            Ok(c) => c,
+            Err(e) => {
+                return kisserr!(KissErrKind::EFormat, "parse abi text error: {:?}", e);
+            }
+        };
+        let mut contract = ContractABI {
+            abi_file: "".to_string(),
+            contract: contract_obj,
+            event_name_map: HashMap::new(),
+            event_hash_map: HashMap::new(),
+            func_selector_map: HashMap::new(),
+            hashtype: hashtype.clone(),
+            event_abi_utils: EventABIUtils::new(hashtype),
+            abiparser,
+        };
+        contract.fix_function_params();
+        contract.map_event_to_hash();
+        contract.map_function_to_selector();
+        Ok(contract)
+    }
+
+    ///ethabi-12解析tuple里嵌套的tuple[]时会丢掉成员的类型(解析成tuple[]而不是(string,uint256)[])，
+    /// 方法签名和编码都会出错，这里对tuple类型的参数按abi原文用ABIParser重新解析一遍
+    fn fix_function_params(&mut self) {
+        let items = match self.abiparser.abiobj.as_array() {
+            Some(a) => a,
+            None => return,
+        };
+        //同名的重载方法，ethabi按abi里出现的顺序保存
+        let mut counter: HashMap<String, usize> = HashMap::new();
+        for item in items {
+            if item["type"] != "function" {
+                continue;
+            }
+            let name = match item["name"].as_str() {
+                Some(n) => n,
+                None => continue,
+            };
+            let index = counter.entry(name.to_string()).or_insert(0);
+            let function = self
+                .contract
+                .functions
+                .get_mut(name)
+                .and_then(|f| f.get_mut(*index));
+            *index += 1;
+            if let Some(function) = function {
+                ContractABI::fix_tuple_params(&mut function.inputs, &item["inputs"]);
+                ContractABI::fix_tuple_params(&mut function.outputs, &item["outputs"]);
+            }
+        }
+    }
+
+    fn fix_tuple_params(params: &mut [Param], abiparams: &JsonValue) {
+        let abiparams = match abiparams.as_array() {
+            Some(a) if a.len() == params.len() => a,
+            _ => return,
+        };
+        for (param, abiparam) in params.iter_mut().zip(abiparams.iter()) {
+            let is_tuple = matches!(abiparam["type"].as_str(), Some(t) if t.starts_with("tuple"));
+            if !is_tuple {
+                continue;
+            }
+            if let Ok(kind) = ABIParser::parse_param_type(abiparam, "") {
+                param.kind = kind;
+            }
+        }
+    }
+
     ///这个方法算hash时，会带上返回值类型一起算，如 set(String):(int256)
     pub fn function_signature_to_4byte_selector(func: &Function) -> Option<Vec<u8>> {
         let signature = func.signature().replace(" ", "");
@@ -332,6 +408,8 @@ impl ContractABI {
     }
 
     //传入的是字符串，转换成token
+    //数组和tuple类型的参数如果是合法的json数组或对象，按json解析(见ABIJsonTokenizer)，
+    //否则还是按原来的字符串格式解析，如 [(alice,23),(bob,45)]
     pub fn convert_function_input_str_to_token(
         &self,
         name_or_signature: &str,
@@ -342,26 +420,144 @@ impl ContractABI {
         // let sig = ContractABI::function_signature_to_4byte_selector(&function).unwrap();
         //let shortsig  = ContractABI::function_short_signature(&function);
         //println!("encode_function_input ,sig is {:?} : {:?}",hex::encode(shortsig),function);
+        let inputabi = self.function_input_abi(&function);
+        let mut tokens: Vec<Token> = vec![];
+        for (i, (param, value)) in function.inputs.iter().zip(values.iter()).enumerate() {
+            //println!("encode input param:{:?},{}",param,value);
+            let tokenres = match ContractABI::parse_json_param(&param.kind, value.as_str()) {
+                Some(json) => ABIJsonTokenizer::tokenize_with_components(
+                    &param.kind,
+                    inputabi[i].as_ref().map(|p| &p["components"]),
+                    &json,
+                )
+                .map_err(From::from),
+                None => self
+                    .collect_tokens(&[(param.kind.clone(), value.as_str())], lenient)
+                    .map(|mut t| t.remove(0)),
+            };
+            match tokenres {
+                Ok(t) => tokens.push(t),
+                Err(e) => {
+                    return kisserr!(
+                        KissErrKind::EFormat,
+                        "make tokens from params error: {:?}",
+                        e
+                    );
+                }
+            }
+        }
+        Ok(tokens)
+    }
+
+    ///数组和tuple类型的参数，值是json数组或对象时返回解析后的json
+    fn parse_json_param(kind: &ParamType, value: &str) -> Option<JsonValue> {
+        match kind {
+            ParamType::Array(_) | ParamType::FixedArray(_, _) | ParamType::Tuple(_) => {}
+            _ => return None,
+        }
+        match serde_json::from_str::<JsonValue>(value.trim()) {
+            Ok(v) if v.is_array() || v.is_object() => Some(v),
+            _ => None,
+        }
+    }
+
+    ///在abi原文里找到function各输入参数的定义，带有components(tuple成员的名字)，
+    /// ethabi解析后的ParamType里没有成员名。找不到时对应位置为None
+    pub fn function_input_abi(&self, function: &Function) -> Vec<Option<JsonValue>> {
+        match self.function_abi(function) {
+            Some(item) => item["inputs"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|i| Some(i.clone()))
+                .collect(),
+            None => vec![None; function.inputs.len()],
+        }
+    }
+
+    ///function在abi原文里的定义
+    pub fn function_abi(&self, function: &Function) -> Option<&JsonValue> {
+        let kinds: Vec<ParamType> = function.inputs.iter().map(|p| p.kind.clone()).collect();
+        self.find_abi_item("function", function.name.as_str(), &kinds)
+    }
+
+    ///event在abi原文里的定义
+    pub fn event_abi(&self, event: &Event) -> Option<&JsonValue> {
+        let kinds: Vec<ParamType> = event.inputs.iter().map(|p| p.kind.clone()).collect();
+        self.find_abi_item("event", event.name.as_str(), &kinds)
+    }
+
+    //按类型、名字和inputs的参数类型(区分重载)在abi原文里查找
+    fn find_abi_item(&self, abitype: &str, name: &str, kinds: &[ParamType]) -> Option<&JsonValue> {
+        let items = self.abiparser.abiobj.as_array()?;
+        items.iter().find(|item| {
+            if item["type"] != abitype || item["name"] != name {
+                return false;
+            }
+            match item["inputs"].as_array() {
+                Some(inputs) if inputs.len() == kinds.len() => {
+                    inputs.iter().zip(kinds.iter()).all(|(i, kind)| {
+                        match ABIParser::parse_param_type(i, "") {
+                            Ok(k) => k == *kind,
+                            Err(_) => false,
+                        }
+                    })
+                }
+                _ => false,
+            }
+        })
+    }
 
-        let params: Vec<_> = function
+    ///传入json数组(按参数顺序)或json对象(以参数名为key)作为全部参数，转换成token
+    /// 如 addbyname 的参数可以是 ["alice",{"name":"alice","age":25}]
+    /// 或 {"name":"alice","_user":["alice",25]}
+    pub fn convert_function_input_json_to_token(
+        &self,
+        name_or_signature: &str,
+        values: &JsonValue,
+    ) -> anyhow::Result<Vec<Token>, KissError> {
+        let function = self.find_function_unwrap(name_or_signature)?;
+        //把参数列表当做一个tuple来解析
+        let kinds: Vec<Box<ParamType>> = function
             .inputs
             .iter()
-            .map(|param| param.kind.clone())
-            .zip(values.iter().map(|v| v as &str))
+            .map(|p| Box::new(p.kind.clone()))
             .collect();
-        //println!("encode input params:{:?}",params);
-        let tokensres = self.collect_tokens(&params, lenient);
-        let tokens = match tokensres {
-            Ok(t) => t,
-            Err(e) => {
-                return kisserr!(
-                    KissErrKind::EFormat,
-                    "make tokens from params error: {:?}",
-                    e
-                );
-            }
-        };
-        Ok(tokens)
+        let inputabi: Vec<JsonValue> = self
+            .function_input_abi(&function)
+            .into_iter()
+            .zip(function.inputs.iter())
+            .map(|(abi, p)| abi.unwrap_or(serde_json::json!({ "name": p.name })))
+            .collect();
+        let tokenres = ABIJsonTokenizer::tokenize_with_components(
+            &ParamType::Tuple(kinds),
+            Some(&JsonValue::Array(inputabi)),
+            values,
+        );
+        match tokenres {
+            Ok(Token::Tuple(tokens)) => Ok(tokens),
+            Ok(t) => kisserr!(KissErrKind::EFormat, "unexpected token {:?}", t),
+            Err(e) => kisserr!(
+                KissErrKind::EFormat,
+                "make tokens from json params error: {:?}",
+                e
+            ),
+        }
+    }
+
+    pub fn encode_function_input_json_to_abi(
+        &self,
+        name_or_signature: &str,
+        values: &JsonValue,
+    ) -> anyhow::Result<String, KissError> {
+        let tokens = self.convert_function_input_json_to_token(name_or_signature, values)?;
+        let function = self.find_function_unwrap(name_or_signature)?;
+        let res = ContractABI::encode_function_input_to_abi_by_tokens(
+            &function,
+            &tokens,
+            &self.hashtype,
+        )?;
+        Ok(hex::encode(&res))
     }
 
     pub fn collect_tokens(
@@ -436,6 +632,54 @@ impl ContractABI {
         }
     }
 
+    ///解析合约方法的返回并转成json，参数名取自abi，格式见abi_json
+    pub fn decode_output_to_json(
+        &self,
+        name_or_signature: &str,
+        data: &str,
+    ) -> Result<JsonValue, KissError> {
+        let function = self.find_function_unwrap(name_or_signature)?;
+        self.decode_function_output_to_json(&function, data)
+    }
+
+    pub fn decode_function_output_to_json(
+        &self,
+        function: &Function,
+        data: &str,
+    ) -> Result<JsonValue, KissError> {
+        let tokens = match self.decode_function_output(function, data) {
+            Ok(t) => t,
+            Err(e) => {
+                return kisserr!(
+                    KissErrKind::EFormat,
+                    "decode {} output error {:?}",
+                    function.name,
+                    e
+                )
+            }
+        };
+        let outputs = self.function_abi(function).map(|f| &f["outputs"]);
+        Ok(abi_json::params_to_json(&tokens, outputs))
+    }
+
+    ///解码交易的input并转成json，如
+    /// {"function":"set","signature":"set(string)","params":{"n":"hello"}}
+    pub fn decode_input_to_json(&self, txinput: &str) -> Result<JsonValue, KissError> {
+        let decoded = self.decode_input_for_tx(txinput)?;
+        let inputs = self.function_abi(&decoded.func).map(|f| &f["inputs"]);
+        let types: Vec<String> = decoded
+            .func
+            .inputs
+            .iter()
+            .map(|p| Writer::write(&p.kind))
+            .collect();
+        Ok(json!({
+            "function": decoded.func.name,
+            "signature": format!("{}({})", decoded.func.name, types.join(",")),
+            "params": abi_json::params_to_json(&decoded.input, inputs),
+        }))
+    }
+
     pub fn convert_json_to_rawlog(&self, logitem: &JsonValue) -> Option<RawLog> {
         //println!("log  {:?}", logitem);
         let logdata = &logitem["data"];
@@ -502,6 +746,41 @@ impl ContractABI {
         Ok(loglistresult)
     }
 
+    ///解析回执里的logs并转成json数组，每个log如
+    /// {"address":"0x..","event":"onset","signature":"onset(string)","params":{"newname":"alice"}}
+    pub fn parse_receipt_logs_to_json(&self, log_list: &JsonValue) -> Result<JsonValue, KissError> {
+        let mut result: Vec<JsonValue> = vec![];
+        let items = match log_list.as_array() {
+            Some(a) => a,
+            None => return Ok(JsonValue::Array(result)),
+        };
+        for item in items {
+            let rawlog = match self.convert_json_to_rawlog(item) {
+                Some(r) if !r.topics.is_empty() => r,
+                _ => return kisserr!(KissErrKind::EFormat, "invalid log {:?}", item),
+            };
+            let event = match self.find_event_by_hash(rawlog.topics[0]) {
+                Some(e) => e,
+                None => return kisserr!(KissErrKind::Error, "event not found for {:?}", item),
+            };
+            let log = self.event_abi_utils.parse_log(event, rawlog)?;
+            let tokens: Vec<Token> = log.params.into_iter().map(|p| p.value).collect();
+            let inputs = self.event_abi(event).map(|e| &e["inputs"]);
+            let types: Vec<String> = event
+                .inputs
+                .iter()
+                .map(|p| Writer::write(&p.kind))
+                .collect();
+            result.push(json!({
+                "address": item["address"],
+                "event": event.name,
+                "signature": format!("{}({})", event.name, types.join(",")),
+                "params": abi_json::params_to_json(&tokens, inputs),
+            }));
+        }
+        Ok(JsonValue::Array(result))
+    }
+
     ///将数组（类型必须是string，如果是其他类型，先转换成string数组），拼接成类似["aa","bb","cc"]这样的格式
     ///合约接口中，如输入的是类似string[] data, uint256[] values这样的参数，则接受类似的数组
     pub fn array_to_param(x: &Vec<String>) -> String {
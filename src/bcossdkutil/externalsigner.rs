@@ -0,0 +1,535 @@
+/*
+  FISCO BCOS/rust-SDK is a rust client for FISCO BCOS2.0 (https://github.com/FISCO-BCOS/)
+  FISCO BCOS/rust-SDK is free software: you can redistribute it and/or modify it under the
+  terms of the MIT License as published by the Free Software Foundation. This project is
+  distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even
+  the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+  @author: kentzhang
+  @date: 2021-07
+*/
+#![allow(
+    clippy::unreadable_literal,
+    clippy::upper_case_acronyms,
+    dead_code,
+    non_camel_case_types,
+    non_snake_case,
+    non_upper_case_globals,
+    overflowing_literals,
+    unused_variables,
+    unused_assignments
+)]
+
+/*
+外部签名：私钥保存在HSM、密钥管理服务或者单独的签名进程里，sdk只把待签名的hash发过去。
+签名服务的协议是一问一答的json：
+  取公钥 {"op":"pubkey","keyid":"..","crypto":"ECDSA"} -> {"pubkey":"0x.."}  (64字节，或带04前缀的65字节)
+  签名   {"op":"sign","keyid":"..","crypto":"GM","hash":"0x.."} -> {"signature":"0x.."}
+  出错时返回 {"error":"..."}
+ECDSA的签名为65字节 r,s,v(v为0/1或27/28)，国密的签名为64字节 r,s(也可以在后面带上64字节公钥)。
+签名和hash的算法与sdk内置的签名器一致，签名返回后会用公钥校验，防止签名服务用错了密钥。
+服务地址的写法：
+  http://127.0.0.1:8600/sign  每次POST一个请求
+  unix:/var/run/signer.sock   每个连接发一行请求，读一行应答
+  cmd:/usr/local/bin/hsm-sign --slot 1   每次启动一个进程，请求写到stdin，从stdout读应答，参数按空格分隔
+LocalSignService是用内存里的私钥实现的签名服务，用于测试和演示。
+*/
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use serde_json::{json, Value as JsonValue};
+
+use crate::bcossdkutil::accountutil::{account_from_privkey, address_from_pubkey, BcosAccount};
+use crate::bcossdkutil::bcosclientconfig::{BcosCryptoKind, CommonConfig};
+use crate::bcossdkutil::commonhash::CommonHash;
+use crate::bcossdkutil::commonsigner::{
+    CommonSignature, CommonSignerWeDPR_SM2, CommonSignerWeDPR_Secp256, ICommonSigner,
+    Secp256Signature,
+};
+use crate::bcossdkutil::kisserror::{KissErrKind, KissError};
+
+///签名服务的地址
+#[derive(Debug, Clone, PartialEq)]
+pub enum SignerEndpoint {
+    Http(String),
+    Unix(String),
+    Command(Vec<String>),
+}
+
+impl SignerEndpoint {
+    pub fn parse(endpoint: &str) -> Result<Self, KissError> {
+        let endpoint = endpoint.trim();
+        if endpoint.starts_with("http://") || endpoint.starts_with("https://") {
+            return Ok(SignerEndpoint::Http(endpoint.to_string()));
+        }
+        if let Some(path) = endpoint.strip_prefix("unix:") {
+            return Ok(SignerEndpoint::Unix(path.to_string()));
+        }
+        if let Some(cmd) = endpoint.strip_prefix("cmd:") {
+            let args: Vec<String> = cmd.split_whitespace().map(|s| s.to_string()).collect();
+            if !args.is_empty() {
+                return Ok(SignerEndpoint::Command(args));
+            }
+        }
+        kisserr!(
+            KissErrKind::EArgument,
+            "unknown signer endpoint {:?}, use http(s)://,unix:,cmd:",
+            endpoint
+        )
+    }
+
+    ///发送一个请求，返回应答的json，应答里有error时转成错误
+    pub fn request(&self, req: &JsonValue) -> Result<JsonValue, KissError> {
+        let text = match self {
+            SignerEndpoint::Http(url) => http_request(url, req)?,
+            SignerEndpoint::Unix(path) => unix_request(path, req)?,
+            SignerEndpoint::Command(args) => command_request(args, req)?,
+        };
+        let resp: JsonValue = match serde_json::from_str(text.trim()) {
+            Ok(v) => v,
+            Err(e) => {
+                return kisserr!(
+                    KissErrKind::EFormat,
+                    "signer response is not json {:?},{}",
+                    e,
+                    text
+                )
+            }
+        };
+        if let Some(err) = resp["error"].as_str() {
+            return kisserr!(KissErrKind::ESign, "signer error: {}", err);
+        }
+        Ok(resp)
+    }
+}
+
+fn http_request(url: &str, req: &JsonValue) -> Result<String, KissError> {
+    let client = reqwest::blocking::Client::new();
+    let response = match client
+        .post(url)
+        .header("Content-Type", "application/json")
+        .timeout(Duration::from_secs(30))
+        .body(req.to_string())
+        .send()
+    {
+        Ok(r) => r,
+        Err(e) => return kisserr!(KissErrKind::ENetwork, "post signer {} error {:?}", url, e),
+    };
+    if !response.status().is_success() {
+        return kisserr!(
+            KissErrKind::ENetwork,
+            "signer {} response status {:?}",
+            url,
+            response.status()
+        );
+    }
+    match response.text() {
+        Ok(text) => Ok(text),
+        Err(e) => kisserr!(KissErrKind::ENetwork, "read signer {} error {:?}", url, e),
+    }
+}
+
+#[cfg(unix)]
+fn unix_request(path: &str, req: &JsonValue) -> Result<String, KissError> {
+    let mut stream = match UnixStream::connect(path) {
+        Ok(s) => s,
+        Err(e) => {
+            return kisserr!(
+                KissErrKind::ENetwork,
+                "connect signer {} error {:?}",
+                path,
+                e
+            )
+        }
+    };
+    let _ = stream.set_read_timeout(Some(Duration::from_secs(30)));
+    if let Err(e) = stream.write_all(format!("{}\n", req).as_bytes()) {
+        return kisserr!(KissErrKind::ENetwork, "write signer {} error {:?}", path, e);
+    }
+    let mut line = String::new();
+    match BufReader::new(stream).read_line(&mut line) {
+        Ok(_) => Ok(line),
+        Err(e) => kisserr!(KissErrKind::ENetwork, "read signer {} error {:?}", path, e),
+    }
+}
+
+#[cfg(not(unix))]
+fn unix_request(path: &str, req: &JsonValue) -> Result<String, KissError> {
+    kisserr!(
+        KissErrKind::EArgument,
+        "unix socket signer is not supported on this platform"
+    )
+}
+
+fn command_request(args: &[String], req: &JsonValue) -> Result<String, KissError> {
+    let mut child = match Command::new(&args[0])
+        .args(&args[1..])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+    {
+        Ok(c) => c,
+        Err(e) => return kisserr!(KissErrKind::Error, "start signer {:?} error {:?}", args, e),
+    };
+    if let Some(mut stdin) = child.stdin.take() {
+        if let Err(e) = stdin.write_all(format!("{}\n", req).as_bytes()) {
+            return kisserr!(KissErrKind::Error, "write signer {:?} error {:?}", args, e);
+        }
+    }
+    let output = match child.wait_with_output() {
+        Ok(o) => o,
+        Err(e) => return kisserr!(KissErrKind::Error, "wait signer {:?} error {:?}", args, e),
+    };
+    if !output.status.success() {
+        return kisserr!(
+            KissErrKind::ESign,
+            "signer {:?} exit with {:?}",
+            args,
+            output.status
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+fn crypto_name(cryptokind: &BcosCryptoKind) -> &'static str {
+    match cryptokind {
+        BcosCryptoKind::ECDSA => "ECDSA",
+        BcosCryptoKind::GM => "GM",
+    }
+}
+
+fn json_bytes(v: &JsonValue, name: &str) -> Result<Vec<u8>, KissError> {
+    match v[name]
+        .as_str()
+        .map(|s| hex::decode(s.trim_start_matches("0x")))
+    {
+        Some(Ok(b)) => Ok(b),
+        _ => kisserr!(
+            KissErrKind::EFormat,
+            "signer response field {} error: {}",
+            name,
+            v
+        ),
+    }
+}
+
+///把签名交给外部服务的签名器，account里只有公钥和地址，没有私钥
+#[derive(Debug, Clone)]
+pub struct ExternalSigner {
+    pub endpoint: SignerEndpoint,
+    pub keyid: String,
+    pub cryptokind: BcosCryptoKind,
+    pub account: BcosAccount,
+}
+
+impl ExternalSigner {
+    ///连接签名服务并取得公钥
+    pub fn connect(
+        endpoint: &str,
+        keyid: &str,
+        cryptokind: &BcosCryptoKind,
+    ) -> Result<Self, KissError> {
+        let endpoint = SignerEndpoint::parse(endpoint)?;
+        let resp = endpoint.request(&json!({
+            "op": "pubkey",
+            "keyid": keyid,
+            "crypto": crypto_name(cryptokind),
+        }))?;
+        let mut pubkey = json_bytes(&resp, "pubkey")?;
+        if pubkey.len() == 65 {
+            pubkey.remove(0);
+        }
+        if pubkey.len() != 64 {
+            return kisserr!(
+                KissErrKind::EFormat,
+                "signer pubkey length {} error",
+                pubkey.len()
+            );
+        }
+        let address = address_from_pubkey(&pubkey, &CommonHash::crypto_to_hashtype(cryptokind));
+        //和本地账户的格式保持一致：ecdsa公钥带04前缀，国密不带
+        if *cryptokind == BcosCryptoKind::ECDSA {
+            pubkey.insert(0, 4);
+        }
+        Ok(ExternalSigner {
+            endpoint,
+            keyid: keyid.to_string(),
+            cryptokind: cryptokind.clone(),
+            account: BcosAccount {
+                privkey: vec![],
+                pubkey,
+                address,
+            },
+        })
+    }
+
+    ///配置了common.signer时创建外部签名器，否则返回None
+    pub fn from_config(config: &CommonConfig) -> Result<Option<Self>, KissError> {
+        if config.signer.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(ExternalSigner::connect(
+            config.signer.as_str(),
+            config.signerkeyid.as_str(),
+            &config.crypto,
+        )?))
+    }
+
+    fn rawpubkey(&self) -> &[u8] {
+        let pubkey = &self.account.pubkey;
+        if pubkey.len() == 65 {
+            &pubkey[1..]
+        } else {
+            pubkey
+        }
+    }
+}
+
+impl ICommonSigner for ExternalSigner {
+    fn sign(&self, data: Vec<u8>) -> Result<CommonSignature, KissError> {
+        let resp = self.endpoint.request(&json!({
+            "op": "sign",
+            "keyid": self.keyid,
+            "crypto": crypto_name(&self.cryptokind),
+            "hash": format!("0x{}", hex::encode(&data)),
+        }))?;
+        let sig = json_bytes(&resp, "signature")?;
+        let commonsig = match self.cryptokind {
+            BcosCryptoKind::ECDSA if sig.len() == 65 => {
+                let v = if sig[64] >= 27 { sig[64] - 27 } else { sig[64] };
+                //和CommonSignerWeDPR_Secp256一样，v调整成27/28
+                let v = Secp256Signature::adjust_v_value(v as u64);
+                CommonSignature::from_rsv(&sig[0..32], &sig[32..64], &v.to_be_bytes())
+            }
+            BcosCryptoKind::GM if sig.len() == 64 || sig.len() == 128 => {
+                CommonSignature::from_rsv(&sig[0..32], &sig[32..64], self.rawpubkey())
+            }
+            _ => {
+                return kisserr!(
+                    KissErrKind::ESign,
+                    "signer signature length {} error",
+                    sig.len()
+                )
+            }
+        };
+        if !commonsig.verify(&data, self.rawpubkey()) {
+            return kisserr!(
+                KissErrKind::ESign,
+                "signature from signer does not match key {}",
+                self.keyid
+            );
+        }
+        Ok(commonsig)
+    }
+}
+
+//---------------------本地的签名服务，用于测试---------------------
+
+///用内存里的私钥实现签名服务协议，keyid为空时接受任意keyid
+pub struct LocalSignService {
+    ///给ExternalSigner::connect用的地址
+    pub endpoint: String,
+    pub account: BcosAccount,
+    stop: Arc<AtomicBool>,
+}
+
+struct LocalKey {
+    keyid: String,
+    cryptokind: BcosCryptoKind,
+    account: BcosAccount,
+}
+
+impl LocalKey {
+    fn handle(&self, req: &JsonValue) -> JsonValue {
+        if !self.keyid.is_empty() && req["keyid"].as_str() != Some(self.keyid.as_str()) {
+            return json!({"error": format!("unknown keyid {}", req["keyid"])});
+        }
+        if req["crypto"].as_str() != Some(crypto_name(&self.cryptokind)) {
+            return json!({"error": format!("unsupported crypto {}", req["crypto"])});
+        }
+        match req["op"].as_str().unwrap_or_default() {
+            "pubkey" => json!({"pubkey": format!("0x{}", hex::encode(&self.account.pubkey))}),
+            "sign" => match json_bytes(req, "hash").and_then(|h| self.sign(h)) {
+                Ok(sig) => json!({"signature": format!("0x{}", hex::encode(&sig))}),
+                Err(e) => json!({"error": format!("{:?}", e)}),
+            },
+            op => json!({"error": format!("unknown op {}", op)}),
+        }
+    }
+
+    fn sign(&self, hash: Vec<u8>) -> Result<Vec<u8>, KissError> {
+        let sig = match self.cryptokind {
+            BcosCryptoKind::ECDSA => {
+                let signer = CommonSignerWeDPR_Secp256 {
+                    account: self.account.clone(),
+                    ..Default::default()
+                };
+                let mut sig = signer.sign(hash)?;
+                //按协议返回1字节的v
+                sig.v = vec![*sig.v.last().unwrap_or(&0)];
+                sig
+            }
+            BcosCryptoKind::GM => {
+                let signer = CommonSignerWeDPR_SM2 {
+                    account: self.account.clone(),
+                    ..Default::default()
+                };
+                let mut sig = signer.sign(hash)?;
+                sig.v = vec![];
+                sig
+            }
+        };
+        Ok(sig.to_vec())
+    }
+
+    fn handle_line(&self, line: &str) -> JsonValue {
+        match serde_json::from_str::<JsonValue>(line.trim()) {
+            Ok(req) => self.handle(&req),
+            Err(e) => json!({"error": format!("bad request {:?}", e)}),
+        }
+    }
+}
+
+impl LocalSignService {
+    fn new_key(
+        privkey: &[u8],
+        keyid: &str,
+        cryptokind: &BcosCryptoKind,
+    ) -> Result<LocalKey, KissError> {
+        Ok(LocalKey {
+            keyid: keyid.to_string(),
+            cryptokind: cryptokind.clone(),
+            account: account_from_privkey(&privkey.to_vec(), cryptokind.clone())?,
+        })
+    }
+
+    ///在unix socket上提供签名服务
+    #[cfg(unix)]
+    pub fn start_unix(
+        path: &str,
+        privkey: &[u8],
+        keyid: &str,
+        cryptokind: &BcosCryptoKind,
+    ) -> Result<Self, KissError> {
+        let key = Arc::new(LocalSignService::new_key(privkey, keyid, cryptokind)?);
+        let _ = std::fs::remove_file(path);
+        let listener = match UnixListener::bind(path) {
+            Ok(l) => l,
+            Err(e) => return kisserr!(KissErrKind::ENetwork, "bind {} error {:?}", path, e),
+        };
+        let _ = listener.set_nonblocking(true);
+        let stop = Arc::new(AtomicBool::new(false));
+        let account = key.account.clone();
+        let thread_stop = stop.clone();
+        thread::spawn(move || {
+            while !thread_stop.load(Ordering::SeqCst) {
+                let mut stream = match listener.accept() {
+                    Ok((s, _)) => s,
+                    Err(_) => {
+                        thread::sleep(Duration::from_millis(10));
+                        continue;
+                    }
+                };
+                let _ = stream.set_nonblocking(false);
+                let mut line = String::new();
+                if let Ok(reader) = stream.try_clone() {
+                    let _ = BufReader::new(reader).read_line(&mut line);
+                }
+                let _ = stream.write_all(format!("{}\n", key.handle_line(&line)).as_bytes());
+            }
+        });
+        Ok(LocalSignService {
+            endpoint: format!("unix:{}", path),
+            account,
+            stop,
+        })
+    }
+
+    ///在127.0.0.1的随机端口上提供http签名服务
+    pub fn start_http(
+        privkey: &[u8],
+        keyid: &str,
+        cryptokind: &BcosCryptoKind,
+    ) -> Result<Self, KissError> {
+        let key = Arc::new(LocalSignService::new_key(privkey, keyid, cryptokind)?);
+        let listener = match TcpListener::bind("127.0.0.1:0") {
+            Ok(l) => l,
+            Err(e) => return kisserr!(KissErrKind::ENetwork, "bind http signer error {:?}", e),
+        };
+        let port = listener.local_addr().unwrap().port();
+        let _ = listener.set_nonblocking(true);
+        let stop = Arc::new(AtomicBool::new(false));
+        let account = key.account.clone();
+        let thread_stop = stop.clone();
+        thread::spawn(move || {
+            while !thread_stop.load(Ordering::SeqCst) {
+                let mut stream = match listener.accept() {
+                    Ok((s, _)) => s,
+                    Err(_) => {
+                        thread::sleep(Duration::from_millis(10));
+                        continue;
+                    }
+                };
+                let _ = stream.set_nonblocking(false);
+                let body = match stream.try_clone() {
+                    Ok(reader) => read_http_body(reader),
+                    Err(_) => continue,
+                };
+                let resp = key.handle_line(&body).to_string();
+                let _ = stream.write_all(
+                    format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        resp.len(),
+                        resp
+                    )
+                    .as_bytes(),
+                );
+            }
+        });
+        Ok(LocalSignService {
+            endpoint: format!("http://127.0.0.1:{}/sign", port),
+            account,
+            stop,
+        })
+    }
+
+    pub fn stop(&self) {
+        self.stop.store(true, Ordering::SeqCst);
+    }
+}
+
+impl Drop for LocalSignService {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+//只处理带Content-Length的POST请求，够测试用
+fn read_http_body<R: Read>(reader: R) -> String {
+    let mut reader = BufReader::new(reader);
+    let mut length = 0usize;
+    loop {
+        let mut line = String::new();
+        match reader.read_line(&mut line) {
+            Ok(0) | Err(_) => return String::new(),
+            Ok(_) => {}
+        }
+        let line = line.trim();
+        if line.is_empty() {
+            break;
+        }
+        let lower = line.to_lowercase();
+        if let Some(v) = lower.strip_prefix("content-length:") {
+            length = v.trim().parse().unwrap_or(0);
+        }
+    }
+    let mut body = vec![0u8; length];
+    let _ = reader.read_exact(&mut body);
+    String::from_utf8_lossy(&body).to_string()
+}
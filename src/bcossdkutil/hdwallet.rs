@@ -0,0 +1,364 @@
+/*
+  FISCO BCOS/rust-SDK is a rust client for FISCO BCOS2.0 (https://github.com/FISCO-BCOS/)
+  FISCO BCOS/rust-SDK is free software: you can redistribute it and/or modify it under the
+  terms of the MIT License as published by the Free Software Foundation. This project is
+  distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even
+  the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+  @author: kentzhang
+  @date: 2021-07
+*/
+#![allow(
+    clippy::unreadable_literal,
+    clippy::upper_case_acronyms,
+    dead_code,
+    non_camel_case_types,
+    non_snake_case,
+    non_upper_case_globals,
+    overflowing_literals,
+    unused_variables,
+    unused_assignments
+)]
+
+/*
+助记词和分层确定性(HD)钱包，用一份备份的助记词管理多个账户
+BIP39：熵 + sha256校验位 -> 英文助记词，助记词 + 口令 -> PBKDF2-HMAC-SHA512(2048次) -> 64字节种子
+BIP32/BIP44：种子 -> 主私钥和链码，按路径逐级派生子私钥，默认路径 m/44'/60'/0'/0/{index}，
+  和metamask等以太坊钱包一致，非国密账户用同一助记词可以得到相同的地址
+国密：派生算法和BIP32相同，只是曲线换成SM2，主密钥的HMAC key用"SM2 seed"(参照SLIP-0010对其他曲线的做法)，
+  没有通用的标准，只保证本sdk内派生结果稳定
+助记词只支持BIP39的英文词表，口令没有做NFKD规范化，请只用ASCII字符
+*/
+use openssl::bn::{BigNum, BigNumContext};
+use openssl::ec::{EcGroup, EcPoint, PointConversionForm};
+use openssl::hash::{hash, MessageDigest};
+use openssl::pkcs5;
+use openssl::pkey::PKey;
+use openssl::sign::Signer;
+
+use crate::bcossdkutil::accountutil::{account_from_privkey, BcosAccount};
+use crate::bcossdkutil::bcosclientconfig::BcosCryptoKind;
+use crate::bcossdkutil::keystore::{curve_of, ossl};
+use crate::bcossdkutil::kisserror::{KissErrKind, KissError};
+
+///BIP39英文词表，2048个词
+const WORDLIST_ENGLISH: &str = include_str!("bip39_english.txt");
+///BIP44的账户路径，后面再加一级账户序号
+pub const DEFAULT_HD_PATH: &str = "m/44'/60'/0'/0";
+///序号大于等于这个值的是强化派生(路径里写成 n')
+pub const HARDENED_OFFSET: u32 = 0x80000000;
+const SECP256K1_SEED_KEY: &[u8] = b"Bitcoin seed";
+const SM2_SEED_KEY: &[u8] = b"SM2 seed";
+const PBKDF2_ROUNDS: usize = 2048;
+///BIP39支持的助记词词数，对应128~256位的熵
+const MNEMONIC_WORDS: [usize; 5] = [12, 15, 18, 21, 24];
+
+fn wordlist() -> Vec<&'static str> {
+    WORDLIST_ENGLISH.lines().collect()
+}
+
+///生成新的随机助记词，words可以是12/15/18/21/24
+pub fn generate_mnemonic(words: usize) -> Result<String, KissError> {
+    if !MNEMONIC_WORDS.contains(&words) {
+        return kisserr!(
+            KissErrKind::EArgument,
+            "mnemonic words {} should be 12/15/18/21/24",
+            words
+        );
+    }
+    let mut entropy = vec![0u8; words / 3 * 4];
+    ossl(openssl::rand::rand_bytes(&mut entropy), "rand")?;
+    mnemonic_from_entropy(&entropy)
+}
+
+fn sha256(data: &[u8]) -> Result<Vec<u8>, KissError> {
+    Ok(ossl(hash(MessageDigest::sha256(), data), "sha256")?.to_vec())
+}
+
+fn bit_of(data: &[u8], i: usize) -> u32 {
+    ((data[i / 8] >> (7 - i % 8)) & 1) as u32
+}
+
+///熵(16~32字节，4字节的倍数)转成助记词
+pub fn mnemonic_from_entropy(entropy: &[u8]) -> Result<String, KissError> {
+    if ![16, 20, 24, 28, 32].contains(&entropy.len()) {
+        return kisserr!(
+            KissErrKind::EArgument,
+            "entropy length {} error",
+            entropy.len()
+        );
+    }
+    let checksum = sha256(entropy)?;
+    let entropy_bits = entropy.len() * 8;
+    let total_bits = entropy_bits + entropy_bits / 32;
+    let list = wordlist();
+    let mut words: Vec<&str> = vec![];
+    for w in 0..total_bits / 11 {
+        let mut index = 0u32;
+        for i in w * 11..(w + 1) * 11 {
+            let bit = if i < entropy_bits {
+                bit_of(entropy, i)
+            } else {
+                bit_of(&checksum, i - entropy_bits)
+            };
+            index = (index << 1) | bit;
+        }
+        words.push(list[index as usize]);
+    }
+    Ok(words.join(" "))
+}
+
+///助记词还原成熵，同时检查词是否在词表里和校验位
+pub fn mnemonic_to_entropy(phrase: &str) -> Result<Vec<u8>, KissError> {
+    let list = wordlist();
+    let words: Vec<String> = phrase
+        .split_whitespace()
+        .map(|w| w.to_lowercase())
+        .collect();
+    if !MNEMONIC_WORDS.contains(&words.len()) {
+        return kisserr!(
+            KissErrKind::EArgument,
+            "mnemonic words count {} error",
+            words.len()
+        );
+    }
+    let mut bits: Vec<u8> = vec![];
+    for w in words.iter() {
+        let index = match list.binary_search(&w.as_str()) {
+            Ok(i) => i,
+            Err(_) => return kisserr!(KissErrKind::EArgument, "unknown mnemonic word {}", w),
+        };
+        for i in (0..11).rev() {
+            bits.push(((index >> i) & 1) as u8);
+        }
+    }
+    let entropy_bits = bits.len() * 32 / 33;
+    let entropy: Vec<u8> = bits[0..entropy_bits]
+        .chunks(8)
+        .map(|c| c.iter().fold(0u8, |acc, b| (acc << 1) | b))
+        .collect();
+    let checksum = sha256(&entropy)?;
+    for (i, bit) in bits.iter().enumerate().skip(entropy_bits) {
+        if *bit as u32 != bit_of(&checksum, i - entropy_bits) {
+            return kisserr!(KissErrKind::EArgument, "mnemonic checksum error");
+        }
+    }
+    Ok(entropy)
+}
+
+pub fn validate_mnemonic(phrase: &str) -> bool {
+    mnemonic_to_entropy(phrase).is_ok()
+}
+
+///助记词加口令生成64字节种子，口令可以为空
+pub fn mnemonic_to_seed(phrase: &str, passphrase: &str) -> Result<Vec<u8>, KissError> {
+    mnemonic_to_entropy(phrase)?;
+    let normalized = phrase
+        .split_whitespace()
+        .map(|w| w.to_lowercase())
+        .collect::<Vec<String>>()
+        .join(" ");
+    let salt = format!("mnemonic{}", passphrase);
+    let mut seed = vec![0u8; 64];
+    ossl(
+        pkcs5::pbkdf2_hmac(
+            normalized.as_bytes(),
+            salt.as_bytes(),
+            PBKDF2_ROUNDS,
+            MessageDigest::sha512(),
+            &mut seed,
+        ),
+        "pbkdf2",
+    )?;
+    Ok(seed)
+}
+
+fn hmac_sha512(key: &[u8], data: &[u8]) -> Result<Vec<u8>, KissError> {
+    let pkey = ossl(PKey::hmac(key), "hmac key")?;
+    let mut signer = ossl(Signer::new(MessageDigest::sha512(), &pkey), "hmac")?;
+    ossl(signer.update(data), "hmac")?;
+    ossl(signer.sign_to_vec(), "hmac")
+}
+
+///解析 m/44'/60'/0'/0/0 格式的路径，强化派生可以写成 ' 或 h
+pub fn parse_path(path: &str) -> Result<Vec<u32>, KissError> {
+    let mut parts = path.trim().split('/');
+    if parts.next() != Some("m") {
+        return kisserr!(
+            KissErrKind::EArgument,
+            "hd path {} should start with m",
+            path
+        );
+    }
+    let mut indexes: Vec<u32> = vec![];
+    for part in parts {
+        let (num, hardened) = match part.strip_suffix(|c| c == '\'' || c == 'h' || c == 'H') {
+            Some(n) => (n, true),
+            None => (part, false),
+        };
+        let index: u32 = match num.parse() {
+            Ok(i) if i < HARDENED_OFFSET => i,
+            _ => return kisserr!(KissErrKind::EArgument, "hd path {} error at {}", path, part),
+        };
+        indexes.push(if hardened {
+            index + HARDENED_OFFSET
+        } else {
+            index
+        });
+    }
+    Ok(indexes)
+}
+
+///BIP32的扩展私钥
+#[derive(Debug, Clone)]
+pub struct ExtendedPrivKey {
+    pub cryptokind: BcosCryptoKind,
+    pub privkey: Vec<u8>,
+    pub chain_code: Vec<u8>,
+    pub depth: u8,
+    ///在上一级里的序号
+    pub child_number: u32,
+}
+
+impl ExtendedPrivKey {
+    ///从种子生成主密钥
+    pub fn from_seed(seed: &[u8], cryptokind: &BcosCryptoKind) -> Result<Self, KissError> {
+        let seedkey = match cryptokind {
+            BcosCryptoKind::GM => SM2_SEED_KEY,
+            BcosCryptoKind::ECDSA => SECP256K1_SEED_KEY,
+        };
+        let i = hmac_sha512(seedkey, seed)?;
+        let group = ossl(EcGroup::from_curve_name(curve_of(cryptokind)), "ec group")?;
+        let il = ossl(BigNum::from_slice(&i[0..32]), "bn")?;
+        if !key_in_range(&il, &group)? {
+            return kisserr!(
+                KissErrKind::EArgument,
+                "invalid master key, try another seed"
+            );
+        }
+        Ok(ExtendedPrivKey {
+            cryptokind: cryptokind.clone(),
+            privkey: i[0..32].to_vec(),
+            chain_code: i[32..64].to_vec(),
+            depth: 0,
+            child_number: 0,
+        })
+    }
+
+    ///33字节的压缩公钥，非强化派生要用
+    pub fn compressed_pubkey(&self) -> Result<Vec<u8>, KissError> {
+        let group = ossl(
+            EcGroup::from_curve_name(curve_of(&self.cryptokind)),
+            "ec group",
+        )?;
+        let mut ctx = ossl(BigNumContext::new(), "bn ctx")?;
+        let k = ossl(BigNum::from_slice(&self.privkey), "bn")?;
+        let mut point = ossl(EcPoint::new(&group), "ec point")?;
+        ossl(point.mul_generator(&group, &k, &ctx), "ec mul")?;
+        ossl(
+            point.to_bytes(&group, PointConversionForm::COMPRESSED, &mut ctx),
+            "ec point",
+        )
+    }
+
+    ///派生一级子私钥，index >= HARDENED_OFFSET 为强化派生
+    pub fn derive_child(&self, index: u32) -> Result<Self, KissError> {
+        let mut data = if index >= HARDENED_OFFSET {
+            let mut d = vec![0u8];
+            d.extend_from_slice(&self.privkey);
+            d
+        } else {
+            self.compressed_pubkey()?
+        };
+        data.extend_from_slice(&index.to_be_bytes());
+        let i = hmac_sha512(&self.chain_code, &data)?;
+
+        let group = ossl(
+            EcGroup::from_curve_name(curve_of(&self.cryptokind)),
+            "ec group",
+        )?;
+        let mut ctx = ossl(BigNumContext::new(), "bn ctx")?;
+        let mut order = ossl(BigNum::new(), "bn")?;
+        ossl(group.order(&mut order, &mut ctx), "ec order")?;
+        let il = ossl(BigNum::from_slice(&i[0..32]), "bn")?;
+        let k = ossl(BigNum::from_slice(&self.privkey), "bn")?;
+        let mut child = ossl(BigNum::new(), "bn")?;
+        ossl(child.mod_add(&il, &k, &order, &mut ctx), "bn add")?;
+        //概率可以忽略，BIP32规定这种情况跳过这个序号
+        if il >= order || child.num_bits() == 0 {
+            return kisserr!(
+                KissErrKind::EArgument,
+                "invalid child key at {}, use the next index",
+                index
+            );
+        }
+        Ok(ExtendedPrivKey {
+            cryptokind: self.cryptokind.clone(),
+            privkey: ossl(child.to_vec_padded(32), "bn")?,
+            chain_code: i[32..64].to_vec(),
+            depth: self.depth.saturating_add(1),
+            child_number: index,
+        })
+    }
+
+    ///按路径从当前密钥往下派生，路径一般从主密钥开始写
+    pub fn derive_path(&self, path: &str) -> Result<Self, KissError> {
+        let mut key = self.clone();
+        for index in parse_path(path)? {
+            key = key.derive_child(index)?;
+        }
+        Ok(key)
+    }
+
+    pub fn to_account(&self) -> Result<BcosAccount, KissError> {
+        account_from_privkey(&self.privkey, self.cryptokind.clone())
+    }
+}
+
+fn key_in_range(k: &BigNum, group: &EcGroup) -> Result<bool, KissError> {
+    let mut ctx = ossl(BigNumContext::new(), "bn ctx")?;
+    let mut order = ossl(BigNum::new(), "bn")?;
+    ossl(group.order(&mut order, &mut ctx), "ec order")?;
+    Ok(k.num_bits() > 0 && *k < order)
+}
+
+///由助记词管理的一组账户，按序号派生：{path}/{index}
+#[derive(Debug, Clone)]
+pub struct HDWallet {
+    pub master: ExtendedPrivKey,
+    ///账户的父路径，默认DEFAULT_HD_PATH
+    pub path: String,
+}
+
+impl HDWallet {
+    pub fn from_mnemonic(
+        phrase: &str,
+        passphrase: &str,
+        cryptokind: &BcosCryptoKind,
+    ) -> Result<Self, KissError> {
+        let seed = mnemonic_to_seed(phrase, passphrase)?;
+        HDWallet::from_seed(&seed, cryptokind)
+    }
+
+    pub fn from_seed(seed: &[u8], cryptokind: &BcosCryptoKind) -> Result<Self, KissError> {
+        Ok(HDWallet {
+            master: ExtendedPrivKey::from_seed(seed, cryptokind)?,
+            path: DEFAULT_HD_PATH.to_string(),
+        })
+    }
+
+    pub fn with_path(mut self, path: &str) -> Self {
+        self.path = path.trim_end_matches('/').to_string();
+        self
+    }
+
+    ///第index个账户的完整路径
+    pub fn account_path(&self, index: u32) -> String {
+        format!("{}/{}", self.path, index)
+    }
+
+    pub fn derive_account(&self, index: u32) -> Result<BcosAccount, KissError> {
+        self.master
+            .derive_path(&self.account_path(index))?
+            .to_account()
+    }
+}
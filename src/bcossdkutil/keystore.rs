@@ -0,0 +1,503 @@
+/*
+  FISCO BCOS/rust-SDK is a rust client for FISCO BCOS2.0 (https://github.com/FISCO-BCOS/)
+  FISCO BCOS/rust-SDK is free software: you can redistribute it and/or modify it under the
+  terms of the MIT License as published by the Free Software Foundation. This project is
+  distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even
+  the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+  @author: kentzhang
+  @date: 2021-07
+*/
+#![allow(
+    clippy::unreadable_literal,
+    clippy::upper_case_acronyms,
+    dead_code,
+    non_camel_case_types,
+    non_snake_case,
+    non_upper_case_globals,
+    overflowing_literals,
+    unused_variables,
+    unused_assignments
+)]
+
+/*
+带密码保护的私钥存储，支持三种格式，按文件扩展名区分：
+.pem：加密的PKCS#8(ENCRYPTED PRIVATE KEY)，非国密用AES-256-CBC，国密用SM4-CBC，密钥由PBKDF2从密码派生。
+      没有密码时仍然读写原来的明文PEM
+.p12：PKCS#12，和java sdk一样，别名为key，带一张私钥自签名的证书(java sdk加载时需要)
+.json：web3风格的keystore(version 3)，kdf为scrypt，非国密用aes-128-ctr + keccak256的mac，
+      国密用sm4-128-ctr + sm3的mac，java/web3的工具可以直接导入非国密的keystore
+密码来源(配置文件common.accountpassword和控制台--password)的写法和openssl的-passin一样：
+pass:明文密码，env:环境变量名，file:文件路径(取第一行)
+*/
+use std::path::Path;
+
+use openssl::asn1::Asn1Time;
+use openssl::bn::{BigNum, BigNumContext};
+use openssl::ec::{EcGroup, EcKey, EcPoint};
+use openssl::hash::MessageDigest;
+use openssl::nid::Nid;
+use openssl::pkcs12::Pkcs12;
+use openssl::pkcs5;
+use openssl::pkey::{PKey, Private};
+use openssl::symm::{self, Cipher};
+use openssl::x509::{X509Name, X509};
+use serde_json::{json, Value as JsonValue};
+use wedpr_l_libsm::sm4;
+
+use crate::bcossdkutil::accountutil::{account_from_privkey, try_from_fisco_pem_format};
+use crate::bcossdkutil::bcosclientconfig::BcosCryptoKind;
+use crate::bcossdkutil::commonhash::{CommonHash, HashType};
+use crate::bcossdkutil::fileutils;
+use crate::bcossdkutil::kisserror::{KissErrKind, KissError};
+
+///openssl里SM2曲线的NID(NID_sm2)，openssl crate没有导出这个常量
+const NID_SM2: i32 = 1172;
+///web3 keystore默认的scrypt参数，和geth的StandardScryptN一致
+pub const DEFAULT_SCRYPT_N: u64 = 262144;
+///geth的LightScryptN，加解密快很多，用于测试或低配置环境
+pub const LIGHT_SCRYPT_N: u64 = 4096;
+const SCRYPT_R: u64 = 8;
+const SCRYPT_P: u64 = 1;
+///keystore文件里的kdf参数不可信，限制上限，避免构造的文件耗尽内存或cpu。
+///n最大2^20(geth的StandardScryptN是2^18)，scrypt占用的内存(128*r*n)最多1G
+const MAX_SCRYPT_N: u64 = 1 << 20;
+const MAX_SCRYPT_R: u64 = 32;
+const MAX_SCRYPT_P: u64 = 16;
+const MAX_SCRYPT_MEM: u64 = 1 << 30;
+const MAX_DKLEN: usize = 64;
+const MAX_PBKDF2_C: usize = 10_000_000;
+///p12里私钥的别名，和java sdk一致
+const P12_KEY_NAME: &str = "key";
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum KeystoreFormat {
+    PEM,
+    P12,
+    JSON,
+}
+
+impl KeystoreFormat {
+    ///按扩展名判断，无法识别的都当作pem
+    pub fn from_path(path: &str) -> Self {
+        let ext = Path::new(path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or_default()
+            .to_lowercase();
+        match ext.as_str() {
+            "p12" | "pfx" => KeystoreFormat::P12,
+            "json" | "keystore" => KeystoreFormat::JSON,
+            _ => KeystoreFormat::PEM,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            KeystoreFormat::PEM => "pem",
+            KeystoreFormat::P12 => "p12",
+            KeystoreFormat::JSON => "json",
+        }
+    }
+}
+
+///按openssl -passin的写法读取密码：pass:xxx，env:VAR，file:path
+pub fn read_password_source(source: &str) -> Result<String, KissError> {
+    if let Some(p) = source.strip_prefix("pass:") {
+        return Ok(p.to_string());
+    }
+    if let Some(var) = source.strip_prefix("env:") {
+        return match std::env::var(var) {
+            Ok(p) => Ok(p),
+            Err(e) => kisserr!(KissErrKind::EArgument, "password env {} error {:?}", var, e),
+        };
+    }
+    if let Some(path) = source.strip_prefix("file:") {
+        let content = fileutils::readstring(path)?;
+        return Ok(content.lines().next().unwrap_or_default().to_string());
+    }
+    kisserr!(
+        KissErrKind::EArgument,
+        "unknown password source {:?}, use pass:/env:/file:",
+        source
+    )
+}
+
+pub(crate) fn ossl<T>(
+    res: Result<T, openssl::error::ErrorStack>,
+    what: &str,
+) -> Result<T, KissError> {
+    match res {
+        Ok(v) => Ok(v),
+        Err(e) => kisserr!(KissErrKind::EFormat, "{} error {:?}", what, e),
+    }
+}
+
+pub(crate) fn curve_of(cryptokind: &BcosCryptoKind) -> Nid {
+    match cryptokind {
+        BcosCryptoKind::GM => Nid::from_raw(NID_SM2),
+        BcosCryptoKind::ECDSA => Nid::SECP256K1,
+    }
+}
+
+///原始私钥转成openssl的key，公钥由私钥算出
+fn to_pkey(key: &[u8], cryptokind: &BcosCryptoKind) -> Result<PKey<Private>, KissError> {
+    let group = ossl(EcGroup::from_curve_name(curve_of(cryptokind)), "ec group")?;
+    let d = ossl(BigNum::from_slice(key), "private key")?;
+    let ctx = ossl(BigNumContext::new(), "bn ctx")?;
+    let mut point = ossl(EcPoint::new(&group), "ec point")?;
+    ossl(point.mul_generator(&group, &d, &ctx), "public key")?;
+    let eckey = ossl(EcKey::from_private_components(&group, &d, &point), "ec key")?;
+    ossl(PKey::from_ec_key(eckey), "pkey")
+}
+
+///openssl的key转回原始私钥，经过未加密的PKCS#8中转，兼容openssl3里国密key不能直接取EcKey的情况
+fn from_pkey(pkey: &PKey<Private>) -> Result<Vec<u8>, KissError> {
+    let pem = ossl(pkey.private_key_to_pem_pkcs8(), "pkcs8")?;
+    let der = match pem::parse(pem) {
+        Ok(p) => p.contents,
+        Err(e) => return kisserr!(KissErrKind::EFormat, "pkcs8 pem error {:?}", e),
+    };
+    match try_from_fisco_pem_format(der) {
+        Some(k) => Ok(k),
+        None => kisserr!(KissErrKind::EFormat, "private key not found in pkcs8"),
+    }
+}
+
+//---------------------加密的PKCS#8 PEM---------------------
+
+pub fn encrypt_pkcs8_pem(
+    key: &[u8],
+    cryptokind: &BcosCryptoKind,
+    password: &str,
+) -> Result<Vec<u8>, KissError> {
+    let cipher = match cryptokind {
+        BcosCryptoKind::GM => Cipher::sm4_cbc(),
+        BcosCryptoKind::ECDSA => Cipher::aes_256_cbc(),
+    };
+    let pkey = to_pkey(key, cryptokind)?;
+    ossl(
+        pkey.private_key_to_pem_pkcs8_passphrase(cipher, password.as_bytes()),
+        "encrypt pkcs8",
+    )
+}
+
+pub fn decrypt_pkcs8_pem(pem: &[u8], password: &str) -> Result<Vec<u8>, KissError> {
+    match PKey::private_key_from_pem_passphrase(pem, password.as_bytes()) {
+        Ok(pkey) => from_pkey(&pkey),
+        Err(e) => kisserr!(
+            KissErrKind::EArgument,
+            "decrypt pem error, wrong password? {:?}",
+            e
+        ),
+    }
+}
+
+//---------------------PKCS#12---------------------
+
+//java sdk从p12里取证书和私钥，这里给私钥签一张自签名证书
+fn self_signed_cert(pkey: &PKey<Private>, cryptokind: &BcosCryptoKind) -> Result<X509, KissError> {
+    let mut name = ossl(X509Name::builder(), "x509 name")?;
+    ossl(
+        name.append_entry_by_text("CN", "fisco-bcos-account"),
+        "x509 name",
+    )?;
+    let name = name.build();
+    let mut builder = ossl(X509::builder(), "x509")?;
+    ossl(builder.set_version(2), "x509 version")?;
+    ossl(builder.set_subject_name(&name), "x509 subject")?;
+    ossl(builder.set_issuer_name(&name), "x509 issuer")?;
+    ossl(builder.set_pubkey(pkey), "x509 pubkey")?;
+    let not_before = ossl(Asn1Time::days_from_now(0), "x509 time")?;
+    let not_after = ossl(Asn1Time::days_from_now(3650), "x509 time")?;
+    ossl(builder.set_not_before(&not_before), "x509 time")?;
+    ossl(builder.set_not_after(&not_after), "x509 time")?;
+    let md = match cryptokind {
+        BcosCryptoKind::GM => MessageDigest::sm3(),
+        BcosCryptoKind::ECDSA => MessageDigest::sha256(),
+    };
+    ossl(builder.sign(pkey, md), "x509 sign")?;
+    Ok(builder.build())
+}
+
+pub fn encrypt_p12(
+    key: &[u8],
+    cryptokind: &BcosCryptoKind,
+    password: &str,
+) -> Result<Vec<u8>, KissError> {
+    let pkey = to_pkey(key, cryptokind)?;
+    let cert = self_signed_cert(&pkey, cryptokind)?;
+    let p12 = ossl(
+        Pkcs12::builder().build(password, P12_KEY_NAME, &pkey, &cert),
+        "build p12",
+    )?;
+    ossl(p12.to_der(), "p12 der")
+}
+
+pub fn decrypt_p12(der: &[u8], password: &str) -> Result<Vec<u8>, KissError> {
+    let p12 = ossl(Pkcs12::from_der(der), "p12 format")?;
+    match p12.parse(password) {
+        Ok(parsed) => from_pkey(&parsed.pkey),
+        Err(e) => kisserr!(
+            KissErrKind::EArgument,
+            "decrypt p12 error, wrong password? {:?}",
+            e
+        ),
+    }
+}
+
+//---------------------web3 json keystore---------------------
+
+fn random_bytes(len: usize) -> Result<Vec<u8>, KissError> {
+    let mut buf = vec![0u8; len];
+    ossl(openssl::rand::rand_bytes(&mut buf), "rand")?;
+    Ok(buf)
+}
+
+fn scrypt_key(
+    password: &str,
+    salt: &[u8],
+    n: u64,
+    r: u64,
+    p: u64,
+    dklen: usize,
+) -> Result<Vec<u8>, KissError> {
+    if n < 2 || !n.is_power_of_two() || n > MAX_SCRYPT_N {
+        return kisserr!(KissErrKind::EFormat, "bad scrypt n {}", n);
+    }
+    if r == 0 || r > MAX_SCRYPT_R || p == 0 || p > MAX_SCRYPT_P {
+        return kisserr!(KissErrKind::EFormat, "bad scrypt r {} or p {}", r, p);
+    }
+    if 128 * r * n > MAX_SCRYPT_MEM {
+        return kisserr!(
+            KissErrKind::EFormat,
+            "scrypt n {} r {} needs too much memory",
+            n,
+            r
+        );
+    }
+    let mut key = vec![0u8; dklen];
+    //scrypt需要128*r*(n+p)字节左右的内存，再留一些余量
+    let maxmem = 128 * r * (n + p + 2) + 1024 * 1024;
+    ossl(
+        pkcs5::scrypt(password.as_bytes(), salt, n, r, p, maxmem, &mut key),
+        "scrypt",
+    )?;
+    Ok(key)
+}
+
+fn keystore_mac(derived: &[u8], ciphertext: &[u8], hashtype: &HashType) -> Vec<u8> {
+    let mut data = derived[16..32].to_vec();
+    data.extend_from_slice(ciphertext);
+    CommonHash::hash(&data, hashtype)
+}
+
+fn ctr_crypt(cipher: &str, key: &[u8], iv: &[u8], data: &[u8]) -> Result<Vec<u8>, KissError> {
+    match cipher {
+        "aes-128-ctr" => ossl(
+            symm::encrypt(Cipher::aes_128_ctr(), key, Some(iv), data),
+            "aes-128-ctr",
+        ),
+        //ctr模式加密和解密是同一个运算
+        "sm4-128-ctr" => Ok(sm4::Cipher::new(key, sm4::Mode::Ctr).encrypt(data, iv)),
+        _ => kisserr!(
+            KissErrKind::EFormat,
+            "unsupported keystore cipher {}",
+            cipher
+        ),
+    }
+}
+
+fn uuid_v4() -> Result<String, KissError> {
+    let mut b = random_bytes(16)?;
+    b[6] = (b[6] & 0x0f) | 0x40;
+    b[8] = (b[8] & 0x3f) | 0x80;
+    let h = hex::encode(&b);
+    Ok(format!(
+        "{}-{}-{}-{}-{}",
+        &h[0..8],
+        &h[8..12],
+        &h[12..16],
+        &h[16..20],
+        &h[20..32]
+    ))
+}
+
+///生成web3 keystore(version 3)，scrypt_n一般用DEFAULT_SCRYPT_N
+pub fn encrypt_json_keystore(
+    key: &[u8],
+    cryptokind: &BcosCryptoKind,
+    password: &str,
+    scrypt_n: u64,
+) -> Result<JsonValue, KissError> {
+    let (cipher, hashtype) = match cryptokind {
+        BcosCryptoKind::GM => ("sm4-128-ctr", HashType::WEDRP_SM3),
+        BcosCryptoKind::ECDSA => ("aes-128-ctr", HashType::KECCAK),
+    };
+    let account = account_from_privkey(&key.to_vec(), cryptokind.clone())?;
+    let salt = random_bytes(32)?;
+    let iv = random_bytes(16)?;
+    let derived = scrypt_key(password, &salt, scrypt_n, SCRYPT_R, SCRYPT_P, 32)?;
+    let ciphertext = ctr_crypt(cipher, &derived[0..16], &iv, key)?;
+    let mac = keystore_mac(&derived, &ciphertext, &hashtype);
+    Ok(json!({
+        "version": 3,
+        "id": uuid_v4()?,
+        "address": hex::encode(&account.address),
+        "crypto": {
+            "cipher": cipher,
+            "cipherparams": {"iv": hex::encode(&iv)},
+            "ciphertext": hex::encode(&ciphertext),
+            "kdf": "scrypt",
+            "kdfparams": {
+                "dklen": 32,
+                "n": scrypt_n,
+                "r": SCRYPT_R,
+                "p": SCRYPT_P,
+                "salt": hex::encode(&salt),
+            },
+            "mac": hex::encode(&mac),
+        }
+    }))
+}
+
+fn keystore_hex(v: &JsonValue, name: &str) -> Result<Vec<u8>, KissError> {
+    match v[name]
+        .as_str()
+        .map(|s| hex::decode(s.trim_start_matches("0x")))
+    {
+        Some(Ok(b)) => Ok(b),
+        _ => kisserr!(KissErrKind::EFormat, "keystore field {} error", name),
+    }
+}
+
+///解密web3 keystore，kdf支持scrypt和pbkdf2(hmac-sha256)
+pub fn decrypt_json_keystore(keystore: &JsonValue, password: &str) -> Result<Vec<u8>, KissError> {
+    //有的工具生成的是大写的Crypto
+    let crypto = if keystore["crypto"].is_object() {
+        &keystore["crypto"]
+    } else {
+        &keystore["Crypto"]
+    };
+    let params = &crypto["kdfparams"];
+    let salt = keystore_hex(params, "salt")?;
+    let dklen = params["dklen"].as_u64().unwrap_or(32) as usize;
+    if !(32..=MAX_DKLEN).contains(&dklen) {
+        return kisserr!(KissErrKind::EFormat, "bad keystore dklen {}", dklen);
+    }
+    let derived = match crypto["kdf"].as_str().unwrap_or_default() {
+        "scrypt" => scrypt_key(
+            password,
+            &salt,
+            params["n"].as_u64().unwrap_or(DEFAULT_SCRYPT_N),
+            params["r"].as_u64().unwrap_or(SCRYPT_R),
+            params["p"].as_u64().unwrap_or(SCRYPT_P),
+            dklen,
+        )?,
+        "pbkdf2" => {
+            if params["prf"].as_str().unwrap_or("hmac-sha256") != "hmac-sha256" {
+                return kisserr!(KissErrKind::EFormat, "unsupported prf {}", params["prf"]);
+            }
+            let mut key = vec![0u8; dklen];
+            let iter = params["c"].as_u64().unwrap_or(262144) as usize;
+            if iter == 0 || iter > MAX_PBKDF2_C {
+                return kisserr!(KissErrKind::EFormat, "bad pbkdf2 iterations {}", iter);
+            }
+            ossl(
+                pkcs5::pbkdf2_hmac(
+                    password.as_bytes(),
+                    &salt,
+                    iter,
+                    MessageDigest::sha256(),
+                    &mut key,
+                ),
+                "pbkdf2",
+            )?;
+            key
+        }
+        other => return kisserr!(KissErrKind::EFormat, "unsupported kdf {}", other),
+    };
+    let cipher = crypto["cipher"].as_str().unwrap_or_default();
+    let hashtype = if cipher.starts_with("sm4") {
+        HashType::WEDRP_SM3
+    } else {
+        HashType::KECCAK
+    };
+    let ciphertext = keystore_hex(crypto, "ciphertext")?;
+    //常数时间比较，不从比较耗时泄露mac
+    let mac = keystore_mac(&derived, &ciphertext, &hashtype);
+    let expected = keystore_hex(crypto, "mac")?;
+    if mac.len() != expected.len() || !openssl::memcmp::eq(&mac, &expected) {
+        return kisserr!(
+            KissErrKind::EArgument,
+            "keystore mac mismatch, wrong password?"
+        );
+    }
+    let iv = keystore_hex(&crypto["cipherparams"], "iv")?;
+    ctr_crypt(cipher, &derived[0..16], &iv, &ciphertext)
+}
+
+//---------------------按格式读写文件---------------------
+
+///按扩展名选择格式保存私钥，pem格式没有密码时保存为明文
+pub fn save_key(
+    key: &[u8],
+    cryptokind: &BcosCryptoKind,
+    path: &str,
+    password: Option<&str>,
+) -> Result<(), KissError> {
+    let format = KeystoreFormat::from_path(path);
+    let data = match (format, password) {
+        (KeystoreFormat::PEM, None) => {
+            return crate::bcossdkutil::accountutil::save_key_to_pem(&key.to_vec(), path)
+        }
+        (KeystoreFormat::PEM, Some(p)) => encrypt_pkcs8_pem(key, cryptokind, p)?,
+        (KeystoreFormat::P12, Some(p)) => encrypt_p12(key, cryptokind, p)?,
+        (KeystoreFormat::JSON, Some(p)) => {
+            let v = encrypt_json_keystore(key, cryptokind, p, DEFAULT_SCRYPT_N)?;
+            serde_json::to_vec_pretty(&v).unwrap_or_default()
+        }
+        (_, None) => {
+            return kisserr!(
+                KissErrKind::EArgument,
+                "{:?} keystore {} needs a password",
+                format,
+                path
+            )
+        }
+    };
+    fileutils::write_all(path, data)
+}
+
+///按扩展名和内容识别格式加载私钥，明文pem不需要密码
+pub fn load_key(path: &str, password: Option<&str>) -> Result<Vec<u8>, KissError> {
+    let format = KeystoreFormat::from_path(path);
+    let data = fileutils::read_all(path)?;
+    if format == KeystoreFormat::PEM
+        && !String::from_utf8_lossy(&data).contains("ENCRYPTED PRIVATE KEY")
+    {
+        return crate::bcossdkutil::accountutil::load_key_from_pem(path);
+    }
+    let password = match password {
+        Some(p) => p,
+        None => {
+            return kisserr!(
+                KissErrKind::EArgument,
+                "keystore {} is encrypted, password required",
+                path
+            )
+        }
+    };
+    match format {
+        KeystoreFormat::PEM => decrypt_pkcs8_pem(&data, password),
+        KeystoreFormat::P12 => decrypt_p12(&data, password),
+        KeystoreFormat::JSON => match serde_json::from_slice(&data) {
+            Ok(v) => decrypt_json_keystore(&v, password),
+            Err(e) => kisserr!(
+                KissErrKind::EFormat,
+                "keystore {} format error {:?}",
+                path,
+                e
+            ),
+        },
+    }
+}
@@ -67,6 +67,12 @@ pub enum KissErrKind {
     EFileWrite,
     #[fail(display = "file read")]
     EFileRead,
+    //交易上链但执行失败，code为回执的状态码，msg里带解析后的状态和revert原因
+    #[fail(display = "transaction failed")]
+    ETxFailed,
+    //预编译合约返回了错误码，code为返回码
+    #[fail(display = "precompiled contract error")]
+    EPrecompiled,
 }
 
 impl Default for KissErrKind {
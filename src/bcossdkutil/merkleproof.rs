@@ -0,0 +1,322 @@
+/*
+  FISCO BCOS/rust-SDK is a rust client for FISCO BCOS2.0 (https://github.com/FISCO-BCOS/)
+  FISCO BCOS/rust-SDK is free software: you can redistribute it and/or modify it under the
+  terms of the MIT License as published by the Free Software Foundation. This project is
+  distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even
+  the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+  @author: kentzhang
+  @date: 2021-07
+*/
+#![allow(
+    clippy::unreadable_literal,
+    clippy::upper_case_acronyms,
+    dead_code,
+    non_camel_case_types,
+    non_snake_case,
+    non_upper_case_globals,
+    overflowing_literals,
+    unused_variables,
+    unused_assignments
+)]
+
+/*
+交易和回执的merkle证明校验，按证明路径重新计算出交易根/回执根，和区块头里的transactionsRoot/receiptsRoot比较，
+这样只需要信任区块头，不需要信任返回交易和回执的节点。hash算法用配置的HashType(Keccak或国密SM3)。
+
+2.x(参考java sdk的MerkleProofUtility)：
+  16叉树，叶子是rlp(交易序号)拼接交易hash，回执的叶子是rlp(交易序号)拼接hash(回执的rlp编码)，叶子本身不做hash。
+  每层把16个节点拼接起来做hash得到上一层，直到只剩一个节点(至少hash一次)，即为根。
+  证明是从叶子到根每层的一个{"left":[..],"right":[..]}，left/right是同组里在本节点左边和右边的兄弟节点(hex)，
+  本层的结果 = hash(left.. + 当前节点 + right..)。
+3.x：
+  证明是hex字符串的列表，从叶子层开始，每层是当前节点所在的一组(最多width=2个，包含当前节点自身)，
+  本层的结果 = hash(组内节点依次拼接)，列表最后一个是根。叶子是交易hash/回执hash，
+  由调用方按交易和回执的字段重新计算(见bcos3transaction)，不用节点返回的hash字段。
+两个版本都要求返回的交易/回执就是请求的那一笔交易。
+*/
+use ethereum_types::U256;
+use rlp::RlpStream;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+use crate::bcossdkutil::commonhash::{CommonHash, HashType};
+use crate::bcossdkutil::kisserror::{KissErrKind, KissError};
+
+///2.x每层的分组宽度
+pub const MERKLE_WIDTH_V2: usize = 16;
+///3.x每层的分组宽度
+pub const MERKLE_WIDTH_V3: usize = 2;
+
+///2.x证明路径的一层
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MerkleProofUnit {
+    pub left: Vec<String>,
+    pub right: Vec<String>,
+}
+
+pub(crate) fn decode_hex(s: &str) -> Result<Vec<u8>, KissError> {
+    match hex::decode(s.trim_start_matches("0x")) {
+        Ok(v) => Ok(v),
+        Err(e) => kisserr!(KissErrKind::EFormat, "hex {} error {:?}", s, e),
+    }
+}
+
+pub(crate) fn json_hex(v: &JsonValue, name: &str) -> Result<Vec<u8>, KissError> {
+    match v[name].as_str() {
+        Some(s) => decode_hex(s),
+        None => kisserr!(KissErrKind::EFormat, "{} not found", name),
+    }
+}
+
+pub(crate) fn json_u256(v: &JsonValue, name: &str) -> Result<U256, KissError> {
+    let s = match &v[name] {
+        JsonValue::String(s) => s.clone(),
+        JsonValue::Number(n) => n.to_string(),
+        _ => return kisserr!(KissErrKind::EFormat, "{} not found", name),
+    };
+    let parsed = if s.starts_with("0x") || s.starts_with("0X") {
+        //U256的FromStr按hex解析
+        let digits = &s[2..];
+        if digits.is_empty() {
+            Some(U256::zero())
+        } else {
+            digits.parse::<U256>().ok()
+        }
+    } else {
+        U256::from_dec_str(s.as_str()).ok()
+    };
+    match parsed {
+        Some(n) => Ok(n),
+        None => kisserr!(KissErrKind::EFormat, "{} is not a number: {}", name, s),
+    }
+}
+
+fn same_hash(a: &[u8], b: &str) -> bool {
+    match decode_hex(b) {
+        Ok(b) => a == b.as_slice(),
+        Err(_) => false,
+    }
+}
+
+///节点返回的交易或回执是否就是请求的那一笔，防止节点拿同一个块里别的交易的证明来应付
+pub fn is_same_txhash(v: &JsonValue, txhash: &str) -> bool {
+    let returned = v["hash"]
+        .as_str()
+        .or_else(|| v["transactionHash"].as_str())
+        .unwrap_or_default();
+    match decode_hex(txhash) {
+        Ok(h) => !h.is_empty() && same_hash(&h, returned),
+        Err(_) => false,
+    }
+}
+
+pub fn parse_proof_v2(proof: &JsonValue) -> Result<Vec<MerkleProofUnit>, KissError> {
+    if proof.is_null() {
+        return Ok(vec![]);
+    }
+    match serde_json::from_value(proof.clone()) {
+        Ok(p) => Ok(p),
+        Err(e) => kisserr!(KissErrKind::EFormat, "merkle proof format error {:?}", e),
+    }
+}
+
+///按2.x的证明路径从叶子计算出根
+pub fn calculate_merkle_root_v2(
+    proof: &[MerkleProofUnit],
+    leaf: &[u8],
+    hashtype: &HashType,
+) -> Result<Vec<u8>, KissError> {
+    let mut result = leaf.to_vec();
+    for unit in proof.iter() {
+        let mut input: Vec<u8> = vec![];
+        for l in unit.left.iter() {
+            input.extend(decode_hex(l)?);
+        }
+        input.extend(result);
+        for r in unit.right.iter() {
+            input.extend(decode_hex(r)?);
+        }
+        result = CommonHash::hash(&input, hashtype);
+    }
+    Ok(result)
+}
+
+///2.x交易的叶子：rlp(序号) + 交易hash
+pub fn transaction_leaf_v2(index: u64, txhash: &str) -> Result<Vec<u8>, KissError> {
+    let mut leaf = rlp::encode(&index);
+    leaf.extend(decode_hex(txhash)?);
+    Ok(leaf)
+}
+
+///2.x回执的rlp编码：[root,gasUsed,contractAddress,logsBloom,status,output,[[address,[topics],data]..]]
+pub fn receipt_rlp_v2(receipt: &JsonValue) -> Result<Vec<u8>, KissError> {
+    let mut stream = RlpStream::new_list(7);
+    stream.append(&json_hex(receipt, "root")?);
+    stream.append(&json_u256(receipt, "gasUsed")?);
+    stream.append(&json_hex(receipt, "contractAddress")?);
+    stream.append(&json_hex(receipt, "logsBloom")?);
+    stream.append(&json_u256(receipt, "status")?);
+    stream.append(&json_hex(receipt, "output")?);
+    let logs = receipt["logs"].as_array().cloned().unwrap_or_default();
+    stream.begin_list(logs.len());
+    for log in logs.iter() {
+        stream.begin_list(3);
+        stream.append(&json_hex(log, "address")?);
+        let topics = log["topics"].as_array().cloned().unwrap_or_default();
+        stream.begin_list(topics.len());
+        for t in topics.iter() {
+            stream.append(&decode_hex(t.as_str().unwrap_or_default())?);
+        }
+        stream.append(&json_hex(log, "data")?);
+    }
+    Ok(stream.out())
+}
+
+///2.x回执的叶子：rlp(序号) + hash(回执的rlp编码)
+pub fn receipt_leaf_v2(
+    index: u64,
+    receipt: &JsonValue,
+    hashtype: &HashType,
+) -> Result<Vec<u8>, KissError> {
+    let mut leaf = rlp::encode(&index);
+    leaf.extend(CommonHash::hash(&receipt_rlp_v2(receipt)?, hashtype));
+    Ok(leaf)
+}
+
+///计算2.x的根，返回每个叶子的证明路径，节点这样构造区块的交易根和回执根
+pub fn merkle_proofs_v2(
+    leaves: &[Vec<u8>],
+    hashtype: &HashType,
+) -> (Vec<u8>, Vec<Vec<MerkleProofUnit>>) {
+    if leaves.is_empty() {
+        return (CommonHash::hash(&vec![], hashtype), vec![]);
+    }
+    let mut proofs: Vec<Vec<MerkleProofUnit>> = vec![vec![]; leaves.len()];
+    //每个叶子在当前层的位置
+    let mut positions: Vec<usize> = (0..leaves.len()).collect();
+    let mut level: Vec<Vec<u8>> = leaves.to_vec();
+    loop {
+        for (leaf, pos) in positions.iter_mut().enumerate() {
+            let start = *pos / MERKLE_WIDTH_V2 * MERKLE_WIDTH_V2;
+            let end = (start + MERKLE_WIDTH_V2).min(level.len());
+            proofs[leaf].push(MerkleProofUnit {
+                left: level[start..*pos].iter().map(hex::encode).collect(),
+                right: level[*pos + 1..end].iter().map(hex::encode).collect(),
+            });
+            *pos /= MERKLE_WIDTH_V2;
+        }
+        level = level
+            .chunks(MERKLE_WIDTH_V2)
+            .map(|group| CommonHash::hash(&group.concat(), hashtype))
+            .collect();
+        if level.len() == 1 {
+            return (level.remove(0), proofs);
+        }
+    }
+}
+
+///校验2.x的交易证明，tx为交易json(需要hash和transactionIndex)，proof为txProof
+pub fn verify_transaction_proof_v2(
+    tx: &JsonValue,
+    proof: &JsonValue,
+    transactions_root: &str,
+    hashtype: &HashType,
+) -> Result<bool, KissError> {
+    let index = json_u256(tx, "transactionIndex")?.as_u64();
+    let txhash = tx["hash"]
+        .as_str()
+        .or_else(|| tx["transactionHash"].as_str())
+        .unwrap_or_default();
+    let leaf = transaction_leaf_v2(index, txhash)?;
+    let root = calculate_merkle_root_v2(&parse_proof_v2(proof)?, &leaf, hashtype)?;
+    Ok(same_hash(&root, transactions_root))
+}
+
+///校验2.x的回执证明，receipt为回执json，proof为receiptProof
+pub fn verify_receipt_proof_v2(
+    receipt: &JsonValue,
+    proof: &JsonValue,
+    receipts_root: &str,
+    hashtype: &HashType,
+) -> Result<bool, KissError> {
+    let index = json_u256(receipt, "transactionIndex")?.as_u64();
+    let leaf = receipt_leaf_v2(index, receipt, hashtype)?;
+    let root = calculate_merkle_root_v2(&parse_proof_v2(proof)?, &leaf, hashtype)?;
+    Ok(same_hash(&root, receipts_root))
+}
+
+///校验3.x的证明：每层找到包含当前节点的一组，拼接后hash，最后和根比较
+pub fn verify_merkle_proof_v3(
+    proof: &[String],
+    leaf_hash: &str,
+    root: &str,
+    hashtype: &HashType,
+) -> Result<bool, KissError> {
+    if proof.is_empty() {
+        return kisserr!(KissErrKind::EArgument, "empty merkle proof");
+    }
+    let nodes: Vec<Vec<u8>> = proof
+        .iter()
+        .map(|p| decode_hex(p))
+        .collect::<Result<_, _>>()?;
+    let (proof_root, levels) = nodes.split_last().unwrap();
+    let mut current = decode_hex(leaf_hash)?;
+    let mut i = 0;
+    while i < levels.len() {
+        let end = (i + MERKLE_WIDTH_V3).min(levels.len());
+        //一层里只有一个节点时这一组只有它自己
+        let group = if levels[i..end].contains(&current) {
+            &levels[i..end]
+        } else if levels[i] == current {
+            &levels[i..i + 1]
+        } else {
+            return Ok(false);
+        };
+        current = CommonHash::hash(&group.concat(), hashtype);
+        i += group.len();
+    }
+    Ok(current == *proof_root && same_hash(proof_root, root))
+}
+
+fn proof_list(v: &JsonValue, name: &str) -> Result<Vec<String>, KissError> {
+    match v[name].as_array() {
+        Some(arr) => Ok(arr
+            .iter()
+            .map(|x| x.as_str().unwrap_or_default().to_string())
+            .collect()),
+        None => kisserr!(KissErrKind::EFormat, "{} not found", name),
+    }
+}
+
+///校验3.x的交易证明，tx为带证明查询的交易json(hash,txProof)，txhash为请求的交易hash。
+///data_hash是调用方按交易字段重新算出的hash(Bcos3TransactionData::from_json(..).hash())，
+///要和请求的hash一致，叶子用它而不用json里的hash
+pub fn verify_transaction_proof_v3(
+    tx: &JsonValue,
+    txhash: &str,
+    data_hash: &[u8],
+    txs_root: &str,
+    hashtype: &HashType,
+) -> Result<bool, KissError> {
+    let proof = proof_list(tx, "txProof")?;
+    if !is_same_txhash(tx, txhash) || !same_hash(data_hash, txhash) {
+        return Ok(false);
+    }
+    verify_merkle_proof_v3(&proof, &hex::encode(data_hash), txs_root, hashtype)
+}
+
+///校验3.x的回执证明，receipt为带证明查询的回执json(transactionHash,receiptProof)，txhash为请求的交易hash。
+///receipt_hash是调用方按回执字段重新算出的hash(bcos3_receipt_hash)，json里的receiptHash不参与校验
+pub fn verify_receipt_proof_v3(
+    receipt: &JsonValue,
+    txhash: &str,
+    receipt_hash: &[u8],
+    receipts_root: &str,
+    hashtype: &HashType,
+) -> Result<bool, KissError> {
+    let proof = proof_list(receipt, "receiptProof")?;
+    if !is_same_txhash(receipt, txhash) {
+        return Ok(false);
+    }
+    verify_merkle_proof_v3(&proof, &hex::encode(receipt_hash), receipts_root, hashtype)
+}
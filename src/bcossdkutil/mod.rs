@@ -11,10 +11,12 @@
 pub mod macrodef;
 #[macro_use]
 pub mod kisserror;
+pub mod abi_json;
 pub mod abi_parser;
 pub mod abi_tokenizer;
 pub mod accountutil;
 pub mod bcosclientconfig;
+pub mod blocksignature;
 pub mod bufferqueue;
 pub mod commonhash;
 pub mod commonsigner;
@@ -22,7 +24,12 @@ pub mod commonutil;
 pub mod contractabi;
 pub mod contracthistory;
 pub mod event_utils;
+pub mod externalsigner;
 pub mod fileutils;
+pub mod hdwallet;
+pub mod keystore;
 pub mod liteutils;
+pub mod merkleproof;
 pub mod solcompile;
 pub mod stattool;
+pub mod txstatus;
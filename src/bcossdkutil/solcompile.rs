@@ -36,7 +36,7 @@ pub fn sol_compile(contract_name: &str, configfile: &str) -> Result<Output, Kiss
         return kisserr!(KissErrKind::EFileMiss,"contract solfile [{}] is not exists,check the config setting in  [{}]->contractpath[{}]",
                 solfullpath.to_str().unwrap(),configfile,config.common.contractpath);
     }
-    println!(
+    printlnex!(
         "compile sol  {} ,use solc {},outputdir:{} options: {:?} ",
         solfullpath.to_str().unwrap(),
         solc_path,
@@ -1,23 +1,70 @@
 use time::Tm;
 
-pub struct StatTime{
-    pub time_begin:Tm,
-    pub time_end:Tm
-
+pub struct StatTime {
+    pub time_begin: Tm,
+    pub time_end: Tm,
 }
 
-impl StatTime{
-    pub fn begin()-> Self{
-        StatTime{
-            time_begin : time::now(),
-            time_end : time::now()
+impl StatTime {
+    pub fn begin() -> Self {
+        StatTime {
+            time_begin: time::now(),
+            time_end: time::now(),
         }
     }
-    pub fn done(&mut self){
+    pub fn done(&mut self) {
         self.time_end = time::now();
     }
-    pub fn used_ms(&self)->i64{
+    pub fn used_ms(&self) -> i64 {
         let time_used = self.time_end - self.time_begin;
-        return time_used.num_milliseconds()
+        return time_used.num_milliseconds();
+    }
+}
+
+///收集多次请求的耗时，统计平均值和分位数，压测时每个线程各自收集，最后merge
+#[derive(Debug, Default, Clone)]
+pub struct LatencyStat {
+    pub samples_us: Vec<u64>,
+}
+
+impl LatencyStat {
+    pub fn add(&mut self, used: std::time::Duration) {
+        self.samples_us.push(used.as_micros() as u64);
+    }
+    pub fn merge(&mut self, other: &LatencyStat) {
+        self.samples_us.extend_from_slice(&other.samples_us);
+    }
+    pub fn count(&self) -> usize {
+        self.samples_us.len()
     }
-}
\ No newline at end of file
+    pub fn avg_ms(&self) -> f64 {
+        if self.samples_us.is_empty() {
+            return 0.0;
+        }
+        let total: u64 = self.samples_us.iter().sum();
+        total as f64 / self.samples_us.len() as f64 / 1000.0
+    }
+    ///p取0~100，按nearest-rank取值，如p99为排序后第ceil(0.99*n)个样本
+    pub fn percentile_ms(&self, p: f64) -> f64 {
+        if self.samples_us.is_empty() {
+            return 0.0;
+        }
+        let mut sorted = self.samples_us.clone();
+        sorted.sort_unstable();
+        let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+        let index = rank.clamp(1, sorted.len()) - 1;
+        sorted[index] as f64 / 1000.0
+    }
+    pub fn max_ms(&self) -> f64 {
+        self.samples_us
+            .iter()
+            .max()
+            .map_or(0.0, |v| *v as f64 / 1000.0)
+    }
+    pub fn min_ms(&self) -> f64 {
+        self.samples_us
+            .iter()
+            .min()
+            .map_or(0.0, |v| *v as f64 / 1000.0)
+    }
+}
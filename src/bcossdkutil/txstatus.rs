@@ -0,0 +1,435 @@
+/*
+  FISCO BCOS/rust-SDK is a rust client for FISCO BCOS2.0 (https://github.com/FISCO-BCOS/)
+  FISCO BCOS/rust-SDK is free software: you can redistribute it and/or modify it under the
+  terms of the MIT License as published by the Free Software Foundation. This project is
+  distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even
+  the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+  @author: kentzhang
+  @date: 2021-07
+*/
+#![allow(
+    clippy::unreadable_literal,
+    clippy::upper_case_acronyms,
+    dead_code,
+    non_camel_case_types,
+    non_snake_case,
+    non_upper_case_globals,
+    overflowing_literals,
+    unused_variables,
+    unused_assignments
+)]
+
+/*
+交易回执的状态码和revert原因的解析
+- FISCO BCOS 2.x和3.x的状态码编号不同(如RevertInstruction在2.x是22，在3.x是16)，
+  分别用from_bcos2/from_bcos3转成同一个TxStatus
+- 2.x的回执里数字都是0x开头的hex字符串，3.x是json数字，只有回执json而不知道链版本时按此区分
+- 合约revert时output里是abi编码的Error(string)或Panic(uint256)，国密链的selector用sm3计算，两种都尝试
+*/
+use std::fmt;
+
+use ethabi::{ParamType, Token, Uint};
+use serde_json::Value as JsonValue;
+
+use crate::bcossdkutil::commonhash::{CommonHash, HashType};
+use crate::bcossdkutil::kisserror::{KissErrKind, KissError};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum TxStatus {
+    Success,
+    Unknown,
+    BadRLP,
+    InvalidFormat,
+    OutOfGasLimit,
+    InvalidSignature,
+    InvalidNonce,
+    NotEnoughCash,
+    OutOfGasBase,
+    BlockGasLimitReached,
+    BadInstruction,
+    BadJumpDestination,
+    OutOfGas,
+    OutOfStack,
+    StackUnderflow,
+    NonceCheckFail,
+    BlockLimitCheckFail,
+    FilterCheckFail,
+    NoDeployPermission,
+    NoCallPermission,
+    NoTxPermission,
+    PrecompiledError,
+    RevertInstruction,
+    InvalidZeroSignatureFormat,
+    AddressAlreadyUsed,
+    PermissionDenied,
+    CallAddressError,
+    GasOverflow,
+    TxPoolIsFull,
+    TransactionRefused,
+    ContractFrozen,
+    AccountFrozen,
+    AccountAbolished,
+    ContractAbolished,
+    WASMValidationFailure,
+    WASMArgumentOutOfRange,
+    WASMUnreachableInstruction,
+    WASMTrap,
+    AlreadyInTxPool,
+    TxAlreadyInChain,
+    InvalidChainId,
+    InvalidGroupId,
+    RequestNotBelongToTheGroup,
+    MalformedTx,
+    OverGroupMemoryLimit,
+    TransactionPoolTimeout,
+    AlreadyInTxPoolAndAccept,
+    //表里没有的状态码
+    Other(i64),
+}
+
+//https://fisco-bcos-documentation.readthedocs.io/zh_CN/latest/docs/api.html 交易回执状态
+const BCOS2_STATUS: &[(i64, TxStatus)] = &[
+    (0, TxStatus::Success),
+    (1, TxStatus::Unknown),
+    (2, TxStatus::BadRLP),
+    (3, TxStatus::InvalidFormat),
+    (4, TxStatus::OutOfGasLimit),
+    (5, TxStatus::InvalidSignature),
+    (6, TxStatus::InvalidNonce),
+    (7, TxStatus::NotEnoughCash),
+    (8, TxStatus::OutOfGasBase),
+    (9, TxStatus::BlockGasLimitReached),
+    (10, TxStatus::BadInstruction),
+    (11, TxStatus::BadJumpDestination),
+    (12, TxStatus::OutOfGas),
+    (13, TxStatus::OutOfStack),
+    (14, TxStatus::StackUnderflow),
+    (15, TxStatus::NonceCheckFail),
+    (16, TxStatus::BlockLimitCheckFail),
+    (17, TxStatus::FilterCheckFail),
+    (18, TxStatus::NoDeployPermission),
+    (19, TxStatus::NoCallPermission),
+    (20, TxStatus::NoTxPermission),
+    (21, TxStatus::PrecompiledError),
+    (22, TxStatus::RevertInstruction),
+    (23, TxStatus::InvalidZeroSignatureFormat),
+    (24, TxStatus::AddressAlreadyUsed),
+    (25, TxStatus::PermissionDenied),
+    (26, TxStatus::CallAddressError),
+    (27, TxStatus::GasOverflow),
+    (28, TxStatus::TxPoolIsFull),
+    (29, TxStatus::TransactionRefused),
+    (30, TxStatus::ContractFrozen),
+    (31, TxStatus::AccountFrozen),
+    (10000, TxStatus::AlreadyInTxPool),
+    (10001, TxStatus::TxAlreadyInChain),
+    (10002, TxStatus::InvalidChainId),
+    (10003, TxStatus::InvalidGroupId),
+    (10004, TxStatus::RequestNotBelongToTheGroup),
+    (10005, TxStatus::MalformedTx),
+    (10006, TxStatus::OverGroupMemoryLimit),
+];
+
+//FISCO BCOS 3.x bcos-framework的TransactionStatus
+const BCOS3_STATUS: &[(i64, TxStatus)] = &[
+    (0, TxStatus::Success),
+    (1, TxStatus::Unknown),
+    (2, TxStatus::OutOfGasLimit),
+    (7, TxStatus::NotEnoughCash),
+    (10, TxStatus::BadInstruction),
+    (11, TxStatus::BadJumpDestination),
+    (12, TxStatus::OutOfGas),
+    (13, TxStatus::OutOfStack),
+    (14, TxStatus::StackUnderflow),
+    (15, TxStatus::PrecompiledError),
+    (16, TxStatus::RevertInstruction),
+    (17, TxStatus::AddressAlreadyUsed),
+    (18, TxStatus::PermissionDenied),
+    (19, TxStatus::CallAddressError),
+    (20, TxStatus::GasOverflow),
+    (21, TxStatus::ContractFrozen),
+    (22, TxStatus::AccountFrozen),
+    (23, TxStatus::AccountAbolished),
+    (24, TxStatus::ContractAbolished),
+    (32, TxStatus::WASMValidationFailure),
+    (33, TxStatus::WASMArgumentOutOfRange),
+    (34, TxStatus::WASMUnreachableInstruction),
+    (35, TxStatus::WASMTrap),
+    (10000, TxStatus::NonceCheckFail),
+    (10001, TxStatus::BlockLimitCheckFail),
+    (10002, TxStatus::TxPoolIsFull),
+    (10003, TxStatus::MalformedTx),
+    (10004, TxStatus::AlreadyInTxPool),
+    (10005, TxStatus::TxAlreadyInChain),
+    (10006, TxStatus::InvalidChainId),
+    (10007, TxStatus::InvalidGroupId),
+    (10008, TxStatus::InvalidSignature),
+    (10009, TxStatus::RequestNotBelongToTheGroup),
+    (10010, TxStatus::TransactionPoolTimeout),
+    (10011, TxStatus::AlreadyInTxPoolAndAccept),
+];
+
+fn lookup(table: &[(i64, TxStatus)], code: i64) -> TxStatus {
+    match table.iter().find(|(c, _)| *c == code) {
+        Some((_, s)) => *s,
+        None => TxStatus::Other(code),
+    }
+}
+
+impl TxStatus {
+    pub fn from_bcos2(code: i64) -> TxStatus {
+        lookup(BCOS2_STATUS, code)
+    }
+    pub fn from_bcos3(code: i64) -> TxStatus {
+        lookup(BCOS3_STATUS, code)
+    }
+    pub fn is_success(&self) -> bool {
+        *self == TxStatus::Success
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            TxStatus::Success => "成功",
+            TxStatus::Unknown => "未知错误",
+            TxStatus::BadRLP => "RLP编码错误",
+            TxStatus::InvalidFormat => "交易格式错误",
+            TxStatus::OutOfGasLimit => "gas不足以支付交易的基础开销",
+            TxStatus::InvalidSignature => "签名错误",
+            TxStatus::InvalidNonce => "nonce错误",
+            TxStatus::NotEnoughCash => "余额不足",
+            TxStatus::OutOfGasBase => "gas不足",
+            TxStatus::BlockGasLimitReached => "超出区块gas上限",
+            TxStatus::BadInstruction => "非法指令",
+            TxStatus::BadJumpDestination => "非法跳转",
+            TxStatus::OutOfGas => "执行时gas耗尽",
+            TxStatus::OutOfStack => "栈溢出",
+            TxStatus::StackUnderflow => "栈下溢",
+            TxStatus::NonceCheckFail => "nonce检查失败(重复交易)",
+            TxStatus::BlockLimitCheckFail => "blocklimit检查失败(交易已过期)",
+            TxStatus::FilterCheckFail => "交易过滤检查失败",
+            TxStatus::NoDeployPermission => "没有部署合约的权限",
+            TxStatus::NoCallPermission => "没有调用合约的权限",
+            TxStatus::NoTxPermission => "没有发送交易的权限",
+            TxStatus::PrecompiledError => "预编译合约执行出错",
+            TxStatus::RevertInstruction => "合约执行revert",
+            TxStatus::InvalidZeroSignatureFormat => "零签名格式错误",
+            TxStatus::AddressAlreadyUsed => "合约地址已被使用",
+            TxStatus::PermissionDenied => "权限不足",
+            TxStatus::CallAddressError => "调用的合约地址不存在",
+            TxStatus::GasOverflow => "gas溢出",
+            TxStatus::TxPoolIsFull => "交易池已满",
+            TxStatus::TransactionRefused => "交易被拒绝",
+            TxStatus::ContractFrozen => "合约已冻结",
+            TxStatus::AccountFrozen => "账户已冻结",
+            TxStatus::AccountAbolished => "账户已废止",
+            TxStatus::ContractAbolished => "合约已废止",
+            TxStatus::WASMValidationFailure => "WASM合约校验失败",
+            TxStatus::WASMArgumentOutOfRange => "WASM参数越界",
+            TxStatus::WASMUnreachableInstruction => "WASM执行到unreachable指令",
+            TxStatus::WASMTrap => "WASM执行异常",
+            TxStatus::AlreadyInTxPool => "交易已在交易池中",
+            TxStatus::TxAlreadyInChain => "交易已上链",
+            TxStatus::InvalidChainId => "chainid错误",
+            TxStatus::InvalidGroupId => "groupid错误",
+            TxStatus::RequestNotBelongToTheGroup => "请求不属于该群组",
+            TxStatus::MalformedTx => "交易格式错误",
+            TxStatus::OverGroupMemoryLimit => "超出群组内存限制",
+            TxStatus::TransactionPoolTimeout => "交易池处理超时",
+            TxStatus::AlreadyInTxPoolAndAccept => "交易已在交易池中并被接受",
+            TxStatus::Other(_) => "未定义的状态码",
+        }
+    }
+}
+
+impl fmt::Display for TxStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TxStatus::Other(code) => write!(f, "Status{}({})", code, self.description()),
+            _ => write!(f, "{:?}({})", self, self.description()),
+        }
+    }
+}
+
+///合约revert时output里带的原因
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum RevertReason {
+    //require(cond, "msg")、revert("msg")
+    Error(String),
+    //assert失败、溢出、除0等，见panic_description
+    Panic(Uint),
+}
+
+impl RevertReason {
+    ///output是hex字符串(可带0x)，不是Error(string)/Panic(uint256)时返回None
+    pub fn decode(output: &str) -> Option<RevertReason> {
+        let data = hex::decode(output.trim().trim_start_matches("0x")).ok()?;
+        if data.len() < 4 {
+            return None;
+        }
+        let (selector, body) = data.split_at(4);
+        if is_selector(selector, "Error(string)") {
+            match ethabi::decode(&[ParamType::String], body).ok()?.pop()? {
+                Token::String(s) => return Some(RevertReason::Error(s)),
+                _ => return None,
+            }
+        }
+        if is_selector(selector, "Panic(uint256)") {
+            match ethabi::decode(&[ParamType::Uint(256)], body).ok()?.pop()? {
+                Token::Uint(code) => return Some(RevertReason::Panic(code)),
+                _ => return None,
+            }
+        }
+        None
+    }
+}
+
+fn is_selector(selector: &[u8], signature: &str) -> bool {
+    let data = signature.as_bytes().to_vec();
+    [HashType::WEDPR_KECCAK, HashType::WEDRP_SM3]
+        .iter()
+        .any(|hashtype| CommonHash::hash(&data, hashtype)[0..4] == *selector)
+}
+
+///solidity编译器定义的panic码
+pub fn panic_description(code: &Uint) -> &'static str {
+    if *code > Uint::from(u64::MAX) {
+        return "unknown panic";
+    }
+    match code.low_u64() {
+        0x00 => "generic compiler panic",
+        0x01 => "assert failed",
+        0x11 => "arithmetic overflow or underflow",
+        0x12 => "division or modulo by zero",
+        0x21 => "invalid enum value",
+        0x22 => "invalid storage byte array encoding",
+        0x31 => "pop on empty array",
+        0x32 => "array index out of bounds",
+        0x41 => "out of memory",
+        0x51 => "call to zero-initialized function",
+        _ => "unknown panic",
+    }
+}
+
+impl fmt::Display for RevertReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RevertReason::Error(msg) => write!(f, "Error({:?})", msg),
+            RevertReason::Panic(code) => {
+                write!(f, "Panic(0x{:02x}: {})", code, panic_description(code))
+            }
+        }
+    }
+}
+
+///回执的状态：原始状态码、按链版本解析的状态和revert原因
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ReceiptStatus {
+    pub code: i64,
+    pub status: TxStatus,
+    pub revert: Option<RevertReason>,
+}
+
+impl ReceiptStatus {
+    pub fn from_bcos2(code: i64, output: &str) -> ReceiptStatus {
+        Self::build(code, TxStatus::from_bcos2(code), output)
+    }
+    pub fn from_bcos3(code: i64, output: &str) -> ReceiptStatus {
+        Self::build(code, TxStatus::from_bcos3(code), output)
+    }
+
+    fn build(code: i64, status: TxStatus, output: &str) -> ReceiptStatus {
+        let revert = match status.is_success() {
+            true => None,
+            false => RevertReason::decode(output),
+        };
+        ReceiptStatus {
+            code,
+            status,
+            revert,
+        }
+    }
+
+    ///bcos2的回执json，可以是带result的完整jsonrpc应答
+    pub fn from_bcos2_receipt(receipt: &JsonValue) -> ReceiptStatus {
+        let receipt = unwrap_result(receipt);
+        Self::from_bcos2(
+            status_code(receipt),
+            receipt["output"].as_str().unwrap_or(""),
+        )
+    }
+    pub fn from_bcos3_receipt(receipt: &JsonValue) -> ReceiptStatus {
+        let receipt = unwrap_result(receipt);
+        Self::from_bcos3(
+            status_code(receipt),
+            receipt["output"].as_str().unwrap_or(""),
+        )
+    }
+    ///不知道链版本时，按status是hex字符串(bcos2)还是数字(bcos3)判断
+    pub fn from_receipt(receipt: &JsonValue) -> ReceiptStatus {
+        match unwrap_result(receipt)["status"].is_string() {
+            true => Self::from_bcos2_receipt(receipt),
+            false => Self::from_bcos3_receipt(receipt),
+        }
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.status.is_success()
+    }
+
+    ///失败时返回ETxFailed，code为节点返回的原始状态码
+    pub fn check(&self, txhash: &str) -> Result<(), KissError> {
+        if self.is_success() {
+            return Ok(());
+        }
+        kisserrcode!(
+            KissErrKind::ETxFailed,
+            self.code,
+            "transaction {} failed: {}",
+            txhash,
+            self
+        )
+    }
+}
+
+impl fmt::Display for ReceiptStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.status)?;
+        if let Some(revert) = &self.revert {
+            write!(f, ", {}", revert)?;
+        }
+        Ok(())
+    }
+}
+
+fn unwrap_result(receipt: &JsonValue) -> &JsonValue {
+    match receipt.get("result") {
+        Some(v) => v,
+        None => receipt,
+    }
+}
+
+fn status_code(receipt: &JsonValue) -> i64 {
+    match &receipt["status"] {
+        JsonValue::String(s) => match s.strip_prefix("0x") {
+            Some(hexstr) => i64::from_str_radix(hexstr, 16).unwrap_or(-1),
+            None => s.parse::<i64>().unwrap_or(-1),
+        },
+        v => v.as_i64().unwrap_or(-1),
+    }
+}
+
+///检查bcos2回执，失败时返回ETxFailed
+pub fn check_bcos2_receipt(receipt: &JsonValue) -> Result<(), KissError> {
+    let txhash = unwrap_result(receipt)["transactionHash"]
+        .as_str()
+        .unwrap_or("");
+    ReceiptStatus::from_bcos2_receipt(receipt).check(txhash)
+}
+
+///检查bcos3回执，失败时返回ETxFailed
+pub fn check_bcos3_receipt(receipt: &JsonValue) -> Result<(), KissError> {
+    let txhash = unwrap_result(receipt)["transactionHash"]
+        .as_str()
+        .unwrap_or("");
+    ReceiptStatus::from_bcos3_receipt(receipt).check(txhash)
+}
@@ -23,6 +23,9 @@ pub struct Cli {
     ///-v -vv -vvv...打开详细的打印
     #[structopt(short = "v", parse(from_occurrences))]
     pub verbos: u32,
+    ///--output json 查询和合约命令只输出一个json结果，可以直接用管道交给jq等工具处理
+    #[structopt(long = "output", default_value = "text", possible_values = &["text", "json"])]
+    pub output: String,
 }
 
 #[derive(StructOpt, Debug)]
@@ -47,4 +50,7 @@ impl Cli {
         let configfile = self.default_configfile();
         ClientConfig::load(configfile.as_str())
     }
+    pub fn json_output(&self) -> bool {
+        self.output == "json"
+    }
 }
@@ -8,22 +8,62 @@
     overflowing_literals
 )]
 use crate::bcossdkutil::accountutil::{
-    account_from_pem, create_account, save_key_to_pem, BcosAccount, EcdsaAccountUtil,
-    GMAccountUtil, IBcosAccountUtil,
+    account_from_keystore, create_account, BcosAccount, EcdsaAccountUtil, GMAccountUtil,
+    IBcosAccountUtil,
 };
 use crate::bcossdkutil::bcosclientconfig::{BcosCryptoKind, ClientConfig};
-use crate::bcossdkutil::kisserror::KissError;
+use crate::bcossdkutil::hdwallet::{self, HDWallet};
+use crate::bcossdkutil::keystore::{self, KeystoreFormat};
+use crate::bcossdkutil::kisserror::{KissErrKind, KissError};
+use crate::kisserr;
 use hex::ToHex;
 use std::path::PathBuf;
 use structopt::StructOpt;
 
 /// bcos_rust_sdk account new alice
 /// bcos_rust_sdk account show alice/none for all
+/// bcos_rust_sdk account new alice --format json --password env:ALICE_PASS
+/// bcos_rust_sdk account mnemonic --words 24
+/// bcos_rust_sdk account derive --mnemonic env:SEED --index 0 --count 10
+/// bcos_rust_sdk account derive bob --mnemonic file:seed.txt --index 3
 #[derive(StructOpt, Debug)]
-#[structopt(about = "account new&show")]
+#[structopt(about = "account new&show&mnemonic&derive")]
 struct OptAccount {
     operation: String,
     name: Option<String>,
+    ///新建账户的保存格式：pem/encrypted-pem/p12/json
+    #[structopt(long, default_value = "pem")]
+    format: String,
+    ///密码来源 pass:xxx/env:VAR/file:path，不传时用配置文件的common.accountpassword
+    #[structopt(long)]
+    password: Option<String>,
+    ///助记词来源，写法同--password
+    #[structopt(long)]
+    mnemonic: Option<String>,
+    ///助记词的口令(BIP39 passphrase)来源，写法同--password，默认为空
+    #[structopt(long)]
+    passphrase: Option<String>,
+    ///新助记词的词数：12/15/18/21/24
+    #[structopt(long, default_value = "12")]
+    words: usize,
+    ///派生的第一个账户序号
+    #[structopt(long, default_value = "0")]
+    index: u32,
+    ///派生显示的账户数
+    #[structopt(long, default_value = "1")]
+    count: u32,
+    ///账户的父路径，完整路径为 {path}/{index}
+    #[structopt(long, default_value = hdwallet::DEFAULT_HD_PATH)]
+    path: String,
+}
+
+impl OptAccount {
+    fn password(&self, config: &ClientConfig) -> Result<Option<String>, KissError> {
+        match &self.password {
+            Some(source) => Ok(Some(keystore::read_password_source(source)?)),
+            None => config.common.account_password(),
+        }
+    }
 }
 
 pub fn cmd_account(cli: &Cli) -> Result<(), KissError> {
@@ -42,12 +82,68 @@ pub fn cmd_account(cli: &Cli) -> Result<(), KissError> {
     println!("{:?}", opt);
     match opt.operation.as_str() {
         "new" => {
-            return newaccount(&opt.name, &config, workpath.to_str().unwrap());
+            let password = opt.password(&config)?;
+            return newaccount(
+                &opt.name,
+                &config,
+                workpath.to_str().unwrap(),
+                opt.format.as_str(),
+                password.as_deref(),
+            );
         }
         "show" => {
-            let res = showaccount(&opt.name, &config, workpath.to_str().unwrap());
+            let password = opt.password(&config)?;
+            let res = showaccount(
+                &opt.name,
+                &config,
+                workpath.to_str().unwrap(),
+                password.as_deref(),
+            );
             println!("show account {:?}", res);
         }
+        "mnemonic" => {
+            let phrase = hdwallet::generate_mnemonic(opt.words)?;
+            println!(">>> new mnemonic, keep it safe and offline:\n{}\n", phrase);
+            let wallet = HDWallet::from_mnemonic(phrase.as_str(), "", &config.common.crypto)?
+                .with_path(opt.path.as_str());
+            show_derived_accounts(&wallet, opt.index, opt.count)?;
+        }
+        "derive" => {
+            let source = match &opt.mnemonic {
+                Some(s) => s,
+                None => {
+                    return kisserr!(
+                        KissErrKind::EArgument,
+                        "derive needs --mnemonic pass:/env:/file:"
+                    )
+                }
+            };
+            let phrase = keystore::read_password_source(source)?;
+            let passphrase = match &opt.passphrase {
+                Some(p) => keystore::read_password_source(p)?,
+                None => "".to_string(),
+            };
+            let wallet = HDWallet::from_mnemonic(
+                phrase.as_str(),
+                passphrase.as_str(),
+                &config.common.crypto,
+            )?
+            .with_path(opt.path.as_str());
+            show_derived_accounts(&wallet, opt.index, opt.count)?;
+            //指定了名字时，把第index个账户保存下来，供配置文件的accountpem使用
+            if opt.name.is_some() {
+                let account = wallet.derive_account(opt.index)?;
+                let password = opt.password(&config)?;
+                return save_account(
+                    &account,
+                    &opt.name,
+                    &config,
+                    workpath.to_str().unwrap(),
+                    opt.format.as_str(),
+                    password.as_deref(),
+                );
+            }
+        }
         _ => {}
     }
     Ok(())
@@ -57,28 +153,84 @@ pub fn newaccount(
     name: &Option<String>,
     config: &ClientConfig,
     workpath: &str,
+    format: &str,
+    password: Option<&str>,
 ) -> Result<(), KissError> {
     let newaccount = create_account(&config.common.crypto);
     println!(">>> create new account --> \n{}", newaccount.to_hexdetail());
+    save_account(&newaccount, name, config, workpath, format, password)
+}
+
+pub fn show_derived_accounts(wallet: &HDWallet, index: u32, count: u32) -> Result<(), KissError> {
+    for i in index..index.saturating_add(count) {
+        let account = wallet.derive_account(i)?;
+        println!(
+            "{} : 0x{}",
+            wallet.account_path(i),
+            hex::encode(&account.address)
+        );
+    }
+    Ok(())
+}
+
+///按格式保存账户，没有名字时用地址作为文件名
+pub fn save_account(
+    account: &BcosAccount,
+    name: &Option<String>,
+    config: &ClientConfig,
+    workpath: &str,
+    format: &str,
+    password: Option<&str>,
+) -> Result<(), KissError> {
+    let (keyformat, encrypted) = match format {
+        "pem" => (KeystoreFormat::PEM, false),
+        "encrypted-pem" => (KeystoreFormat::PEM, true),
+        "p12" => (KeystoreFormat::P12, true),
+        "json" => (KeystoreFormat::JSON, true),
+        _ => {
+            return kisserr!(
+                KissErrKind::EArgument,
+                "unknown account format {}, use pem/encrypted-pem/p12/json",
+                format
+            )
+        }
+    };
+    if encrypted && password.is_none() {
+        return kisserr!(
+            KissErrKind::EArgument,
+            "account format {} needs --password or common.accountpassword",
+            format
+        );
+    }
     let mut fullpath = PathBuf::from(workpath);
+    let ext = keyformat.extension();
     match name {
         Some(n) => {
-            fullpath = fullpath.join(format!("{}.pem", n));
+            fullpath = fullpath.join(format!("{}.{}", n, ext));
         }
         Option::None => {
-            fullpath = fullpath.join(format!("{}.pem", hex::encode(&newaccount.address)));
+            fullpath = fullpath.join(format!("{}.{}", hex::encode(&account.address), ext));
         }
     }
-    println!("new account save to : {}", fullpath.to_str().unwrap());
-    save_key_to_pem(&newaccount.privkey, fullpath.as_path().to_str().unwrap())
+    println!("account save to : {}", fullpath.to_str().unwrap());
+    keystore::save_key(
+        &account.privkey,
+        &config.common.crypto,
+        fullpath.as_path().to_str().unwrap(),
+        if encrypted { password } else { None },
+    )
 }
 
-pub fn show_account_from_pem(path: &str, cryptokind: &BcosCryptoKind) -> Result<(), KissError> {
+pub fn show_account_from_pem(
+    path: &str,
+    cryptokind: &BcosCryptoKind,
+    password: Option<&str>,
+) -> Result<(), KissError> {
     println!(
         "\n>>> load acccount from {} ,crypto : {:?}",
         path, cryptokind
     );
-    let account = account_from_pem(path, cryptokind)?;
+    let account = account_from_keystore(path, password, cryptokind)?;
 
     println!("{}", account.to_hexdetail());
     Ok(())
@@ -91,15 +243,20 @@ pub fn showaccount(
     name: &Option<String>,
     config: &ClientConfig,
     workpath: &str,
+    password: Option<&str>,
 ) -> Result<(), KissError> {
     // bcossdkutil::macrodef::set_debugprint(true);
     //println!("name {:?}",name);
     match name {
         Some(n) => {
-            let mut path = PathBuf::from(workpath);
-            path = path.join(format!("{}.pem", n));
+            //按pem,p12,json的顺序找第一个存在的账户文件
+            let path = ["pem", "p12", "json"]
+                .iter()
+                .map(|ext| PathBuf::from(workpath).join(format!("{}.{}", n, ext)))
+                .find(|p| p.exists())
+                .unwrap_or_else(|| PathBuf::from(workpath).join(format!("{}.pem", n)));
             //println!("path is {:?}",path);
-            return show_account_from_pem(path.to_str().unwrap(), &config.common.crypto);
+            return show_account_from_pem(path.to_str().unwrap(), &config.common.crypto, password);
         }
         _ => {}
     }
@@ -114,8 +271,15 @@ pub fn showaccount(
         match extstr {
             Some(ss) => {
                 let exts = ss.to_str().unwrap();
-                if exts == "pem" {
-                    show_account_from_pem(entry.path().to_str().unwrap(), &config.common.crypto)?
+                if ["pem", "p12", "json"].contains(&exts) {
+                    //目录里可能有其他json文件，或者没有密码的加密文件，跳过继续显示其他账户
+                    if let Err(e) = show_account_from_pem(
+                        entry.path().to_str().unwrap(),
+                        &config.common.crypto,
+                        password,
+                    ) {
+                        println!("skip {} : {:?}", entry.path().display(), e);
+                    }
                 }
             }
             _ => {}
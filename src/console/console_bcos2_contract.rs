@@ -6,14 +6,17 @@ use fisco_bcos_rust_gears_sdk::bcossdkutil::kisserror::{KissErrKind, KissError};
 
 use crate::console::cli_common::OptContract;
 use crate::console::console_compile::console_compile;
+use crate::console::console_utils::{print_json, transaction_to_json};
 use crate::console_cmdmap::CliCmdMap;
 use crate::kisserr;
+use crate::kisserrcode;
 use crate::sample::demo_bcos3client::demo_bcos3client;
 use crate::{cmdmap, Cli};
 use fisco_bcos_rust_gears_sdk::bcos2sdk::bcossdkquery::json_hextoint;
 use fisco_bcos_rust_gears_sdk::bcossdkutil::bcosclientconfig::{BcosCryptoKind, ClientConfig};
 use fisco_bcos_rust_gears_sdk::bcossdkutil::solcompile::sol_compile;
-use serde_json::Value as JsonValue;
+use fisco_bcos_rust_gears_sdk::bcossdkutil::txstatus::ReceiptStatus;
+use serde_json::{json, Value as JsonValue};
 use std::path::PathBuf;
 use std::process::Command;
 use std::thread;
@@ -40,14 +43,18 @@ impl Bcos2Contract {
 pub fn deploy(cli: &Cli) -> Result<(), KissError> {
     let configfile = cli.default_configfile();
     let mut bcossdk = Bcos2Client::new_from_config(configfile.as_str())?;
-    println!("BcosSDK: {}", bcossdk.to_summary());
+    if !cli.json_output() {
+        println!("BcosSDK: {}", bcossdk.to_summary());
+    }
     //每次部署前强制编译一次对应合约，考虑到合约sol可能会有修改
     let res = console_compile(cli)?;
 
     let contractname = &cli.params[0];
 
     let params = &cli.params[1..];
-    println!("deploy contract {} ,params:{:?}", contractname, params);
+    if !cli.json_output() {
+        println!("deploy contract {} ,params:{:?}", contractname, params);
+    }
 
     let binfile = format!(
         "{}/{}.bin",
@@ -68,10 +75,6 @@ pub fn deploy(cli: &Cli) -> Result<(), KissError> {
         Ok(v) => {
             let address = v["result"]["contractAddress"].as_str().unwrap();
             let blocknum = json_hextoint(&v["result"]["blockNumber"]).unwrap();
-            println!(
-                "deploy contract on block[{}], address is {}",
-                blocknum, address
-            );
             let chf = ContractHistory::history_file(bcossdk.config.common.contractpath.as_str());
             let res = ContractHistory::save_to_file(
                 chf.as_str(),
@@ -80,6 +83,19 @@ pub fn deploy(cli: &Cli) -> Result<(), KissError> {
                 address,
                 blocknum as u64,
             );
+            if cli.json_output() {
+                print_json(&json!({
+                    "contractName": contractname,
+                    "address": address,
+                    "blockNumber": blocknum,
+                    "transactionHash": txhash,
+                }));
+                return Ok(());
+            }
+            println!(
+                "deploy contract on block[{}], address is {}",
+                blocknum, address
+            );
             println!("save contract history to file {} ,{:?}", chf, res);
         }
         Err(e) => {
@@ -102,7 +118,9 @@ pub fn sendtx(cli: &Cli) -> Result<(), KissError> {
     let mut bcossdk = Bcos2Client::new_from_config(configfile.as_str())?;
     let contractdir = "contracts";
     let contractfullname = format!("{}/{}.abi", contractdir, &opt.contract_name);
-    println!("contract file is {}", contractfullname);
+    if !cli.json_output() {
+        println!("contract file is {}", contractfullname);
+    }
     let contract = ContractABI::new(contractfullname.as_str(), &bcossdk.hashtype)?;
     let chfile = format!(
         "{}/contracthistory.toml",
@@ -115,7 +133,9 @@ pub fn sendtx(cli: &Cli) -> Result<(), KissError> {
         opt.address.as_str(),
     )?;
 
-    println!("contract address is {}", &address.as_str());
+    if !cli.json_output() {
+        println!("contract address is {}", &address.as_str());
+    }
     let response = bcossdk.send_raw_transaction(
         &contract,
         address.as_str(),
@@ -125,10 +145,19 @@ pub fn sendtx(cli: &Cli) -> Result<(), KissError> {
     //println!("send_raw_transaction result {:?}", response);
     //println!("response[\"result\"] {:?}",response);
     let txhash = response["result"].as_str().unwrap();
-    println!("\n>>>>>>>>>>>>>>>>>>>after sendtx getTransactionByHash");
+    if !cli.json_output() {
+        println!("\n>>>>>>>>>>>>>>>>>>>after sendtx getTransactionByHash");
+    }
     let receiptresult = bcossdk.try_getTransactionReceipt(txhash, 3, false);
     //println!("receiptresult result :{:?} ",receiptresult);
     match receiptresult {
+        Ok(receipt) if cli.json_output() => {
+            print_json(&transaction_to_json(
+                &JsonValue::Null,
+                &receipt,
+                Some(&contract),
+            ));
+        }
         Ok(receipt) => {
             crate::console::console_utils::display_transaction_receipt(
                 &receipt,
@@ -156,7 +185,9 @@ pub fn call(cli: &Cli) -> Result<(), KissError> {
     let mut bcossdk = Bcos2Client::new_from_config(configfile.as_str())?;
     let contractdir = "contracts";
     let contractfullname = format!("{}/{}.abi", contractdir, &opt.contract_name);
-    println!("contract file is {}", contractfullname);
+    if !cli.json_output() {
+        println!("contract file is {}", contractfullname);
+    }
     let contract = ContractABI::new(contractfullname.as_str(), &bcossdk.hashtype)?;
     let chfile = format!(
         "{}/contracthistory.toml",
@@ -169,26 +200,54 @@ pub fn call(cli: &Cli) -> Result<(), KissError> {
         opt.address.as_str(),
     )?;
 
-    println!("contract address is {}", &address.as_str());
+    if !cli.json_output() {
+        println!("contract address is {}", &address.as_str());
+    }
     let res = bcossdk.call(
         &contract,
         address.as_str(),
         opt.method.as_str(),
         opt.params.as_slice(),
     )?;
+    let status = res["result"]["status"].as_str().unwrap();
+    let ustatus = u32::from_str_radix(status.trim_start_matches("0x"), 16).unwrap();
+    //失败时output里可能带有revert原因
+    let callstatus = ReceiptStatus::from_bcos2(
+        ustatus as i64,
+        res["result"]["output"].as_str().unwrap_or(""),
+    );
+    if cli.json_output() {
+        if ustatus != 0 {
+            return kisserrcode!(
+                KissErrKind::Error,
+                ustatus as i64,
+                "call error {}",
+                callstatus
+            );
+        }
+        let output = res["result"]["output"].as_str().unwrap();
+        print_json(&json!({
+            "status": ustatus,
+            "output": contract.decode_output_to_json(opt.method.as_str(), output)?,
+        }));
+        return Ok(());
+    }
     println!(
         "call result :{}",
         serde_json::to_string_pretty(&res).unwrap()
     );
-    let status = res["result"]["status"].as_str().unwrap();
-    let ustatus = u32::from_str_radix(status.trim_start_matches("0x"), 16).unwrap();
     println!("call status code {} ({:?}) ", status, ustatus);
     if ustatus == 0 {
         let output = res["result"]["output"].as_str().unwrap();
         let decodereuslt = contract.decode_output_byname(opt.method.as_str(), output);
         println!("call output: {:?}", decodereuslt);
     } else {
-        return kisserr!(KissErrKind::Error, "call error !!!");
+        return kisserrcode!(
+            KissErrKind::Error,
+            ustatus as i64,
+            "call error {}",
+            callstatus
+        );
     }
     Ok(())
 }
@@ -0,0 +1,241 @@
+use fisco_bcos_rust_gears_sdk::bcos2sdk::bcos2client::Bcos2Client;
+use fisco_bcos_rust_gears_sdk::bcos2sdk::precompiled::{
+    PERMISSION_TABLE_CNS, PERMISSION_TABLE_DEPLOY_AND_CREATE, PERMISSION_TABLE_NODE,
+    PERMISSION_TABLE_PERMISSION, PERMISSION_TABLE_SYS_CONFIG,
+};
+use fisco_bcos_rust_gears_sdk::bcossdkutil::fileutils;
+use fisco_bcos_rust_gears_sdk::bcossdkutil::kisserror::KissError;
+use serde_json::json;
+
+use crate::console::console_cmdmap::CliCmdMap;
+use crate::console::console_utils::{param_at, print_json};
+use crate::{cmdmap, Cli};
+
+///FISCO BCOS 2.x预编译合约的管理命令，命令名和FISCO BCOS控制台保持一致
+pub struct Bcos2Precompiled {
+    pub cmdmap: CliCmdMap,
+}
+impl Bcos2Precompiled {
+    pub fn new() -> Self {
+        let mut cmdhandler = Bcos2Precompiled {
+            cmdmap: CliCmdMap::new("BCOS2 Precompiled"),
+        };
+        cmdmap!(cmdhandler.cmdmap.cmd_func_map, setSystemConfigByKey);
+        cmdmap!(cmdhandler.cmdmap.cmd_func_map, addSealer);
+        cmdmap!(cmdhandler.cmdmap.cmd_func_map, addObserver);
+        cmdmap!(cmdhandler.cmdmap.cmd_func_map, removeNode);
+        cmdmap!(cmdhandler.cmdmap.cmd_func_map, registerCNS);
+        cmdmap!(cmdhandler.cmdmap.cmd_func_map, queryCNS);
+        cmdmap!(cmdhandler.cmdmap.cmd_func_map, grantPermission);
+        cmdmap!(cmdhandler.cmdmap.cmd_func_map, revokePermission);
+        cmdmap!(cmdhandler.cmdmap.cmd_func_map, listPermission);
+        cmdmap!(cmdhandler.cmdmap.cmd_func_map, grantContractWritePermission);
+        cmdmap!(
+            cmdhandler.cmdmap.cmd_func_map,
+            revokeContractWritePermission
+        );
+        cmdmap!(cmdhandler.cmdmap.cmd_func_map, listContractWritePermission);
+        cmdmap!(cmdhandler.cmdmap.cmd_func_map, freezeContract);
+        cmdmap!(cmdhandler.cmdmap.cmd_func_map, unfreezeContract);
+        cmdmap!(cmdhandler.cmdmap.cmd_func_map, grantContractStatusManager);
+        cmdmap!(cmdhandler.cmdmap.cmd_func_map, getContractStatus);
+        cmdmap!(cmdhandler.cmdmap.cmd_func_map, listContractStatusManager);
+        cmdhandler
+    }
+}
+
+fn client(cli: &Cli) -> Result<Bcos2Client, KissError> {
+    Bcos2Client::new_from_config(cli.default_configfile().as_str())
+}
+
+//写操作成功时返回码一般是影响的行数
+fn print_code(cli: &Cli, code: i64) {
+    if cli.json_output() {
+        print_json(&json!({ "code": code }));
+    } else {
+        println!("{} success, return code {}", cli.cmd, code);
+    }
+}
+
+//权限表可以用简称：deploy、node、cns、sysconfig、permission，其余原样作为表名
+fn permission_table(name: &str) -> String {
+    match name.to_lowercase().as_str() {
+        "deploy" => PERMISSION_TABLE_DEPLOY_AND_CREATE.to_string(),
+        "node" => PERMISSION_TABLE_NODE.to_string(),
+        "cns" => PERMISSION_TABLE_CNS.to_string(),
+        "sysconfig" => PERMISSION_TABLE_SYS_CONFIG.to_string(),
+        "permission" => PERMISSION_TABLE_PERMISSION.to_string(),
+        _ => name.to_string(),
+    }
+}
+
+pub fn setSystemConfigByKey(cli: &Cli) -> Result<(), KissError> {
+    let key = param_at(&cli.params, 0)?;
+    let value = param_at(&cli.params, 1)?;
+    let mut bcossdk = client(cli)?;
+    let code = bcossdk.set_system_config(key.as_str(), value.as_str());
+    bcossdk.finish();
+    print_code(cli, code?);
+    Ok(())
+}
+
+pub fn addSealer(cli: &Cli) -> Result<(), KissError> {
+    let nodeid = param_at(&cli.params, 0)?;
+    let mut bcossdk = client(cli)?;
+    let code = bcossdk.add_sealer(nodeid.as_str());
+    bcossdk.finish();
+    print_code(cli, code?);
+    Ok(())
+}
+
+pub fn addObserver(cli: &Cli) -> Result<(), KissError> {
+    let nodeid = param_at(&cli.params, 0)?;
+    let mut bcossdk = client(cli)?;
+    let code = bcossdk.add_observer(nodeid.as_str());
+    bcossdk.finish();
+    print_code(cli, code?);
+    Ok(())
+}
+
+pub fn removeNode(cli: &Cli) -> Result<(), KissError> {
+    let nodeid = param_at(&cli.params, 0)?;
+    let mut bcossdk = client(cli)?;
+    let code = bcossdk.remove_node(nodeid.as_str());
+    bcossdk.finish();
+    print_code(cli, code?);
+    Ok(())
+}
+
+///registerCNS [合约名] [地址] [版本]，abi取合约目录下的[合约名].abi，没有时为空
+pub fn registerCNS(cli: &Cli) -> Result<(), KissError> {
+    let name = param_at(&cli.params, 0)?;
+    let address = param_at(&cli.params, 1)?;
+    let version = param_at(&cli.params, 2)?;
+    let mut bcossdk = client(cli)?;
+    let abifile = format!("{}/{}.abi", bcossdk.config.common.contractpath, name);
+    let abi = fileutils::readstring(abifile.as_str()).unwrap_or_default();
+    let code = bcossdk.cns_register(
+        name.as_str(),
+        version.as_str(),
+        address.as_str(),
+        abi.as_str(),
+    );
+    bcossdk.finish();
+    print_code(cli, code?);
+    Ok(())
+}
+
+///queryCNS [合约名] [版本(可选)]
+pub fn queryCNS(cli: &Cli) -> Result<(), KissError> {
+    let name = param_at(&cli.params, 0)?;
+    let version = cli.params.get(1);
+    let mut bcossdk = client(cli)?;
+    let v = bcossdk.cns_query(name.as_str(), version.map(|v| v.as_str()));
+    bcossdk.finish();
+    print_json(&v?);
+    Ok(())
+}
+
+pub fn grantPermission(cli: &Cli) -> Result<(), KissError> {
+    let table = permission_table(param_at(&cli.params, 0)?.as_str());
+    let account = param_at(&cli.params, 1)?;
+    let mut bcossdk = client(cli)?;
+    let code = bcossdk.grant_permission(table.as_str(), account.as_str());
+    bcossdk.finish();
+    print_code(cli, code?);
+    Ok(())
+}
+
+pub fn revokePermission(cli: &Cli) -> Result<(), KissError> {
+    let table = permission_table(param_at(&cli.params, 0)?.as_str());
+    let account = param_at(&cli.params, 1)?;
+    let mut bcossdk = client(cli)?;
+    let code = bcossdk.revoke_permission(table.as_str(), account.as_str());
+    bcossdk.finish();
+    print_code(cli, code?);
+    Ok(())
+}
+
+pub fn listPermission(cli: &Cli) -> Result<(), KissError> {
+    let table = permission_table(param_at(&cli.params, 0)?.as_str());
+    let mut bcossdk = client(cli)?;
+    let v = bcossdk.list_permission(table.as_str());
+    bcossdk.finish();
+    print_json(&v?);
+    Ok(())
+}
+
+pub fn grantContractWritePermission(cli: &Cli) -> Result<(), KissError> {
+    let contract = param_at(&cli.params, 0)?;
+    let account = param_at(&cli.params, 1)?;
+    let mut bcossdk = client(cli)?;
+    let code = bcossdk.grant_contract_write(contract.as_str(), account.as_str());
+    bcossdk.finish();
+    print_code(cli, code?);
+    Ok(())
+}
+
+pub fn revokeContractWritePermission(cli: &Cli) -> Result<(), KissError> {
+    let contract = param_at(&cli.params, 0)?;
+    let account = param_at(&cli.params, 1)?;
+    let mut bcossdk = client(cli)?;
+    let code = bcossdk.revoke_contract_write(contract.as_str(), account.as_str());
+    bcossdk.finish();
+    print_code(cli, code?);
+    Ok(())
+}
+
+pub fn listContractWritePermission(cli: &Cli) -> Result<(), KissError> {
+    let contract = param_at(&cli.params, 0)?;
+    let mut bcossdk = client(cli)?;
+    let v = bcossdk.list_contract_write(contract.as_str());
+    bcossdk.finish();
+    print_json(&v?);
+    Ok(())
+}
+
+pub fn freezeContract(cli: &Cli) -> Result<(), KissError> {
+    let contract = param_at(&cli.params, 0)?;
+    let mut bcossdk = client(cli)?;
+    let code = bcossdk.freeze_contract(contract.as_str());
+    bcossdk.finish();
+    print_code(cli, code?);
+    Ok(())
+}
+
+pub fn unfreezeContract(cli: &Cli) -> Result<(), KissError> {
+    let contract = param_at(&cli.params, 0)?;
+    let mut bcossdk = client(cli)?;
+    let code = bcossdk.unfreeze_contract(contract.as_str());
+    bcossdk.finish();
+    print_code(cli, code?);
+    Ok(())
+}
+
+pub fn grantContractStatusManager(cli: &Cli) -> Result<(), KissError> {
+    let contract = param_at(&cli.params, 0)?;
+    let account = param_at(&cli.params, 1)?;
+    let mut bcossdk = client(cli)?;
+    let code = bcossdk.grant_contract_manager(contract.as_str(), account.as_str());
+    bcossdk.finish();
+    print_code(cli, code?);
+    Ok(())
+}
+
+pub fn getContractStatus(cli: &Cli) -> Result<(), KissError> {
+    let contract = param_at(&cli.params, 0)?;
+    let mut bcossdk = client(cli)?;
+    let status = bcossdk.get_contract_status(contract.as_str());
+    bcossdk.finish();
+    print_json(&json!(status?));
+    Ok(())
+}
+
+pub fn listContractStatusManager(cli: &Cli) -> Result<(), KissError> {
+    let contract = param_at(&cli.params, 0)?;
+    let mut bcossdk = client(cli)?;
+    let managers = bcossdk.list_contract_manager(contract.as_str());
+    bcossdk.finish();
+    print_json(&json!(managers?));
+    Ok(())
+}
@@ -2,7 +2,7 @@ use crate::cmdmap;
 use crate::console::cli_common::Cli;
 use crate::console::console_utils::{
     cli_groupid, display_transaction, display_transaction_receipt, find_contract,
-    is_deploy_address, param_at, param_int,
+    is_deploy_address, param_at, param_int, print_json, transaction_to_json,
 };
 use crate::console_cmdmap::CliCmdMap;
 use fisco_bcos_rust_gears_sdk::bcos2sdk::bcos2client::Bcos2Client;
@@ -10,7 +10,7 @@ use fisco_bcos_rust_gears_sdk::bcos2sdk::bcossdkquery::json_hextoint;
 use fisco_bcos_rust_gears_sdk::bcossdkutil::kisserror::{KissErrKind, KissError};
 use fisco_bcos_rust_gears_sdk::bcossdkutil::liteutils::get_opt_str;
 use serde::de::Unexpected::Option as JsonOption;
-use serde_json::Value as JsonValue;
+use serde_json::{json, Value as JsonValue};
 use std::str::FromStr;
 
 pub struct Bcos2Query {
@@ -66,10 +66,44 @@ pub fn getNodeVersion(cli: &Cli) -> Result<(), KissError> {
 pub fn getBlockNumber(cli: &Cli) -> Result<(), KissError> {
     let mut bcossdk = Bcos2Client::new_from_config(cli.default_configfile().as_str())?;
     let v = bcossdk.getBlockNumber()?;
+    if cli.json_output() {
+        print_json(&json!(v));
+        return Ok(());
+    }
     println!("\n {:?}\n", v);
     Ok(())
 }
 
+///json模式下输出交易、回执和按abi解析的结果，找不到合约时只输出原始数据
+fn print_transaction_json(
+    cli: &Cli,
+    bcossdk: &mut Bcos2Client,
+    tx: &JsonValue,
+    receipt: &JsonValue,
+) -> Result<(), KissError> {
+    let receipt = match receipt.get("result") {
+        Some(r) => r.clone(),
+        None => receipt.clone(),
+    };
+    let to = tx["to"]
+        .as_str()
+        .or_else(|| receipt["to"].as_str())
+        .unwrap_or("");
+    let contract = match is_deploy_address(to) {
+        true => None,
+        false => find_contract(
+            cli,
+            "bcos2",
+            get_opt_str(&cli.contractname).as_str(),
+            to,
+            &bcossdk.config,
+        )
+        .ok(),
+    };
+    print_json(&transaction_to_json(tx, &receipt, contract.as_ref()));
+    Ok(())
+}
+
 pub fn getBlockByNumber(cli: &Cli) -> Result<(), KissError> {
     let mut bcossdk = Bcos2Client::new_from_config(cli.default_configfile().as_str())?;
     let num = u32::from_str_radix(cli.params[0].as_str(), 10).unwrap();
@@ -123,7 +157,13 @@ pub fn getTransactionByHash(cli: &Cli) -> Result<(), KissError> {
     let cmd = "getTransactionByHash";
     let hash = cli.params[0].as_str();
     let v = bcossdk.getTransactionByHash(hash)?;
+    if cli.json_output() {
+        let hash = v["result"]["hash"].as_str().unwrap_or("");
+        let receipt = bcossdk.getTransactionReceipt(hash)?;
+        return print_transaction_json(cli, &mut bcossdk, &v["result"], &receipt);
+    }
     let res = display_transaction(
+        cli,
         &v["result"],
         &bcossdk.config,
         "bcos2",
@@ -134,6 +174,7 @@ pub fn getTransactionByHash(cli: &Cli) -> Result<(), KissError> {
     let receipt = bcossdk.getTransactionReceipt(hash)?;
     let to = v["result"]["to"].as_str().unwrap();
     let contractABI = find_contract(
+        cli,
         "bcos2",
         get_opt_str(&cli.contractname).as_str(),
         to,
@@ -149,8 +190,14 @@ pub fn getTransactionByBlockNumberAndIndex(cli: &Cli) -> Result<(), KissError> {
     let num = u32::from_str_radix(cli.params[0].as_str(), 10).unwrap();
     let index = u32::from_str_radix(cli.params[1].as_str(), 10).unwrap();
     let v = bcossdk.getTransactionByBlockNumberAndIndex(num, index)?;
+    if cli.json_output() {
+        let hash = v["result"]["hash"].as_str().unwrap_or("");
+        let receipt = bcossdk.getTransactionReceipt(hash)?;
+        return print_transaction_json(cli, &mut bcossdk, &v["result"], &receipt);
+    }
     let cli_contractname = get_opt_str(&cli.contractname);
     let res = display_transaction(
+        cli,
         &v["result"],
         &bcossdk.config,
         "bcos2",
@@ -162,6 +209,7 @@ pub fn getTransactionByBlockNumberAndIndex(cli: &Cli) -> Result<(), KissError> {
     let receipt = bcossdk.getTransactionReceipt(hash)?;
     let to = v["result"]["to"].as_str().unwrap();
     let contractABI = find_contract(
+        cli,
         "bcos2",
         get_opt_str(&cli.contractname).as_str(),
         to,
@@ -177,7 +225,13 @@ pub fn getTransactionByBlockHashAndIndex(cli: &Cli) -> Result<(), KissError> {
     let blockhash = cli.params[0].as_str();
     let index = u32::from_str_radix(cli.params[1].as_str(), 10).unwrap();
     let v = bcossdk.getTransactionByBlockHashAndIndex(blockhash, index)?;
+    if cli.json_output() {
+        let hash = v["result"]["hash"].as_str().unwrap_or("");
+        let receipt = bcossdk.getTransactionReceipt(hash)?;
+        return print_transaction_json(cli, &mut bcossdk, &v["result"], &receipt);
+    }
     let res = display_transaction(
+        cli,
         &v["result"],
         &bcossdk.config,
         "bcos2",
@@ -189,6 +243,7 @@ pub fn getTransactionByBlockHashAndIndex(cli: &Cli) -> Result<(), KissError> {
     let receipt = bcossdk.getTransactionReceipt(hash)?;
     let to = v["result"]["to"].as_str().unwrap();
     let contract = find_contract(
+        cli,
         "bcos2",
         get_opt_str(&cli.contractname).as_str(),
         to,
@@ -204,6 +259,13 @@ pub fn getTransactionReceipt(cli: &Cli) -> Result<(), KissError> {
     let cmd = "getTransactionReceipt";
     let hash = cli.params[0].as_str();
     let v = bcossdk.getTransactionReceipt(hash)?;
+    if cli.json_output() {
+        if v["result"] == JsonValue::Null {
+            print_json(&v);
+            return Ok(());
+        }
+        return print_transaction_json(cli, &mut bcossdk, &JsonValue::Null, &v);
+    }
     println!(
         "\n[{}] : {}\n",
         cmd,
@@ -221,6 +283,7 @@ pub fn getTransactionReceipt(cli: &Cli) -> Result<(), KissError> {
     }
 
     let contractres = find_contract(
+        cli,
         "bcos2",
         get_opt_str(&cli.contractname).as_str(),
         to,
@@ -325,6 +388,9 @@ pub fn getTotalTransactionCount(cli: &Cli) -> Result<(), KissError> {
     let groupid = cli_groupid(&cli);
     let v = bcossdk.getTotalTransactionCount(groupid)?;
     println!("{}", serde_json::to_string_pretty(&v).unwrap());
+    if cli.json_output() {
+        return Ok(());
+    }
 
     let blockNumber = json_hextoint(&v["result"]["blockNumber"])?;
     let failedTxSum = json_hextoint(&v["result"]["failedTxSum"])?;
@@ -5,14 +5,17 @@ use fisco_bcos_rust_gears_sdk::bcossdkutil::kisserror::{KissErrKind, KissError};
 use crate::bcossdkutil::liteutils;
 use crate::console::cli_common::OptContract;
 use crate::console::console_compile::console_compile;
+use crate::console::console_utils::{print_json, transaction_to_json};
 use crate::console_cmdmap::CliCmdMap;
 use crate::kisserr;
+use crate::kisserrcode;
 use crate::{cmdmap, Cli};
 use fisco_bcos_rust_gears_sdk::bcos2sdk::bcossdkquery::json_hextoint;
 use fisco_bcos_rust_gears_sdk::bcos3sdk::bcos3client::Bcos3Client;
 use fisco_bcos_rust_gears_sdk::bcossdkutil::bcosclientconfig::{BcosCryptoKind, ClientConfig};
 use fisco_bcos_rust_gears_sdk::bcossdkutil::solcompile::sol_compile;
-use serde_json::Value as JsonValue;
+use fisco_bcos_rust_gears_sdk::bcossdkutil::txstatus::ReceiptStatus;
+use serde_json::{json, Value as JsonValue};
 use std::path::PathBuf;
 use std::process::Command;
 use std::thread;
@@ -39,15 +42,19 @@ impl Bcos3Contract {
 pub fn deploy(cli: &Cli) -> Result<(), KissError> {
     let configfile = cli.default_configfile();
     let mut bcos3client = Bcos3Client::new(configfile.as_str())?;
-    println!("{}",bcos3client.get_info());
-    println!("-------------------------------------");
+    if !cli.json_output() {
+        println!("{}", bcos3client.get_info());
+        println!("-------------------------------------");
+    }
     //每次部署前强制编译一次对应合约，考虑到合约sol可能会有修改
     let res = console_compile(cli)?;
 
     let contractname = &cli.params[0];
 
     let params = &cli.params[1..];
-    println!("deploy contract {} ,params:{:?}", contractname, params);
+    if !cli.json_output() {
+        println!("deploy contract {} ,params:{:?}", contractname, params);
+    }
 
     let binfile = format!(
         "{}/{}.bin",
@@ -60,18 +67,28 @@ pub fn deploy(cli: &Cli) -> Result<(), KissError> {
 
     let address = res["contractAddress"].as_str().unwrap();
     let blocknum = liteutils::json_u64(&res, "blockNumber", -1);
-    println!(
-        "deploy contract on block[{}], address is {}",
-        blocknum, address
-    );
     let chf = ContractHistory::history_file(bcos3client.config.common.contractpath.as_str());
-    let res = ContractHistory::save_to_file(
+    let saveres = ContractHistory::save_to_file(
         chf.as_str(),
         bcos3client.get_full_name().as_str(),
         contractname,
         address,
         blocknum as u64,
     );
+    if cli.json_output() {
+        print_json(&json!({
+            "contractName": contractname,
+            "address": address,
+            "blockNumber": blocknum,
+            "transactionHash": res["transactionHash"],
+        }));
+        return Ok(());
+    }
+    println!(
+        "deploy contract on block[{}], address is {}",
+        blocknum, address
+    );
+    let res = saveres;
     println!("save contract history to file {} ,{:?}", chf, res);
     Ok(())
 }
@@ -79,8 +96,10 @@ pub fn sendtx(cli: &Cli) -> Result<(), KissError> {
     let configfile = cli.default_configfile();
 
     let mut bcos3client = Bcos3Client::new(configfile.as_str())?;
-    println!("{}",bcos3client.get_info());
-    println!("-------------------------------------");
+    if !cli.json_output() {
+        println!("{}", bcos3client.get_info());
+        println!("-------------------------------------");
+    }
     //将cmd和param拼在一起，作为新的args，给到StructOpt去解析（因为第一个参数总是app名）
     let mut cmdparams: Vec<String> = vec![cli.cmd.clone()];
     cmdparams.append(&mut cli.params.clone());
@@ -88,7 +107,9 @@ pub fn sendtx(cli: &Cli) -> Result<(), KissError> {
 
     let contractdir = "contracts";
     let contractfullname = format!("{}/{}.abi", contractdir, &opt.contract_name);
-    println!("contract file is {}", contractfullname);
+    if !cli.json_output() {
+        println!("contract file is {}", contractfullname);
+    }
     let contract = ContractABI::new(contractfullname.as_str(), &bcos3client.hashtype)?;
     let chfile = format!(
         "{}/contracthistory.toml",
@@ -101,8 +122,10 @@ pub fn sendtx(cli: &Cli) -> Result<(), KissError> {
         opt.address.as_str(),
     )?;
 
-    println!("contract address is {}", &address.as_str());
-    println!("method is {}", opt.method);
+    if !cli.json_output() {
+        println!("contract address is {}", &address.as_str());
+        println!("method is {}", opt.method);
+    }
     let response = bcos3client.sendTransaction(
         address.as_str(),
         opt.method.as_str(),
@@ -110,12 +133,23 @@ pub fn sendtx(cli: &Cli) -> Result<(), KissError> {
         &contract,
     )?;
     //println!("send_raw_transaction result {:?}", response);
-    println!("response[\"result\"] {:?}", response);
+    if !cli.json_output() {
+        println!("response[\"result\"] {:?}", response);
+    }
     let txhash = response["transactionHash"].as_str().unwrap();
-    println!("\n>>>>>>>>>>>>>>>>>>>after sendtx getTransactionByHash");
+    if !cli.json_output() {
+        println!("\n>>>>>>>>>>>>>>>>>>>after sendtx getTransactionByHash");
+    }
     let receiptresult = bcos3client.getTransactionReceipt(txhash, 0);
 
     match receiptresult {
+        Ok(receipt) if cli.json_output() => {
+            print_json(&transaction_to_json(
+                &JsonValue::Null,
+                &receipt,
+                Some(&contract),
+            ));
+        }
         Ok(receipt) => {
             crate::console::console_utils::display_transaction_receipt(
                 &receipt,
@@ -138,8 +172,10 @@ pub fn call(cli: &Cli) -> Result<(), KissError> {
     let configfile = cli.default_configfile();
 
     let bcos3client = Bcos3Client::new(configfile.as_str())?;
-    println!("{}",bcos3client.get_info());
-    println!("-------------------------------------");
+    if !cli.json_output() {
+        println!("{}", bcos3client.get_info());
+        println!("-------------------------------------");
+    }
 
     //将cmd和param拼在一起，作为新的args，给到StructOpt去解析（因为第一个参数总是app名）
     let mut cmdparams: Vec<String> = vec![cli.cmd.clone()];
@@ -147,7 +183,9 @@ pub fn call(cli: &Cli) -> Result<(), KissError> {
     let opt: OptContract = StructOpt::from_iter(cmdparams.iter());
     let contractdir = "contracts";
     let contractfullname = format!("{}/{}.abi", contractdir, &opt.contract_name);
-    println!("contract file is {}", contractfullname);
+    if !cli.json_output() {
+        println!("contract file is {}", contractfullname);
+    }
     let contract = ContractABI::new(contractfullname.as_str(), &bcos3client.hashtype)?;
     let chfile = format!(
         "{}/contracthistory.toml",
@@ -160,26 +198,52 @@ pub fn call(cli: &Cli) -> Result<(), KissError> {
         opt.address.as_str(),
     )?;
 
-    println!("contract address is {}", &address.as_str());
+    if !cli.json_output() {
+        println!("contract address is {}", &address.as_str());
+    }
     let res = bcos3client.call(
         address.as_str(),
         opt.method.as_str(),
         &opt.params,
         &contract,
     )?;
+    let ustatus = res["status"].as_i64().unwrap();
+    //失败时output里可能带有revert原因
+    let callstatus =
+        ReceiptStatus::from_bcos3(ustatus as i64, res["output"].as_str().unwrap_or(""));
+    if cli.json_output() {
+        if ustatus != 0 {
+            return kisserrcode!(
+                KissErrKind::Error,
+                ustatus as i64,
+                "call error {}",
+                callstatus
+            );
+        }
+        let output = res["output"].as_str().unwrap();
+        print_json(&json!({
+            "status": ustatus,
+            "output": contract.decode_output_to_json(opt.method.as_str(), output)?,
+        }));
+        return Ok(());
+    }
     println!(
         "call result :{}",
         serde_json::to_string_pretty(&res).unwrap()
     );
 
-    let ustatus = res["status"].as_i64().unwrap();
     println!("call status code ({:?}) ", ustatus);
     if ustatus == 0 {
         let output = res["output"].as_str().unwrap();
         let decodereuslt = contract.decode_output_byname(opt.method.as_str(), output);
         println!("call output: {:?}", decodereuslt);
     } else {
-        return kisserr!(KissErrKind::Error, "call error !!!");
+        return kisserrcode!(
+            KissErrKind::Error,
+            ustatus as i64,
+            "call error {}",
+            callstatus
+        );
     }
     Ok(())
 }
@@ -3,7 +3,7 @@ use crate::cmdmap;
 use crate::console::cli_common::Cli;
 use crate::console::console_utils::{
     cli_groupid, display_transaction, display_transaction_receipt, find_contract,
-    is_deploy_address, param_at, param_int,
+    is_deploy_address, param_at, param_int, print_json, transaction_to_json,
 };
 use crate::console_cmdmap::CliCmdMap;
 use fisco_bcos_rust_gears_sdk::bcos2sdk::bcossdkquery::json_hextoint;
@@ -11,7 +11,7 @@ use fisco_bcos_rust_gears_sdk::bcos3sdk::bcos3client::Bcos3Client;
 use fisco_bcos_rust_gears_sdk::bcossdkutil::kisserror::{KissErrKind, KissError};
 use fisco_bcos_rust_gears_sdk::bcossdkutil::liteutils::get_opt_str;
 use serde::de::Unexpected::Option as JsonOption;
-use serde_json::Value as JsonValue;
+use serde_json::{json, Value as JsonValue};
 use std::str::FromStr;
 
 pub struct Bcos3Query {
@@ -51,6 +51,14 @@ impl Bcos3Query {
 pub fn getVersion(cli: &Cli) -> Result<(), KissError> {
     let bcossdk = Bcos3Client::new(cli.default_configfile().as_str())?;
     let v = bcossdk.getVersion();
+    if cli.json_output() {
+        //版本信息本身是json文本
+        match serde_json::from_str::<JsonValue>(v.as_str()) {
+            Ok(j) => print_json(&j),
+            Err(_) => print_json(&json!(v)),
+        }
+        return Ok(());
+    }
     println!("{}\n", v);
     Ok(())
 }
@@ -58,6 +66,10 @@ pub fn getVersion(cli: &Cli) -> Result<(), KissError> {
 pub fn getBlockLimit(cli: &Cli) -> Result<(), KissError> {
     let mut bcossdk = Bcos3Client::new(cli.default_configfile().as_str())?;
     let v = bcossdk.getBlocklimit()?;
+    if cli.json_output() {
+        print_json(&json!(v));
+        return Ok(());
+    }
     println!("\n {:?}\n", v);
     Ok(())
 }
@@ -65,14 +77,48 @@ pub fn getBlockLimit(cli: &Cli) -> Result<(), KissError> {
 pub fn getBlockNumber(cli: &Cli) -> Result<(), KissError> {
     let bcossdk = Bcos3Client::new(cli.default_configfile().as_str())?;
     let v = bcossdk.getBlockNumber()?;
+    if cli.json_output() {
+        print_json(&json!(v));
+        return Ok(());
+    }
     println!("\n {:?}\n", v);
     Ok(())
 }
 
+///json模式下输出交易、回执和按abi解析的结果，找不到合约时只输出原始数据
+fn print_transaction_json(
+    cli: &Cli,
+    bcossdk: &Bcos3Client,
+    tx: &JsonValue,
+    receipt: &JsonValue,
+) -> Result<(), KissError> {
+    let to = tx["to"]
+        .as_str()
+        .or_else(|| receipt["to"].as_str())
+        .unwrap_or("");
+    let contract = match is_deploy_address(to) {
+        true => None,
+        false => find_contract(
+            cli,
+            bcossdk.get_full_name().as_str(),
+            get_opt_str(&cli.contractname).as_str(),
+            to,
+            &bcossdk.config,
+        )
+        .ok(),
+    };
+    print_json(&transaction_to_json(tx, receipt, contract.as_ref()));
+    Ok(())
+}
+
 pub fn getBlockHashByNumber(cli: &Cli) -> Result<(), KissError> {
     let bcossdk = Bcos3Client::new(cli.default_configfile().as_str())?;
     let num = u64::from_str_radix(cli.params[0].as_str(), 10).unwrap();
     let v = bcossdk.getBlockHashByNumber(num)?;
+    if cli.json_output() {
+        print_json(&json!(v));
+        return Ok(());
+    }
     println!("\n {:?}\n", v);
 
     Ok(())
@@ -119,7 +165,13 @@ pub fn getTransactionByHash(cli: &Cli) -> Result<(), KissError> {
         proof = u32::from_str_radix(cli.params[2].as_str(), 10).unwrap();
     }
     let v = bcossdk.getTransactionByHash(hash, proof as i32)?;
+    if cli.json_output() {
+        let hash = v["hash"].as_str().unwrap_or("");
+        let receipt = bcossdk.getTransactionReceipt(hash, proof as i32)?;
+        return print_transaction_json(cli, &bcossdk, &v, &receipt);
+    }
     let res = display_transaction(
+        cli,
         &v,
         &bcossdk.config,
         bcossdk.get_full_name().as_str(),
@@ -131,6 +183,7 @@ pub fn getTransactionByHash(cli: &Cli) -> Result<(), KissError> {
     let to = v["to"].as_str().unwrap();
     println!("->try find contract for {}", bcossdk.get_full_name());
     let contractABI = find_contract(
+        cli,
         bcossdk.get_full_name().as_str(),
         get_opt_str(&cli.contractname).as_str(),
         to,
@@ -151,6 +204,13 @@ pub fn getTransactionReceipt(cli: &Cli) -> Result<(), KissError> {
     }
 
     let v = bcossdk.getTransactionReceipt(hash, proof as i32)?;
+    if cli.json_output() {
+        if v == JsonValue::Null {
+            print_json(&v);
+            return Ok(());
+        }
+        return print_transaction_json(cli, &bcossdk, &JsonValue::Null, &v);
+    }
     println!(
         "\n[{}] : {}\n",
         cmd,
@@ -168,6 +228,7 @@ pub fn getTransactionReceipt(cli: &Cli) -> Result<(), KissError> {
     }
 
     let contractres = find_contract(
+        cli,
         bcossdk.get_full_name().as_str(),
         get_opt_str(&cli.contractname).as_str(),
         to,
@@ -265,6 +326,9 @@ pub fn getTotalTransactionCount(cli: &Cli) -> Result<(), KissError> {
     let groupid = cli_groupid(&cli);
     let v = bcossdk.getTotalTransactionCount()?;
     println!("{}", serde_json::to_string_pretty(&v).unwrap());
+    if cli.json_output() {
+        return Ok(());
+    }
 
     let blockNumber = liteutils::json_u64(&v, "blockNumber", -1);
     let failedTxSum = liteutils::json_u64(&v, "failedTxSum", -1);
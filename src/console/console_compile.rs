@@ -8,6 +8,8 @@ pub fn console_compile(cli: &Cli) -> Result<(), KissError> {
     let outputres = sol_compile(contract_name.as_str(), cli.default_configfile().as_str());
     //println!("compile [{}] done。",contract_name);
     match outputres {
+        //json模式下stdout只输出结果json，编译信息不打印
+        Ok(output) if cli.json_output() => {}
         Ok(output) => {
             println!("compiler  status : {}", output.status);
             if output.stdout.len() > 0 {
@@ -0,0 +1,98 @@
+#![allow(
+    clippy::unreadable_literal,
+    clippy::upper_case_acronyms,
+    dead_code,
+    non_camel_case_types,
+    non_snake_case,
+    non_upper_case_globals,
+    overflowing_literals
+)]
+use fisco_bcos_rust_gears_sdk::bcosclient::bcoschainclient::create_chain_client;
+use fisco_bcos_rust_gears_sdk::bcosclient::bcosloadtest::BcosLoadTest;
+use fisco_bcos_rust_gears_sdk::bcossdkutil::contractabi::ContractABI;
+use fisco_bcos_rust_gears_sdk::bcossdkutil::contracthistory::ContractHistory;
+use fisco_bcos_rust_gears_sdk::bcossdkutil::kisserror::{KissErrKind, KissError};
+
+use crate::kisserr;
+use crate::Cli;
+use structopt::StructOpt;
+
+/// 带--开头的选项，命令后面要加 -- ，否则会被当成控制台的全局选项：
+/// bcos_rust_sdk loadtest -- HelloWorld last set hello --count 10000 --connections 8 --window 128
+/// bcos_rust_sdk loadtest -- HelloWorld 0x1234... set hello --count 1000 --report report.json
+#[derive(StructOpt, Debug)]
+#[structopt(about = "send transactions concurrently and report TPS")]
+struct OptLoadTest {
+    contract_name: String,
+    address: String,
+    method: String,
+    params: Vec<String>,
+    ///发送的交易数
+    #[structopt(long, default_value = "1000")]
+    count: usize,
+    ///并发的连接数，每个连接一个线程
+    #[structopt(long, default_value = "4")]
+    connections: usize,
+    ///每个连接上最多同时在途(已发送未收到回执)的交易数，为1时逐笔发送并等待回执
+    #[structopt(long, default_value = "64")]
+    window: usize,
+    ///压测结果另存为json文件，不用--output，避免和控制台的--output text/json混淆
+    #[structopt(long)]
+    report: Option<String>,
+}
+
+pub fn cmd_loadtest(cli: &Cli) -> Result<(), KissError> {
+    //将cmd和param拼在一起，作为新的args，给到StructOpt去解析（因为第一个参数总是app名）
+    let mut cmdparams: Vec<String> = vec![cli.cmd.clone()];
+    cmdparams.append(&mut cli.params.clone());
+    let opt: OptLoadTest = StructOpt::from_iter(cmdparams.iter());
+    let configfile = cli.default_configfile();
+    let mut client = create_chain_client(configfile.as_str())?;
+    println!("{}", client.get_info());
+    let contractpath = client.get_config().common.contractpath.clone();
+    let contract = ContractABI::new_by_name(
+        opt.contract_name.as_str(),
+        contractpath.as_str(),
+        client.get_hashtype(),
+    )?;
+    let address = ContractHistory::check_address_from_file(
+        ContractHistory::history_file(contractpath.as_str()).as_str(),
+        client.get_full_name().as_str(),
+        opt.contract_name.as_str(),
+        opt.address.as_str(),
+    )?;
+    let mut loadtest = BcosLoadTest::new(configfile.as_str(), opt.connections);
+    loadtest.window = opt.window.max(1);
+    let presigned = loadtest.presign(
+        client.as_mut(),
+        &contract,
+        address.as_str(),
+        opt.method.as_str(),
+        opt.params.as_slice(),
+        opt.count,
+    );
+    client.finish();
+    presigned?;
+    println!(
+        "{} transactions signed in {} ms, sending over {} connections, window {}",
+        loadtest.rawtxs.len(),
+        loadtest.sign_ms,
+        loadtest.connections,
+        loadtest.window
+    );
+    let report = loadtest.run()?;
+    println!("{}", report.summary());
+    if let Some(reportfile) = &opt.report {
+        let text = serde_json::to_string_pretty(&report.to_json()).unwrap();
+        if let Err(e) = std::fs::write(reportfile, text) {
+            return kisserr!(
+                KissErrKind::EFileWrite,
+                "write {} error {:?}",
+                reportfile,
+                e
+            );
+        }
+        println!("report saved to {}", reportfile);
+    }
+    Ok(())
+}
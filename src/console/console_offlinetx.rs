@@ -0,0 +1,167 @@
+#![allow(
+    clippy::unreadable_literal,
+    clippy::upper_case_acronyms,
+    dead_code,
+    non_camel_case_types,
+    non_snake_case,
+    non_upper_case_globals,
+    overflowing_literals
+)]
+use fisco_bcos_rust_gears_sdk::bcosclient::bcoschainclient::{
+    create_chain_client, BcosChainVersion,
+};
+use fisco_bcos_rust_gears_sdk::bcosclient::bcosofflinetx::{
+    signer_from_config, BlockLimitStatus, OfflineTxBuilder, SignedTxFile,
+};
+use fisco_bcos_rust_gears_sdk::bcossdkutil::bcosclientconfig::ClientConfig;
+use fisco_bcos_rust_gears_sdk::bcossdkutil::contractabi::ContractABI;
+use fisco_bcos_rust_gears_sdk::bcossdkutil::contracthistory::ContractHistory;
+use fisco_bcos_rust_gears_sdk::bcossdkutil::kisserror::{KissErrKind, KissError};
+
+use crate::kisserr;
+use crate::Cli;
+use colored::Colorize;
+use structopt::StructOpt;
+
+/// bcos_rust_sdk signtx HelloWorld 0x1234... set hello --blocklimit 600
+/// bcos_rust_sdk signtx HelloWorld last set hello --blocklimit 600 --output tx.json
+#[derive(StructOpt, Debug)]
+#[structopt(about = "sign transaction offline")]
+struct OptSignTx {
+    contract_name: String,
+    address: String,
+    method: String,
+    params: Vec<String>,
+    ///交易的blocklimit，离线时无法从节点获取，必须指定，一般为当前块高+500
+    #[structopt(long)]
+    blocklimit: u64,
+    ///覆盖配置文件里的chainid，bcos3默认为chain0
+    #[structopt(long)]
+    chainid: Option<String>,
+    ///覆盖配置文件里的groupid(bcos2)或group(bcos3)
+    #[structopt(long)]
+    groupid: Option<String>,
+    ///签名结果保存的文件，默认为{txhash}.json
+    #[structopt(long)]
+    output: Option<String>,
+}
+
+/// bcos_rust_sdk broadcast tx.json
+/// bcos_rust_sdk broadcast tx.json --check
+#[derive(StructOpt, Debug)]
+#[structopt(about = "broadcast signed transaction")]
+struct OptBroadcast {
+    file: String,
+    ///只检查签名和blocklimit，不发送
+    #[structopt(long)]
+    check: bool,
+    ///blocklimit已过期也照样发送
+    #[structopt(long)]
+    force: bool,
+}
+
+fn show_signed_tx(txfile: &SignedTxFile) {
+    let mut summary = txfile.to_json();
+    summary.as_object_mut().unwrap().remove("rawTransaction");
+    println!("{}", serde_json::to_string_pretty(&summary).unwrap());
+}
+
+///离线签名，不连接节点
+pub fn cmd_signtx(cli: &Cli) -> Result<(), KissError> {
+    //将cmd和param拼在一起，作为新的args，给到StructOpt去解析（因为第一个参数总是app名）
+    let mut cmdparams: Vec<String> = vec![cli.cmd.clone()];
+    cmdparams.append(&mut cli.params.clone());
+    let opt: OptSignTx = StructOpt::from_iter(cmdparams.iter());
+    let config = ClientConfig::load(cli.default_configfile().as_str())?;
+    let mut builder = OfflineTxBuilder::from_config(&config, opt.blocklimit);
+    if let Some(chainid) = &opt.chainid {
+        builder.chainid = chainid.clone();
+    }
+    if let Some(groupid) = &opt.groupid {
+        builder.groupid = groupid.clone();
+    }
+    let contract = ContractABI::new_by_name(
+        opt.contract_name.as_str(),
+        config.common.contractpath.as_str(),
+        &builder.hashtype(),
+    )?;
+    //合约历史里bcos2的段名是bcos2，bcos3是客户端的全名
+    let segment = match builder.chain {
+        BcosChainVersion::V2 => "bcos2".to_string(),
+        BcosChainVersion::V3 => format!("BCOS3-{}-{}", builder.chainid, builder.groupid),
+    };
+    let address = ContractHistory::check_address_from_file(
+        ContractHistory::history_file(config.common.contractpath.as_str()).as_str(),
+        segment.as_str(),
+        opt.contract_name.as_str(),
+        opt.address.as_str(),
+    )?;
+    let signer = signer_from_config(&config.common)?;
+    let txfile = builder.sign_call(
+        signer.as_ref(),
+        &contract,
+        address.as_str(),
+        opt.method.as_str(),
+        opt.params.as_slice(),
+    )?;
+    let output = match &opt.output {
+        Some(f) => f.clone(),
+        None => format!("{}.json", txfile.txhash),
+    };
+    txfile.save(output.as_str())?;
+    show_signed_tx(&txfile);
+    println!("signed transaction saved to {}", output);
+    Ok(())
+}
+
+///检查签名交易文件并发送到节点
+pub fn cmd_broadcast(cli: &Cli) -> Result<(), KissError> {
+    let mut cmdparams: Vec<String> = vec![cli.cmd.clone()];
+    cmdparams.append(&mut cli.params.clone());
+    let opt: OptBroadcast = StructOpt::from_iter(cmdparams.iter());
+    let txfile = SignedTxFile::load(opt.file.as_str())?;
+    show_signed_tx(&txfile);
+    let mut client = create_chain_client(cli.default_configfile().as_str())?;
+    let status = txfile.check_with_client(client.as_mut())?;
+    match &status {
+        BlockLimitStatus::Valid { remaining } => {
+            println!(
+                "blockLimit {} is valid, expires in {} blocks",
+                txfile.block_limit, remaining
+            );
+        }
+        BlockLimitStatus::Expired { blocknumber } => {
+            let msg = format!(
+                "WARNING: blockLimit {} has expired at block {}, sign the transaction again",
+                txfile.block_limit, blocknumber
+            );
+            println!("{}", msg.red());
+        }
+        BlockLimitStatus::TooFar { blocknumber } => {
+            let msg = format!(
+                "WARNING: blockLimit {} is too far ahead of block {}, node will reject it",
+                txfile.block_limit, blocknumber
+            );
+            println!("{}", msg.red());
+        }
+    }
+    if opt.check {
+        client.finish();
+        return Ok(());
+    }
+    if !status.is_valid() && !opt.force {
+        client.finish();
+        return kisserr!(
+            KissErrKind::EArgument,
+            "transaction not sent, use --force to send anyway"
+        );
+    }
+    let receipt = txfile.broadcast(client.as_mut(), opt.force);
+    client.finish();
+    let receipt = receipt?;
+    println!(
+        "transaction {} on block {}, status {}",
+        receipt.transaction_hash, receipt.block_number, receipt.status
+    );
+    Ok(())
+}
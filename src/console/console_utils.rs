@@ -1,7 +1,8 @@
+use colored::Colorize;
 use ethabi::Log;
 use serde::de::Unexpected::Option;
 
-use serde_json::{Value as JsonValue, Value};
+use serde_json::{json, Value as JsonValue, Value};
 
 use crate::Cli;
 use fisco_bcos_rust_gears_sdk::bcos2sdk::bcos2client::Bcos2Client;
@@ -11,10 +12,15 @@ use fisco_bcos_rust_gears_sdk::bcossdkutil::contractabi::ContractABI;
 use fisco_bcos_rust_gears_sdk::bcossdkutil::contracthistory::ContractHistory;
 use fisco_bcos_rust_gears_sdk::bcossdkutil::contracthistory::ContractRecord;
 use fisco_bcos_rust_gears_sdk::bcossdkutil::kisserror::{KissErrKind, KissError};
+use fisco_bcos_rust_gears_sdk::bcossdkutil::txstatus::ReceiptStatus;
 use fisco_bcos_rust_gears_sdk::kisserr;
 
 static DELOPY_ADDRESS: &str = "0000000000000000000000000000000000000000";
 
+pub fn print_json(v: &JsonValue) {
+    println!("{}", serde_json::to_string_pretty(v).unwrap());
+}
+
 pub fn param_at(params: &Vec<String>, index: usize) -> Result<String, KissError> {
     if params.len() <= index as usize {
         return kisserr!(
@@ -87,15 +93,18 @@ pub fn find_contracthistory_byaddress(
     Ok(chrecord)
 }
 
-///根据名字或address去找一个合约实例
+///根据名字或address去找一个合约实例，--output json时不打印提示信息
 pub fn find_contract(
+    cli: &Cli,
     segment: &str,
     contractname: &str,
     address: &str,
     config: &ClientConfig,
 ) -> Result<ContractABI, KissError> {
     if contractname.len() > 0 {
-        println!("load contract by name : {}", contractname);
+        if !cli.json_output() {
+            println!("load contract by name : {}", contractname);
+        }
         let contract = ContractABI::new_by_name(
             contractname,
             config.common.contractpath.as_str(),
@@ -112,10 +121,12 @@ pub fn find_contract(
             config.common.contractpath.as_str(),
             &CommonHash::crypto_to_hashtype(&config.common.crypto),
         );
-        println!(
-            "load contract {} by address : {}",
-            chrecord.name, chrecord.address
-        );
+        if !cli.json_output() {
+            println!(
+                "load contract {} by address : {}",
+                chrecord.name, chrecord.address
+            );
+        }
         return contract;
     }
 }
@@ -171,6 +182,12 @@ pub fn display_transaction_receipt(
     } else {
         println!("is Normal Tx, on block [{}],to address :{} ", blocknum, to);
     }
+    let receiptstatus = ReceiptStatus::from_receipt(&receipt);
+    if receiptstatus.is_success() {
+        println!("status: {} {}", istatus, receiptstatus);
+    } else {
+        println!("{}", format!("status: {} {}", istatus, receiptstatus).red());
+    }
     match contractopt {
         Some(contract) => {
             let outputstr = receipt["output"].as_str().unwrap();
@@ -211,11 +228,67 @@ pub fn display_transaction_receipt(
     };
 }
 
+///交易和回执的json输出：原始的交易和回执(没有的为null)，能找到合约时加上按abi解析的input、output和logs
+/// 解析失败的项为null，不影响其他项
+pub fn transaction_to_json(
+    tx: &JsonValue,
+    receipt_in: &JsonValue,
+    contractopt: std::option::Option<&ContractABI>,
+) -> JsonValue {
+    let receipt = match receipt_in.get("result") {
+        Some(v) => v,
+        None => receipt_in,
+    };
+    let mut result = json!({
+        "transaction": tx,
+        "receipt": receipt,
+        "input": null,
+        "output": null,
+        "logs": null,
+    });
+    let contract = match contractopt {
+        Some(c) => c,
+        None => return result,
+    };
+    //部署交易的input是合约的bin，不做解析
+    let input = tx["input"]
+        .as_str()
+        .or_else(|| receipt["input"].as_str())
+        .filter(|i| i.trim_start_matches("0x").len() >= 8);
+    let to = tx["to"].as_str().or_else(|| receipt["to"].as_str());
+    if to.map_or(false, is_deploy_address) {
+        return result;
+    }
+    if let Some(input) = input {
+        if let Ok(v) = contract.decode_input_to_json(input) {
+            result["input"] = v;
+        }
+        let output = receipt["output"].as_str().unwrap_or("0x");
+        let success = receipt["status"] == 0 || receipt["status"] == "0x0";
+        if success && output.trim_start_matches("0x").len() > 0 {
+            if let Ok(detail) = contract.decode_input_for_tx(input) {
+                if let Ok(v) = contract.decode_function_output_to_json(&detail.func, output) {
+                    result["output"] = v;
+                }
+            }
+        }
+    }
+    let mut logs = &receipt["logs"];
+    if logs.is_null() {
+        logs = &receipt["logEntries"];
+    }
+    if let Ok(v) = contract.parse_receipt_logs_to_json(logs) {
+        result["logs"] = v;
+    }
+    result
+}
+
 /// 判断是否部署合约
 /// 如果调用合约，在历史表里找合约记录
 /// 找到的额话，解析交易的input
 /// 获取交易对应的receipt，打印receipt
 pub fn display_transaction(
+    cli: &Cli,
     v: &JsonValue,
     config: &ClientConfig,
     segment: &str,
@@ -232,7 +305,7 @@ pub fn display_transaction(
 
     let hash = v["hash"].as_str().unwrap();
 
-    let contractres = find_contract(segment, contractname, to, &config);
+    let contractres = find_contract(cli, segment, contractname, to, &config);
     let contract = match contractres {
         Ok(c) => c,
         Err(e) => {
@@ -1,10 +1,13 @@
 pub(crate) mod cli_common;
 pub(crate) mod console_account;
 pub(crate) mod console_bcos2_contract;
+pub(crate) mod console_bcos2_precompiled;
 pub(crate) mod console_bcos2_query;
 pub(crate) mod console_bcos3_contracts;
 pub(crate) mod console_bcos3_query;
 pub(crate) mod console_cmdmap;
 pub(crate) mod console_compile;
+pub(crate) mod console_loadtest;
+pub(crate) mod console_offlinetx;
 pub(crate) mod console_utils;
 pub(crate) mod usage;
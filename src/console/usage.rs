@@ -7,7 +7,17 @@ pub fn usage_account(config: &ClientConfig) {
     let msg = r###"
     account new [名字]，创建新的账户,名字可选，用于保存时的文件名,如未指定，则用地址（address）作为文件名
 
-    account show [名字]，显示指定名字的账户信息，如未指定名字，则展示配置文件指定目录下所有的账户（.pem）信息
+    account show [名字]，显示指定名字的账户信息，如未指定名字，则展示配置文件指定目录下所有的账户（.pem/.p12/.json）信息
+
+    --format pem/encrypted-pem/p12/json，new和derive保存账户的格式，默认pem（不加密）
+    --password pass:明文/env:环境变量名/file:文件路径，加密账户文件的密码，不传时用配置文件的common.accountpassword
+
+    account mnemonic [--words 12]，生成新的助记词，并显示派生的第一个账户
+
+    account derive [名字] --mnemonic env:SEED [--passphrase ..] [--index 0] [--count 1] [--path "m/44'/60'/0'/0"]
+        从助记词派生账户，显示 {path}/{index} 开始的count个地址，指定名字时保存第index个账户
+
+    带--开头的选项时，需要在account后面加 -- ，如 account -- derive bob --mnemonic env:SEED --index 3
 
     写入和寻找账户文件的路径与配置文件同级。"###;
     let mut p = PathBuf::from(&config.configfile.as_ref().unwrap().as_str());
@@ -25,10 +35,28 @@ pub fn usage_contract(config: &ClientConfig) {
 
     call   [合约名] [地址或latest/last] [方法名] [方法对应的参数...], 如 call HelloWorld latest  get
 
+    结构体(tuple)和数组类型的参数可以用json传入，嵌套的tuple、tuple[]、定长数组都能准确解析，tuple可以用成员名作为key：
+        sendtx TestStruct last addUsers '[{"name":"alice","age":25},["bob",30]]'
+        数字可以是json数字或十进制/0x开头的字符串，bytes/address用hex字符串。原来的 [(alice,25),(bob,30)] 写法仍然可用
+
+    --output json  bcos2/bcos3的查询和合约命令只输出一个json结果，解码后的参数和返回值按abi里的参数名和tuple成员名组织，可以直接交给jq处理：
+        bcos2 call HelloWorld last get --output json | jq .output
+        bcos3 getTransactionReceipt 0x... --output json | jq '.logs[].params'
+
     compile [合约名]  调用配置好的solc编译器，编译合约，默认合约sol文件和输出都在配置的contracts目录，solc下载参见contrats目录下的README（注：用deploy指令部署合约时，会先尝试编译）
 
     合约成功部署后，新地址会写入合约目录的contracthistory.toml文件，后续就可以用lastest/last代替地址调用了
 
+    离线签名、在线广播（不用加bcos2/bcos3，按配置文件的protocol选择链版本）：
+    signtx [合约名] [地址或latest/last] [方法名] [参数...] --blocklimit 600 [--chainid ..] [--groupid ..] [--output tx.json]
+        不连接节点，签名后保存为可查看的json文件，blocklimit需要在联网的机器上查好当前块高后指定(一般加500)
+    broadcast [文件] [--check] [--force]
+        校验文件里的签名和摘要，检查blocklimit是否过期后发送，--check只检查不发送，--force过期也照样发送
+    loadtest [合约名] [地址或latest/last] [方法名] [参数...] [--count 1000] [--connections 4] [--window 64] [--report report.json]
+        压测：预先签好count笔交易，用connections个连接并发发送，统计TPS、回执延迟分位数和失败原因
+        每个连接最多window笔交易在途，--report把结果另存为json文件，如 loadtest -- HelloWorld last set hello --count 10000 --connections 8
+    带--开头的选项时，需要在命令后面加 -- ，如 signtx -- HelloWorld last set "hello" --blocklimit 600
+
     写入历史和寻找合约ABI文件的路径以配置文件里的[contract]contractpath=项为准。
     "###;
 
@@ -44,6 +72,29 @@ pub fn usage_get(config: &ClientConfig) {
     let bcos3query = Bcos3Query::new();
     bcos3query.cmdmap.print_cmds(false);
 }
+pub fn usage_precompiled(config: &ClientConfig) {
+    println!(
+        "\n--Precompiled:FISCO BCOS 2.x系统管理命令，需要加bcos2，如 bcos2 addSealer [节点id]--\n"
+    );
+    let msg = r###"
+    setSystemConfigByKey [key] [value], 如 setSystemConfigByKey tx_count_limit 2000
+
+    addSealer [节点id] / addObserver [节点id] / removeNode [节点id], 调整节点的共识角色
+
+    registerCNS [合约名] [地址] [版本], 合约目录下有[合约名].abi时一起注册
+    queryCNS [合约名] [版本(可选)]
+
+    grantPermission [表名] [账户地址] / revokePermission [表名] [账户地址] / listPermission [表名]
+        系统表可以用简称：deploy(部署和建表)、node(节点管理)、cns、sysconfig、permission(权限管理)
+    grantContractWritePermission [合约地址] [账户地址] / revokeContractWritePermission / listContractWritePermission [合约地址]
+
+    freezeContract [合约地址] / unfreezeContract [合约地址] / getContractStatus [合约地址]
+    grantContractStatusManager [合约地址] [账户地址] / listContractStatusManager [合约地址]
+
+    预编译合约返回的错误码(如-51101 最后一个共识节点不能移除)会翻译成对应的错误说明
+    "###;
+    println!("{}", msg);
+}
 pub fn usage_all(config: &ClientConfig) {
     println!("--所有命令--");
     println!("1)");
@@ -52,6 +103,8 @@ pub fn usage_all(config: &ClientConfig) {
     usage_contract(&config);
     println!("3)");
     usage_get(&config);
+    println!("4)");
+    usage_precompiled(&config);
 }
 
 pub fn usage(cli: &Cli) {
@@ -91,12 +144,16 @@ pub fn usage(cli: &Cli) {
             usage_get(&config);
             return;
         }
+        "precompiled" => {
+            usage_precompiled(&config);
+            return;
+        }
         "all" => {
             usage_all(&config);
             return;
         }
         _ => {
-            println!("\n\n输入： usage account / contract / get / all");
+            println!("\n\n输入： usage account / contract / get / precompiled / all");
         }
     }
 }
@@ -12,6 +12,7 @@
 
 pub mod bcos2sdk;
 pub mod bcos3sdk;
+pub mod bcosclient;
 pub mod bcossdkutil;
 
 #[cfg(test)]
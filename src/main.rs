@@ -19,11 +19,14 @@ use colored::Colorize;
 use std::env::Args;
 use std::{env, thread};
 
+use crate::console::console_bcos2_precompiled::Bcos2Precompiled;
 use crate::console::console_bcos2_query::Bcos2Query;
 use crate::console::console_bcos3_contracts::Bcos3Contract;
 use crate::console::console_bcos3_query::Bcos3Query;
 use crate::console::console_cmdmap;
-use crate::console::{console_account, console_bcos2_contract, console_compile};
+use crate::console::{
+    console_account, console_bcos2_contract, console_compile, console_loadtest, console_offlinetx,
+};
 use crate::console_bcos2_contract::Bcos2Contract;
 use crate::sample::demo_bcos3event;
 use console::cli_common::Cli;
@@ -47,7 +50,10 @@ pub async fn main() {
     log4rs::init_file("log4rs.yml", Default::default()).unwrap();
     let mut cli: Cli = Cli::from_args();
     info!("start with cli {:?}", &cli);
-    println!("console input {:?}", &cli);
+    //--output json时stdout只输出结果json，便于用管道交给jq等工具处理
+    if !cli.json_output() {
+        println!("console input {:?}", &cli);
+    }
     if cli.verbos > 0 {
         bcossdkutil::macrodef::set_debugprint(true);
     }
@@ -94,6 +100,18 @@ pub async fn main() {
             let result = console_account::cmd_account(&cli);
             println!("account cmd reuslt {:?}", result);
         }
+        "signtx" => {
+            let result = console_offlinetx::cmd_signtx(&cli);
+            println!("signtx result {:?}", result);
+        }
+        "broadcast" => {
+            let result = console_offlinetx::cmd_broadcast(&cli);
+            println!("broadcast result {:?}", result);
+        }
+        "loadtest" => {
+            let result = console_loadtest::cmd_loadtest(&cli);
+            println!("loadtest result {:?}", result);
+        }
         "usage" => {
             console::usage::usage(&cli);
         }
@@ -131,13 +149,17 @@ pub async fn main() {
         "bcos2" => {
             let bcos2query = Bcos2Query::new();
             let bcos2contract = Bcos2Contract::new();
-            println!("{}", "\n>---BCOS2 console---<\n".green());
+            let bcos2precompiled = Bcos2Precompiled::new();
+            if !cli.json_output() {
+                println!("{}", "\n>---BCOS2 console---<\n".green());
+            }
             if cli.params.len() == 0 {
                 println!("{}", "-->!! NO Enough params !!<<--".red());
                 println!("Input: bcos3 [cmd] [params]");
                 println!("eg:cargo run  bcos 3 getBlockByNumber 5");
                 bcos2query.cmdmap.print_cmds(true);
                 bcos2contract.cmdmap.print_cmds(true);
+                bcos2precompiled.cmdmap.print_cmds(true);
                 return;
             }
             let cmd = cli.params.get(0).unwrap().clone();
@@ -147,23 +169,31 @@ pub async fn main() {
             if bcos2query.cmdmap.in_cmd(cmd.as_str()) {
                 let r = bcos2query.cmdmap.handle_cmd(&cli);
                 if r.is_err() {
-                    println!("console : {:?}", r);
+                    print_console_error(&cli, &r);
                 }
             } else if bcos2contract.cmdmap.in_cmd(cmd.as_str()) {
                 let r = bcos2contract.cmdmap.handle_cmd(&cli);
                 if r.is_err() {
-                    println!("console : {:?}", r);
+                    print_console_error(&cli, &r);
+                }
+            } else if bcos2precompiled.cmdmap.in_cmd(cmd.as_str()) {
+                let r = bcos2precompiled.cmdmap.handle_cmd(&cli);
+                if r.is_err() {
+                    print_console_error(&cli, &r);
                 }
             } else {
                 bcos2query.cmdmap.print_cmds(true);
                 bcos2contract.cmdmap.print_cmds(true);
+                bcos2precompiled.cmdmap.print_cmds(true);
                 return;
             }
         }
         "bcos3" => {
             let bcos3query = Bcos3Query::new();
             let bcos3contract = Bcos3Contract::new();
-            println!("{}", "\n>---BCOS3 console---<\n".yellow());
+            if !cli.json_output() {
+                println!("{}", "\n>---BCOS3 console---<\n".yellow());
+            }
             if cli.params.len() == 0 {
                 println!("{}", "-->!! NO Enough params !!<<--".red());
                 println!("Input: bcos3 [cmd] [params]");
@@ -179,12 +209,12 @@ pub async fn main() {
             if bcos3query.cmdmap.in_cmd(cmd.as_str()) {
                 let r = bcos3query.cmdmap.handle_cmd(&cli);
                 if r.is_err() {
-                    println!("console : {:?}", r);
+                    print_console_error(&cli, &r);
                 }
             } else if bcos3contract.climap.in_cmd(cmd.as_str()) {
                 let r = bcos3contract.climap.handle_cmd(&cli);
                 if r.is_err() {
-                    println!("console : {:?}", r);
+                    print_console_error(&cli, &r);
                 }
             } else {
                 bcos3query.cmdmap.print_cmds(true);
@@ -203,6 +233,15 @@ pub async fn main() {
     }
 }
 
+///json模式下错误输出到stderr，不混进stdout的json结果里
+fn print_console_error<T: std::fmt::Debug>(cli: &Cli, r: &T) {
+    if cli.json_output() {
+        eprintln!("console : {:?}", r);
+    } else {
+        println!("console : {:?}", r);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
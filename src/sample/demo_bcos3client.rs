@@ -45,7 +45,7 @@ pub fn demo_tx(cli: &Cli) -> Result<(), KissError> {
     let result =
         bcos3client.sendTransaction(contract_address, methoname, &paramsvec, &contractabi)?;
     println!("send Transaction result {:?}", result);
-    display_transaction(&result, &bcos3client.config, "bcos3", "HelloWorld")?;
+    display_transaction(cli, &result, &bcos3client.config, "bcos3", "HelloWorld")?;
 
     // 3) call
     let callresult = bcos3client.call(contract_address, "get", &vec![], &contractabi)?;
@@ -65,7 +65,7 @@ pub extern "C" fn demo_bcos3_event_callback(resp: *const bcos_sdk_c_struct_respo
 
 pub fn demo_event(cli: &Cli) -> Result<(), KissError> {
     unsafe {
-        let mut mode: String = "sync".to_string(); //sync or async
+        let mut mode: String = "sync".to_string(); //sync, async or closure
         if cli.params.len() > 0 {
             mode = cli.params[0].clone();
         }
@@ -101,6 +101,22 @@ pub fn demo_event(cli: &Cli) -> Result<(), KissError> {
         println!("event sub param : {:?}", event_sub_param);
         println!("event sub param(in string): {}", paramstr);
 
+        if mode.as_str() == "closure" {
+            //用Bcos3Client的封装，传入闭包即可，回调里拿到的是已经按abi解析好的日志
+            let subscription =
+                bcos3client.subscribe_event(&event_sub_param, &contractabi, |logs| match logs {
+                    Ok(logs) => {
+                        println!("->display logs :");
+                        display_receipt_logs(&logs);
+                    }
+                    Err(e) => println!("event sub error {:?}", e),
+                })?;
+            println!("event sub task id : {}", subscription.task_id);
+            thread::sleep(Duration::from_secs(15));
+            subscription.unsubscribe();
+            bcos3client.finish();
+            return Ok(());
+        }
         if mode.as_str() == "async" {
             //给bcos3 c sdk传入一个独立的函数回调，不用wait模式，在生命周期中，监听的事件会一直出发回调函数
             //这里构造的一个上下文结构体，将其指针传给了c sdk，会带到回调函数里，要保证其实例一直没有被释放，即指针有效
@@ -143,6 +143,18 @@ pub fn demo(cli: &Cli) -> Result<(), KissError> {
     //println!("send tx result {}",&txres.unwrap().to_string());
     display_transaction_receipt(&txres, &Option::from(&contract), &bcossdk.config);
 
+    println!("\n-----------------addUsers with json--------------------------\n");
+    //用json传结构体数组，不用担心字段里的逗号、括号、引号，tuple可以按成员名或按顺序传
+    let strdata = json!([{"name": "helen, jr", "age": 31}, ["(ivan)", 42]]).to_string();
+    let param = vec![strdata];
+    let txres = bcossdk.sendRawTransactionGetReceipt(
+        &contract,
+        address.as_str(),
+        "addUsers",
+        param.as_slice(),
+    )?;
+    display_transaction_receipt(&txres, &Option::from(&contract), &bcossdk.config);
+
     println!("\n-----------------addUser with param tokens--------------------------\n");
     /*演示先将参数解析为token，直接传入去调用合约
     借助ContractABI, ABILenientTokenizer等工具精细化控制参数的解析。
@@ -0,0 +1,130 @@
+//解码结果转json：按abi里的参数名和tuple成员名组织，便于脚本处理
+use ethabi::{Token, Uint};
+use fisco_bcos_rust_gears_sdk::bcossdkutil::abi_json::{params_to_json, token_to_json};
+use fisco_bcos_rust_gears_sdk::bcossdkutil::commonhash::{CommonHash, HashType};
+use fisco_bcos_rust_gears_sdk::bcossdkutil::contractabi::ContractABI;
+use serde_json::json;
+
+const GROUP_ABI: &str = r#"[
+{"type":"function","name":"getGroup","stateMutability":"view",
+ "inputs":[{"name":"id","type":"uint256"}],
+ "outputs":[
+  {"name":"g","type":"tuple","components":[
+    {"name":"name","type":"string"},
+    {"name":"members","type":"tuple[]","components":[
+      {"name":"name","type":"string"},
+      {"name":"age","type":"uint256"}]},
+    {"name":"owner","type":"address"}]},
+  {"name":"","type":"int256"}]},
+{"type":"event","name":"GroupChanged","anonymous":false,
+ "inputs":[
+  {"name":"id","type":"uint256","indexed":true},
+  {"name":"name","type":"string","indexed":false},
+  {"name":"delta","type":"int256","indexed":false}]}]"#;
+
+fn member(name: &str, age: u64) -> Token {
+    Token::Tuple(vec![
+        Token::String(name.to_string()),
+        Token::Uint(Uint::from(age)),
+    ])
+}
+
+#[test]
+fn token_to_json_uses_names() {
+    //超出u64/i64的数用十进制字符串，避免json解析丢精度
+    assert_eq!(
+        token_to_json(&Token::Uint(Uint::max_value()), None),
+        json!(Uint::max_value().to_string())
+    );
+    assert_eq!(
+        token_to_json(&Token::Int(!Uint::from(5) + 1), None),
+        json!(-5)
+    );
+    assert_eq!(
+        token_to_json(&Token::Int(!Uint::from(u64::MAX)), None),
+        json!(format!("-{}", u64::MAX as u128 + 1))
+    );
+    assert_eq!(
+        token_to_json(&Token::FixedBytes(vec![1, 0xab]), None),
+        json!("0x01ab")
+    );
+
+    let abiparams = json!([
+        {"name": "name", "type": "string"},
+        {"name": "age", "type": "uint256"}
+    ]);
+    assert_eq!(
+        params_to_json(
+            &[Token::String("alice".into()), Token::Uint(Uint::from(25))],
+            Some(&abiparams)
+        ),
+        json!({"name": "alice", "age": 25})
+    );
+    //有成员没名字或个数对不上时按数组输出
+    let tokens = [Token::Bool(true), Token::String("x".into())];
+    assert_eq!(params_to_json(&tokens, None), json!([true, "x"]));
+    assert_eq!(
+        params_to_json(&tokens, Some(&json!([{"name": "a"}, {"name": ""}]))),
+        json!([true, "x"])
+    );
+}
+
+#[test]
+fn decode_output_input_and_logs_to_json() {
+    let contract = ContractABI::new_from_text(GROUP_ABI, &HashType::WEDPR_KECCAK).unwrap();
+    let owner = [0x11u8; 20];
+    let output = ethabi::encode(&[
+        Token::Tuple(vec![
+            Token::String("dev".to_string()),
+            Token::Array(vec![member("alice", 25), member("bob", 30)]),
+            Token::Address(owner.into()),
+        ]),
+        Token::Int(!Uint::from(7) + 1),
+    ]);
+    let decoded = contract
+        .decode_output_to_json("getGroup", hex::encode(&output).as_str())
+        .unwrap();
+    //第二个返回值没有名字，整个返回值按数组输出，tuple内部仍然用成员名
+    assert_eq!(
+        decoded,
+        json!([
+            {
+                "name": "dev",
+                "members": [{"name": "alice", "age": 25}, {"name": "bob", "age": 30}],
+                "owner": format!("0x{}", hex::encode(owner))
+            },
+            -7
+        ])
+    );
+
+    let input = contract
+        .encode_function_input_to_abi("getGroup", &["42".to_string()], true)
+        .unwrap();
+    assert_eq!(
+        contract.decode_input_to_json(input.as_str()).unwrap(),
+        json!({
+            "function": "getGroup",
+            "signature": "getGroup(uint256)",
+            "params": {"id": 42}
+        })
+    );
+
+    let sig = "GroupChanged(uint256,string,int256)";
+    let topic0 = CommonHash::hash(&sig.as_bytes().to_vec(), &HashType::WEDPR_KECCAK);
+    let topic1 = ethabi::encode(&[Token::Uint(Uint::from(42))]);
+    let data = ethabi::encode(&[Token::String("ops".to_string()), Token::Int(Uint::from(3))]);
+    let logs = json!([{
+        "address": "0x1234567890123456789012345678901234567890",
+        "topics": [format!("0x{}", hex::encode(topic0)), format!("0x{}", hex::encode(topic1))],
+        "data": format!("0x{}", hex::encode(data))
+    }]);
+    assert_eq!(
+        contract.parse_receipt_logs_to_json(&logs).unwrap(),
+        json!([{
+            "address": "0x1234567890123456789012345678901234567890",
+            "event": "GroupChanged",
+            "signature": sig,
+            "params": {"id": 42, "name": "ops", "delta": 3}
+        }])
+    );
+}
@@ -0,0 +1,131 @@
+//json格式的合约参数：嵌套的tuple、tuple[]、定长数组和bytes
+use ethabi::{ParamType, Token, Uint};
+use fisco_bcos_rust_gears_sdk::bcossdkutil::abi_tokenizer::ABIJsonTokenizer;
+use fisco_bcos_rust_gears_sdk::bcossdkutil::commonhash::{CommonHash, HashType};
+use fisco_bcos_rust_gears_sdk::bcossdkutil::contractabi::ContractABI;
+use serde_json::json;
+
+const GROUP_ABI: &str = r#"[{"type":"function","name":"setGroup","stateMutability":"nonpayable","outputs":[],
+"inputs":[
+ {"name":"g","type":"tuple","components":[
+   {"name":"name","type":"string"},
+   {"name":"members","type":"tuple[]","components":[
+     {"name":"name","type":"string"},
+     {"name":"age","type":"uint256"}]},
+   {"name":"scores","type":"uint8[3]"}]},
+ {"name":"data","type":"bytes"},
+ {"name":"delta","type":"int256"}]}]"#;
+
+fn member(name: &str, age: u64) -> Token {
+    Token::Tuple(vec![
+        Token::String(name.to_string()),
+        Token::Uint(Uint::from(age)),
+    ])
+}
+
+#[test]
+fn nested_tuple_from_json() {
+    let contract = ContractABI::new_from_text(GROUP_ABI, &HashType::WEDPR_KECCAK).unwrap();
+    //tuple里嵌套的tuple[]要带上成员类型，否则selector是错的
+    let sig = "setGroup((string,(string,uint256)[],uint8[3]),bytes,int256)";
+    let selector =
+        hex::encode(&CommonHash::hash(&sig.as_bytes().to_vec(), &HashType::WEDPR_KECCAK)[0..4]);
+    let expected = vec![
+        Token::Tuple(vec![
+            Token::String("dev, \"core\"".to_string()),
+            Token::Array(vec![member("alice", 25), member("bob", 30)]),
+            Token::FixedArray(vec![
+                Token::Uint(Uint::from(1)),
+                Token::Uint(Uint::from(2)),
+                Token::Uint(Uint::from(3)),
+            ]),
+        ]),
+        Token::Bytes(vec![0x12, 0x34]),
+        Token::Int(!Uint::from(5) + 1),
+    ];
+
+    //每个参数一个字符串，tuple可以用成员名，也可以按顺序
+    let params = vec![
+        r#"{"name":"dev, \"core\"","members":[{"name":"alice","age":25},["bob","30"]],"scores":[1,"2","0x3"]}"#.to_string(),
+        "1234".to_string(),
+        "-5".to_string(),
+    ];
+    let tokens = contract
+        .convert_function_input_str_to_token("setGroup", &params, true)
+        .unwrap();
+    assert_eq!(tokens, expected);
+
+    //全部参数作为一个json
+    let values = json!({
+        "g": ["dev, \"core\"", [["alice", 25], {"age": 30, "name": "bob"}], [1, 2, 3]],
+        "data": "1234",
+        "delta": -5
+    });
+    let tokens = contract
+        .convert_function_input_json_to_token("setGroup", &values)
+        .unwrap();
+    assert_eq!(tokens, expected);
+    let encoded = contract
+        .encode_function_input_json_to_abi("setGroup", &json!([values["g"], "0x1234", "-5"]))
+        .unwrap();
+    assert!(encoded.starts_with(selector.as_str()));
+    assert_eq!(
+        encoded,
+        contract
+            .encode_function_input_to_abi("setGroup", &params, true)
+            .unwrap()
+    );
+
+    //定长数组长度不对、缺少成员、uint传负数都报错
+    let bad = json!(["g", [], [1, 2]]);
+    assert!(contract
+        .convert_function_input_json_to_token("setGroup", &json!([bad, "", 0]))
+        .is_err());
+    let bad = json!({"name": "g", "scores": [1, 2, 3]});
+    assert!(contract
+        .convert_function_input_json_to_token("setGroup", &json!([bad, "", 0]))
+        .is_err());
+    let bad = json!(["g", [["alice", -1]], [1, 2, 3]]);
+    assert!(contract
+        .convert_function_input_json_to_token("setGroup", &json!([bad, "", 0]))
+        .is_err());
+}
+
+#[test]
+fn json_and_legacy_format_agree() {
+    let root = env!("CARGO_MANIFEST_DIR");
+    let contract = ContractABI::new_by_name(
+        "TestStruct",
+        format!("{}/contracts", root).as_str(),
+        &HashType::WEDPR_KECCAK,
+    )
+    .unwrap();
+    let legacy = contract
+        .encode_function_input_to_abi("addUsers", &["[(frank,23),(grant55,77)]".to_string()], true)
+        .unwrap();
+    let json = contract
+        .encode_function_input_to_abi(
+            "addUsers",
+            &[r#"[{"name":"frank","age":23},{"name":"grant55","age":77}]"#.to_string()],
+            true,
+        )
+        .unwrap();
+    assert_eq!(legacy, json);
+
+    //没有abi成员名时，tuple只能按顺序传
+    let user = ParamType::Tuple(vec![
+        Box::new(ParamType::String),
+        Box::new(ParamType::Uint(256)),
+    ]);
+    assert_eq!(
+        ABIJsonTokenizer::tokenize(&user, &json!(["frank", 23])).unwrap(),
+        member("frank", 23)
+    );
+    assert!(ABIJsonTokenizer::tokenize(&user, &json!({"name": "frank", "age": 23})).is_err());
+    let bytes4 = ParamType::FixedBytes(4);
+    assert_eq!(
+        ABIJsonTokenizer::tokenize(&bytes4, &json!("0x01020304")).unwrap(),
+        Token::FixedBytes(vec![1, 2, 3, 4])
+    );
+    assert!(ABIJsonTokenizer::tokenize(&bytes4, &json!("0x0102")).is_err());
+}
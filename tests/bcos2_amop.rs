@@ -0,0 +1,149 @@
+//2.x AMOP的订阅、单播请求应答、广播，用模拟节点在两个连接间转发
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use fisco_bcos_rust_gears_sdk::bcos2sdk::bcosamop::{AmopMessage, BcosAmop};
+use fisco_bcos_rust_gears_sdk::bcossdkutil::kisserror::KissErrKind;
+
+mod common;
+
+//订阅方在后台线程里收包
+fn poll_in_background(amop: Arc<BcosAmop>, stop: Arc<AtomicBool>) -> JoinHandle<()> {
+    std::thread::spawn(move || {
+        while !stop.load(Ordering::SeqCst) {
+            amop.poll().unwrap();
+            std::thread::sleep(Duration::from_millis(20));
+        }
+    })
+}
+
+#[test]
+fn request_response_and_unsubscribe() {
+    let (_node, configfile) = common::start_node("bcos2amop_request");
+    let subscriber = Arc::new(BcosAmop::new(configfile.as_str()).unwrap());
+    let publisher = BcosAmop::new(configfile.as_str()).unwrap();
+    subscriber
+        .subscribe(
+            "hello",
+            Arc::new(|msg: &AmopMessage| {
+                let mut reply = b"pong:".to_vec();
+                reply.extend_from_slice(&msg.data);
+                Some(reply)
+            }),
+        )
+        .unwrap();
+    assert_eq!(subscriber.subscribed_topics(), vec!["hello".to_string()]);
+    let stop = Arc::new(AtomicBool::new(false));
+    let handle = poll_in_background(subscriber.clone(), stop.clone());
+    std::thread::sleep(Duration::from_millis(200));
+
+    let reply = publisher.request("hello", b"ping", 3).unwrap();
+    assert_eq!(reply, b"pong:ping".to_vec());
+
+    //取消订阅后节点找不到订阅方，返回错误码100
+    subscriber.unsubscribe("hello").unwrap();
+    std::thread::sleep(Duration::from_millis(200));
+    let err = publisher.request("hello", b"ping", 3).unwrap_err();
+    assert_eq!(err.code, 100);
+
+    stop.store(true, Ordering::SeqCst);
+    handle.join().unwrap();
+    subscriber.finish();
+    publisher.finish();
+}
+
+#[test]
+fn request_times_out_when_subscriber_is_busy() {
+    let (_node, configfile) = common::start_node("bcos2amop_timeout");
+    let subscriber = BcosAmop::new(configfile.as_str()).unwrap();
+    let publisher = BcosAmop::new(configfile.as_str()).unwrap();
+    subscriber
+        .subscribe("slow", Arc::new(|_: &AmopMessage| Some(vec![])))
+        .unwrap();
+    std::thread::sleep(Duration::from_millis(200));
+    //订阅方不收包，收不到回包
+    let err = publisher.request("slow", b"ping", 1).unwrap_err();
+    assert_eq!(err.kind, KissErrKind::ETimeout);
+    subscriber.finish();
+    publisher.finish();
+}
+
+#[test]
+fn broadcast_reaches_every_subscriber() {
+    let (_node, configfile) = common::start_node("bcos2amop_broadcast");
+    let received = Arc::new(AtomicUsize::new(0));
+    let stop = Arc::new(AtomicBool::new(false));
+    let mut handles = vec![];
+    let mut subscribers = vec![];
+    for _ in 0..2 {
+        let subscriber = Arc::new(BcosAmop::new(configfile.as_str()).unwrap());
+        let counter = received.clone();
+        subscriber
+            .subscribe(
+                "news",
+                Arc::new(move |msg: &AmopMessage| {
+                    assert!(msg.is_broadcast);
+                    assert_eq!(msg.data, b"hi all".to_vec());
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    None
+                }),
+            )
+            .unwrap();
+        handles.push(poll_in_background(subscriber.clone(), stop.clone()));
+        subscribers.push(subscriber);
+    }
+    std::thread::sleep(Duration::from_millis(200));
+
+    let publisher = BcosAmop::new(configfile.as_str()).unwrap();
+    publisher.broadcast("news", b"hi all").unwrap();
+    for _ in 0..100 {
+        if received.load(Ordering::SeqCst) == 2 {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    assert_eq!(received.load(Ordering::SeqCst), 2);
+
+    stop.store(true, Ordering::SeqCst);
+    for h in handles {
+        h.join().unwrap();
+    }
+    for s in subscribers {
+        s.finish();
+    }
+    publisher.finish();
+}
+
+//回调里的panic被截住，收包线程和之后的请求照常
+#[test]
+fn panicking_callback_does_not_stop_polling() {
+    let (_node, configfile) = common::start_node("bcos2amop_panic");
+    let subscriber = Arc::new(BcosAmop::new(configfile.as_str()).unwrap());
+    let publisher = BcosAmop::new(configfile.as_str()).unwrap();
+    subscriber
+        .subscribe(
+            "fragile",
+            Arc::new(|msg: &AmopMessage| {
+                if msg.data == b"boom".to_vec() {
+                    panic!("amop callback panic");
+                }
+                Some(msg.data.clone())
+            }),
+        )
+        .unwrap();
+    let stop = Arc::new(AtomicBool::new(false));
+    let handle = poll_in_background(subscriber.clone(), stop.clone());
+    std::thread::sleep(Duration::from_millis(200));
+
+    let err = publisher.request("fragile", b"boom", 1).unwrap_err();
+    assert_eq!(err.kind, KissErrKind::ETimeout);
+    let reply = publisher.request("fragile", b"ping", 3).unwrap();
+    assert_eq!(reply, b"ping".to_vec());
+
+    stop.store(true, Ordering::SeqCst);
+    handle.join().unwrap();
+    subscriber.finish();
+    publisher.finish();
+}
@@ -0,0 +1,145 @@
+//channel连接的心跳、断线检测和自动重连测试，重连后要重新订阅topic和事件
+use std::time::Duration;
+
+use fisco_bcos_rust_gears_sdk::bcos2sdk::bcos2client::Bcos2Client;
+use fisco_bcos_rust_gears_sdk::bcos2sdk::bcos_channel_client::{BcosChannelClient, IBcosChannel};
+use fisco_bcos_rust_gears_sdk::bcos2sdk::bcos_channel_mocknode::{MockChainState, MockChannelNode};
+use fisco_bcos_rust_gears_sdk::bcos2sdk::channelpack::{pack_amop, ChannelPack, CHANNEL_PACK_TYPE};
+use fisco_bcos_rust_gears_sdk::bcossdkutil::bcosclientconfig::ChannelConfig;
+use serde_json::json;
+
+mod common;
+
+fn start_node(name: &str) -> (MockChannelNode, ChannelConfig) {
+    let dir = common::workdir(format!("bcos2reconnect_{}", name).as_str());
+    let node = MockChannelNode::start(MockChainState::new(), dir.to_str().unwrap()).unwrap();
+    let mut config = node.channel_config();
+    config.heartbeat_sec = 1;
+    config.timeout = 1;
+    (node, config)
+}
+
+//一边调用keep_alive一边读包，直到读到指定类型的包
+fn wait_pack(client: &mut BcosChannelClient, packtype: CHANNEL_PACK_TYPE) -> Option<ChannelPack> {
+    let packtype = packtype as u16;
+    for _ in 0..200 {
+        client.keep_alive().unwrap();
+        for pack in client.read_packets().unwrap() {
+            if pack.packtype == packtype {
+                return Some(pack);
+            }
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    None
+}
+
+fn subscribe(client: &mut BcosChannelClient) {
+    client
+        .report_topics(&["_block_notify_1".to_string()])
+        .unwrap();
+    let filter = json!({"fromBlock": "latest", "toBlock": "latest", "addresses": [],
+        "topics": [], "groupID": "1", "filterID": "f1"});
+    client
+        .register_event_filter(&pack_amop(&vec![], &filter.to_string().into_bytes()))
+        .unwrap();
+    assert!(wait_pack(client, CHANNEL_PACK_TYPE::CLIENT_REGISTER_EVENT_LOG).is_some());
+}
+
+#[test]
+fn reconnect_after_node_closes_connection() {
+    let (node, config) = start_node("closed");
+    let mut client = BcosChannelClient::new(&config).unwrap();
+    subscribe(&mut client);
+
+    node.drop_connections();
+    std::thread::sleep(Duration::from_millis(200));
+    //重连后重新注册了事件过滤，节点会再回一个注册结果
+    assert!(wait_pack(&mut client, CHANNEL_PACK_TYPE::CLIENT_REGISTER_EVENT_LOG).is_some());
+    assert_eq!(node.connection_count(), 1);
+    //topic也重新报告了，能收到出块通知
+    node.seal_empty_block();
+    let pack = wait_pack(&mut client, CHANNEL_PACK_TYPE::TX_BLOCKNUM).unwrap();
+    assert_eq!(
+        serde_json::from_slice::<serde_json::Value>(&pack.data).unwrap()["blockNumber"],
+        "1"
+    );
+    client.finish();
+}
+
+#[test]
+fn heartbeat_timeout_triggers_reconnect() {
+    let (node, config) = start_node("silent");
+    let mut client = BcosChannelClient::new(&config).unwrap();
+    subscribe(&mut client);
+
+    //正常时心跳有应答，不会重连
+    for _ in 0..125 {
+        assert!(!client.keep_alive().unwrap());
+        assert!(client.read_packets().unwrap().is_empty());
+        std::thread::sleep(Duration::from_millis(20));
+    }
+
+    node.set_silent(true);
+    let mut reconnected = false;
+    for _ in 0..250 {
+        client.read_packets().unwrap();
+        if client.keep_alive().unwrap() {
+            reconnected = true;
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    assert!(reconnected);
+    node.set_silent(false);
+    client.finish();
+}
+
+#[test]
+fn heartbeat_body_follows_protocol_version() {
+    //V1节点的心跳是"0"，格式不对时节点不回，客户端会当成断线
+    let (node, config) = start_node("v1");
+    node.state().max_protocol = 1;
+    let mut client = BcosChannelClient::new(&config).unwrap();
+    assert_eq!(client.protocol_version, 1);
+    for _ in 0..175 {
+        assert!(!client.keep_alive().unwrap());
+        client.read_packets().unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    client.finish();
+}
+
+#[test]
+fn idle_without_keep_alive_does_not_reconnect() {
+    //同步使用时长时间没有调用keep_alive，不能据此判断断线
+    let (_node, config) = start_node("idle");
+    let mut client = BcosChannelClient::new(&config).unwrap();
+    std::thread::sleep(Duration::from_millis(3500));
+    assert!(!client.keep_alive().unwrap());
+    assert!(client.heartbeat_pending);
+    for _ in 0..50 {
+        client.read_packets().unwrap();
+        if !client.heartbeat_pending {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    assert!(!client.heartbeat_pending);
+    assert!(!client.keep_alive().unwrap());
+    client.finish();
+}
+
+#[test]
+fn request_is_transparent_across_reconnect() {
+    let (node, _config) = start_node("request");
+    let configfile = common::write_client_config(&node);
+    let mut client = Bcos2Client::new_from_config(configfile.as_str()).unwrap();
+    assert_eq!(client.getBlockNumber().unwrap(), 0);
+    node.drop_connections();
+    std::thread::sleep(Duration::from_millis(200));
+    node.seal_empty_block();
+    assert_eq!(client.getBlockNumber().unwrap(), 1);
+    assert_eq!(node.connection_count(), 1);
+    client.finish();
+}
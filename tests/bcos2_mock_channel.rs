@@ -0,0 +1,230 @@
+//用进程内的模拟2.x节点(bcos_channel_mocknode)测试channel协议和Bcos2Client，不需要真实节点
+use std::time::Duration;
+
+use fisco_bcos_rust_gears_sdk::bcos2sdk::bcos2client::Bcos2Client;
+use fisco_bcos_rust_gears_sdk::bcos2sdk::bcos_channel_client::{BcosChannelClient, IBcosChannel};
+use fisco_bcos_rust_gears_sdk::bcos2sdk::bcos_channel_mocknode::{
+    MockChainState, MockChannelNode, MockTxResult,
+};
+use fisco_bcos_rust_gears_sdk::bcos2sdk::bcossdkquery::json_hextoint;
+use fisco_bcos_rust_gears_sdk::bcos2sdk::channelpack::{
+    make_channel_pack, make_channel_pack_by_rawdata, pack_amop, unpack_amop, ChannelPack,
+    CHANNEL_PACK_TYPE,
+};
+use fisco_bcos_rust_gears_sdk::bcossdkutil::contractabi::ContractABI;
+use fisco_bcos_rust_gears_sdk::bcossdkutil::fileutils;
+use fisco_bcos_rust_gears_sdk::bcossdkutil::kisserror::KissErrKind;
+use serde_json::{json, Value as JsonValue};
+
+mod common;
+
+fn start_node(name: &str, state: MockChainState) -> (MockChannelNode, String) {
+    common::start_node_with_state(format!("bcos2mock_{}", name).as_str(), state)
+}
+
+fn connect(node: &MockChannelNode) -> BcosChannelClient {
+    BcosChannelClient::new(&node.channel_config()).unwrap()
+}
+
+//读包直到拿到指定类型的包
+fn wait_pack(client: &mut BcosChannelClient, packtype: CHANNEL_PACK_TYPE) -> ChannelPack {
+    let packtype = packtype as u16;
+    for _ in 0..100 {
+        for pack in client.read_packets().unwrap() {
+            if pack.packtype == packtype {
+                return pack;
+            }
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    panic!("no pack of type 0x{:x}", packtype);
+}
+
+#[test]
+fn bcos2client_over_mock_node() {
+    let (node, configfile) = start_node("client", MockChainState::new());
+    let mut client = Bcos2Client::new_from_config(configfile.as_str()).unwrap();
+    assert_eq!(client.getBlockNumber().unwrap(), 0);
+    let version = client.getNodeVersion().unwrap();
+    assert_eq!(version["FISCO-BCOS Version"], "2.9.1");
+
+    //部署HelloWorld，mock节点给每笔交易出一个块
+    let bin = fileutils::readstring("contracts/HelloWorld.bin").unwrap();
+    let response = client.deploy_hexcode(bin.as_str()).unwrap();
+    let txhash = response["result"].as_str().unwrap().to_string();
+    let receipt = client
+        .try_getTransactionReceipt(txhash.as_str(), 3, false)
+        .unwrap();
+    let address = receipt["result"]["contractAddress"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    assert_eq!(json_hextoint(&receipt["result"]["status"]).unwrap(), 0);
+    assert_eq!(client.getBlockNumber().unwrap(), 1);
+    let block = client.getBlockByNumber(1, true).unwrap();
+    assert_eq!(block["result"]["transactions"][0]["hash"], txhash.as_str());
+    let code = client.getCode(1, address.as_str()).unwrap();
+    assert!(code["result"].as_str().unwrap().len() > 2);
+
+    //编排call的返回
+    let contract = ContractABI::new("contracts/HelloWorld.abi", &client.hashtype).unwrap();
+    let output = ethabi::encode(&[ethabi::Token::String("mock".to_string())]);
+    node.state()
+        .call_outputs
+        .insert(address.clone(), format!("0x{}", hex::encode(output)));
+    let result = client
+        .call(&contract, address.as_str(), "get", &[])
+        .unwrap();
+    let tokens = contract
+        .decode_output_byname("get", result["result"]["output"].as_str().unwrap())
+        .unwrap();
+    assert_eq!(tokens[0], ethabi::Token::String("mock".to_string()));
+
+    //交易的执行状态可以编排
+    node.state().tx_results.insert(
+        address.clone(),
+        MockTxResult {
+            status: 22,
+            ..Default::default()
+        },
+    );
+    //执行失败的交易，发送并等待回执的接口返回ETxFailed，code为回执的状态码
+    let err = client
+        .sendRawTransactionGetReceipt(&contract, address.as_str(), "set", &["x".to_string()])
+        .unwrap_err();
+    assert_eq!(err.kind, KissErrKind::ETxFailed);
+    assert_eq!(err.code, 22);
+
+    //回执不存在时result为null，和真实节点一致
+    let missing = client.getTransactionReceipt("0x01").unwrap();
+    assert_eq!(missing["result"], JsonValue::Null);
+    client.finish();
+}
+
+#[test]
+fn heartbeat_and_handshake() {
+    let (node, _) = start_node("handshake", MockChainState::new());
+    let mut client = connect(&node);
+    //连接时已握手到V3，心跳用json格式
+    let pack = make_channel_pack(CHANNEL_PACK_TYPE::HEART_BEAT, "{\"heartBeat\":\"0\"}").unwrap();
+    let resp = client.request_channelpack_sync(&pack).unwrap();
+    assert_eq!(
+        String::from_utf8(resp.data).unwrap(),
+        "{\"heartBeat\":\"1\"}"
+    );
+
+    let req = json!({"minimumSupport": 1, "maximumSupport": 5, "clientType": "rust"});
+    let pack = make_channel_pack(CHANNEL_PACK_TYPE::HAND_SHAKE, req.to_string().as_str()).unwrap();
+    let resp = client.request_channelpack_sync(&pack).unwrap();
+    let v: JsonValue = serde_json::from_slice(&resp.data).unwrap();
+    assert_eq!(v["protocol"], 3);
+    assert_eq!(v["nodeVersion"], "2.9.1");
+    client.finish();
+}
+
+#[test]
+fn block_notify_and_event_push() {
+    let (node, configfile) = start_node("notify", MockChainState::new());
+    let mut listener = connect(&node);
+    let topics = json!(["_block_notify_1"]).to_string();
+    let pack = make_channel_pack(CHANNEL_PACK_TYPE::TOPIC_REPORT, topics.as_str()).unwrap();
+    listener.try_send(&pack.pack()).unwrap();
+    let filter = json!({"fromBlock": "latest", "toBlock": "latest", "addresses": [],
+        "topics": ["0xaa"], "groupID": "1", "filterID": "f1"});
+    let data = pack_amop(&vec![], &filter.to_string().into_bytes());
+    let pack =
+        make_channel_pack_by_rawdata(CHANNEL_PACK_TYPE::CLIENT_REGISTER_EVENT_LOG, &data).unwrap();
+    let resp = listener.request_channelpack_sync(&pack).unwrap();
+    let (_, body) = unpack_amop(&resp.data);
+    let v: JsonValue = serde_json::from_slice(&body).unwrap();
+    assert_eq!(v["result"], 0);
+
+    //部署时带一条log，应推送给filter
+    node.state().tx_results.insert(
+        "".to_string(),
+        MockTxResult {
+            logs: vec![json!({"address": "", "topics": ["0xaa"], "data": "0x"})],
+            ..Default::default()
+        },
+    );
+    let mut client = Bcos2Client::new_from_config(configfile.as_str()).unwrap();
+    client.deploy_hexcode("6080").unwrap();
+    let pack = wait_pack(&mut listener, CHANNEL_PACK_TYPE::TX_BLOCKNUM);
+    let v: JsonValue = serde_json::from_slice(&pack.data).unwrap();
+    assert_eq!(v["blockNumber"], "1");
+    let pack = wait_pack(&mut listener, CHANNEL_PACK_TYPE::EVENT_LOG_PUSH);
+    let v: JsonValue = serde_json::from_slice(&pack.data).unwrap();
+    assert_eq!(v["filterID"], "f1");
+    assert_eq!(v["logs"].as_array().unwrap().len(), 1);
+
+    assert_eq!(node.seal_empty_block(), 2);
+    let pack = wait_pack(&mut listener, CHANNEL_PACK_TYPE::TX_BLOCKNUM);
+    let v: JsonValue = serde_json::from_slice(&pack.data).unwrap();
+    assert_eq!(v["blockNumber"], "2");
+    client.finish();
+    listener.finish();
+}
+
+#[test]
+fn amop_is_routed_between_connections() {
+    let (node, _) = start_node("amop", MockChainState::new());
+    let mut subscriber = connect(&node);
+    let mut publisher = connect(&node);
+    let topics = json!(["hello"]).to_string();
+    let pack = make_channel_pack(CHANNEL_PACK_TYPE::TOPIC_REPORT, topics.as_str()).unwrap();
+    subscriber.try_send(&pack.pack()).unwrap();
+    std::thread::sleep(Duration::from_millis(200));
+
+    let data = pack_amop(&b"hello".to_vec(), &b"ping".to_vec());
+    let request = make_channel_pack_by_rawdata(CHANNEL_PACK_TYPE::AMOP_REQ, &data).unwrap();
+    publisher.try_send(&request.pack()).unwrap();
+    let received = wait_pack(&mut subscriber, CHANNEL_PACK_TYPE::AMOP_REQ);
+    assert_eq!(received.seq, request.seq);
+    assert_eq!(unpack_amop(&received.data).1, b"ping".to_vec());
+
+    let mut response = received.clone();
+    response.packtype = CHANNEL_PACK_TYPE::AMOP_RESP as u16;
+    response.data = pack_amop(&b"hello".to_vec(), &b"pong".to_vec());
+    response.length = 42 + response.data.len();
+    subscriber.try_send(&response.pack()).unwrap();
+    let answer = wait_pack(&mut publisher, CHANNEL_PACK_TYPE::AMOP_RESP);
+    assert_eq!(answer.seq, request.seq);
+    assert_eq!(unpack_amop(&answer.data).1, b"pong".to_vec());
+
+    //没有订阅者时返回错误码100
+    let data = pack_amop(&b"nobody".to_vec(), &b"ping".to_vec());
+    let request = make_channel_pack_by_rawdata(CHANNEL_PACK_TYPE::AMOP_REQ, &data).unwrap();
+    publisher.try_send(&request.pack()).unwrap();
+    let answer = wait_pack(&mut publisher, CHANNEL_PACK_TYPE::AMOP_RESP);
+    assert_eq!(answer.result, 100);
+    subscriber.finish();
+    publisher.finish();
+}
+
+#[test]
+fn handshake_negotiates_protocol_on_connect() {
+    let (node, _) = start_node("negotiate", MockChainState::new());
+    let client = connect(&node);
+    assert_eq!(client.protocol_version, 3);
+    assert_eq!(client.node_version, "2.9.1");
+    assert!(client.supports_event_push());
+
+    //老节点只支持到V2，不能注册事件推送
+    let mut state = MockChainState::new();
+    state.max_protocol = 2;
+    state.node_version = "2.2.0".to_string();
+    let (old, _) = start_node("negotiate_old", state);
+    let mut client = connect(&old);
+    assert_eq!(client.protocol_version, 2);
+    assert!(!client.supports_event_push());
+    assert!(client
+        .register_event_filter(&pack_amop(&vec![], &b"{}".to_vec()))
+        .is_err());
+
+    //没有共同支持的版本时连接失败
+    let mut state = MockChainState::new();
+    state.min_protocol = 4;
+    state.max_protocol = 5;
+    let (newer, _) = start_node("negotiate_new", state);
+    assert!(BcosChannelClient::new(&newer.channel_config()).is_err());
+}
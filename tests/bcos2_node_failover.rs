@@ -0,0 +1,153 @@
+//多节点的选择和故障切换测试，用bcos_channel_mocknode起多个模拟节点，用nodeVersion区分请求落在哪个节点
+use std::net::TcpListener;
+use std::time::Duration;
+
+use fisco_bcos_rust_gears_sdk::bcos2sdk::bcos_channel_mocknode::{MockChainState, MockChannelNode};
+use fisco_bcos_rust_gears_sdk::bcos2sdk::bcosnodeselector::BcosNodeSelector;
+use fisco_bcos_rust_gears_sdk::bcos2sdk::bcosrpcwraper::BcosRPC;
+use fisco_bcos_rust_gears_sdk::bcossdkutil::bcosclientconfig::{BcosNodePolicy, ClientConfig};
+use fisco_bcos_rust_gears_sdk::bcossdkutil::kisserror::{KissErrKind, KissError};
+use serde_json::json;
+
+mod common;
+
+fn start_nodes(name: &str, count: usize) -> (Vec<MockChannelNode>, ClientConfig) {
+    let dir = common::workdir(format!("bcos2failover_{}", name).as_str());
+    let nodes: Vec<MockChannelNode> = (0..count)
+        .map(|i| {
+            let mut state = MockChainState::new();
+            state.node_version = format!("node{}", i);
+            MockChannelNode::start(state, dir.to_str().unwrap()).unwrap()
+        })
+        .collect();
+    let configfile = common::write_client_config(&nodes[0]);
+    let mut config = ClientConfig::load(configfile.as_str()).unwrap();
+    config.channel.timeout = 1;
+    config.channel.nodes = nodes[1..]
+        .iter()
+        .map(|n| format!("127.0.0.1:{}", n.port))
+        .collect();
+    config.bcos2.node_retry_sec = 1;
+    (nodes, config)
+}
+
+//返回处理请求的节点
+fn which_node(rpc: &mut BcosRPC) -> String {
+    let v = rpc
+        .rpc_request_sync("getClientVersion", &json!([1]))
+        .unwrap();
+    v["result"]["FISCO-BCOS Version"]
+        .as_str()
+        .unwrap()
+        .to_string()
+}
+
+#[test]
+fn sticky_failover_and_retry() {
+    let (nodes, config) = start_nodes("sticky", 2);
+    let mut rpc = BcosRPC::new(&config).unwrap();
+    assert_eq!(which_node(&mut rpc), "node0");
+    assert_eq!(which_node(&mut rpc), "node0");
+
+    nodes[0].set_offline(true);
+    assert_eq!(which_node(&mut rpc), "node1");
+    assert!(rpc.selector.nodes[0].is_down);
+    nodes[0].set_offline(false);
+    //sticky：恢复后也不切回去
+    std::thread::sleep(Duration::from_millis(1100));
+    assert_eq!(which_node(&mut rpc), "node1");
+
+    //node1宕机时，node0已经过了重试时间，重新启用
+    nodes[1].set_offline(true);
+    assert_eq!(which_node(&mut rpc), "node0");
+    assert!(!rpc.selector.nodes[0].is_down);
+    assert!(rpc.selector.nodes[1].is_down);
+    rpc.finish();
+}
+
+//没有请求时，心跳的探测把恢复的节点重新标记为可用，sticky的当前节点不变
+#[test]
+fn down_nodes_are_probed_without_traffic() {
+    let (nodes, config) = start_nodes("probe", 2);
+    let mut rpc = BcosRPC::new(&config).unwrap();
+    nodes[0].set_offline(true);
+    assert_eq!(which_node(&mut rpc), "node1");
+    assert!(rpc.selector.nodes[0].is_down);
+    //还没到重试时间不探测
+    assert_eq!(rpc.probe_down_nodes(), 0);
+
+    std::thread::sleep(Duration::from_millis(1100));
+    assert_eq!(rpc.probe_down_nodes(), 0);
+    assert!(rpc.selector.nodes[0].is_down);
+
+    nodes[0].set_offline(false);
+    std::thread::sleep(Duration::from_millis(1100));
+    assert!(!rpc.keep_alive().unwrap());
+    assert!(!rpc.selector.nodes[0].is_down);
+    assert_eq!(which_node(&mut rpc), "node1");
+    rpc.finish();
+}
+
+#[test]
+fn round_robin_skips_down_nodes() {
+    let (nodes, mut config) = start_nodes("roundrobin", 3);
+    config.bcos2.node_policy = BcosNodePolicy::ROUNDROBIN;
+    let mut rpc = BcosRPC::new(&config).unwrap();
+    let seen: Vec<String> = (0..6).map(|_| which_node(&mut rpc)).collect();
+    assert_eq!(
+        seen,
+        vec!["node0", "node1", "node2", "node0", "node1", "node2"]
+    );
+
+    nodes[1].set_offline(true);
+    let seen: Vec<String> = (0..4).map(|_| which_node(&mut rpc)).collect();
+    assert!(!seen.contains(&"node1".to_string()));
+    assert!(seen.contains(&"node0".to_string()) && seen.contains(&"node2".to_string()));
+
+    nodes[1].set_offline(false);
+    std::thread::sleep(Duration::from_millis(1100));
+    let seen: Vec<String> = (0..3).map(|_| which_node(&mut rpc)).collect();
+    assert!(seen.contains(&"node1".to_string()));
+    rpc.finish();
+}
+
+#[test]
+fn unreachable_first_node_on_create() {
+    let (nodes, mut config) = start_nodes("create", 1);
+    //取一个没有监听的端口
+    let port = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    config.channel.nodes = vec![format!("127.0.0.1:{}", nodes[0].port)];
+    config.channel.port = port as u32;
+    let mut rpc = BcosRPC::new(&config).unwrap();
+    assert_eq!(which_node(&mut rpc), "node0");
+    assert!(rpc.selector.nodes[0].is_down);
+
+    nodes[0].set_offline(true);
+    let e = rpc
+        .rpc_request_sync("getBlockNumber", &json!([1]))
+        .unwrap_err();
+    assert!(BcosNodeSelector::is_failover_error(&e));
+    rpc.finish();
+}
+
+#[test]
+fn only_network_errors_fail_over() {
+    let e = KissError {
+        kind: KissErrKind::EFormat,
+        code: -1,
+        msg: "".to_string(),
+    };
+    assert!(!BcosNodeSelector::is_failover_error(&e));
+    let endpoints = vec!["a:1".to_string(), "b:2".to_string(), "a:1".to_string()];
+    let mut selector = BcosNodeSelector::new(&endpoints, &BcosNodePolicy::STICKY, 30);
+    assert_eq!(selector.nodes.len(), 2);
+    assert_eq!(selector.candidates(), vec![0, 1]);
+    selector.mark_down(0, &e);
+    assert_eq!(selector.candidates(), vec![1, 0]);
+    selector.mark_ok(1);
+    assert_eq!(selector.candidates(), vec![1, 0]);
+}
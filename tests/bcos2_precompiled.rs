@@ -0,0 +1,81 @@
+//预编译合约的返回码解析成EPrecompiled错误，查询接口把返回的json字符串解析出来
+use ethabi::{Token, Uint};
+use fisco_bcos_rust_gears_sdk::bcos2sdk::bcos2client::Bcos2Client;
+use fisco_bcos_rust_gears_sdk::bcos2sdk::bcos_channel_mocknode::MockTxResult;
+use fisco_bcos_rust_gears_sdk::bcos2sdk::precompiled::{
+    check_precompiled_code, precompiled_error_message, CNS_ADDRESS, CONSENSUS_ADDRESS,
+    SYS_CONFIG_ADDRESS,
+};
+use fisco_bcos_rust_gears_sdk::bcossdkutil::kisserror::KissErrKind;
+use serde_json::json;
+
+mod common;
+
+const NODE_ID: &str = "b8acb51b9fe84f88d670646be36f31c52e67544ce56faf3dc8ea4cf1b0ebff0864c6b218fdcd9cf9891ebd414a995847911bd26a770f429300085f37e1131f36";
+
+fn code_output(code: i64) -> String {
+    //负数按int256的补码编码
+    let v = if code < 0 {
+        !Uint::from(-code) + 1
+    } else {
+        Uint::from(code)
+    };
+    format!("0x{}", hex::encode(ethabi::encode(&[Token::Int(v)])))
+}
+
+#[test]
+fn precompiled_error_codes() {
+    assert_eq!(check_precompiled_code(1, "insert").unwrap(), 1);
+    assert_eq!(check_precompiled_code(0, "insert").unwrap(), 0);
+    let err = check_precompiled_code(-51000, "insert").unwrap_err();
+    assert_eq!(err.kind, KissErrKind::EPrecompiled);
+    assert_eq!(err.code, -51000);
+    assert!(err.msg.contains("-51000"), "{}", err.msg);
+    assert_eq!(
+        precompiled_error_message(-51101),
+        "the last sealer cannot be removed"
+    );
+    assert_eq!(precompiled_error_message(-1), "unknown precompiled error");
+}
+
+#[test]
+fn precompiled_on_mock_node() {
+    let (node, configfile) = common::start_node("precompiled");
+    let mut client = Bcos2Client::new_from_config(configfile.as_str()).unwrap();
+
+    node.state().tx_results.insert(
+        CONSENSUS_ADDRESS.to_string(),
+        MockTxResult {
+            status: 0,
+            output: code_output(-51101),
+            ..Default::default()
+        },
+    );
+    let err = client.remove_node(NODE_ID).unwrap_err();
+    assert_eq!(err.kind, KissErrKind::EPrecompiled);
+    assert_eq!(err.code, -51101);
+    assert!(err.msg.contains("last sealer"), "{}", err.msg);
+
+    node.state().tx_results.insert(
+        SYS_CONFIG_ADDRESS.to_string(),
+        MockTxResult {
+            status: 0,
+            output: code_output(1),
+            ..Default::default()
+        },
+    );
+    assert_eq!(
+        client.set_system_config("tx_count_limit", "2000").unwrap(),
+        1
+    );
+
+    let records = json!([{"name": "HelloWorld", "version": "1.0",
+        "address": "0x1234567890123456789012345678901234567890", "abi": ""}]);
+    let output = ethabi::encode(&[Token::String(records.to_string())]);
+    node.state().call_outputs.insert(
+        CNS_ADDRESS.to_string(),
+        format!("0x{}", hex::encode(output)),
+    );
+    assert_eq!(client.cns_query("HelloWorld", None).unwrap(), records);
+    client.finish();
+}
@@ -0,0 +1,56 @@
+//channel协议下交易回执由节点推送的TX_COMMITTED得到，不再轮询getTransactionReceipt
+use fisco_bcos_rust_gears_sdk::bcos2sdk::bcos2client::Bcos2Client;
+use fisco_bcos_rust_gears_sdk::bcos2sdk::bcos_channel_mocknode::MockChannelNode;
+use fisco_bcos_rust_gears_sdk::bcos2sdk::bcossdkquery::json_hextoint;
+use fisco_bcos_rust_gears_sdk::bcossdkutil::contractabi::ContractABI;
+use fisco_bcos_rust_gears_sdk::bcossdkutil::fileutils;
+
+mod common;
+
+fn deploy_helloworld(name: &str) -> (MockChannelNode, Bcos2Client, ContractABI, String) {
+    let (node, configfile) = common::start_node(format!("bcos2txpush_{}", name).as_str());
+    let mut client = Bcos2Client::new_from_config(configfile.as_str()).unwrap();
+    let bin = fileutils::readstring("contracts/HelloWorld.bin").unwrap();
+    let response = client.deploy_hexcode(bin.as_str()).unwrap();
+    let receipt = client
+        .try_getTransactionReceipt(response["result"].as_str().unwrap(), 3, false)
+        .unwrap();
+    let address = receipt["result"]["contractAddress"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let contract = ContractABI::new("contracts/HelloWorld.abi", &client.hashtype).unwrap();
+    node.state().rpc_calls.clear();
+    (node, client, contract, address)
+}
+
+#[test]
+fn receipt_is_pushed_by_node() {
+    let (node, mut client, contract, address) = deploy_helloworld("push");
+    let receipt = client
+        .sendRawTransactionGetReceipt(&contract, address.as_str(), "set", &["x".to_string()])
+        .unwrap();
+    assert_eq!(json_hextoint(&receipt["result"]["status"]).unwrap(), 0);
+    assert_eq!(node.state().rpc_calls.get("getTransactionReceipt"), None);
+
+    //推送的回执和查询到的一致
+    let txhash = receipt["result"]["transactionHash"].as_str().unwrap();
+    let queried = client.getTransactionReceipt(txhash).unwrap();
+    assert_eq!(queried["result"], receipt["result"]);
+    client.finish();
+}
+
+#[test]
+fn missing_push_falls_back_to_query() {
+    let (node, mut client, contract, address) = deploy_helloworld("nopush");
+    node.set_tx_push(false);
+    let receipt = client
+        .sendRawTransactionGetReceipt(&contract, address.as_str(), "set", &["y".to_string()])
+        .unwrap();
+    assert_eq!(json_hextoint(&receipt["result"]["status"]).unwrap(), 0);
+    assert_eq!(
+        node.state().rpc_calls.get("getTransactionReceipt"),
+        Some(&1)
+    );
+    client.finish();
+}
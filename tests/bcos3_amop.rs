@@ -0,0 +1,131 @@
+//3.x AMOP回调的路由：模拟c sdk用context调用extern "C"回调，检查消息送到了对应的闭包
+#![cfg(feature = "bcos3sdk_native")]
+
+use std::ffi::{c_void, CString};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+
+use fisco_bcos_rust_gears_sdk::bcos3sdk::bcos3sdkamop;
+use fisco_bcos_rust_gears_sdk::bcos3sdk::bcos3sdkamop::Bcos3AmopMessage;
+use fisco_bcos_rust_gears_sdk::bcos3sdk::bcos3sdkresponse::bcos_sdk_c_struct_response;
+
+fn response(error: i32, data: &[u8], context: *const c_void) -> bcos_sdk_c_struct_response {
+    bcos_sdk_c_struct_response {
+        error,
+        desc: std::ptr::null(),
+        data: data.as_ptr() as *const c_void,
+        size: data.len(),
+        context,
+    }
+}
+
+#[test]
+fn subscriber_callbacks_are_routed_by_context() {
+    let sdk_a = 0x1000 as *const c_void;
+    let sdk_b = 0x2000 as *const c_void;
+    let received: Arc<Mutex<Vec<(String, Bcos3AmopMessage)>>> = Arc::new(Mutex::new(vec![]));
+    let mut ids = vec![];
+    for (sdk, name) in [(sdk_a, "a"), (sdk_b, "b")] {
+        let received = received.clone();
+        let id = bcos3sdkamop::register_subscriber(
+            sdk,
+            "topic",
+            Arc::new(move |msg: &Bcos3AmopMessage| {
+                received
+                    .lock()
+                    .unwrap()
+                    .push((name.to_string(), msg.clone()));
+                None
+            }),
+        );
+        ids.push(id);
+    }
+    assert_eq!(
+        bcos3sdkamop::subscribed_topics(sdk_a),
+        vec!["topic".to_string()]
+    );
+
+    let endpoint = CString::new("127.0.0.1:20200").unwrap();
+    let seq = CString::new("seq1").unwrap();
+    let data = vec![0u8, 0xff, 1];
+    let resp = response(0, &data, bcos3sdkamop::context_ptr(ids[1]));
+    bcos3sdkamop::amop_sub_callback(endpoint.as_ptr(), seq.as_ptr(), &resp);
+    {
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0].0, "b");
+        assert_eq!(received[0].1.topic, "topic");
+        assert_eq!(received[0].1.endpoint, "127.0.0.1:20200");
+        assert_eq!(received[0].1.seq, "seq1");
+        //二进制数据原样送到
+        assert_eq!(received[0].1.data, data);
+    }
+
+    //取消订阅后不再回调
+    assert_eq!(bcos3sdkamop::unregister_subscriber(sdk_b, "topic"), 1);
+    bcos3sdkamop::amop_sub_callback(endpoint.as_ptr(), seq.as_ptr(), &resp);
+    assert_eq!(received.lock().unwrap().len(), 1);
+    bcos3sdkamop::unregister_sdk(sdk_a);
+    assert!(bcos3sdkamop::subscribed_topics(sdk_a).is_empty());
+}
+
+#[test]
+fn publish_callback_is_called_once() {
+    let (tx, rx) = mpsc::channel();
+    let id = bcos3sdkamop::register_publish(Box::new(move |res| {
+        tx.send(res).unwrap();
+    }));
+    let resp = response(0, b"pong", bcos3sdkamop::context_ptr(id));
+    bcos3sdkamop::amop_publish_callback(&resp);
+    bcos3sdkamop::amop_publish_callback(&resp);
+    assert_eq!(rx.recv().unwrap().unwrap(), b"pong".to_vec());
+    assert!(rx.try_recv().is_err());
+
+    //c sdk回调错误码时得到Err
+    let (tx, rx) = mpsc::channel();
+    let id = bcos3sdkamop::register_publish(Box::new(move |res| {
+        tx.send(res).unwrap();
+    }));
+    let resp = response(-1, b"", bcos3sdkamop::context_ptr(id));
+    bcos3sdkamop::amop_publish_callback(&resp);
+    assert_eq!(rx.recv().unwrap().unwrap_err().code, -1);
+
+    //已取消的publish，应答被丢弃
+    let id = bcos3sdkamop::register_publish(Box::new(|_| panic!("should not be called")));
+    assert!(bcos3sdkamop::unregister_publish(id));
+    bcos3sdkamop::amop_publish_callback(&response(0, b"late", bcos3sdkamop::context_ptr(id)));
+}
+
+//闭包里的panic在回调里截住，不会穿过c的栈帧，之后的回调照常
+#[test]
+fn panicking_callbacks_do_not_unwind() {
+    let sdk = 0x4000 as *const c_void;
+    let (tx, rx) = mpsc::channel();
+    let id = bcos3sdkamop::register_subscriber(
+        sdk,
+        "panic",
+        Arc::new(move |msg: &Bcos3AmopMessage| {
+            if msg.data == b"boom" {
+                panic!("subscriber panic");
+            }
+            tx.send(msg.data.clone()).unwrap();
+            None
+        }),
+    );
+    let endpoint = CString::new("127.0.0.1:20200").unwrap();
+    let seq = CString::new("seq1").unwrap();
+    let resp = response(0, b"boom", bcos3sdkamop::context_ptr(id));
+    bcos3sdkamop::amop_sub_callback(endpoint.as_ptr(), seq.as_ptr(), &resp);
+    let resp = response(0, b"ok", bcos3sdkamop::context_ptr(id));
+    bcos3sdkamop::amop_sub_callback(endpoint.as_ptr(), seq.as_ptr(), &resp);
+    assert_eq!(rx.recv().unwrap(), b"ok".to_vec());
+    assert_eq!(
+        bcos3sdkamop::subscribed_topics(sdk),
+        vec!["panic".to_string()]
+    );
+
+    let id = bcos3sdkamop::register_publish(Box::new(|_| panic!("publish panic")));
+    bcos3sdkamop::amop_publish_callback(&response(0, b"pong", bcos3sdkamop::context_ptr(id)));
+    assert!(!bcos3sdkamop::unregister_publish(id));
+    bcos3sdkamop::unregister_sdk(sdk);
+}
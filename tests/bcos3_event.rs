@@ -0,0 +1,107 @@
+//3.x事件订阅回调的路由和日志解析：模拟c sdk用context调用extern "C"回调
+#![cfg(feature = "bcos3sdk_native")]
+
+use std::ffi::c_void;
+use std::sync::mpsc;
+
+use ethabi::Token;
+use fisco_bcos_rust_gears_sdk::bcos3sdk::bcos3sdkevent;
+use fisco_bcos_rust_gears_sdk::bcos3sdk::bcos3sdkevent::Bcos3EventSubscription;
+use fisco_bcos_rust_gears_sdk::bcos3sdk::bcos3sdkresponse::bcos_sdk_c_struct_response;
+use fisco_bcos_rust_gears_sdk::bcossdkutil::commonhash::HashType;
+use fisco_bcos_rust_gears_sdk::bcossdkutil::contractabi::ContractABI;
+use serde_json::json;
+
+fn response(error: i32, data: &[u8], context: *const c_void) -> bcos_sdk_c_struct_response {
+    bcos_sdk_c_struct_response {
+        error,
+        desc: std::ptr::null(),
+        data: data.as_ptr() as *const c_void,
+        size: data.len(),
+        context,
+    }
+}
+
+fn onset_push(contract: &ContractABI, value: &str) -> Vec<u8> {
+    let event = contract.find_event_by_name("onset").unwrap();
+    let topic = contract.event_abi_utils.event_signature(&event);
+    let data = ethabi::encode(&[Token::String(value.to_string())]);
+    json!({
+        "id": "task1",
+        "status": 0,
+        "result": [{
+            "address": "0x1234",
+            "topics": [format!("0x{}", hex::encode(topic.as_bytes()))],
+            "data": format!("0x{}", hex::encode(data)),
+        }]
+    })
+    .to_string()
+    .into_bytes()
+}
+
+#[test]
+fn pushed_logs_are_decoded_for_subscriber() {
+    let sdk = 0x3000 as *const c_void;
+    let contract = ContractABI::new("contracts/HelloWorld.abi", &HashType::KECCAK).unwrap();
+    let (tx, rx) = mpsc::channel();
+    let id = bcos3sdkevent::register_subscriber(
+        sdk,
+        &contract,
+        std::sync::Arc::new(move |logs| {
+            tx.send(logs).unwrap();
+        }),
+    );
+    let subscription = Bcos3EventSubscription::new(sdk, id, "");
+    assert_eq!(bcos3sdkevent::subscriber_count(sdk), 1);
+
+    //订阅成功的应答里没有日志，不回调
+    let ack = json!({"id": "task1", "status": 0}).to_string().into_bytes();
+    bcos3sdkevent::event_sub_callback(&response(0, &ack, bcos3sdkevent::context_ptr(id)));
+    assert!(rx.try_recv().is_err());
+
+    let push = onset_push(&contract, "hello");
+    bcos3sdkevent::event_sub_callback(&response(0, &push, bcos3sdkevent::context_ptr(id)));
+    let logs = rx.recv().unwrap().unwrap();
+    assert_eq!(logs.len(), 1);
+    assert_eq!(logs[0].params[0].name, "newname");
+    assert_eq!(logs[0].params[0].value, Token::String("hello".to_string()));
+
+    //节点返回错误状态时闭包得到Err
+    let failed = json!({"id": "task1", "status": -51003})
+        .to_string()
+        .into_bytes();
+    bcos3sdkevent::event_sub_callback(&response(0, &failed, bcos3sdkevent::context_ptr(id)));
+    assert_eq!(rx.recv().unwrap().unwrap_err().code, -51003);
+
+    //取消订阅后推送被丢弃
+    assert!(subscription.unsubscribe());
+    assert!(!subscription.unsubscribe());
+    bcos3sdkevent::event_sub_callback(&response(0, &push, bcos3sdkevent::context_ptr(id)));
+    assert!(rx.try_recv().is_err());
+    assert_eq!(bcos3sdkevent::subscriber_count(sdk), 0);
+}
+
+#[test]
+fn panicking_event_callback_does_not_unwind() {
+    let sdk = 0x5000 as *const c_void;
+    let contract = ContractABI::new("contracts/HelloWorld.abi", &HashType::KECCAK).unwrap();
+    let (tx, rx) = mpsc::channel();
+    let id = bcos3sdkevent::register_subscriber(
+        sdk,
+        &contract,
+        std::sync::Arc::new(move |logs| {
+            let logs = logs.unwrap();
+            if logs[0].params[0].value == Token::String("boom".to_string()) {
+                panic!("event subscriber panic");
+            }
+            tx.send(logs).unwrap();
+        }),
+    );
+    let push = onset_push(&contract, "boom");
+    bcos3sdkevent::event_sub_callback(&response(0, &push, bcos3sdkevent::context_ptr(id)));
+    let push = onset_push(&contract, "hello");
+    bcos3sdkevent::event_sub_callback(&response(0, &push, bcos3sdkevent::context_ptr(id)));
+    assert_eq!(rx.recv().unwrap().len(), 1);
+    assert_eq!(bcos3sdkevent::subscriber_count(sdk), 1);
+    bcos3sdkevent::unregister_sdk(sdk);
+}
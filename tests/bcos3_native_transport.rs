@@ -0,0 +1,299 @@
+//bcos3sdk_native的纯rust传输层测试：在本地起一个模拟的3.0节点(websocket + boostssl消息格式)，
+//用Bcos3Client和IBcosChainClient去查询，运行: cargo test --no-default-features --features bcos3sdk_native
+#![cfg(feature = "bcos3sdk_native")]
+
+use std::ffi::{CStr, CString};
+use std::fs;
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+use ethabi::Token;
+use fisco_bcos_rust_gears_sdk::bcos3sdk::bcos3client::Bcos3Client;
+use fisco_bcos_rust_gears_sdk::bcos3sdk::bcos3sdk_ini::Bcos3sdkIni;
+use fisco_bcos_rust_gears_sdk::bcos3sdk::bcos3sdkwrapper::bcos3sdk_def::bcos_sdk_get_group_chain_id;
+use fisco_bcos_rust_gears_sdk::bcos3sdk::bcos3sdkwrapper::EventSubParam;
+use fisco_bcos_rust_gears_sdk::bcos3sdk::bcos3transaction::Bcos3Transaction;
+use fisco_bcos_rust_gears_sdk::bcos3sdk::bcos3wsclient::Bcos3WsClient;
+use fisco_bcos_rust_gears_sdk::bcos3sdk::bcos3wsmessage::{Bcos3WsMessage, BCOS3_WS_MSG_TYPE};
+use fisco_bcos_rust_gears_sdk::bcosclient::bcoschainclient::{
+    create_chain_client, BcosChainVersion,
+};
+use fisco_bcos_rust_gears_sdk::bcossdkutil::contractabi::ContractABI;
+use fisco_bcos_rust_gears_sdk::bcossdkutil::kisserror::KissErrKind;
+use serde_json::{json, Value as JsonValue};
+use tungstenite::Message;
+
+const TXHASH: &str = "0x6a3b1e5f9c1d3f3e1d5b2a1c0e9f8d7c6b5a49382716f5e4d3c2b1a098765432";
+
+fn node_response(method: &str, params: &JsonValue) -> JsonValue {
+    match method {
+        "getBlockNumber" => json!(8),
+        "getBlockByNumber" => json!({
+            "number": params[2],
+            "hash": "0xb10c",
+            "parentInfo": [{"blockHash": "0xb10b", "blockNumber": 7}],
+            "timestamp": 1660000000000u64,
+            "sealer": 1,
+            "sealerList": ["aa", "bb"],
+            "transactions": [TXHASH],
+            "txsRoot": "0x01",
+            "receiptsRoot": "0x02",
+            "stateRoot": "0x03"
+        }),
+        "call" => {
+            let output = ethabi::encode(&[Token::String("hello".to_string())]);
+            json!({"blockNumber": 8, "output": format!("0x{}", hex::encode(output)), "status": 0})
+        }
+        "getTransactionReceipt" => json!({
+            "transactionHash": params[2],
+            "blockNumber": 8,
+            "status": 0,
+            "from": "0x1111",
+            "to": "0x2222",
+            "contractAddress": "",
+            "output": "0x",
+            "gasUsed": "21000",
+            "logEntries": [{"address": "0x2222", "topics": ["0xaa"], "data": "0x"}]
+        }),
+        //解码签名交易，把交易里的字段放到回执里，供测试检查
+        "sendTransaction" => {
+            let tx = Bcos3Transaction::decode_hex(params[2].as_str().unwrap()).unwrap();
+            json!({
+                "transactionHash": tx.txhash_hex(),
+                "blockNumber": tx.data.block_limit,
+                "status": 0,
+                "to": tx.data.to,
+                "input": format!("0x{}", hex::encode(&tx.data.input)),
+                "output": "0x",
+                "gasUsed": "0",
+                "logEntries": []
+            })
+        }
+        _ => JsonValue::Null,
+    }
+}
+
+//模拟节点：握手应答群组信息，第一个rpc请求前先推送一个新区块通知，之后按方法名应答
+fn start_stand_in_node() -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut ws = tungstenite::accept(stream.unwrap()).unwrap();
+            let mut notified = false;
+            loop {
+                let data = match ws.read_message() {
+                    Ok(Message::Binary(d)) => d,
+                    Ok(_) => continue,
+                    Err(_) => break,
+                };
+                let req = Bcos3WsMessage::decode(&data).unwrap();
+                let mut resp = req.clone();
+                if req.msgtype == BCOS3_WS_MSG_TYPE::HANDSHAKE as u16 {
+                    resp.payload = json!({
+                        "protocolVersion": 1,
+                        "groupInfoList": [{"groupID": "group0", "chainID": "chain0",
+                            "genesisConfig": {"smCrypto": false, "isWasm": false}}],
+                        "groupBlockNumber": [{"group0": 7}]
+                    })
+                    .to_string()
+                    .into_bytes();
+                } else {
+                    if !notified {
+                        notified = true;
+                        let notify = Bcos3WsMessage::new(
+                            BCOS3_WS_MSG_TYPE::BLOCK_NOTIFY,
+                            json!({"group": "group0", "blockNumber": 8})
+                                .to_string()
+                                .as_bytes(),
+                        );
+                        ws.write_message(Message::Binary(notify.encode())).unwrap();
+                    }
+                    let rpc: JsonValue = serde_json::from_slice(&req.payload).unwrap();
+                    let method = rpc["method"].as_str().unwrap();
+                    resp.payload = json!({
+                        "id": rpc["id"],
+                        "jsonrpc": "2.0",
+                        "result": node_response(method, &rpc["params"])
+                    })
+                    .to_string()
+                    .into_bytes();
+                }
+                ws.write_message(Message::Binary(resp.encode())).unwrap();
+            }
+        }
+    });
+    port
+}
+
+fn write_config(port: u16, name: &str) -> String {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let dir = std::env::temp_dir().join(format!("bcos3native_{}_{}", name, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let ini = dir.join("bcos3_sdk_config.ini");
+    fs::write(
+        &ini,
+        format!(
+            "[common]\n    disable_ssl = true\n    message_timeout_ms = 3000\n[peers]\n    node.0=127.0.0.1:{}\n",
+            port
+        ),
+    )
+    .unwrap();
+    let config = dir.join("config.toml");
+    fs::write(
+        &config,
+        format!(
+            r#"[common]
+crypto = "ECDSA"
+accountpem = "{pem}"
+contractpath = "{contracts}"
+solc = ""
+solcgm = ""
+[bcos3]
+sdk_config_file = "{ini}"
+group = "group0"
+[bcos2]
+chainid = 1
+groupid = 1
+protocol = "BCOS3"
+[rpc]
+url = ""
+timeout = 3
+[channel]
+ip = "127.0.0.1"
+port = 0
+tlskind = "ECDSA"
+timeout = 3
+nativelib_echo_mode = 0
+cacert = ""
+sdkcert = ""
+sdkkey = ""
+gmcacert = ""
+gmsdkcert = ""
+gmsdkkey = ""
+gmensdkcert = ""
+gmensdkkey = ""
+"#,
+            pem = root.join("conf/client.pem").display(),
+            contracts = root.join("contracts").display(),
+            ini = ini.display()
+        ),
+    )
+    .unwrap();
+    config.to_str().unwrap().to_string()
+}
+
+#[test]
+fn ws_message_roundtrip() {
+    let msg = Bcos3WsMessage::new(BCOS3_WS_MSG_TYPE::RPC_REQUEST, b"{\"a\":1}");
+    let data = msg.encode();
+    assert_eq!(data.len(), 10 + 32 + 7);
+    assert_eq!(&data[2..4], &[0x01, 0x02]);
+    assert_eq!(Bcos3WsMessage::decode(&data).unwrap(), msg);
+    assert!(Bcos3WsMessage::decode(&data[..9]).is_err());
+}
+
+#[test]
+fn bcos3client_over_native_transport() {
+    let port = start_stand_in_node();
+    let configfile = write_config(port, "client");
+    let mut client = Bcos3Client::new(configfile.as_str()).unwrap();
+    assert_eq!(client.getBlockNumber().unwrap(), 8);
+    //新区块通知把缓存块高更新为8
+    assert_eq!(client.getBlocklimit().unwrap(), 508);
+    let block = client.getBlockByNumber(8, 0, 1).unwrap();
+    assert_eq!(block["number"], json!(8));
+    let contract = ContractABI::new_by_name(
+        "HelloWorld",
+        client.config.common.contractpath.as_str(),
+        &client.hashtype,
+    )
+    .unwrap();
+    let res = client.call("0x2222", "get", &vec![], &contract).unwrap();
+    let output = contract
+        .decode_output_byname("get", res["output"].as_str().unwrap())
+        .unwrap();
+    assert_eq!(output, vec![Token::String("hello".to_string())]);
+    let receipt = client
+        .sendTransaction("0x2222", "set", &["hi".to_string()], &contract)
+        .unwrap();
+    assert_eq!(receipt["to"], json!("0x2222"));
+    assert_eq!(receipt["blockNumber"], json!(508));
+    let input = contract
+        .encode_function_input_to_abi("set", &["hi".to_string()], true)
+        .unwrap();
+    assert_eq!(
+        receipt["input"].as_str().unwrap(),
+        format!("0x{}", input.trim_start_matches("0x"))
+    );
+    assert_eq!(receipt["transactionHash"].as_str().unwrap().len(), 66);
+    let err = client
+        .send_signed_transaction("00\u{0}11", "set")
+        .unwrap_err();
+    assert_eq!(err.kind, KissErrKind::EArgument);
+    client.finish();
+}
+
+#[test]
+fn chain_client_over_native_transport() {
+    let port = start_stand_in_node();
+    let configfile = write_config(port, "chain");
+    let mut client = create_chain_client(configfile.as_str()).unwrap();
+    assert_eq!(client.chain_version(), BcosChainVersion::V3);
+    assert_eq!(client.get_block_number().unwrap(), 8);
+    let block = client.get_block_by_number(8, false).unwrap();
+    assert_eq!(block.parent_hash, "0xb10b");
+    assert_eq!(block.tx_hashes, vec![TXHASH.to_string()]);
+    let receipt = client.get_transaction_receipt(TXHASH).unwrap().unwrap();
+    assert!(receipt.is_success());
+    assert_eq!(receipt.gas_used, 21000);
+    assert_eq!(receipt.logs.len(), 1);
+    client.finish();
+}
+
+#[test]
+fn chain_id_and_unsupported_push_interfaces() {
+    let port = start_stand_in_node();
+    let configfile = write_config(port, "push");
+    let mut client = Bcos3Client::new(configfile.as_str()).unwrap();
+    let group = CString::new("group0").unwrap();
+    let chain_id =
+        unsafe { CStr::from_ptr(bcos_sdk_get_group_chain_id(client.sdk, group.as_ptr())) };
+    assert_eq!(chain_id.to_str().unwrap(), "chain0");
+    let nogroup = CString::new("group9").unwrap();
+    assert!(unsafe { bcos_sdk_get_group_chain_id(client.sdk, nogroup.as_ptr()) }.is_null());
+
+    //事件订阅和AMOP在native实现里不可用，订阅时就返回错误，不会静默地收不到消息
+    let err = client.amop_subscribe("topic", |_| None).unwrap_err();
+    assert!(err.msg.contains("bcos3sdk_ffi"), "{}", err.msg);
+    assert!(client.amop_subscribed_topics().is_empty());
+    assert!(client.amop_publish("topic", b"hi", 1000, |_| {}).is_err());
+    let contract = ContractABI::new_by_name(
+        "HelloWorld",
+        client.config.common.contractpath.as_str(),
+        &client.hashtype,
+    )
+    .unwrap();
+    let err = client
+        .subscribe_event(&EventSubParam::default(), &contract, |_| {})
+        .unwrap_err();
+    assert!(err.msg.contains("event subscribe"), "{}", err.msg);
+    client.finish();
+}
+
+#[test]
+fn cached_blocknumber_expires() {
+    let port = start_stand_in_node();
+    let configfile = write_config(port, "blocknumber");
+    let ini = PathBuf::from(configfile).with_file_name("bcos3_sdk_config.ini");
+    let sdkini = Bcos3sdkIni::load(ini.to_str().unwrap()).unwrap();
+    let mut client = Bcos3WsClient::connect(&sdkini).unwrap();
+    //握手时的块高还没过期，不查询节点
+    assert_eq!(client.blocknumber("group0").unwrap(), 7);
+    //过期后用getBlockNumber刷新
+    client.blocknumber_max_age = Duration::from_secs(0);
+    assert_eq!(client.blocknumber("group0").unwrap(), 8);
+    client.finish();
+}
@@ -0,0 +1,133 @@
+//3.0交易的tars编码、hash、签名测试，不需要节点和c sdk
+use fisco_bcos_rust_gears_sdk::bcos3sdk::bcos3tars::{TarsReader, TarsValue, TarsWriter};
+use fisco_bcos_rust_gears_sdk::bcos3sdk::bcos3transaction::{
+    Bcos3Transaction, Bcos3TransactionData,
+};
+use fisco_bcos_rust_gears_sdk::bcossdkutil::commonhash::HashType;
+use fisco_bcos_rust_gears_sdk::bcossdkutil::commonsigner::{
+    CommonSignerWeDPR_SM2, CommonSignerWeDPR_Secp256,
+};
+use wedpr_l_crypto_signature_secp256k1::WedprSecp256k1Recover;
+use wedpr_l_crypto_signature_sm2::WedprSm2p256v1;
+use wedpr_l_utils::traits::Signature;
+
+static DEMO_KEY: &str = "82dcd33c98a23d5d06f9331554e14ab4044a1d71b169b7a38b61c214f0690f80";
+
+fn demo_txdata(hashtype: &HashType) -> Bcos3TransactionData {
+    //HelloWorld.set("hello")
+    let input = hex::decode("4ed3885e0000000000000000000000000000000000000000000000000000000000000020000000000000000000000000000000000000000000000000000000000000000568656c6c6f000000000000000000000000000000000000000000000000000000").unwrap();
+    Bcos3TransactionData::new(
+        "chain0",
+        "group0",
+        1500,
+        "0x2222222222222222222222222222222222222222",
+        input,
+        "",
+        hashtype,
+    )
+}
+
+#[test]
+fn tars_int_and_head_encoding() {
+    let mut writer = TarsWriter::new();
+    writer.write_int(0, 0);
+    writer.write_int(1, -1);
+    writer.write_int(2, 300);
+    writer.write_int(3, 70000);
+    writer.write_int(4, 1 << 40);
+    writer.write_string(20, "ab");
+    writer.write_bytes(5, &[1, 2, 3]);
+    assert_eq!(
+        writer.buffer,
+        vec![
+            0x0C, 0x10, 0xFF, 0x21, 0x01, 0x2C, 0x32, 0x00, 0x01, 0x11, 0x70, 0x43, 0x00, 0x00,
+            0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0xF6, 20, 2, b'a', b'b', 0x5D, 0x00, 0x00, 3, 1, 2,
+            3
+        ]
+    );
+    let mut reader = TarsReader::new(&writer.buffer);
+    let expect = vec![
+        (0, TarsValue::Int(0)),
+        (1, TarsValue::Int(-1)),
+        (2, TarsValue::Int(300)),
+        (3, TarsValue::Int(70000)),
+        (4, TarsValue::Int(1 << 40)),
+        (20, TarsValue::Str("ab".to_string())),
+        (5, TarsValue::Bytes(vec![1, 2, 3])),
+    ];
+    for item in expect {
+        assert_eq!(reader.read_field().unwrap(), item);
+    }
+    assert!(reader.is_end());
+    assert!(TarsReader::new(&[0x5D, 0x00, 0x00, 9, 1])
+        .read_field()
+        .is_err());
+}
+
+#[test]
+fn transaction_hash_covers_fields() {
+    let txdata = demo_txdata(&HashType::WEDPR_KECCAK);
+    let hash = txdata.hash();
+    assert_eq!(hash.len(), 32);
+    assert_eq!(hash, txdata.clone().hash());
+    let mut other = txdata.clone();
+    other.block_limit += 1;
+    assert_ne!(hash, other.hash());
+    let mut other = txdata.clone();
+    other.nonce = "1".to_string();
+    assert_ne!(hash, other.hash());
+    let mut sm3data = txdata;
+    sm3data.hashtype = HashType::WEDRP_SM3;
+    assert_ne!(hash, sm3data.hash());
+}
+
+#[test]
+fn sign_ecdsa_transaction() {
+    let mut signer = CommonSignerWeDPR_Secp256::default();
+    signer.key_from_hexstr(DEMO_KEY);
+    let txdata = demo_txdata(&HashType::WEDPR_KECCAK);
+    let tx = Bcos3Transaction::sign(&signer, &txdata).unwrap();
+    assert_eq!(tx.data_hash, txdata.hash());
+    assert_eq!(tx.signature.len(), 65);
+    assert!(tx.signature[64] <= 1);
+    let recover = WedprSecp256k1Recover::default()
+        .recover_public_key(&tx.data_hash, &tx.signature)
+        .unwrap();
+    assert!(recover.ends_with(&signer.account.pubkey));
+
+    let decoded = Bcos3Transaction::decode_hex(tx.encode_hex().as_str()).unwrap();
+    let mut expect = tx.clone();
+    expect.data.hashtype = HashType::Unknow;
+    assert_eq!(decoded, expect);
+    assert_eq!(
+        Bcos3TransactionData::decode_bytes(&txdata.encode())
+            .unwrap()
+            .nonce,
+        txdata.nonce
+    );
+}
+
+#[test]
+fn sign_gm_transaction() {
+    let mut signer = CommonSignerWeDPR_SM2::default();
+    signer.key_from_hexstr(DEMO_KEY);
+    let txdata = demo_txdata(&HashType::WEDRP_SM3);
+    let tx = Bcos3Transaction::sign(&signer, &txdata).unwrap();
+    assert_eq!(tx.signature.len(), 128);
+    assert_eq!(&tx.signature[64..], signer.account.pubkey.as_slice());
+    let mut pubkey = vec![4u8];
+    pubkey.extend_from_slice(&signer.account.pubkey);
+    assert!(WedprSm2p256v1::default().verify(
+        pubkey.as_slice(),
+        tx.data_hash.as_slice(),
+        &tx.signature[..64]
+    ));
+}
+
+#[test]
+fn unknown_hashtype_is_rejected() {
+    let mut signer = CommonSignerWeDPR_Secp256::default();
+    signer.key_from_hexstr(DEMO_KEY);
+    let txdata = demo_txdata(&HashType::Unknow);
+    assert!(Bcos3Transaction::sign(&signer, &txdata).is_err());
+}
@@ -0,0 +1,135 @@
+//事件索引器：追赶、重启后从checkpoint继续、追上后等待新块通知
+use std::time::Duration;
+
+use ethabi::Token;
+use fisco_bcos_rust_gears_sdk::bcos2sdk::bcos2client::Bcos2Client;
+use fisco_bcos_rust_gears_sdk::bcos2sdk::bcos_channel_mocknode::{MockChannelNode, MockTxResult};
+use fisco_bcos_rust_gears_sdk::bcosclient::bcoseventindexer::{
+    EventIndexer, IndexedEvent, IndexerCheckpoint, IndexerMode, JsonLinesSink,
+};
+use fisco_bcos_rust_gears_sdk::bcossdkutil::contractabi::ContractABI;
+use fisco_bcos_rust_gears_sdk::bcossdkutil::fileutils;
+use serde_json::json;
+
+mod common;
+
+fn deploy(client: &mut Bcos2Client) -> String {
+    let response = client.deploy_hexcode("6080").unwrap();
+    let receipt = client
+        .try_getTransactionReceipt(response["result"].as_str().unwrap(), 3, false)
+        .unwrap();
+    receipt["result"]["contractAddress"]
+        .as_str()
+        .unwrap()
+        .to_string()
+}
+
+//让合约的每笔交易都带一条onset事件
+fn emit_onset(node: &MockChannelNode, contract: &ContractABI, address: &str, value: &str) {
+    let event = contract.find_event_by_name("onset").unwrap();
+    let topic = contract.event_abi_utils.event_signature(event);
+    let data = ethabi::encode(&[Token::String(value.to_string())]);
+    node.state().tx_results.insert(
+        address.to_string(),
+        MockTxResult {
+            logs: vec![json!({
+                "address": "",
+                "topics": [format!("0x{}", hex::encode(topic.as_bytes()))],
+                "data": format!("0x{}", hex::encode(data)),
+            })],
+            ..Default::default()
+        },
+    );
+}
+
+fn set(client: &mut Bcos2Client, contract: &ContractABI, address: &str) {
+    client
+        .sendRawTransactionGetReceipt(contract, address, "set", &["x".to_string()])
+        .unwrap();
+}
+
+fn read_events(path: &str) -> Vec<IndexedEvent> {
+    fileutils::readstring(path)
+        .unwrap()
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect()
+}
+
+#[test]
+fn indexer_resumes_from_checkpoint_and_follows_new_blocks() {
+    let dir = common::workdir("bcosindexer");
+    let (node, configfile) = common::start_node("bcosindexer");
+    let mut client = Bcos2Client::new_from_config(configfile.as_str()).unwrap();
+    let contract = ContractABI::new("contracts/HelloWorld.abi", &client.hashtype).unwrap();
+    let watched = deploy(&mut client);
+    let other = deploy(&mut client);
+    emit_onset(&node, &contract, watched.as_str(), "hello");
+    emit_onset(&node, &contract, other.as_str(), "ignored");
+    set(&mut client, &contract, watched.as_str());
+    set(&mut client, &contract, other.as_str());
+    set(&mut client, &contract, watched.as_str());
+
+    let checkpoint = dir.join("indexer.checkpoint");
+    let checkpoint = checkpoint.to_str().unwrap();
+    let output = dir.join("events.jsonl");
+    let output = output.to_str().unwrap();
+    let _ = std::fs::remove_file(checkpoint);
+    let _ = std::fs::remove_file(output);
+
+    //追赶：只有被索引的合约的事件，按块高顺序
+    let mut indexer = EventIndexer::new(checkpoint, Box::new(JsonLinesSink::new(output))).unwrap();
+    indexer.add_contract(watched.to_uppercase().as_str(), &contract);
+    indexer.batch_size = 2;
+    let mut total = 0;
+    while indexer.next_block() <= 5 {
+        total += indexer.run_once(&mut client).unwrap();
+    }
+    assert_eq!(total, 2);
+    assert_eq!(indexer.mode, IndexerMode::Live);
+    let events = read_events(output);
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].event, "onset");
+    assert_eq!(events[0].params["newname"], "hello");
+    assert_eq!(events[0].address, watched);
+    assert!(events[0].block_number < events[1].block_number);
+    assert_eq!(
+        IndexerCheckpoint::load(checkpoint)
+            .unwrap()
+            .unwrap()
+            .last_block,
+        5
+    );
+
+    //重启后从checkpoint继续，不重复也不遗漏
+    drop(indexer);
+    set(&mut client, &contract, watched.as_str());
+    let mut indexer = EventIndexer::new(checkpoint, Box::new(JsonLinesSink::new(output))).unwrap();
+    indexer.add_contract(watched.as_str(), &contract);
+    assert_eq!(indexer.next_block(), 6);
+    assert_eq!(indexer.run_once(&mut client).unwrap(), 1);
+    assert_eq!(read_events(output).len(), 3);
+
+    //追上以后等待节点的新块通知
+    let sender_config = configfile.clone();
+    let sender_contract = contract.clone();
+    let sender_address = watched.clone();
+    let sender = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(500));
+        let mut sender = Bcos2Client::new_from_config(sender_config.as_str()).unwrap();
+        set(&mut sender, &sender_contract, sender_address.as_str());
+        sender.finish();
+    });
+    indexer.live_wait_ms = 10000;
+    let mut total = 0;
+    while total == 0 {
+        total = indexer.run_once(&mut client).unwrap();
+    }
+    sender.join().unwrap();
+    assert_eq!(indexer.mode, IndexerMode::Live);
+    let events = read_events(output);
+    assert_eq!(events.len(), 4);
+    assert_eq!(events[3].block_number, 7);
+    assert_eq!(indexer.last_block, Some(7));
+    client.finish();
+}
@@ -0,0 +1,159 @@
+//压测：预签名后多个连接并发发送，统计TPS、延迟分位数和失败原因
+use fisco_bcos_rust_gears_sdk::bcos2sdk::bcos_channel_mocknode::MockTxResult;
+use fisco_bcos_rust_gears_sdk::bcosclient::bcoschainclient::create_chain_client;
+use fisco_bcos_rust_gears_sdk::bcosclient::bcosloadtest::BcosLoadTest;
+use fisco_bcos_rust_gears_sdk::bcosclient::bcosofflinetx::{signer_from_config, OfflineTxBuilder};
+use fisco_bcos_rust_gears_sdk::bcossdkutil::bcosclientconfig::ClientConfig;
+use fisco_bcos_rust_gears_sdk::bcossdkutil::contractabi::ContractABI;
+use fisco_bcos_rust_gears_sdk::bcossdkutil::stattool::LatencyStat;
+use std::time::Duration;
+
+mod common;
+
+const TO: &str = "0x7029c502b4f824d19bd7921e9cb74ef92392fb1c";
+
+#[test]
+fn concurrent_send_with_report() {
+    let (node, configfile) = common::start_node("bcosloadtest");
+    let root = env!("CARGO_MANIFEST_DIR");
+    let mut client = create_chain_client(configfile.as_str()).unwrap();
+    //没有配置receipt_timeout_sec时用默认值
+    assert_eq!(client.receipt_timeout_sec(), 30);
+    let contract = ContractABI::new_by_name(
+        "HelloWorld",
+        format!("{}/contracts", root).as_str(),
+        client.get_hashtype(),
+    )
+    .unwrap();
+    let mut loadtest = BcosLoadTest::new(configfile.as_str(), 3);
+    loadtest
+        .presign(
+            client.as_mut(),
+            &contract,
+            TO,
+            "set",
+            &["x".to_string()],
+            20,
+        )
+        .unwrap();
+    client.finish();
+    assert_eq!(loadtest.rawtxs.len(), 20);
+
+    let report = loadtest.run().unwrap();
+    assert_eq!(report.total, 20);
+    assert_eq!(report.success, 20);
+    assert_eq!(report.connections, 3);
+    assert_eq!(report.latency.count(), 20);
+    assert!(report.tps() > 0.0);
+    assert!(report.failures.is_empty());
+    assert_eq!(node.state().block_number(), 20);
+
+    //执行失败的回执按状态码计数，发送失败的按错误码计数
+    node.state().tx_results.insert(
+        TO.to_string(),
+        MockTxResult {
+            status: 22,
+            ..Default::default()
+        },
+    );
+    let mut client = create_chain_client(configfile.as_str()).unwrap();
+    loadtest
+        .presign(client.as_mut(), &contract, TO, "set", &["x".to_string()], 4)
+        .unwrap();
+    client.finish();
+    let config = ClientConfig::load(configfile.as_str()).unwrap();
+    let signer = signer_from_config(&config.common).unwrap();
+    let expired = OfflineTxBuilder::from_config(&config, 1)
+        .sign_raw(signer.as_ref(), TO, &[1, 2, 3, 4])
+        .unwrap();
+    loadtest.rawtxs.push(expired);
+    let report = loadtest.run().unwrap();
+    assert_eq!(report.total, 5);
+    assert_eq!(report.success, 0);
+    assert_eq!(report.failed, 5);
+    assert_eq!(report.failures["status:22"], 4);
+    assert_eq!(report.failures["error:-32602"], 1);
+    assert_eq!(report.latency.count(), 4);
+    let v = report.to_json();
+    assert_eq!(v["failures"]["status:22"], 4);
+    assert!(report.summary().contains("failure status:22 : 4"));
+}
+
+//每个连接连续发送window笔交易，再通过TX_COMMITTED推送收回执
+#[test]
+fn pipelined_send_with_window() {
+    let (node, configfile) = common::start_node("bcosloadtest_window");
+    let root = env!("CARGO_MANIFEST_DIR");
+    let mut client = create_chain_client(configfile.as_str()).unwrap();
+    assert!(client.supports_send_nowait());
+    let contract = ContractABI::new_by_name(
+        "HelloWorld",
+        format!("{}/contracts", root).as_str(),
+        client.get_hashtype(),
+    )
+    .unwrap();
+    let mut loadtest = BcosLoadTest::new(configfile.as_str(), 2);
+    loadtest.window = 8;
+    loadtest
+        .presign(
+            client.as_mut(),
+            &contract,
+            TO,
+            "set",
+            &["x".to_string()],
+            30,
+        )
+        .unwrap();
+    client.finish();
+    let report = loadtest.run().unwrap();
+    assert_eq!(report.total, 30);
+    assert_eq!(report.success, 30);
+    assert_eq!(report.latency.count(), 30);
+    assert!(report.failures.is_empty());
+    assert_eq!(node.state().block_number(), 30);
+
+    node.state().tx_results.insert(
+        TO.to_string(),
+        MockTxResult {
+            status: 22,
+            ..Default::default()
+        },
+    );
+    let mut client = create_chain_client(configfile.as_str()).unwrap();
+    loadtest
+        .presign(client.as_mut(), &contract, TO, "set", &["x".to_string()], 6)
+        .unwrap();
+    client.finish();
+    let config = ClientConfig::load(configfile.as_str()).unwrap();
+    let signer = signer_from_config(&config.common).unwrap();
+    let expired = OfflineTxBuilder::from_config(&config, 1)
+        .sign_raw(signer.as_ref(), TO, &[1, 2, 3, 4])
+        .unwrap();
+    loadtest.rawtxs.push(expired);
+    let report = loadtest.run().unwrap();
+    assert_eq!(report.total, 7);
+    assert_eq!(report.failures["status:22"], 6);
+    assert_eq!(report.failures["error:-32602"], 1);
+    assert_eq!(report.latency.count(), 6);
+}
+
+#[test]
+fn latency_percentiles() {
+    let mut stat = LatencyStat::default();
+    assert_eq!(stat.percentile_ms(99.0), 0.0);
+    for ms in (1..=100).rev() {
+        stat.add(Duration::from_millis(ms));
+    }
+    assert_eq!(stat.count(), 100);
+    assert_eq!(stat.percentile_ms(50.0), 50.0);
+    assert_eq!(stat.percentile_ms(90.0), 90.0);
+    assert_eq!(stat.percentile_ms(99.0), 99.0);
+    assert_eq!(stat.percentile_ms(100.0), 100.0);
+    assert_eq!(stat.min_ms(), 1.0);
+    assert_eq!(stat.max_ms(), 100.0);
+    assert_eq!(stat.avg_ms(), 50.5);
+    let mut other = LatencyStat::default();
+    other.add(Duration::from_millis(1000));
+    stat.merge(&other);
+    assert_eq!(stat.max_ms(), 1000.0);
+}
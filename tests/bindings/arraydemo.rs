@@ -0,0 +1,67 @@
+// 由bcoscontractgen根据ArrayDemo.abi生成，不要手工修改
+#[allow(non_snake_case, non_camel_case_types, dead_code, unused_mut, unused_imports, clippy::all)]
+pub mod arraydemo {
+    use fisco_bcos_rust_gears_sdk::bcosclient::bcoschainclient::IBcosChainClient;
+    use fisco_bcos_rust_gears_sdk::bcosclient::bcoschaintypes::BcosReceiptInfo;
+    use fisco_bcos_rust_gears_sdk::bcosclient::bcoscontract::*;
+    use fisco_bcos_rust_gears_sdk::bcossdkutil::kisserror::KissError;
+
+    #[derive(Debug, Clone)]
+    pub struct ArrayDemo {
+        pub contract: BcosContract,
+    }
+
+    impl ArrayDemo {
+        pub const NAME: &'static str = "ArrayDemo";
+        pub const ABI: &'static str = r#"[{"constant":false,"inputs":[{"name":"v","type":"uint256"},{"name":"inputdata","type":"string[]"}],"name":"add","outputs":[{"name":"","type":"uint256"}],"payable":false,"stateMutability":"nonpayable","type":"function"},{"constant":true,"inputs":[],"name":"total","outputs":[{"name":"","type":"uint256"}],"payable":false,"stateMutability":"view","type":"function"},{"constant":true,"inputs":[{"name":"index","type":"uint256"}],"name":"get","outputs":[{"name":"","type":"uint256"},{"name":"","type":"string"}],"payable":false,"stateMutability":"view","type":"function"},{"inputs":[],"payable":false,"stateMutability":"nonpayable","type":"constructor"},{"anonymous":false,"inputs":[{"indexed":false,"name":"value","type":"uint256"},{"indexed":false,"name":"data","type":"string[]"}],"name":"on_set","type":"event"}]"#;
+
+        ///已部署在address的合约
+        pub fn at(client: &dyn IBcosChainClient, address: &str) -> Result<Self, KissError> {
+            Ok(ArrayDemo {
+                contract: BcosContract::new(Self::NAME, Self::ABI, address, client.get_hashtype())?,
+            })
+        }
+
+        ///add(uint256,string[]):(uint256) (发送交易)
+        pub fn add(&self, client: &mut dyn IBcosChainClient, v: Uint, inputdata: Vec<String>) -> Result<BcosReceiptInfo, KissError> {
+            self.contract.send(client, "add", &[Token::Uint(v), Token::Array(inputdata.into_iter().map(|v0| Token::String(v0)).collect())])
+        }
+
+        ///解码add交易回执里的返回值
+        pub fn add_output(&self, receipt: &BcosReceiptInfo) -> Result<Uint, KissError> {
+            let mut outputs = self.contract.decode_output("add", receipt.output.as_str())?.into_iter();
+            Ok(token_into_uint(next_token(&mut outputs)?)?)
+        }
+
+        ///total():(uint256) (call)
+        pub fn total(&self, client: &mut dyn IBcosChainClient) -> Result<Uint, KissError> {
+            let mut outputs = self.contract.call(client, "total", &[])?.into_iter();
+            Ok(token_into_uint(next_token(&mut outputs)?)?)
+        }
+
+        ///get(uint256):(uint256,string) (call)
+        pub fn get(&self, client: &mut dyn IBcosChainClient, index: Uint) -> Result<(Uint, String), KissError> {
+            let mut outputs = self.contract.call(client, "get", &[Token::Uint(index)])?.into_iter();
+            Ok((token_into_uint(next_token(&mut outputs)?)?, token_into_string(next_token(&mut outputs)?)?))
+        }
+
+        ///解析回执里的on_set事件
+        pub fn parse_on_set_events(&self, receipt: &BcosReceiptInfo) -> Result<Vec<ArrayDemoOnSetEvent>, KissError> {
+            let mut events = vec![];
+            for log in self.contract.parse_events(receipt, "on_set")? {
+                let mut params = log.params.into_iter().map(|p| p.value);
+                events.push(ArrayDemoOnSetEvent {
+                    value: token_into_uint(next_token(&mut params)?)?,
+                    data: token_into_array(next_token(&mut params)?)?.into_iter().map(|v0| -> Result<_, KissError> { Ok(token_into_string(v0)?) }).collect::<Result<Vec<_>, KissError>>()?,
+                });
+            }
+            Ok(events)
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct ArrayDemoOnSetEvent {
+        pub value: Uint,
+        pub data: Vec<String>,
+    }
+}
@@ -0,0 +1,60 @@
+// 由bcoscontractgen根据HelloWorld.abi生成，不要手工修改
+#[allow(non_snake_case, non_camel_case_types, dead_code, unused_mut, unused_imports, clippy::all)]
+pub mod helloworld {
+    use fisco_bcos_rust_gears_sdk::bcosclient::bcoschainclient::IBcosChainClient;
+    use fisco_bcos_rust_gears_sdk::bcosclient::bcoschaintypes::BcosReceiptInfo;
+    use fisco_bcos_rust_gears_sdk::bcosclient::bcoscontract::*;
+    use fisco_bcos_rust_gears_sdk::bcossdkutil::kisserror::KissError;
+
+    #[derive(Debug, Clone)]
+    pub struct HelloWorld {
+        pub contract: BcosContract,
+    }
+
+    impl HelloWorld {
+        pub const NAME: &'static str = "HelloWorld";
+        pub const ABI: &'static str = r#"[{"constant":false,"inputs":[{"name":"n","type":"string"}],"name":"set","outputs":[],"payable":false,"stateMutability":"nonpayable","type":"function"},{"constant":true,"inputs":[],"name":"get","outputs":[{"name":"","type":"string"}],"payable":false,"stateMutability":"view","type":"function"},{"inputs":[],"payable":false,"stateMutability":"nonpayable","type":"constructor"},{"anonymous":false,"inputs":[{"indexed":false,"name":"newname","type":"string"}],"name":"onset","type":"event"}]"#;
+        pub const BIN: &'static str = "608060405234801561001057600080fd5b506040805190810160405280600d81526020017f48656c6c6f2c20576f726c6421000000000000000000000000000000000000008152506000908051906020019061005c929190610062565b50610107565b828054600181600116156101000203166002900490600052602060002090601f016020900481019282601f106100a357805160ff19168380011785556100d1565b828001600101855582156100d1579182015b828111156100d05782518255916020019190600101906100b5565b5b5090506100de91906100e2565b5090565b61010491905b808211156101005760008160009055506001016100e8565b5090565b90565b610373806101166000396000f30060806040526004361061004c576000357c0100000000000000000000000000000000000000000000000000000000900463ffffffff1680634ed3885e146100515780636d4ce63c146100ba575b600080fd5b34801561005d57600080fd5b506100b8600480360381019080803590602001908201803590602001908080601f016020809104026020016040519081016040528093929190818152602001838380828437820191505050505050919291929050505061014a565b005b3480156100c657600080fd5b506100cf610200565b6040518080602001828103825283818151815260200191508051906020019080838360005b8381101561010f5780820151818401526020810190506100f4565b50505050905090810190601f16801561013c5780820380516001836020036101000a031916815260200191505b509250505060405180910390f35b7fafb180742c1292ea5d67c4f6d51283ecb11e49f8389f4539bef82135d689e118816040518080602001828103825283818151815260200191508051906020019080838360005b838110156101ac578082015181840152602081019050610191565b50505050905090810190601f1680156101d95780820380516001836020036101000a031916815260200191505b509250505060405180910390a180600090805190602001906101fc9291906102a2565b5050565b606060008054600181600116156101000203166002900480601f0160208091040260200160405190810160405280929190818152602001828054600181600116156101000203166002900480156102985780601f1061026d57610100808354040283529160200191610298565b820191906000526020600020905b81548152906001019060200180831161027b57829003601f168201915b5050505050905090565b828054600181600116156101000203166002900490600052602060002090601f016020900481019282601f106102e357805160ff1916838001178555610311565b82800160010185558215610311579182015b828111156103105782518255916020019190600101906102f5565b5b50905061031e9190610322565b5090565b61034491905b80821115610340576000816000905550600101610328565b5090565b905600a165627a7a723058204bbcb5a5bceb1f0ccd9bda0b5a81b9098491c37319d7f227f5dea89b2494db970029";
+
+        ///已部署在address的合约
+        pub fn at(client: &dyn IBcosChainClient, address: &str) -> Result<Self, KissError> {
+            Ok(HelloWorld {
+                contract: BcosContract::new(Self::NAME, Self::ABI, address, client.get_hashtype())?,
+            })
+        }
+
+        ///部署合约，新合约的地址在回执的contract_address里
+        pub fn deploy(client: &mut dyn IBcosChainClient) -> Result<(Self, BcosReceiptInfo), KissError> {
+            let (contract, receipt) = BcosContract::deploy(client, Self::NAME, Self::ABI, Self::BIN, &[])?;
+            Ok((HelloWorld { contract }, receipt))
+        }
+
+        ///set(string) (发送交易)
+        pub fn set(&self, client: &mut dyn IBcosChainClient, n: String) -> Result<BcosReceiptInfo, KissError> {
+            self.contract.send(client, "set", &[Token::String(n)])
+        }
+
+        ///get():(string) (call)
+        pub fn get(&self, client: &mut dyn IBcosChainClient) -> Result<String, KissError> {
+            let mut outputs = self.contract.call(client, "get", &[])?.into_iter();
+            Ok(token_into_string(next_token(&mut outputs)?)?)
+        }
+
+        ///解析回执里的onset事件
+        pub fn parse_onset_events(&self, receipt: &BcosReceiptInfo) -> Result<Vec<HelloWorldOnsetEvent>, KissError> {
+            let mut events = vec![];
+            for log in self.contract.parse_events(receipt, "onset")? {
+                let mut params = log.params.into_iter().map(|p| p.value);
+                events.push(HelloWorldOnsetEvent {
+                    newname: token_into_string(next_token(&mut params)?)?,
+                });
+            }
+            Ok(events)
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct HelloWorldOnsetEvent {
+        pub newname: String,
+    }
+}
@@ -0,0 +1,215 @@
+// 由bcoscontractgen根据SimpleInfo.abi生成，不要手工修改
+#[allow(non_snake_case, non_camel_case_types, dead_code, unused_mut, unused_imports, clippy::all)]
+pub mod simpleinfo {
+    use fisco_bcos_rust_gears_sdk::bcosclient::bcoschainclient::IBcosChainClient;
+    use fisco_bcos_rust_gears_sdk::bcosclient::bcoschaintypes::BcosReceiptInfo;
+    use fisco_bcos_rust_gears_sdk::bcosclient::bcoscontract::*;
+    use fisco_bcos_rust_gears_sdk::bcossdkutil::kisserror::KissError;
+
+    #[derive(Debug, Clone)]
+    pub struct SimpleInfo {
+        pub contract: BcosContract,
+    }
+
+    impl SimpleInfo {
+        pub const NAME: &'static str = "SimpleInfo";
+        pub const ABI: &'static str = r#"[{"constant":false,"inputs":[{"name":"b","type":"uint256"}],"name":"add","outputs":[{"name":"","type":"uint256"}],"payable":false,"stateMutability":"nonpayable","type":"function"},{"constant":true,"inputs":[],"name":"getaddress","outputs":[{"name":"","type":"address"}],"payable":false,"stateMutability":"view","type":"function"},{"constant":true,"inputs":[],"name":"getbalance","outputs":[{"name":"","type":"uint256"}],"payable":false,"stateMutability":"view","type":"function"},{"constant":false,"inputs":[{"name":"b","type":"uint256"}],"name":"setbalance","outputs":[],"payable":false,"stateMutability":"nonpayable","type":"function"},{"constant":true,"inputs":[{"name":"plus","type":"uint256"}],"name":"getbalance1","outputs":[{"name":"","type":"uint256"}],"payable":false,"stateMutability":"view","type":"function"},{"constant":true,"inputs":[],"name":"getall","outputs":[{"name":"","type":"string"},{"name":"","type":"uint256"},{"name":"","type":"address"}],"payable":false,"stateMutability":"view","type":"function"},{"constant":true,"inputs":[],"name":"getname","outputs":[{"name":"","type":"string"}],"payable":false,"stateMutability":"view","type":"function"},{"constant":false,"inputs":[{"name":"n","type":"string"},{"name":"b","type":"uint256"},{"name":"a","type":"address"}],"name":"set","outputs":[{"name":"","type":"int256"}],"payable":false,"stateMutability":"nonpayable","type":"function"},{"constant":false,"inputs":[],"name":"reset","outputs":[{"name":"","type":"int256"}],"payable":false,"stateMutability":"nonpayable","type":"function"},{"constant":true,"inputs":[],"name":"getcounter","outputs":[{"name":"","type":"uint256"}],"payable":false,"stateMutability":"view","type":"function"},{"constant":false,"inputs":[],"name":"setempty","outputs":[],"payable":false,"stateMutability":"nonpayable","type":"function"},{"inputs":[],"payable":false,"stateMutability":"nonpayable","type":"constructor"},{"payable":false,"stateMutability":"nonpayable","type":"fallback"},{"anonymous":false,"inputs":[{"indexed":false,"name":"retcode","type":"int256"},{"indexed":false,"name":"name","type":"string"},{"indexed":false,"name":"balance","type":"uint256"},{"indexed":false,"name":"addr","type":"address"},{"indexed":false,"name":"memo","type":"string"}],"name":"on_set","type":"event"},{"anonymous":false,"inputs":[{"indexed":false,"name":"retcode","type":"int256"},{"indexed":true,"name":"name","type":"string"},{"indexed":false,"name":"balance","type":"uint256"},{"indexed":true,"name":"addr","type":"address"},{"indexed":false,"name":"memo","type":"string"}],"name":"on_change","type":"event"},{"anonymous":false,"inputs":[{"indexed":false,"name":"retcode","type":"int256"},{"indexed":false,"name":"name","type":"string"},{"indexed":false,"name":"balance","type":"uint256"},{"indexed":false,"name":"addr","type":"address"},{"indexed":false,"name":"memo","type":"string"}],"name":"on_sender","type":"event"},{"anonymous":true,"inputs":[{"indexed":false,"name":"retcode","type":"int256"},{"indexed":true,"name":"name","type":"string"}],"name":"on_reset","type":"event"},{"anonymous":false,"inputs":[{"indexed":false,"name":"msg","type":"string"}],"name":"on_set_empty","type":"event"}]"#;
+
+        ///已部署在address的合约
+        pub fn at(client: &dyn IBcosChainClient, address: &str) -> Result<Self, KissError> {
+            Ok(SimpleInfo {
+                contract: BcosContract::new(Self::NAME, Self::ABI, address, client.get_hashtype())?,
+            })
+        }
+
+        ///add(uint256):(uint256) (发送交易)
+        pub fn add(&self, client: &mut dyn IBcosChainClient, b: Uint) -> Result<BcosReceiptInfo, KissError> {
+            self.contract.send(client, "add", &[Token::Uint(b)])
+        }
+
+        ///解码add交易回执里的返回值
+        pub fn add_output(&self, receipt: &BcosReceiptInfo) -> Result<Uint, KissError> {
+            let mut outputs = self.contract.decode_output("add", receipt.output.as_str())?.into_iter();
+            Ok(token_into_uint(next_token(&mut outputs)?)?)
+        }
+
+        ///getaddress():(address) (call)
+        pub fn getaddress(&self, client: &mut dyn IBcosChainClient) -> Result<Address, KissError> {
+            let mut outputs = self.contract.call(client, "getaddress", &[])?.into_iter();
+            Ok(token_into_address(next_token(&mut outputs)?)?)
+        }
+
+        ///getbalance():(uint256) (call)
+        pub fn getbalance(&self, client: &mut dyn IBcosChainClient) -> Result<Uint, KissError> {
+            let mut outputs = self.contract.call(client, "getbalance", &[])?.into_iter();
+            Ok(token_into_uint(next_token(&mut outputs)?)?)
+        }
+
+        ///setbalance(uint256) (发送交易)
+        pub fn setbalance(&self, client: &mut dyn IBcosChainClient, b: Uint) -> Result<BcosReceiptInfo, KissError> {
+            self.contract.send(client, "setbalance", &[Token::Uint(b)])
+        }
+
+        ///getbalance1(uint256):(uint256) (call)
+        pub fn getbalance1(&self, client: &mut dyn IBcosChainClient, plus: Uint) -> Result<Uint, KissError> {
+            let mut outputs = self.contract.call(client, "getbalance1", &[Token::Uint(plus)])?.into_iter();
+            Ok(token_into_uint(next_token(&mut outputs)?)?)
+        }
+
+        ///getall():(string,uint256,address) (call)
+        pub fn getall(&self, client: &mut dyn IBcosChainClient) -> Result<(String, Uint, Address), KissError> {
+            let mut outputs = self.contract.call(client, "getall", &[])?.into_iter();
+            Ok((token_into_string(next_token(&mut outputs)?)?, token_into_uint(next_token(&mut outputs)?)?, token_into_address(next_token(&mut outputs)?)?))
+        }
+
+        ///getname():(string) (call)
+        pub fn getname(&self, client: &mut dyn IBcosChainClient) -> Result<String, KissError> {
+            let mut outputs = self.contract.call(client, "getname", &[])?.into_iter();
+            Ok(token_into_string(next_token(&mut outputs)?)?)
+        }
+
+        ///set(string,uint256,address):(int256) (发送交易)
+        pub fn set(&self, client: &mut dyn IBcosChainClient, n: String, b: Uint, a: Address) -> Result<BcosReceiptInfo, KissError> {
+            self.contract.send(client, "set", &[Token::String(n), Token::Uint(b), Token::Address(a)])
+        }
+
+        ///解码set交易回执里的返回值
+        pub fn set_output(&self, receipt: &BcosReceiptInfo) -> Result<Int, KissError> {
+            let mut outputs = self.contract.decode_output("set", receipt.output.as_str())?.into_iter();
+            Ok(token_into_int(next_token(&mut outputs)?)?)
+        }
+
+        ///reset():(int256) (发送交易)
+        pub fn reset(&self, client: &mut dyn IBcosChainClient) -> Result<BcosReceiptInfo, KissError> {
+            self.contract.send(client, "reset", &[])
+        }
+
+        ///解码reset交易回执里的返回值
+        pub fn reset_output(&self, receipt: &BcosReceiptInfo) -> Result<Int, KissError> {
+            let mut outputs = self.contract.decode_output("reset", receipt.output.as_str())?.into_iter();
+            Ok(token_into_int(next_token(&mut outputs)?)?)
+        }
+
+        ///getcounter():(uint256) (call)
+        pub fn getcounter(&self, client: &mut dyn IBcosChainClient) -> Result<Uint, KissError> {
+            let mut outputs = self.contract.call(client, "getcounter", &[])?.into_iter();
+            Ok(token_into_uint(next_token(&mut outputs)?)?)
+        }
+
+        ///setempty() (发送交易)
+        pub fn setempty(&self, client: &mut dyn IBcosChainClient) -> Result<BcosReceiptInfo, KissError> {
+            self.contract.send(client, "setempty", &[])
+        }
+
+        ///解析回执里的on_set事件
+        pub fn parse_on_set_events(&self, receipt: &BcosReceiptInfo) -> Result<Vec<SimpleInfoOnSetEvent>, KissError> {
+            let mut events = vec![];
+            for log in self.contract.parse_events(receipt, "on_set")? {
+                let mut params = log.params.into_iter().map(|p| p.value);
+                events.push(SimpleInfoOnSetEvent {
+                    retcode: token_into_int(next_token(&mut params)?)?,
+                    name: token_into_string(next_token(&mut params)?)?,
+                    balance: token_into_uint(next_token(&mut params)?)?,
+                    addr: token_into_address(next_token(&mut params)?)?,
+                    memo: token_into_string(next_token(&mut params)?)?,
+                });
+            }
+            Ok(events)
+        }
+
+        ///解析回执里的on_change事件
+        pub fn parse_on_change_events(&self, receipt: &BcosReceiptInfo) -> Result<Vec<SimpleInfoOnChangeEvent>, KissError> {
+            let mut events = vec![];
+            for log in self.contract.parse_events(receipt, "on_change")? {
+                let mut params = log.params.into_iter().map(|p| p.value);
+                events.push(SimpleInfoOnChangeEvent {
+                    retcode: token_into_int(next_token(&mut params)?)?,
+                    name: token_into_bytes(next_token(&mut params)?)?,
+                    balance: token_into_uint(next_token(&mut params)?)?,
+                    addr: token_into_address(next_token(&mut params)?)?,
+                    memo: token_into_string(next_token(&mut params)?)?,
+                });
+            }
+            Ok(events)
+        }
+
+        ///解析回执里的on_sender事件
+        pub fn parse_on_sender_events(&self, receipt: &BcosReceiptInfo) -> Result<Vec<SimpleInfoOnSenderEvent>, KissError> {
+            let mut events = vec![];
+            for log in self.contract.parse_events(receipt, "on_sender")? {
+                let mut params = log.params.into_iter().map(|p| p.value);
+                events.push(SimpleInfoOnSenderEvent {
+                    retcode: token_into_int(next_token(&mut params)?)?,
+                    name: token_into_string(next_token(&mut params)?)?,
+                    balance: token_into_uint(next_token(&mut params)?)?,
+                    addr: token_into_address(next_token(&mut params)?)?,
+                    memo: token_into_string(next_token(&mut params)?)?,
+                });
+            }
+            Ok(events)
+        }
+
+        ///解析回执里的on_reset事件
+        pub fn parse_on_reset_events(&self, receipt: &BcosReceiptInfo) -> Result<Vec<SimpleInfoOnResetEvent>, KissError> {
+            let mut events = vec![];
+            for log in self.contract.parse_events(receipt, "on_reset")? {
+                let mut params = log.params.into_iter().map(|p| p.value);
+                events.push(SimpleInfoOnResetEvent {
+                    retcode: token_into_int(next_token(&mut params)?)?,
+                    name: token_into_bytes(next_token(&mut params)?)?,
+                });
+            }
+            Ok(events)
+        }
+
+        ///解析回执里的on_set_empty事件
+        pub fn parse_on_set_empty_events(&self, receipt: &BcosReceiptInfo) -> Result<Vec<SimpleInfoOnSetEmptyEvent>, KissError> {
+            let mut events = vec![];
+            for log in self.contract.parse_events(receipt, "on_set_empty")? {
+                let mut params = log.params.into_iter().map(|p| p.value);
+                events.push(SimpleInfoOnSetEmptyEvent {
+                    msg: token_into_string(next_token(&mut params)?)?,
+                });
+            }
+            Ok(events)
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct SimpleInfoOnSetEvent {
+        pub retcode: Int,
+        pub name: String,
+        pub balance: Uint,
+        pub addr: Address,
+        pub memo: String,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct SimpleInfoOnChangeEvent {
+        pub retcode: Int,
+        pub name: Vec<u8>,
+        pub balance: Uint,
+        pub addr: Address,
+        pub memo: String,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct SimpleInfoOnSenderEvent {
+        pub retcode: Int,
+        pub name: String,
+        pub balance: Uint,
+        pub addr: Address,
+        pub memo: String,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct SimpleInfoOnResetEvent {
+        pub retcode: Int,
+        pub name: Vec<u8>,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct SimpleInfoOnSetEmptyEvent {
+        pub msg: String,
+    }
+}
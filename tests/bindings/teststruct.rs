@@ -0,0 +1,101 @@
+// 由bcoscontractgen根据TestStruct.abi生成，不要手工修改
+#[allow(non_snake_case, non_camel_case_types, dead_code, unused_mut, unused_imports, clippy::all)]
+pub mod teststruct {
+    use fisco_bcos_rust_gears_sdk::bcosclient::bcoschainclient::IBcosChainClient;
+    use fisco_bcos_rust_gears_sdk::bcosclient::bcoschaintypes::BcosReceiptInfo;
+    use fisco_bcos_rust_gears_sdk::bcosclient::bcoscontract::*;
+    use fisco_bcos_rust_gears_sdk::bcossdkutil::kisserror::KissError;
+
+    #[derive(Debug, Clone)]
+    pub struct TestStruct {
+        pub contract: BcosContract,
+    }
+
+    impl TestStruct {
+        pub const NAME: &'static str = "TestStruct";
+        pub const ABI: &'static str = r#"[{"inputs":[],"stateMutability":"nonpayable","type":"constructor"},{"anonymous":false,"inputs":[{"indexed":false,"internalType":"string","name":"newname","type":"string"}],"name":"onadd","type":"event"},{"anonymous":false,"inputs":[{"indexed":false,"internalType":"string","name":"newname","type":"string"},{"components":[{"internalType":"string","name":"name","type":"string"},{"internalType":"uint256","name":"age","type":"uint256"}],"indexed":false,"internalType":"struct TestStruct.User","name":"u","type":"tuple"}],"name":"onadduser","type":"event"},{"anonymous":false,"inputs":[{"indexed":false,"internalType":"uint256","name":"len","type":"uint256"},{"components":[{"internalType":"string","name":"name","type":"string"},{"internalType":"uint256","name":"age","type":"uint256"}],"indexed":false,"internalType":"struct TestStruct.User[]","name":"u","type":"tuple[]"}],"name":"onaddusers","type":"event"},{"inputs":[{"components":[{"internalType":"string","name":"name","type":"string"},{"internalType":"uint256","name":"age","type":"uint256"}],"internalType":"struct TestStruct.User","name":"_user","type":"tuple"}],"name":"addUser","outputs":[],"stateMutability":"nonpayable","type":"function"},{"inputs":[{"components":[{"internalType":"string","name":"name","type":"string"},{"internalType":"uint256","name":"age","type":"uint256"}],"internalType":"struct TestStruct.User[]","name":"_users","type":"tuple[]"}],"name":"addUsers","outputs":[],"stateMutability":"nonpayable","type":"function"},{"inputs":[{"internalType":"string","name":"name","type":"string"},{"components":[{"internalType":"string","name":"name","type":"string"},{"internalType":"uint256","name":"age","type":"uint256"}],"internalType":"struct TestStruct.User","name":"_user","type":"tuple"}],"name":"addbyname","outputs":[],"stateMutability":"nonpayable","type":"function"},{"inputs":[{"internalType":"string","name":"username","type":"string"}],"name":"getUser","outputs":[{"components":[{"internalType":"string","name":"name","type":"string"},{"internalType":"uint256","name":"age","type":"uint256"}],"internalType":"struct TestStruct.User","name":"","type":"tuple"}],"stateMutability":"view","type":"function"}]"#;
+
+        ///已部署在address的合约
+        pub fn at(client: &dyn IBcosChainClient, address: &str) -> Result<Self, KissError> {
+            Ok(TestStruct {
+                contract: BcosContract::new(Self::NAME, Self::ABI, address, client.get_hashtype())?,
+            })
+        }
+
+        ///addUser((string,uint256)) (发送交易)
+        pub fn addUser(&self, client: &mut dyn IBcosChainClient, _user: (String, Uint)) -> Result<BcosReceiptInfo, KissError> {
+            self.contract.send(client, "addUser", &[{ let (t0_0, t0_1) = _user; Token::Tuple(vec![Token::String(t0_0), Token::Uint(t0_1)]) }])
+        }
+
+        ///addUsers((string,uint256)[]) (发送交易)
+        pub fn addUsers(&self, client: &mut dyn IBcosChainClient, _users: Vec<(String, Uint)>) -> Result<BcosReceiptInfo, KissError> {
+            self.contract.send(client, "addUsers", &[Token::Array(_users.into_iter().map(|v0| { let (t1_0, t1_1) = v0; Token::Tuple(vec![Token::String(t1_0), Token::Uint(t1_1)]) }).collect())])
+        }
+
+        ///addbyname(string,(string,uint256)) (发送交易)
+        pub fn addbyname(&self, client: &mut dyn IBcosChainClient, name: String, _user: (String, Uint)) -> Result<BcosReceiptInfo, KissError> {
+            self.contract.send(client, "addbyname", &[Token::String(name), { let (t0_0, t0_1) = _user; Token::Tuple(vec![Token::String(t0_0), Token::Uint(t0_1)]) }])
+        }
+
+        ///getUser(string):((string,uint256)) (call)
+        pub fn getUser(&self, client: &mut dyn IBcosChainClient, username: String) -> Result<(String, Uint), KissError> {
+            let mut outputs = self.contract.call(client, "getUser", &[Token::String(username)])?.into_iter();
+            Ok({ let mut it0 = token_into_tuple(next_token(&mut outputs)?, 2)?.into_iter(); (token_into_string(next_token(&mut it0)?)?, token_into_uint(next_token(&mut it0)?)?) })
+        }
+
+        ///解析回执里的onadd事件
+        pub fn parse_onadd_events(&self, receipt: &BcosReceiptInfo) -> Result<Vec<TestStructOnaddEvent>, KissError> {
+            let mut events = vec![];
+            for log in self.contract.parse_events(receipt, "onadd")? {
+                let mut params = log.params.into_iter().map(|p| p.value);
+                events.push(TestStructOnaddEvent {
+                    newname: token_into_string(next_token(&mut params)?)?,
+                });
+            }
+            Ok(events)
+        }
+
+        ///解析回执里的onadduser事件
+        pub fn parse_onadduser_events(&self, receipt: &BcosReceiptInfo) -> Result<Vec<TestStructOnadduserEvent>, KissError> {
+            let mut events = vec![];
+            for log in self.contract.parse_events(receipt, "onadduser")? {
+                let mut params = log.params.into_iter().map(|p| p.value);
+                events.push(TestStructOnadduserEvent {
+                    newname: token_into_string(next_token(&mut params)?)?,
+                    u: { let mut it0 = token_into_tuple(next_token(&mut params)?, 2)?.into_iter(); (token_into_string(next_token(&mut it0)?)?, token_into_uint(next_token(&mut it0)?)?) },
+                });
+            }
+            Ok(events)
+        }
+
+        ///解析回执里的onaddusers事件
+        pub fn parse_onaddusers_events(&self, receipt: &BcosReceiptInfo) -> Result<Vec<TestStructOnaddusersEvent>, KissError> {
+            let mut events = vec![];
+            for log in self.contract.parse_events(receipt, "onaddusers")? {
+                let mut params = log.params.into_iter().map(|p| p.value);
+                events.push(TestStructOnaddusersEvent {
+                    len: token_into_uint(next_token(&mut params)?)?,
+                    u: token_into_array(next_token(&mut params)?)?.into_iter().map(|v0| -> Result<_, KissError> { Ok({ let mut it1 = token_into_tuple(v0, 2)?.into_iter(); (token_into_string(next_token(&mut it1)?)?, token_into_uint(next_token(&mut it1)?)?) }) }).collect::<Result<Vec<_>, KissError>>()?,
+                });
+            }
+            Ok(events)
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct TestStructOnaddEvent {
+        pub newname: String,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct TestStructOnadduserEvent {
+        pub newname: String,
+        pub u: (String, Uint),
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct TestStructOnaddusersEvent {
+        pub len: Uint,
+        pub u: Vec<(String, Uint)>,
+    }
+}
@@ -0,0 +1,144 @@
+//区块头的共识签名校验：mock节点按2.x的区块头计算hash，所有共识节点签名
+use fisco_bcos_rust_gears_sdk::bcos2sdk::bcos2client::Bcos2Client;
+use fisco_bcos_rust_gears_sdk::bcos2sdk::bcos_channel_mocknode::MockChainState;
+use fisco_bcos_rust_gears_sdk::bcossdkutil::blocksignature;
+use fisco_bcos_rust_gears_sdk::bcossdkutil::commonhash::HashType;
+use serde_json::json;
+
+mod common;
+
+#[test]
+fn sealer_signatures_of_block_header() {
+    let (node, configfile) = common::start_node("bcosblocksig");
+    let mut client = Bcos2Client::new_from_config(configfile.as_str()).unwrap();
+    client.deploy_hexcode("6080").unwrap();
+    let report = client.verify_block_signatures(1).unwrap();
+    assert!(report.is_valid());
+    assert_eq!(report.block_number, 1);
+    assert_eq!(report.sealer_count, 4);
+    assert_eq!(report.quorum, 3);
+    assert_eq!(report.signers.len(), 4);
+    assert_eq!(report.signers[0], node.state().sealers[0]);
+    assert!(report.invalid.is_empty());
+
+    //签名不足法定数
+    let full = node.state().blocks[1]["signatureList"].clone();
+    node.state().blocks[1]["signatureList"] = json!([full[0], full[2]]);
+    let report = client.verify_block_signatures(1).unwrap();
+    assert!(!report.is_valid());
+    assert_eq!(report.signers.len(), 2);
+
+    //同一个签名重复出现只算一次
+    node.state().blocks[1]["signatureList"] = json!([full[0], full[0], full[0]]);
+    assert_eq!(client.verify_block_signatures(1).unwrap().signers.len(), 1);
+
+    //有一个签名者不在当前的共识节点列表里，3个有效签名仍然够法定数
+    node.state().blocks[1]["signatureList"] = full;
+    node.state().sealers[3] = hex::encode([9u8; 64]);
+    let report = client.verify_block_signatures(1).unwrap();
+    assert!(report.is_valid());
+    assert_eq!(report.signers.len(), 3);
+    assert_eq!(report.invalid, vec![3]);
+
+    //篡改区块头后hash对不上，签名也全部无效
+    node.state().blocks[1]["stateRoot"] = json!(format!("0x{}", hex::encode([1u8; 32])));
+    let report = client.verify_block_signatures(1).unwrap();
+    assert!(!report.hash_matched);
+    assert!(report.signers.is_empty());
+    client.finish();
+}
+
+#[test]
+fn sm2_signatures_and_v3_sealer_list() {
+    let mut state = MockChainState::new();
+    state.hashtype = HashType::WEDRP_SM3;
+    state.sealers = state
+        .sealer_keys
+        .iter()
+        .map(|k| hex::encode(blocksignature::nodeid_from_privkey(k, &state.hashtype).unwrap()))
+        .collect();
+    let number = state.seal_block(vec![]) as usize;
+    let block = state.blocks[number].clone();
+    //3.x的getSealerList格式
+    let sealers: Vec<_> = state
+        .sealers
+        .iter()
+        .map(|s| json!({"nodeID": s, "weight": 1}))
+        .collect();
+    let report =
+        blocksignature::verify_block_signatures_v2(&block, &json!(sealers), &HashType::WEDRP_SM3)
+            .unwrap();
+    assert!(report.is_valid());
+    assert_eq!(report.signers.len(), 4);
+    //用非国密算法校验国密签名失败
+    let report = blocksignature::verify_block_signatures_v2(
+        &block,
+        &json!(sealers),
+        &HashType::WEDPR_KECCAK,
+    )
+    .unwrap();
+    assert!(!report.is_valid());
+    assert_eq!(blocksignature::pbft_quorum(1), 1);
+    assert_eq!(blocksignature::pbft_quorum(7), 5);
+}
+
+//3.x区块头按字段拼接计算hash，签名用sealerIndex指向区块头的sealerList
+#[test]
+fn v3_header_hash_and_signatures() {
+    let state = MockChainState::new();
+    let hashtype = HashType::WEDPR_KECCAK;
+    let mut block = json!({
+        "version": 0x3010000,
+        "parentInfo": [{"blockNumber": 9, "blockHash": format!("0x{}", hex::encode([2u8; 32]))}],
+        "txsRoot": format!("0x{}", hex::encode([3u8; 32])),
+        "receiptsRoot": format!("0x{}", hex::encode([4u8; 32])),
+        "stateRoot": format!("0x{}", hex::encode([5u8; 32])),
+        "number": 10,
+        "gasUsed": "21000",
+        "timestamp": 1660000000000u64,
+        "sealer": 1,
+        "sealerList": state.sealers.iter().map(|s| format!("0x{}", s)).collect::<Vec<_>>(),
+        "extraData": "0x",
+        "consensusWeights": [1, 1, 1, 1],
+    });
+    let hash = blocksignature::header_hash_v3(&block, &hashtype).unwrap();
+    block["hash"] = json!(format!("0x{}", hex::encode(&hash)));
+    let signatures: Vec<_> = state
+        .sealer_keys
+        .iter()
+        .enumerate()
+        .map(|(i, k)| {
+            let sig = blocksignature::sign_block_hash(k, &hash, &hashtype).unwrap();
+            json!({"sealerIndex": i, "signature": format!("0x{}", hex::encode(sig))})
+        })
+        .collect();
+    block["signatureList"] = json!(signatures);
+    let sealers: Vec<_> = state
+        .sealers
+        .iter()
+        .map(|s| json!({"nodeID": s, "weight": 1}))
+        .collect();
+    let report =
+        blocksignature::verify_block_signatures_v3(&block, &json!(sealers), &hashtype).unwrap();
+    assert!(report.is_valid());
+    assert_eq!(report.block_number, 10);
+    assert_eq!(report.signers.len(), 4);
+
+    //parentInfo、共识权重也参与hash
+    let mut tampered = block.clone();
+    tampered["parentInfo"][0]["blockNumber"] = json!(8);
+    let report =
+        blocksignature::verify_block_signatures_v3(&tampered, &json!(sealers), &hashtype).unwrap();
+    assert!(!report.hash_matched);
+    assert!(report.signers.is_empty());
+    let mut tampered = block.clone();
+    tampered["consensusWeights"] = json!([1, 1, 1, 2]);
+    assert_ne!(
+        blocksignature::header_hash_v3(&tampered, &hashtype).unwrap(),
+        hash
+    );
+    //按2.x的rlp计算3.x区块头的hash是对不上的
+    assert!(
+        blocksignature::verify_block_signatures_v2(&block, &json!(sealers), &hashtype).is_err()
+    );
+}
@@ -0,0 +1,32 @@
+//集成测试共用的模拟2.x节点启动代码，测试文件里用 mod common; 引入
+#![allow(dead_code)]
+
+use std::path::PathBuf;
+
+use fisco_bcos_rust_gears_sdk::bcos2sdk::bcos_channel_mocknode::{MockChainState, MockChannelNode};
+
+///每个测试一个临时目录，带上进程号，避免同时跑的测试互相覆盖证书和配置
+pub fn workdir(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("{}_{}", name, std::process::id()))
+}
+
+///用仓库里的client.pem和contracts目录给模拟节点写客户端配置，返回配置文件路径
+pub fn write_client_config(node: &MockChannelNode) -> String {
+    let root = env!("CARGO_MANIFEST_DIR");
+    node.write_client_config(
+        format!("{}/conf/client.pem", root).as_str(),
+        format!("{}/contracts", root).as_str(),
+    )
+    .unwrap()
+}
+
+///在workdir(name)下用指定的链状态起模拟节点，并写好客户端配置
+pub fn start_node_with_state(name: &str, state: MockChainState) -> (MockChannelNode, String) {
+    let node = MockChannelNode::start(state, workdir(name).to_str().unwrap()).unwrap();
+    let configfile = write_client_config(&node);
+    (node, configfile)
+}
+
+pub fn start_node(name: &str) -> (MockChannelNode, String) {
+    start_node_with_state(name, MockChainState::new())
+}
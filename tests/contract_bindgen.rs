@@ -0,0 +1,301 @@
+//合约绑定代码生成测试：生成结果和tests/bindings下的文件一致，绑定代码通过模拟的IBcosChainClient调用
+use std::collections::HashMap;
+
+use ethabi::Token;
+use fisco_bcos_rust_gears_sdk::bcosclient::bcoschainclient::{BcosChainVersion, IBcosChainClient};
+use fisco_bcos_rust_gears_sdk::bcosclient::bcoschaintypes::{
+    BcosBlockInfo, BcosCallResult, BcosLogInfo, BcosReceiptInfo, BcosTxInfo,
+};
+use fisco_bcos_rust_gears_sdk::bcosclient::bcoscontract::{
+    token_into_string, token_into_tuple, token_into_uint, Uint,
+};
+use fisco_bcos_rust_gears_sdk::bcosclient::bcoscontractgen::{
+    camel_name, rust_ident, BcosContractGen,
+};
+use fisco_bcos_rust_gears_sdk::bcossdkutil::accountutil::BcosAccount;
+use fisco_bcos_rust_gears_sdk::bcossdkutil::bcosclientconfig::ClientConfig;
+use fisco_bcos_rust_gears_sdk::bcossdkutil::commonhash::HashType;
+use fisco_bcos_rust_gears_sdk::bcossdkutil::contractabi::ContractABI;
+use fisco_bcos_rust_gears_sdk::bcossdkutil::kisserror::{KissErrKind, KissError};
+use fisco_bcos_rust_gears_sdk::kisserr;
+
+include!("bindings/helloworld.rs");
+include!("bindings/teststruct.rs");
+include!("bindings/simpleinfo.rs");
+include!("bindings/arraydemo.rs");
+
+use helloworld::HelloWorld;
+use teststruct::{TestStruct, TestStructOnadduserEvent};
+
+///在内存里模拟HelloWorld和TestStruct的行为，事件按abi编码成log
+struct MockChain {
+    config: ClientConfig,
+    account: BcosAccount,
+    hashtype: HashType,
+    names: HashMap<String, String>,
+    users: Vec<(String, Uint)>,
+    deployed: u8,
+    last_deploy: String,
+}
+
+impl MockChain {
+    fn new() -> Self {
+        let configfile = format!("{}/conf/config.toml.sample", env!("CARGO_MANIFEST_DIR"));
+        MockChain {
+            config: ClientConfig::load(configfile.as_str()).unwrap(),
+            account: BcosAccount::default(),
+            hashtype: HashType::WEDPR_KECCAK,
+            names: HashMap::new(),
+            users: vec![],
+            deployed: 0,
+            last_deploy: "".to_string(),
+        }
+    }
+
+    fn log(
+        &self,
+        contract: &ContractABI,
+        address: &str,
+        event: &str,
+        params: Vec<Token>,
+    ) -> BcosLogInfo {
+        let event = contract.find_event_by_name(event).unwrap();
+        BcosLogInfo {
+            address: address.to_string(),
+            topics: vec![format!(
+                "0x{:x}",
+                contract.event_abi_utils.event_signature(event)
+            )],
+            data: format!("0x{}", hex::encode(ethabi::encode(&params))),
+        }
+    }
+}
+
+impl IBcosChainClient for MockChain {
+    fn chain_version(&self) -> BcosChainVersion {
+        BcosChainVersion::V3
+    }
+    fn get_full_name(&self) -> String {
+        "mock".to_string()
+    }
+    fn get_info(&self) -> String {
+        "mock".to_string()
+    }
+    fn get_config(&self) -> &ClientConfig {
+        &self.config
+    }
+    fn get_account(&self) -> &BcosAccount {
+        &self.account
+    }
+    fn get_hashtype(&self) -> &HashType {
+        &self.hashtype
+    }
+    fn get_block_number(&mut self) -> Result<u64, KissError> {
+        Ok(1)
+    }
+    fn get_block_limit(&mut self) -> Result<u64, KissError> {
+        Ok(501)
+    }
+    fn get_block_by_number(
+        &mut self,
+        _num: u64,
+        _include_txs: bool,
+    ) -> Result<BcosBlockInfo, KissError> {
+        kisserr!(KissErrKind::Error, "not supported by mock")
+    }
+    fn get_block_by_hash(
+        &mut self,
+        _hash: &str,
+        _include_txs: bool,
+    ) -> Result<BcosBlockInfo, KissError> {
+        kisserr!(KissErrKind::Error, "not supported by mock")
+    }
+    fn get_transaction_by_hash(&mut self, _hash: &str) -> Result<BcosTxInfo, KissError> {
+        kisserr!(KissErrKind::Error, "not supported by mock")
+    }
+    fn get_transaction_receipt(
+        &mut self,
+        _hash: &str,
+    ) -> Result<Option<BcosReceiptInfo>, KissError> {
+        kisserr!(KissErrKind::Error, "not supported by mock")
+    }
+    fn call(
+        &mut self,
+        _c: &ContractABI,
+        _a: &str,
+        _m: &str,
+        _p: &[String],
+    ) -> Result<BcosCallResult, KissError> {
+        kisserr!(KissErrKind::Error, "not supported by mock")
+    }
+    fn send_transaction(
+        &mut self,
+        _c: &ContractABI,
+        _a: &str,
+        _m: &str,
+        _p: &[String],
+    ) -> Result<BcosReceiptInfo, KissError> {
+        kisserr!(KissErrKind::Error, "not supported by mock")
+    }
+    fn deploy(&mut self, _name: &str, _params: &[String]) -> Result<BcosReceiptInfo, KissError> {
+        kisserr!(KissErrKind::Error, "not supported by mock")
+    }
+
+    fn call_with_tokens(
+        &mut self,
+        _contract: &ContractABI,
+        address: &str,
+        method: &str,
+        params: &[Token],
+    ) -> Result<BcosCallResult, KissError> {
+        let output = match method {
+            "get" => vec![Token::String(
+                self.names.get(address).cloned().unwrap_or_default(),
+            )],
+            "getUser" => {
+                let name = token_into_string(params[0].clone()).unwrap();
+                let (n, age) = self.users.iter().find(|u| u.0 == name).cloned().unwrap();
+                vec![Token::Tuple(vec![Token::String(n), Token::Uint(age)])]
+            }
+            _ => {
+                return Ok(BcosCallResult {
+                    status: 1,
+                    ..Default::default()
+                })
+            }
+        };
+        Ok(BcosCallResult {
+            status: 0,
+            output: format!("0x{}", hex::encode(ethabi::encode(&output))),
+            ..Default::default()
+        })
+    }
+
+    fn send_transaction_with_tokens(
+        &mut self,
+        contract: &ContractABI,
+        address: &str,
+        method: &str,
+        params: &[Token],
+    ) -> Result<BcosReceiptInfo, KissError> {
+        let mut receipt = BcosReceiptInfo {
+            to: address.to_string(),
+            ..Default::default()
+        };
+        match method {
+            "set" => {
+                self.names.insert(
+                    address.to_string(),
+                    token_into_string(params[0].clone()).unwrap(),
+                );
+                receipt
+                    .logs
+                    .push(self.log(contract, address, "onset", params.to_vec()));
+            }
+            "addUser" => {
+                let user = token_into_tuple(params[0].clone(), 2).unwrap();
+                let name = token_into_string(user[0].clone()).unwrap();
+                let age = token_into_uint(user[1].clone()).unwrap();
+                self.users.push((name.clone(), age));
+                receipt.logs.push(self.log(
+                    contract,
+                    address,
+                    "onadduser",
+                    vec![Token::String(name), params[0].clone()],
+                ));
+                //别的合约的同名事件不应被解析
+                receipt.logs.push(self.log(
+                    contract,
+                    "0x01",
+                    "onadduser",
+                    vec![Token::String("other".to_string()), params[0].clone()],
+                ));
+            }
+            _ => receipt.status = 22,
+        }
+        Ok(receipt)
+    }
+
+    fn deploy_hexcode(&mut self, hexcode: &str) -> Result<BcosReceiptInfo, KissError> {
+        self.deployed += 1;
+        self.last_deploy = hexcode.to_string();
+        Ok(BcosReceiptInfo {
+            contract_address: format!("0x{}", hex::encode([self.deployed; 20])),
+            ..Default::default()
+        })
+    }
+
+    fn finish(&mut self) {}
+}
+
+#[test]
+fn generated_bindings_are_up_to_date() {
+    let gen = BcosContractGen::new();
+    let outdir = std::env::temp_dir().join(format!("bindgen_{}", std::process::id()));
+    let files = gen
+        .generate_dir("contracts", outdir.to_str().unwrap())
+        .unwrap();
+    assert!(files.iter().any(|f| f.ends_with("needinit.rs")));
+    for name in ["helloworld", "teststruct", "simpleinfo", "arraydemo"].iter() {
+        let generated = std::fs::read_to_string(outdir.join(format!("{}.rs", name))).unwrap();
+        let expect = std::fs::read_to_string(format!("tests/bindings/{}.rs", name)).unwrap();
+        assert_eq!(
+            generated, expect,
+            "{} binding changed, regenerate tests/bindings",
+            name
+        );
+    }
+    std::fs::remove_dir_all(outdir).unwrap();
+}
+
+#[test]
+fn naming_rules() {
+    assert_eq!(camel_name("on_set"), "OnSet");
+    assert_eq!(camel_name("helloWorld"), "HelloWorld");
+    assert_eq!(rust_ident("type", "arg0"), "r#type");
+    assert_eq!(rust_ident("self", "arg0"), "self_");
+    assert_eq!(rust_ident("client", "arg0"), "client_");
+    assert_eq!(rust_ident("", "arg1"), "arg1");
+    assert_eq!(rust_ident("$x", "arg0"), "_x");
+}
+
+#[test]
+fn helloworld_binding() {
+    let mut chain = MockChain::new();
+    let (hello, receipt) = HelloWorld::deploy(&mut chain).unwrap();
+    assert_eq!(hello.contract.address, receipt.contract_address);
+    assert_eq!(chain.last_deploy, HelloWorld::BIN);
+
+    let receipt = hello.set(&mut chain, "hi rust".to_string()).unwrap();
+    assert_eq!(hello.get(&mut chain).unwrap(), "hi rust");
+    let events = hello.parse_onset_events(&receipt).unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].newname, "hi rust");
+
+    let other = HelloWorld::at(&chain, "0x0202020202020202020202020202020202020202").unwrap();
+    assert_eq!(other.get(&mut chain).unwrap(), "");
+}
+
+#[test]
+fn struct_binding() {
+    let mut chain = MockChain::new();
+    let users = TestStruct::at(&chain, "0x0303030303030303030303030303030303030303").unwrap();
+    let receipt = users
+        .addUser(&mut chain, ("alice".to_string(), Uint::from(18)))
+        .unwrap();
+    assert_eq!(
+        users.getUser(&mut chain, "alice".to_string()).unwrap(),
+        ("alice".to_string(), Uint::from(18))
+    );
+    assert_eq!(
+        users.parse_onadduser_events(&receipt).unwrap(),
+        vec![TestStructOnadduserEvent {
+            newname: "alice".to_string(),
+            u: ("alice".to_string(), Uint::from(18)),
+        }]
+    );
+    assert!(users.parse_onadd_events(&receipt).unwrap().is_empty());
+    //模拟的链不认识的方法返回失败状态
+    let info = simpleinfo::SimpleInfo::at(&chain, "0x04").unwrap();
+    assert!(info.getname(&mut chain).is_err());
+}
@@ -0,0 +1,107 @@
+//外部签名：私钥在本地签名服务里，client只拿到公钥和地址
+use fisco_bcos_rust_gears_sdk::bcos2sdk::bcos2client::Bcos2Client;
+use fisco_bcos_rust_gears_sdk::bcossdkutil::accountutil::create_account;
+use fisco_bcos_rust_gears_sdk::bcossdkutil::bcosclientconfig::BcosCryptoKind;
+use fisco_bcos_rust_gears_sdk::bcossdkutil::commonhash::{CommonHash, HashType};
+use fisco_bcos_rust_gears_sdk::bcossdkutil::commonsigner::ICommonSigner;
+use fisco_bcos_rust_gears_sdk::bcossdkutil::externalsigner::{ExternalSigner, LocalSignService};
+
+mod common;
+
+fn sender_of(client: &mut Bcos2Client) -> String {
+    let response = client.deploy_hexcode("6080").unwrap();
+    let tx = client
+        .getTransactionByHash(response["result"].as_str().unwrap())
+        .unwrap();
+    tx["result"]["from"].as_str().unwrap().to_string()
+}
+
+#[test]
+fn client_signs_through_unix_socket_service() {
+    let dir = common::workdir("bcosextsigner");
+    let (_node, configfile) = common::start_node("bcosextsigner");
+    let root = env!("CARGO_MANIFEST_DIR");
+    let key = create_account(&BcosCryptoKind::ECDSA);
+    let sock = dir.join("signer.sock");
+    let service = LocalSignService::start_unix(
+        sock.to_str().unwrap(),
+        &key.privkey,
+        "key1",
+        &BcosCryptoKind::ECDSA,
+    )
+    .unwrap();
+    let signer =
+        ExternalSigner::connect(&service.endpoint, "key1", &BcosCryptoKind::ECDSA).unwrap();
+    assert_eq!(signer.account.address, key.address);
+    assert!(signer.account.privkey.is_empty());
+
+    //代码里直接传入签名器
+    let account = signer.account.clone();
+    let mut client =
+        Bcos2Client::new_with_signer(configfile.as_str(), Box::new(signer), account).unwrap();
+    let expect = format!("0x{}", hex::encode(&key.address));
+    assert_eq!(sender_of(&mut client), expect);
+    client.finish();
+
+    //配置文件里指定签名服务，accountpem指向不存在的文件也不影响
+    let text = std::fs::read_to_string(&configfile).unwrap();
+    let text = text.replacen(
+        "[common]\n",
+        &format!(
+            "[common]\nsigner = \"{}\"\nsignerkeyid = \"key1\"\n",
+            service.endpoint
+        ),
+        1,
+    );
+    let text = text.replace(
+        format!("{}/conf/client.pem", root).as_str(),
+        "/nonexistent/client.pem",
+    );
+    std::fs::write(&configfile, text).unwrap();
+    let mut client = Bcos2Client::new_from_config(configfile.as_str()).unwrap();
+    assert_eq!(sender_of(&mut client), expect);
+    client.finish();
+}
+
+#[test]
+fn gm_signer_over_http_and_key_checks() {
+    let key = create_account(&BcosCryptoKind::GM);
+    let service = LocalSignService::start_http(&key.privkey, "", &BcosCryptoKind::GM).unwrap();
+    let signer = ExternalSigner::connect(&service.endpoint, "any", &BcosCryptoKind::GM).unwrap();
+    assert_eq!(signer.account.address, key.address);
+    let hash = CommonHash::hash(&b"fisco".to_vec(), &HashType::WEDRP_SM3);
+    let sig = signer.sign(hash.clone()).unwrap();
+    assert!(sig.is_sm2());
+    assert_eq!(
+        sig.recover_address(&hash, &HashType::WEDRP_SM3).unwrap(),
+        key.address
+    );
+    //签名服务不支持请求的算法
+    assert!(ExternalSigner::connect(&service.endpoint, "any", &BcosCryptoKind::ECDSA).is_err());
+
+    //带keyid的服务拒绝其他keyid
+    let ecdsa = create_account(&BcosCryptoKind::ECDSA);
+    let service =
+        LocalSignService::start_http(&ecdsa.privkey, "key1", &BcosCryptoKind::ECDSA).unwrap();
+    assert!(ExternalSigner::connect(&service.endpoint, "key2", &BcosCryptoKind::ECDSA).is_err());
+    assert!(ExternalSigner::connect("ftp://x", "key1", &BcosCryptoKind::ECDSA).is_err());
+}
+
+#[test]
+fn command_signer_with_wrong_key_is_rejected() {
+    let key = create_account(&BcosCryptoKind::ECDSA);
+    let script = std::env::temp_dir().join(format!("bcossigner_{}.sh", std::process::id()));
+    //公钥正确，但返回的签名不是这个私钥签的
+    let body = format!(
+        "#!/bin/sh\nread line\ncase \"$line\" in\n*pubkey*) echo '{{\"pubkey\":\"0x{}\"}}';;\n*) echo '{{\"signature\":\"0x{}00\"}}';;\nesac\n",
+        hex::encode(&key.pubkey),
+        "11".repeat(64)
+    );
+    std::fs::write(&script, body).unwrap();
+    let endpoint = format!("cmd:sh {}", script.to_str().unwrap());
+    let signer = ExternalSigner::connect(&endpoint, "k", &BcosCryptoKind::ECDSA).unwrap();
+    assert_eq!(signer.account.address, key.address);
+    let hash = CommonHash::hash(&b"fisco".to_vec(), &HashType::KECCAK);
+    assert!(signer.sign(hash).is_err());
+    std::fs::remove_file(&script).unwrap();
+}
@@ -0,0 +1,102 @@
+//助记词和HD钱包：BIP39/BIP32的标准测试向量，以及国密的派生
+use fisco_bcos_rust_gears_sdk::bcossdkutil::accountutil::account_from_mnemonic;
+use fisco_bcos_rust_gears_sdk::bcossdkutil::bcosclientconfig::BcosCryptoKind;
+use fisco_bcos_rust_gears_sdk::bcossdkutil::hdwallet::{self, ExtendedPrivKey, HDWallet};
+
+const ABANDON: &str =
+    "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+const HARDHAT: &str = "test test test test test test test test test test test junk";
+
+#[test]
+fn bip39_vectors() {
+    assert_eq!(
+        hdwallet::mnemonic_from_entropy(&[0u8; 16]).unwrap(),
+        ABANDON
+    );
+    assert_eq!(
+        hdwallet::mnemonic_from_entropy(&[0xffu8; 32]).unwrap(),
+        "zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo vote"
+    );
+    assert_eq!(
+        hex::encode(hdwallet::mnemonic_to_seed(ABANDON, "TREZOR").unwrap()),
+        "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04"
+    );
+    //校验位不对，或者词不在词表里
+    assert!(!hdwallet::validate_mnemonic(
+        &ABANDON.replace("about", "abandon")
+    ));
+    assert!(!hdwallet::validate_mnemonic(
+        &ABANDON.replace("about", "fisco")
+    ));
+    for words in [12, 18, 24].iter() {
+        let phrase = hdwallet::generate_mnemonic(*words).unwrap();
+        assert_eq!(phrase.split(' ').count(), *words);
+        assert!(hdwallet::validate_mnemonic(&phrase));
+    }
+    assert!(hdwallet::generate_mnemonic(13).is_err());
+}
+
+#[test]
+fn bip32_vector() {
+    let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+    let master = ExtendedPrivKey::from_seed(&seed, &BcosCryptoKind::ECDSA).unwrap();
+    assert_eq!(
+        hex::encode(&master.privkey),
+        "e8f32e723decf4051aefac8e2c93c9c5b214313817cdb01a1494b917c8436b35"
+    );
+    assert_eq!(
+        hex::encode(&master.chain_code),
+        "873dff81c02f525623fd1fe5167eac3a55a049de3d314bb42ee227ffed37d508"
+    );
+    let child = master.derive_path("m/0'").unwrap();
+    assert_eq!(
+        hex::encode(&child.privkey),
+        "edb2e14f9ee77d26dd93b4ecede8d16ed408ce149b6cd80b0715a2d911a0afea"
+    );
+    assert_eq!(child.depth, 1);
+    assert_eq!(
+        hdwallet::parse_path("m/44'/60h/0'/0/7").unwrap(),
+        vec![0x8000002c, 0x8000003c, 0x80000000, 0, 7]
+    );
+    assert!(hdwallet::parse_path("44'/60'").is_err());
+    assert!(hdwallet::parse_path("m/x").is_err());
+}
+
+#[test]
+fn derive_accounts_from_one_mnemonic() {
+    //和hardhat/metamask的默认账户一致
+    let account = account_from_mnemonic(HARDHAT, "", 0, &BcosCryptoKind::ECDSA).unwrap();
+    assert_eq!(
+        hex::encode(&account.address),
+        "f39fd6e51aad88f6f4ce6ab8827279cfffb92266"
+    );
+    assert_eq!(
+        hex::encode(&account.privkey),
+        "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80"
+    );
+    let wallet = HDWallet::from_mnemonic(HARDHAT, "", &BcosCryptoKind::ECDSA).unwrap();
+    assert_eq!(wallet.account_path(1), "m/44'/60'/0'/0/1");
+    assert_eq!(
+        hex::encode(&wallet.derive_account(1).unwrap().address),
+        "70997970c51812dc3a010c7d01b50e0d17dc79c8"
+    );
+
+    //国密：同一助记词派生结果稳定，和非国密的不同，不同序号不同
+    let gm = HDWallet::from_mnemonic(HARDHAT, "", &BcosCryptoKind::GM).unwrap();
+    let a0 = gm.derive_account(0).unwrap();
+    assert_eq!(
+        a0.address,
+        account_from_mnemonic(HARDHAT, "", 0, &BcosCryptoKind::GM)
+            .unwrap()
+            .address
+    );
+    assert_ne!(a0.address, account.address);
+    assert_ne!(a0.address, gm.derive_account(1).unwrap().address);
+    //口令不同得到另一组账户
+    let other = account_from_mnemonic(HARDHAT, "fisco", 0, &BcosCryptoKind::GM).unwrap();
+    assert_ne!(other.address, a0.address);
+    //自定义路径
+    let custom = gm.clone().with_path("m/44'/1'/0'/0/");
+    assert_eq!(custom.account_path(2), "m/44'/1'/0'/0/2");
+    assert_ne!(custom.derive_account(0).unwrap().address, a0.address);
+}
@@ -0,0 +1,150 @@
+//加密的账户文件：加密pem，p12，web3 json keystore的读写，国密和非国密
+use fisco_bcos_rust_gears_sdk::bcossdkutil::accountutil::{
+    account_from_keystore, account_from_pem, create_account,
+};
+use fisco_bcos_rust_gears_sdk::bcossdkutil::bcosclientconfig::BcosCryptoKind;
+use fisco_bcos_rust_gears_sdk::bcossdkutil::keystore;
+use fisco_bcos_rust_gears_sdk::bcossdkutil::kisserror::KissErrKind;
+
+fn check_files(cryptokind: BcosCryptoKind, dir: &std::path::Path) {
+    let account = create_account(&cryptokind);
+    for ext in ["pem", "p12"].iter() {
+        let path = dir.join(format!("{:?}.{}", cryptokind, ext));
+        let path = path.to_str().unwrap();
+        keystore::save_key(&account.privkey, &cryptokind, path, Some("123456")).unwrap();
+        let loaded = account_from_keystore(path, Some("123456"), &cryptokind).unwrap();
+        assert_eq!(loaded.address, account.address);
+        assert!(account_from_keystore(path, Some("654321"), &cryptokind).is_err());
+        //加密的文件不给密码直接报错
+        assert!(account_from_pem(path, &cryptokind).is_err());
+    }
+    let pem = String::from_utf8(std::fs::read(dir.join(format!("{:?}.pem", cryptokind))).unwrap())
+        .unwrap();
+    assert!(pem.contains("ENCRYPTED PRIVATE KEY"));
+
+    //没有密码时保存为原来的明文pem
+    let path = dir.join(format!("{:?}_plain.pem", cryptokind));
+    let path = path.to_str().unwrap();
+    keystore::save_key(&account.privkey, &cryptokind, path, None).unwrap();
+    assert_eq!(
+        account_from_pem(path, &cryptokind).unwrap().address,
+        account.address
+    );
+}
+
+#[test]
+fn encrypted_pem_and_p12_round_trip() {
+    let dir = std::env::temp_dir().join(format!("bcoskeystore_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    check_files(BcosCryptoKind::ECDSA, &dir);
+    check_files(BcosCryptoKind::GM, &dir);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn json_keystore_round_trip() {
+    for cryptokind in [BcosCryptoKind::ECDSA, BcosCryptoKind::GM].iter() {
+        let account = create_account(cryptokind);
+        let v = keystore::encrypt_json_keystore(
+            &account.privkey,
+            cryptokind,
+            "123456",
+            keystore::LIGHT_SCRYPT_N,
+        )
+        .unwrap();
+        assert_eq!(v["version"], 3);
+        assert_eq!(v["address"], hex::encode(&account.address));
+        let cipher = match cryptokind {
+            BcosCryptoKind::GM => "sm4-128-ctr",
+            BcosCryptoKind::ECDSA => "aes-128-ctr",
+        };
+        assert_eq!(v["crypto"]["cipher"], cipher);
+        assert_eq!(
+            keystore::decrypt_json_keystore(&v, "123456").unwrap(),
+            account.privkey
+        );
+        assert!(keystore::decrypt_json_keystore(&v, "654321").is_err());
+    }
+}
+
+//keystore文件里的kdf参数不可信，超过上限的直接拒绝，不去分配内存
+#[test]
+fn json_keystore_kdf_limits() {
+    let account = create_account(&BcosCryptoKind::ECDSA);
+    let v = keystore::encrypt_json_keystore(
+        &account.privkey,
+        &BcosCryptoKind::ECDSA,
+        "123456",
+        keystore::LIGHT_SCRYPT_N,
+    )
+    .unwrap();
+    for (name, value) in [
+        ("n", serde_json::json!(1u64 << 40)),
+        ("n", serde_json::json!(4097)),
+        ("r", serde_json::json!(1024)),
+        ("p", serde_json::json!(1u64 << 30)),
+        ("dklen", serde_json::json!(1u64 << 32)),
+    ]
+    .iter()
+    {
+        let mut bad = v.clone();
+        bad["crypto"]["kdfparams"][*name] = value.clone();
+        let e = keystore::decrypt_json_keystore(&bad, "123456").unwrap_err();
+        assert_eq!(e.kind, KissErrKind::EFormat, "{} {}", name, value);
+    }
+    assert!(keystore::encrypt_json_keystore(
+        &account.privkey,
+        &BcosCryptoKind::ECDSA,
+        "123456",
+        1 << 24
+    )
+    .is_err());
+    //mac长度不对也只是校验失败
+    let mut bad = v.clone();
+    bad["crypto"]["mac"] = serde_json::json!("00");
+    assert!(keystore::decrypt_json_keystore(&bad, "123456").is_err());
+}
+
+#[test]
+fn web3_test_vector() {
+    //web3 secret storage定义里的pbkdf2测试向量
+    let v = serde_json::json!({
+        "crypto": {
+            "cipher": "aes-128-ctr",
+            "cipherparams": {"iv": "6087dab2f9fdbbfaddc31a909735c1e6"},
+            "ciphertext": "5318b4d5bcd28de64ee5559e671353e16f075ecae9f99c7a79a38af5f869aa46",
+            "kdf": "pbkdf2",
+            "kdfparams": {
+                "c": 262144,
+                "dklen": 32,
+                "prf": "hmac-sha256",
+                "salt": "ae3cd4e7013836a3df6bd7241b12db061dbe2c6785853cce422d148a624ce0bd"
+            },
+            "mac": "517ead924a9d0dc3124507e3393d175ce3ff7c1e96529c6c555ce9e51205e9b2"
+        },
+        "id": "3198bc9c-6672-5ab3-d995-4942343ae5b6",
+        "version": 3
+    });
+    assert_eq!(
+        hex::encode(keystore::decrypt_json_keystore(&v, "testpassword").unwrap()),
+        "7a28b5ba57c53603b0b07b56bba752f7784bf506fa95edc395f5cf6c7514fe9d"
+    );
+}
+
+#[test]
+fn password_sources() {
+    std::env::set_var("BCOS_KEYSTORE_TEST_PASS", "fromenv");
+    assert_eq!(
+        keystore::read_password_source("env:BCOS_KEYSTORE_TEST_PASS").unwrap(),
+        "fromenv"
+    );
+    assert_eq!(keystore::read_password_source("pass:abc").unwrap(), "abc");
+    let file = std::env::temp_dir().join(format!("bcospass_{}", std::process::id()));
+    std::fs::write(&file, "fromfile\nignored\n").unwrap();
+    assert_eq!(
+        keystore::read_password_source(&format!("file:{}", file.to_str().unwrap())).unwrap(),
+        "fromfile"
+    );
+    std::fs::remove_file(&file).unwrap();
+    assert!(keystore::read_password_source("abc").is_err());
+}
@@ -0,0 +1,204 @@
+//交易和回执的merkle证明：2.x用mock节点返回的证明校验，3.x用手工构造的树校验
+use fisco_bcos_rust_gears_sdk::bcos2sdk::bcos2client::Bcos2Client;
+use fisco_bcos_rust_gears_sdk::bcos2sdk::bcos_channel_mocknode::MockTxResult;
+use fisco_bcos_rust_gears_sdk::bcos3sdk::bcos3transaction::{
+    bcos3_receipt_hash, Bcos3TransactionData,
+};
+use fisco_bcos_rust_gears_sdk::bcossdkutil::commonhash::{CommonHash, HashType};
+use fisco_bcos_rust_gears_sdk::bcossdkutil::contractabi::ContractABI;
+use fisco_bcos_rust_gears_sdk::bcossdkutil::merkleproof;
+use serde_json::json;
+
+mod common;
+
+#[test]
+fn v2_proofs_of_many_leaves_rebuild_root() {
+    for hashtype in [HashType::WEDPR_KECCAK, HashType::WEDRP_SM3].iter() {
+        let leaves: Vec<Vec<u8>> = (0..40u64)
+            .map(|i| merkleproof::transaction_leaf_v2(i, &format!("0x{:064x}", i)).unwrap())
+            .collect();
+        let (root, proofs) = merkleproof::merkle_proofs_v2(&leaves, hashtype);
+        assert_eq!(proofs.len(), 40);
+        //40个叶子需要两层
+        assert_eq!(proofs[0].len(), 2);
+        for (leaf, proof) in leaves.iter().zip(proofs.iter()) {
+            let rebuilt = merkleproof::calculate_merkle_root_v2(proof, leaf, hashtype).unwrap();
+            assert_eq!(rebuilt, root);
+        }
+        let rebuilt = merkleproof::calculate_merkle_root_v2(&proofs[1], &leaves[0], hashtype);
+        assert_ne!(rebuilt.unwrap(), root);
+    }
+}
+
+#[test]
+fn v2_transaction_and_receipt_proofs_from_node() {
+    let (node, configfile) = common::start_node("bcosmerkle");
+    let mut client = Bcos2Client::new_from_config(configfile.as_str()).unwrap();
+    let contract = ContractABI::new("contracts/HelloWorld.abi", &client.hashtype).unwrap();
+    let response = client.deploy_hexcode("6080").unwrap();
+    let receipt = client
+        .try_getTransactionReceipt(response["result"].as_str().unwrap(), 3, false)
+        .unwrap();
+    let address = receipt["result"]["contractAddress"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    //回执里带一条日志，覆盖logs的编码
+    node.state().tx_results.insert(
+        address.clone(),
+        MockTxResult {
+            logs: vec![
+                json!({"address": "", "topics": [format!("0x{:064x}", 1)], "data": "0x1234"}),
+            ],
+            ..Default::default()
+        },
+    );
+    let receipt = client
+        .sendRawTransactionGetReceipt(&contract, address.as_str(), "set", &["x".to_string()])
+        .unwrap();
+    let txhash = receipt["result"]["transactionHash"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    assert!(client.verify_transaction_proof(txhash.as_str()).unwrap());
+    assert!(client.verify_receipt_proof(txhash.as_str()).unwrap());
+
+    //把查询到的回执和两个证明拼起来，相当于sendRawTransactionAndGetProof推送的回执
+    let mut pushed = client
+        .getTransactionReceiptByHashWithProof(txhash.as_str())
+        .unwrap()["result"]
+        .clone();
+    let txproof = client
+        .getTransactionByHashWithProof(txhash.as_str())
+        .unwrap()["result"]["txProof"]
+        .clone();
+    pushed["transactionReceipt"]["txProof"] = txproof;
+    pushed["transactionReceipt"]["receiptProof"] = pushed["receiptProof"].clone();
+    assert!(client
+        .verify_receipt_with_proofs(&pushed["transactionReceipt"])
+        .unwrap());
+
+    //节点用别的交易应付请求时不能通过
+    let mut state = node.state();
+    let original = state.transactions.get(&txhash).unwrap().clone();
+    let mut other = original.clone();
+    other["hash"] = json!(format!("0x{:064x}", 7));
+    state.transactions.insert(txhash.clone(), other);
+    drop(state);
+    assert!(!client.verify_transaction_proof(txhash.as_str()).unwrap());
+    node.state().transactions.insert(txhash.clone(), original);
+    assert!(client.verify_transaction_proof(txhash.as_str()).unwrap());
+
+    //节点篡改回执或交易后证明不再成立
+    node.state().receipts.get_mut(&txhash).unwrap()["output"] = json!("0x01");
+    assert!(!client.verify_receipt_proof(txhash.as_str()).unwrap());
+    node.state().transactions.get_mut(&txhash).unwrap()["transactionIndex"] = json!("0x1");
+    assert!(!client.verify_transaction_proof(txhash.as_str()).unwrap());
+    client.finish();
+}
+
+#[test]
+fn v3_proof_path_is_checked_against_root() {
+    let hashtype = HashType::KECCAK;
+    let h = |data: &[u8]| CommonHash::hash(&data.to_vec(), &hashtype);
+    let a = h(b"a");
+    let b = h(b"b");
+    let c = h(b"c");
+    let ab = h(&[a.clone(), b.clone()].concat());
+    let cc = h(&c);
+    let root = h(&[ab.clone(), cc.clone()].concat());
+    let proof: Vec<String> = vec![&a, &b, &ab, &cc, &root]
+        .into_iter()
+        .map(|x| format!("0x{}", hex::encode(x)))
+        .collect();
+    let roothex = hex::encode(&root);
+    assert!(
+        merkleproof::verify_merkle_proof_v3(&proof, &hex::encode(&b), &roothex, &hashtype).unwrap()
+    );
+    //不在树里的叶子，或者根对不上
+    assert!(
+        !merkleproof::verify_merkle_proof_v3(&proof, &hex::encode(&c), &roothex, &hashtype)
+            .unwrap()
+    );
+    assert!(!merkleproof::verify_merkle_proof_v3(
+        &proof,
+        &hex::encode(&a),
+        &hex::encode(&ab),
+        &hashtype
+    )
+    .unwrap());
+
+    let receipt = json!({"transactionHash": hex::encode(&a), "receiptProof": [hex::encode(&a), roothex.clone()]});
+    assert!(!merkleproof::verify_receipt_proof_v3(
+        &receipt,
+        &hex::encode(&a),
+        &a,
+        &roothex,
+        &hashtype
+    )
+    .unwrap());
+}
+
+//3.x的叶子用交易和回执字段重新计算的hash，节点返回的hash字段不可信
+#[test]
+fn v3_leaf_hashes_are_recomputed() {
+    let hashtype = HashType::KECCAK;
+    let h = |data: &[u8]| CommonHash::hash(&data.to_vec(), &hashtype);
+    let mut tx = json!({"version": 0, "chainID": "chain0", "groupID": "group0", "blockLimit": 500,
+        "nonce": "12345", "to": "0x1234567890123456789012345678901234567890",
+        "input": "0x4ed3885e", "abi": ""});
+    let txhash = Bcos3TransactionData::from_json(&tx, &hashtype)
+        .unwrap()
+        .hash();
+    let other = h(b"other");
+    let root = h(&[txhash.clone(), other.clone()].concat());
+    let proof: Vec<String> = vec![&txhash, &other, &root]
+        .into_iter()
+        .map(hex::encode)
+        .collect();
+    let roothex = hex::encode(&root);
+    let txhashhex = format!("0x{}", hex::encode(&txhash));
+    tx["hash"] = json!(txhashhex);
+    tx["txProof"] = json!(proof);
+    let verify_tx = |tx: &serde_json::Value, requested: &str| {
+        let data_hash = Bcos3TransactionData::from_json(tx, &hashtype)
+            .unwrap()
+            .hash();
+        merkleproof::verify_transaction_proof_v3(tx, requested, &data_hash, &roothex, &hashtype)
+            .unwrap()
+    };
+    assert!(verify_tx(&tx, &txhashhex));
+    //字段被改过，hash和证明都不变也不能通过
+    let mut tampered = tx.clone();
+    tampered["input"] = json!("0x4ed3885f");
+    assert!(!verify_tx(&tampered, &txhashhex));
+    //请求的是另一笔交易
+    assert!(!verify_tx(&tx, &hex::encode(&other)));
+
+    let mut receipt = json!({"version": 0, "gasUsed": "21000", "contractAddress": "", "status": 0,
+        "output": "0x", "blockNumber": 3, "transactionHash": txhashhex,
+        "logEntries": [{"address": "1234567890123456789012345678901234567890",
+            "topics": [format!("0x{:064x}", 1)], "data": "0x1234"}]});
+    let receipt_hash = bcos3_receipt_hash(&receipt, &hashtype).unwrap();
+    let root = h(&[receipt_hash.clone(), other.clone()].concat());
+    let roothex = hex::encode(&root);
+    receipt["receiptHash"] = json!(hex::encode(&receipt_hash));
+    receipt["receiptProof"] = json!(vec![&receipt_hash, &other, &root]
+        .into_iter()
+        .map(hex::encode)
+        .collect::<Vec<String>>());
+    let verify_receipt = |receipt: &serde_json::Value| {
+        let receipt_hash = bcos3_receipt_hash(receipt, &hashtype).unwrap();
+        merkleproof::verify_receipt_proof_v3(
+            receipt,
+            &txhashhex,
+            &receipt_hash,
+            &roothex,
+            &hashtype,
+        )
+        .unwrap()
+    };
+    assert!(verify_receipt(&receipt));
+    receipt["status"] = json!(16);
+    assert!(!verify_receipt(&receipt));
+}
@@ -0,0 +1,133 @@
+//离线签名、在线广播：签名时不连接节点，签名文件可查看，广播前检查签名和blocklimit
+use fisco_bcos_rust_gears_sdk::bcosclient::bcoschainclient::{
+    create_chain_client, BcosChainVersion,
+};
+use fisco_bcos_rust_gears_sdk::bcosclient::bcosofflinetx::{
+    signer_from_config, BlockLimitStatus, OfflineTxBuilder, SignedTxFile,
+};
+use fisco_bcos_rust_gears_sdk::bcossdkutil::accountutil::create_account;
+use fisco_bcos_rust_gears_sdk::bcossdkutil::bcosclientconfig::{BcosCryptoKind, ClientConfig};
+use fisco_bcos_rust_gears_sdk::bcossdkutil::commonsigner::CommonSignerWeDPR_SM2;
+use fisco_bcos_rust_gears_sdk::bcossdkutil::contractabi::ContractABI;
+
+mod common;
+
+const TO: &str = "0x7029c502b4f824d19bd7921e9cb74ef92392fb1c";
+
+#[test]
+fn sign_offline_and_broadcast() {
+    let dir = common::workdir("bcosofflinetx");
+    let (node, configfile) = common::start_node("bcosofflinetx");
+    for _ in 0..3 {
+        node.seal_empty_block();
+    }
+
+    //离线部分只用到配置文件，不创建客户端
+    let config = ClientConfig::load(configfile.as_str()).unwrap();
+    let builder = OfflineTxBuilder::from_config(&config, 100);
+    assert_eq!(builder.chain, BcosChainVersion::V2);
+    let contract = ContractABI::new_by_name(
+        "HelloWorld",
+        &config.common.contractpath,
+        &builder.hashtype(),
+    )
+    .unwrap();
+    let signer = signer_from_config(&config.common).unwrap();
+    let params = vec!["hello offline".to_string()];
+    let txfile = builder
+        .sign_call(signer.as_ref(), &contract, TO, "set", &params)
+        .unwrap();
+    let path = dir.join("tx.json");
+    let path = path.to_str().unwrap();
+    txfile.save(path).unwrap();
+
+    //文件是可读的json
+    let v: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+    assert_eq!(v["chain"], "bcos2");
+    assert_eq!(v["blockLimit"], 100);
+    assert_eq!(v["to"], TO);
+    assert_eq!(v["method"], "set");
+    assert_eq!(v["params"][0], "hello offline");
+    let loaded = SignedTxFile::load(path).unwrap();
+    assert_eq!(loaded, txfile);
+    loaded.verify().unwrap();
+
+    //摘要字段和签名交易不一致时拒绝
+    let mut tampered = loaded.clone();
+    tampered.to = "0x0000000000000000000000000000000000000001".to_string();
+    assert!(tampered.verify().is_err());
+    tampered = loaded.clone();
+    tampered.block_limit = 900;
+    assert!(tampered.verify().is_err());
+
+    let mut client = create_chain_client(configfile.as_str()).unwrap();
+    assert_eq!(
+        loaded.check_with_client(client.as_mut()).unwrap(),
+        BlockLimitStatus::Valid { remaining: 97 }
+    );
+    let receipt = loaded.broadcast(client.as_mut(), false).unwrap();
+    assert_eq!(receipt.transaction_hash, loaded.txhash);
+    assert_eq!(receipt.from, loaded.from);
+    //同一笔交易不能重复上链
+    assert!(loaded.broadcast(client.as_mut(), false).is_err());
+
+    //过期的blocklimit：默认不发送，force时发送，由节点拒绝
+    let expired = OfflineTxBuilder::from_config(&config, 3)
+        .sign_call(signer.as_ref(), &contract, TO, "set", &params)
+        .unwrap();
+    assert_eq!(
+        expired.check_with_client(client.as_mut()).unwrap(),
+        BlockLimitStatus::Expired { blocknumber: 4 }
+    );
+    assert!(expired.broadcast(client.as_mut(), false).is_err());
+    assert!(expired.broadcast(client.as_mut(), true).is_err());
+
+    //签给其他群组的交易
+    let mut other = OfflineTxBuilder::from_config(&config, 100);
+    other.groupid = "2".to_string();
+    let other = other
+        .sign_call(signer.as_ref(), &contract, TO, "set", &params)
+        .unwrap();
+    assert!(other.check_with_client(client.as_mut()).is_err());
+    client.finish();
+}
+
+#[test]
+fn bcos3_offline_tx_round_trip() {
+    let key = create_account(&BcosCryptoKind::GM);
+    let signer = CommonSignerWeDPR_SM2::new(key.privkey.clone());
+    let builder = OfflineTxBuilder::new(
+        BcosChainVersion::V3,
+        &BcosCryptoKind::GM,
+        "chain0",
+        "group0",
+        1234,
+    );
+    let txfile = builder.sign(&signer, TO, &[1, 2, 3, 4]).unwrap();
+    assert_eq!(txfile.chainid, "chain0");
+    assert_eq!(txfile.groupid, "group0");
+    assert_eq!(txfile.block_limit, 1234);
+    assert_eq!(txfile.from, format!("0x{}", hex::encode(&key.address)));
+    assert_eq!(txfile.to, TO);
+    assert_eq!(txfile.input, "0x01020304");
+
+    let loaded = SignedTxFile::from_json(&txfile.to_json()).unwrap();
+    loaded.verify().unwrap();
+    assert_eq!(
+        loaded.check_block_limit(234),
+        BlockLimitStatus::Valid { remaining: 1000 }
+    );
+    assert_eq!(
+        loaded.check_block_limit(233),
+        BlockLimitStatus::TooFar { blocknumber: 233 }
+    );
+    assert_eq!(
+        loaded.check_block_limit(1234),
+        BlockLimitStatus::Expired { blocknumber: 1234 }
+    );
+    //用错误的算法解码时签名校验不过
+    let mut wrong = loaded.clone();
+    wrong.crypto = BcosCryptoKind::ECDSA;
+    assert!(wrong.verify().is_err());
+}
@@ -0,0 +1,91 @@
+//签名交易的解码：恢复发送者、交易hash和按abi解码的调用
+use ethabi::Token;
+use ethereum_types::U256;
+use fisco_bcos_rust_gears_sdk::bcos2sdk::bcos2client::Bcos2Client;
+use fisco_bcos_rust_gears_sdk::bcos2sdk::bcostransaction::{
+    decode_signed_transaction, encode_address, BcosTransaction, BcosTransactionWithSig,
+};
+use fisco_bcos_rust_gears_sdk::bcossdkutil::accountutil::{
+    EcdsaAccountUtil, GMAccountUtil, IBcosAccountUtil,
+};
+use fisco_bcos_rust_gears_sdk::bcossdkutil::commonhash::{CommonHash, HashType};
+use fisco_bcos_rust_gears_sdk::bcossdkutil::commonsigner::{
+    CommonSignerWeDPR_SM2, CommonSignerWeDPR_Secp256, ICommonSigner,
+};
+use fisco_bcos_rust_gears_sdk::bcossdkutil::contractabi::ContractABI;
+
+mod common;
+
+const TO: &str = "0x40034be5fd46006238c04c2cedfe92dbddbdb651";
+
+fn helloworld_tx(contract: &ContractABI, hashtype: &HashType) -> BcosTransaction {
+    let input = contract
+        .encode_function_input_to_abi("set", &["hello".to_string()], true)
+        .unwrap();
+    BcosTransaction {
+        random_id: U256::from(7),
+        gas_price: U256::from(30000000),
+        gas_limit: U256::from(30000000),
+        block_limit: U256::from(501),
+        to_address: encode_address(TO),
+        value: U256::zero(),
+        data: hex::decode(input.trim_start_matches("0x")).unwrap(),
+        fisco_chain_id: U256::from(1),
+        group_id: U256::from(1),
+        extra_data: vec![],
+        hashtype: hashtype.clone(),
+    }
+}
+
+fn check(signer: &dyn ICommonSigner, address: &[u8], hashtype: HashType) {
+    let contract = ContractABI::new("contracts/HelloWorld.abi", &hashtype).unwrap();
+    let tx = helloworld_tx(&contract, &hashtype);
+    let raw = BcosTransactionWithSig::sign(signer, &tx).unwrap().encode();
+    let decoded = decode_signed_transaction(&raw, &hashtype, Some(&contract)).unwrap();
+    assert_eq!(decoded.sender, format!("0x{}", hex::encode(address)));
+    assert_eq!(
+        decoded.txhash,
+        format!("0x{}", hex::encode(CommonHash::hash(&raw, &hashtype)))
+    );
+    assert_eq!(decoded.to, TO);
+    let call = decoded.call.unwrap();
+    assert_eq!(call.func.name, "set");
+    assert_eq!(call.input, vec![Token::String("hello".to_string())]);
+    //不传abi时只解码发送者
+    let decoded = decode_signed_transaction(&raw, &hashtype, None).unwrap();
+    assert!(decoded.call.is_none());
+
+    //签名后再修改交易内容，发送者对不上(国密签名直接校验失败)
+    let mut tampered = BcosTransactionWithSig::decode_bytes(&raw).unwrap();
+    tampered.transaction.block_limit = U256::from(502);
+    match decode_signed_transaction(&tampered.encode(), &hashtype, None) {
+        Ok(d) => assert_ne!(d.sender, format!("0x{}", hex::encode(address))),
+        Err(_) => assert_eq!(hashtype, HashType::WEDRP_SM3),
+    }
+}
+
+#[test]
+fn recover_sender_of_ecdsa_and_sm2_transactions() {
+    let account = EcdsaAccountUtil::default().create_random();
+    let mut signer = CommonSignerWeDPR_Secp256::default();
+    signer.key_from_bytes(&account.privkey);
+    check(&signer, &account.address, HashType::WEDPR_KECCAK);
+
+    let account = GMAccountUtil::default().create_random();
+    let signer = CommonSignerWeDPR_SM2::new(account.privkey.clone());
+    check(&signer, &account.address, HashType::WEDRP_SM3);
+}
+
+#[test]
+fn mock_node_records_recovered_sender() {
+    let (_node, configfile) = common::start_node("bcossignedtx");
+    let mut client = Bcos2Client::new_from_config(configfile.as_str()).unwrap();
+    let response = client.deploy_hexcode("6080").unwrap();
+    let txhash = response["result"].as_str().unwrap();
+    let tx = client.getTransactionByHash(txhash).unwrap();
+    assert_eq!(
+        tx["result"]["from"].as_str().unwrap(),
+        format!("0x{}", hex::encode(&client.account.address))
+    );
+    client.finish();
+}
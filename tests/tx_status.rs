@@ -0,0 +1,153 @@
+//回执状态码按链版本解析，revert原因从output解析，发送并等待回执的接口在交易失败时返回ETxFailed
+use ethabi::{Token, Uint};
+use fisco_bcos_rust_gears_sdk::bcos2sdk::bcos2client::Bcos2Client;
+use fisco_bcos_rust_gears_sdk::bcos2sdk::bcos_channel_mocknode::MockTxResult;
+use fisco_bcos_rust_gears_sdk::bcosclient::bcoschainclient::create_chain_client;
+use fisco_bcos_rust_gears_sdk::bcosclient::bcoschaintypes::{BcosCallResult, BcosReceiptInfo};
+use fisco_bcos_rust_gears_sdk::bcosclient::bcosofflinetx::{signer_from_config, OfflineTxBuilder};
+use fisco_bcos_rust_gears_sdk::bcossdkutil::bcosclientconfig::ClientConfig;
+use fisco_bcos_rust_gears_sdk::bcossdkutil::commonhash::{CommonHash, HashType};
+use fisco_bcos_rust_gears_sdk::bcossdkutil::contractabi::ContractABI;
+use fisco_bcos_rust_gears_sdk::bcossdkutil::fileutils;
+use fisco_bcos_rust_gears_sdk::bcossdkutil::kisserror::KissErrKind;
+use fisco_bcos_rust_gears_sdk::bcossdkutil::txstatus::{ReceiptStatus, RevertReason, TxStatus};
+use serde_json::json;
+
+mod common;
+
+fn revert_output(signature: &str, hashtype: &HashType, token: Token) -> String {
+    let mut data = CommonHash::hash(&signature.as_bytes().to_vec(), hashtype)[0..4].to_vec();
+    data.extend(ethabi::encode(&[token]));
+    format!("0x{}", hex::encode(data))
+}
+
+#[test]
+fn status_codes_by_version() {
+    assert_eq!(TxStatus::from_bcos2(0), TxStatus::Success);
+    assert_eq!(TxStatus::from_bcos2(22), TxStatus::RevertInstruction);
+    assert_eq!(TxStatus::from_bcos3(16), TxStatus::RevertInstruction);
+    assert_eq!(TxStatus::from_bcos2(12), TxStatus::OutOfGas);
+    assert_eq!(TxStatus::from_bcos2(30), TxStatus::ContractFrozen);
+    assert_eq!(TxStatus::from_bcos3(21), TxStatus::ContractFrozen);
+    assert_eq!(TxStatus::from_bcos2(25), TxStatus::PermissionDenied);
+    assert_eq!(TxStatus::from_bcos3(18), TxStatus::PermissionDenied);
+    assert_eq!(TxStatus::from_bcos3(10001), TxStatus::BlockLimitCheckFail);
+    assert_eq!(TxStatus::from_bcos3(99), TxStatus::Other(99));
+
+    //bcos2的状态码是hex字符串，bcos3是数字
+    let v2 = json!({"result": {"status": "0x16", "output": "0x"}});
+    assert_eq!(
+        ReceiptStatus::from_receipt(&v2).status,
+        TxStatus::RevertInstruction
+    );
+    let v3 = json!({"status": 16, "output": "0x"});
+    assert_eq!(
+        ReceiptStatus::from_receipt(&v3).status,
+        TxStatus::RevertInstruction
+    );
+    assert!(ReceiptStatus::from_receipt(&json!({"status": "0x0"}))
+        .check("")
+        .is_ok());
+}
+
+#[test]
+fn revert_reason_from_output() {
+    let reason = Token::String("not owner".to_string());
+    //国密链的selector用sm3计算
+    for hashtype in [HashType::WEDPR_KECCAK, HashType::WEDRP_SM3].iter() {
+        let output = revert_output("Error(string)", hashtype, reason.clone());
+        assert_eq!(
+            RevertReason::decode(output.as_str()),
+            Some(RevertReason::Error("not owner".to_string()))
+        );
+    }
+    let output = revert_output(
+        "Panic(uint256)",
+        &HashType::WEDPR_KECCAK,
+        Token::Uint(Uint::from(0x11)),
+    );
+    let status = ReceiptStatus::from_bcos3(16, output.as_str());
+    assert_eq!(status.revert, Some(RevertReason::Panic(Uint::from(0x11))));
+    assert!(status
+        .to_string()
+        .contains("Panic(0x11: arithmetic overflow or underflow)"));
+
+    //成功的交易和普通返回值不解析
+    assert_eq!(RevertReason::decode("0x"), None);
+    assert_eq!(RevertReason::decode(hex::encode([7u8; 32]).as_str()), None);
+    let output = revert_output("Error(string)", &HashType::WEDPR_KECCAK, reason);
+    assert_eq!(ReceiptStatus::from_bcos2(0, output.as_str()).revert, None);
+}
+
+#[test]
+fn send_and_wait_returns_tx_failed() {
+    let (node, configfile) = common::start_node("txstatus");
+    let mut client = Bcos2Client::new_from_config(configfile.as_str()).unwrap();
+    let bin = fileutils::readstring("contracts/HelloWorld.bin").unwrap();
+    let response = client.deploy_hexcode(bin.as_str()).unwrap();
+    let receipt = client
+        .try_getTransactionReceipt(response["result"].as_str().unwrap(), 3, false)
+        .unwrap();
+    let address = receipt["result"]["contractAddress"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let contract = ContractABI::new("contracts/HelloWorld.abi", &client.hashtype).unwrap();
+
+    let output = revert_output(
+        "Error(string)",
+        &client.hashtype,
+        Token::String("not owner".to_string()),
+    );
+    node.state().tx_results.insert(
+        address.clone(),
+        MockTxResult {
+            status: 22,
+            output,
+            ..Default::default()
+        },
+    );
+    let err = client
+        .sendRawTransactionGetReceipt(&contract, address.as_str(), "set", &["x".to_string()])
+        .unwrap_err();
+    assert_eq!(err.kind, KissErrKind::ETxFailed);
+    assert_eq!(err.code, 22);
+    assert!(err.msg.contains("RevertInstruction"), "{}", err.msg);
+    assert!(err.msg.contains("Error(\"not owner\")"), "{}", err.msg);
+    client.finish();
+}
+
+#[test]
+fn missing_status_is_not_success() {
+    let err = BcosReceiptInfo::from_json(&json!({"transactionHash": "0x01"})).unwrap_err();
+    assert_eq!(err.kind, KissErrKind::EFormat);
+    let err = BcosCallResult::from_json(&json!({"status": "bad", "output": "0x"})).unwrap_err();
+    assert_eq!(err.kind, KissErrKind::EFormat);
+    let receipt = BcosReceiptInfo::from_json(&json!({"status": "0x16"})).unwrap();
+    assert_eq!(receipt.status, 22);
+}
+
+#[test]
+fn signed_transaction_returns_tx_failed() {
+    let to = "0x7029c502b4f824d19bd7921e9cb74ef92392fb1c";
+    let (node, configfile) = common::start_node("txstatus_signed");
+    node.state().tx_results.insert(
+        to.to_string(),
+        MockTxResult {
+            status: 22,
+            ..Default::default()
+        },
+    );
+    let config = ClientConfig::load(configfile.as_str()).unwrap();
+    let signer = signer_from_config(&config.common).unwrap();
+    let txfile = OfflineTxBuilder::from_config(&config, 100)
+        .sign(signer.as_ref(), to, &[1, 2, 3, 4])
+        .unwrap();
+    let mut client = create_chain_client(configfile.as_str()).unwrap();
+    let err = client
+        .send_signed_transaction(txfile.rawtx.as_str())
+        .unwrap_err();
+    assert_eq!(err.kind, KissErrKind::ETxFailed);
+    assert_eq!(err.code, 22);
+    client.finish();
+}